@@ -0,0 +1,267 @@
+// Arena PLM BOM CSV export
+//
+// Item Number,Name,Description,Category,Rev,Lifecycle,Quantity,Notes
+// 001,Bike,City bike,Product,B,Production,1,
+// 001.001,Frame,Aluminium frame,Assembly,A,Production,1,
+// 001.001.001,Tube,Frame tube,Part,A,Production,3,Cut to length
+// 001.002,Wheel,28" wheel,Part,C,Production,2,
+//
+// The hierarchy is encoded in `Item Number`: each dot-separated segment is one
+// level, so `001.001` is a child of `001`. Parents must precede their children.
+// Top-level rows are roots and their quantity is ignored. `Rev` and `Lifecycle`
+// have no counterpart in the core model and are not imported.
+
+use crate::BomData;
+use bom_core::{BomError, BomItem, Component, ComponentId, ComponentType, ProcurementType, Result};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use std::collections::HashSet;
+use std::io::Read;
+use uuid::Uuid;
+
+/// Importer for Arena PLM BOM CSV exports
+pub struct ArenaBomImporter;
+
+impl ArenaBomImporter {
+    /// Parse an Arena BOM CSV export
+    pub fn from_csv(reader: impl Read) -> Result<BomData> {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+
+        let headers = reader.headers().map_err(csv_error)?.clone();
+        let columns = Columns::from_headers(&headers)?;
+
+        let mut bom_data = BomData::default();
+        let mut known_items: HashSet<String> = HashSet::new();
+
+        for (index, record) in reader.records().enumerate() {
+            let record = record.map_err(csv_error)?;
+            // Header is line 1
+            let line = index + 2;
+
+            let item_number = columns.get(&record, columns.item_number);
+            if item_number.is_empty() {
+                // Blank separator rows between sheets
+                continue;
+            }
+
+            let segments = parse_item_number(item_number, line)?;
+            if !known_items.insert(item_number.to_string()) {
+                return Err(BomError::SerializationError(format!(
+                    "Line {}: duplicate Item Number {}",
+                    line, item_number
+                )));
+            }
+
+            let is_root = segments.len() == 1;
+            let component_type = parse_category(columns.get(&record, columns.category), is_root);
+            let description = match columns.get(&record, columns.description) {
+                "" => columns.get(&record, columns.name),
+                description => description,
+            };
+
+            let id = ComponentId::new(item_number);
+            let now = Utc::now();
+            bom_data.components.push(Component {
+                id: id.clone(),
+                description: if description.is_empty() {
+                    item_number.to_string()
+                } else {
+                    description.to_string()
+                },
+                component_type,
+                uom: "EA".to_string(),
+                standard_cost: None,
+                lead_time_days: None,
+                procurement_type: match component_type {
+                    ComponentType::FinishedProduct | ComponentType::SemiFinished => ProcurementType::Make,
+                    _ => ProcurementType::Buy,
+                },
+                preferred_vendor: None,
+                organization: String::new(),
+                version: 0,
+                created_at: now,
+                updated_at: now,
+            });
+
+            if is_root {
+                continue;
+            }
+
+            let parent_number = &item_number[..item_number.rfind('.').unwrap()];
+            if !known_items.contains(parent_number) {
+                return Err(BomError::SerializationError(format!(
+                    "Line {}: Item Number {} has no parent row {}",
+                    line, item_number, parent_number
+                )));
+            }
+
+            let quantity = match columns.get(&record, columns.quantity) {
+                "" => Decimal::ONE,
+                value => value
+                    .parse()
+                    .map_err(|_| BomError::InvalidQuantity(format!("{} (line {})", item_number, line)))?,
+            };
+            let sequence = segments[segments.len() - 1].checked_mul(10).ok_or_else(|| {
+                BomError::SerializationError(format!("Line {}: Item Number {} is out of range", line, item_number))
+            })?;
+            let notes = match columns.get(&record, columns.notes) {
+                "" => None,
+                notes => Some(notes.to_string()),
+            };
+
+            bom_data.bom_items.push(BomItem {
+                id: Uuid::new_v4(),
+                parent_id: ComponentId::new(parent_number),
+                child_id: id,
+                quantity,
+                scrap_factor: Decimal::ZERO,
+                sequence,
+                operation_sequence: None,
+                is_phantom: false,
+                effective_from: None,
+                effective_to: None,
+                alternative_group: None,
+                alternative_priority: None,
+                reference_designator: None,
+                position: None,
+                notes,
+                version: 0,
+            });
+        }
+
+        Ok(bom_data)
+    }
+}
+
+/// Column positions in the export (only `Item Number` is mandatory)
+struct Columns {
+    item_number: usize,
+    name: Option<usize>,
+    description: Option<usize>,
+    category: Option<usize>,
+    quantity: Option<usize>,
+    notes: Option<usize>,
+}
+
+impl Columns {
+    fn from_headers(headers: &csv::StringRecord) -> Result<Self> {
+        let find = |name: &str| headers.iter().position(|header| header.eq_ignore_ascii_case(name));
+
+        Ok(Self {
+            item_number: find("Item Number")
+                .ok_or_else(|| BomError::SerializationError("Missing column: Item Number".to_string()))?,
+            name: find("Name"),
+            description: find("Description"),
+            category: find("Category"),
+            quantity: find("Quantity"),
+            notes: find("Notes"),
+        })
+    }
+
+    fn get<'r>(&self, record: &'r csv::StringRecord, column: impl Into<Option<usize>>) -> &'r str {
+        column.into().and_then(|index| record.get(index)).unwrap_or("")
+    }
+}
+
+/// Split `001.002.003` into its level numbers
+fn parse_item_number(item_number: &str, line: usize) -> Result<Vec<u32>> {
+    let malformed = || BomError::SerializationError(format!("Line {}: malformed Item Number {}", line, item_number));
+
+    item_number
+        .split('.')
+        .map(|segment| {
+            if segment.is_empty() || !segment.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(malformed());
+            }
+            segment.parse().map_err(|_| malformed())
+        })
+        .collect()
+}
+
+/// Map an Arena category to a component type
+/// Categories are user-defined in Arena; unknown ones fall back by position
+fn parse_category(category: &str, is_root: bool) -> ComponentType {
+    match category.to_ascii_lowercase().as_str() {
+        "product" | "finished good" | "top level assembly" => ComponentType::FinishedProduct,
+        "assembly" | "sub-assembly" | "subassembly" => ComponentType::SemiFinished,
+        "part" | "raw material" | "component" => ComponentType::RawMaterial,
+        "packaging" => ComponentType::Packaging,
+        "service" | "labor" => ComponentType::Service,
+        _ if is_root => ComponentType::FinishedProduct,
+        _ => ComponentType::RawMaterial,
+    }
+}
+
+fn csv_error(error: csv::Error) -> BomError {
+    BomError::SerializationError(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = "\
+Item Number,Name,Description,Category,Rev,Lifecycle,Quantity,Notes
+001,Bike,City bike,Product,B,Production,1,
+001.001,Frame,Aluminium frame,Assembly,A,Production,1,
+001.001.001,Tube,,Part,A,Production,3,Cut to length
+001.001.002,Bolt,M6 bolt,Part,A,Production,8,
+001.002,Wheel,28 inch wheel,Part,C,Production,2,
+001.003,Box,Shipping box,Packaging,A,Production,1,
+";
+
+    #[test]
+    fn test_import_arena_csv() {
+        let bom_data = ArenaBomImporter::from_csv(FIXTURE.as_bytes()).unwrap();
+        assert_eq!(bom_data.components.len(), 6);
+        assert_eq!(bom_data.bom_items.len(), 5);
+
+        let component = |id: &str| bom_data.components.iter().find(|c| c.id.as_str() == id).unwrap();
+        assert_eq!(component("001").component_type, ComponentType::FinishedProduct);
+        assert_eq!(component("001.001").component_type, ComponentType::SemiFinished);
+        assert_eq!(component("001.003").component_type, ComponentType::Packaging);
+        // Falls back to Name without a description
+        assert_eq!(component("001.001.001").description, "Tube");
+
+        let tube = bom_data
+            .bom_items
+            .iter()
+            .find(|item| item.child_id.as_str() == "001.001.001")
+            .unwrap();
+        assert_eq!(tube.parent_id, ComponentId::new("001.001"));
+        assert_eq!(tube.quantity, Decimal::from(3));
+        assert_eq!(tube.sequence, 10);
+        assert_eq!(tube.notes.as_deref(), Some("Cut to length"));
+
+        let bolt = bom_data
+            .bom_items
+            .iter()
+            .find(|item| item.child_id.as_str() == "001.001.002")
+            .unwrap();
+        assert_eq!(bolt.sequence, 20);
+    }
+
+    #[test]
+    fn test_malformed_hierarchy_is_rejected() {
+        let header = "Item Number,Name,Quantity\n";
+
+        // Parent row missing
+        let orphan = format!("{}001,A,1\n001.002.001,B,1\n", header);
+        assert!(ArenaBomImporter::from_csv(orphan.as_bytes()).is_err());
+
+        // Empty segment
+        let malformed = format!("{}001,A,1\n001..001,B,1\n", header);
+        assert!(ArenaBomImporter::from_csv(malformed.as_bytes()).is_err());
+
+        // Duplicate item number
+        let duplicate = format!("{}001,A,1\n001.001,B,1\n001.001,C,1\n", header);
+        assert!(ArenaBomImporter::from_csv(duplicate.as_bytes()).is_err());
+
+        // Last segment too large for a sequence number (x 10 overflows u32)
+        let overflow = format!("{}001,A,1\n001.999999999,B,1\n", header);
+        assert!(ArenaBomImporter::from_csv(overflow.as_bytes()).is_err());
+    }
+}
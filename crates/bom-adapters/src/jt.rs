@@ -0,0 +1,428 @@
+// Siemens JT2Go / NX flat XML BOM format
+//
+// <BOM>
+//   <Assembly id="A" description="Bike" uom="EA" component_type="FinishedProduct">
+//     <Item id="B" description="Frame" quantity="1" component_type="SemiFinished"/>
+//     <Item id="C" quantity="2" effective_dates="2025-01-01/2025-12-31"/>
+//   </Assembly>
+//   <Assembly id="B"> ... </Assembly>
+// </BOM>
+//
+// Assemblies may also be nested; a nested <Assembly> with a `quantity`
+// attribute is a BOM item of the enclosing assembly.
+
+use crate::BomData;
+use bom_core::{
+    BomError, BomItem, Component, ComponentId, ComponentType, ProcurementType, Result,
+};
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, Event};
+use quick_xml::{Reader, Writer};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufReader, Read, Write};
+use uuid::Uuid;
+
+/// Effective from / to of a BOM item (`None` = open-ended)
+type Effectivity = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+/// Importer for JT flat XML BOM exports
+pub struct JtBomImporter;
+
+impl JtBomImporter {
+    /// Parse a JT flat XML export
+    pub fn from_xml(reader: impl Read) -> Result<BomData> {
+        let mut reader = Reader::from_reader(BufReader::new(reader));
+        reader.trim_text(true);
+
+        let mut state = ImportState::default();
+        let mut assemblies: Vec<ComponentId> = Vec::new();
+        let mut buf = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf).map_err(xml_error)? {
+                Event::Start(e) if e.local_name().as_ref() == b"Assembly" => {
+                    let id = state.assembly(&e, assemblies.last())?;
+                    assemblies.push(id);
+                }
+                Event::Empty(e) if e.local_name().as_ref() == b"Assembly" => {
+                    state.assembly(&e, assemblies.last())?;
+                }
+                Event::End(e) if e.local_name().as_ref() == b"Assembly" => {
+                    assemblies.pop();
+                }
+                Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"Item" => {
+                    let parent = assemblies.last().ok_or_else(|| {
+                        BomError::SerializationError("<Item> outside of an <Assembly>".to_string())
+                    })?;
+                    state.item(&e, parent)?;
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        Ok(BomData {
+            components: state.components,
+            bom_items: state.bom_items,
+        })
+    }
+}
+
+/// Exporter for JT flat XML BOM files
+pub struct JtBomExporter;
+
+impl JtBomExporter {
+    /// Write BOM data as flat XML: one `<Assembly>` per parent with its `<Item>` children
+    pub fn to_xml(bom_data: &BomData, writer: impl Write) -> Result<()> {
+        let components: HashMap<&ComponentId, &Component> =
+            bom_data.components.iter().map(|c| (&c.id, c)).collect();
+
+        // Parents in order of first appearance
+        let mut parents: Vec<&ComponentId> = Vec::new();
+        let mut items_by_parent: HashMap<&ComponentId, Vec<&BomItem>> = HashMap::new();
+        for item in &bom_data.bom_items {
+            let items = items_by_parent.entry(&item.parent_id).or_default();
+            if items.is_empty() {
+                parents.push(&item.parent_id);
+            }
+            items.push(item);
+        }
+
+        let mut writer = Writer::new_with_indent(writer, b' ', 2);
+        writer
+            .write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))
+            .map_err(xml_error)?;
+        writer
+            .write_event(Event::Start(BytesStart::new("BOM")))
+            .map_err(xml_error)?;
+
+        for parent_id in parents {
+            let mut assembly = BytesStart::new("Assembly");
+            push_component_attributes(&mut assembly, parent_id, components.get(parent_id));
+            writer.write_event(Event::Start(assembly)).map_err(xml_error)?;
+
+            let mut items = items_by_parent.remove(parent_id).unwrap_or_default();
+            items.sort_by_key(|item| item.sequence);
+
+            for item in items {
+                let mut element = BytesStart::new("Item");
+                push_component_attributes(&mut element, &item.child_id, components.get(&item.child_id));
+                element.push_attribute(("quantity", item.quantity.normalize().to_string().as_str()));
+                element.push_attribute(("sequence", item.sequence.to_string().as_str()));
+                if item.effective_from.is_some() || item.effective_to.is_some() {
+                    element.push_attribute((
+                        "effective_dates",
+                        format_effective_dates(item.effective_from, item.effective_to).as_str(),
+                    ));
+                }
+                writer.write_event(Event::Empty(element)).map_err(xml_error)?;
+            }
+
+            writer
+                .write_event(Event::End(BytesEnd::new("Assembly")))
+                .map_err(xml_error)?;
+        }
+
+        writer
+            .write_event(Event::End(BytesEnd::new("BOM")))
+            .map_err(xml_error)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct ImportState {
+    components: Vec<Component>,
+    bom_items: Vec<BomItem>,
+    known_components: HashSet<ComponentId>,
+    items_per_parent: HashMap<ComponentId, u32>,
+}
+
+impl ImportState {
+    /// Register an `<Assembly>`; nested assemblies with a quantity become items of their parent
+    fn assembly(&mut self, element: &BytesStart, parent: Option<&ComponentId>) -> Result<ComponentId> {
+        let attributes = read_attributes(element)?;
+        let id = self.component(&attributes, ComponentType::FinishedProduct, ProcurementType::Make)?;
+
+        if let Some(parent) = parent {
+            if attributes.contains_key("quantity") {
+                self.bom_item(&attributes, parent, &id)?;
+            }
+        }
+
+        Ok(id)
+    }
+
+    /// Register an `<Item>` under `parent`
+    fn item(&mut self, element: &BytesStart, parent: &ComponentId) -> Result<()> {
+        let attributes = read_attributes(element)?;
+        let id = self.component(&attributes, ComponentType::RawMaterial, ProcurementType::Buy)?;
+        self.bom_item(&attributes, parent, &id)
+    }
+
+    fn component(
+        &mut self,
+        attributes: &HashMap<String, String>,
+        default_type: ComponentType,
+        procurement_type: ProcurementType,
+    ) -> Result<ComponentId> {
+        let id = ComponentId::new(required(attributes, "id")?);
+        if !self.known_components.insert(id.clone()) {
+            return Ok(id);
+        }
+
+        let component_type = match attributes.get("component_type") {
+            Some(value) => parse_component_type(value)?,
+            None => default_type,
+        };
+
+        let now = Utc::now();
+        self.components.push(Component {
+            id: id.clone(),
+            description: attributes.get("description").cloned().unwrap_or_else(|| id.as_str().to_string()),
+            component_type,
+            uom: attributes.get("uom").cloned().unwrap_or_else(|| "EA".to_string()),
+            standard_cost: None,
+            lead_time_days: None,
+            procurement_type,
+            preferred_vendor: None,
+            organization: String::new(),
+            version: 0,
+            created_at: now,
+            updated_at: now,
+        });
+
+        Ok(id)
+    }
+
+    fn bom_item(
+        &mut self,
+        attributes: &HashMap<String, String>,
+        parent: &ComponentId,
+        child: &ComponentId,
+    ) -> Result<()> {
+        let quantity: Decimal = required(attributes, "quantity")?
+            .parse()
+            .map_err(|_| BomError::InvalidQuantity(format!("{} -> {}", parent.as_str(), child.as_str())))?;
+
+        let position = self.items_per_parent.entry(parent.clone()).or_insert(0);
+        *position += 1;
+        let sequence = match attributes.get("sequence") {
+            Some(value) => value
+                .parse()
+                .map_err(|_| BomError::SerializationError(format!("Invalid sequence: {}", value)))?,
+            None => *position * 10,
+        };
+
+        let (effective_from, effective_to) = match attributes.get("effective_dates") {
+            Some(value) => parse_effective_dates(value)?,
+            None => (None, None),
+        };
+
+        self.bom_items.push(BomItem {
+            id: Uuid::new_v4(),
+            parent_id: parent.clone(),
+            child_id: child.clone(),
+            quantity,
+            scrap_factor: Decimal::ZERO,
+            sequence,
+            operation_sequence: None,
+            is_phantom: false,
+            effective_from,
+            effective_to,
+            alternative_group: None,
+            alternative_priority: None,
+            reference_designator: None,
+            position: None,
+            notes: None,
+            version: 0,
+        });
+
+        Ok(())
+    }
+}
+
+fn read_attributes(element: &BytesStart) -> Result<HashMap<String, String>> {
+    element
+        .attributes()
+        .map(|attribute| {
+            let attribute = attribute.map_err(|e| BomError::SerializationError(e.to_string()))?;
+            let key = String::from_utf8_lossy(attribute.key.as_ref()).into_owned();
+            let value = attribute.unescape_value().map_err(xml_error)?.into_owned();
+            Ok((key, value))
+        })
+        .collect()
+}
+
+fn required<'a>(attributes: &'a HashMap<String, String>, name: &str) -> Result<&'a str> {
+    attributes
+        .get(name)
+        .map(String::as_str)
+        .ok_or_else(|| BomError::SerializationError(format!("Missing attribute: {}", name)))
+}
+
+fn push_component_attributes(element: &mut BytesStart, id: &ComponentId, component: Option<&&Component>) {
+    element.push_attribute(("id", id.as_str()));
+    if let Some(component) = component {
+        element.push_attribute(("description", component.description.as_str()));
+        element.push_attribute(("uom", component.uom.as_str()));
+        element.push_attribute(("component_type", component_type_name(component.component_type)));
+    }
+}
+
+fn parse_component_type(value: &str) -> Result<ComponentType> {
+    match value {
+        "FinishedProduct" => Ok(ComponentType::FinishedProduct),
+        "SemiFinished" => Ok(ComponentType::SemiFinished),
+        "RawMaterial" => Ok(ComponentType::RawMaterial),
+        "Packaging" => Ok(ComponentType::Packaging),
+        "Service" => Ok(ComponentType::Service),
+        other => Err(BomError::SerializationError(format!("Unknown component type: {}", other))),
+    }
+}
+
+fn component_type_name(component_type: ComponentType) -> &'static str {
+    match component_type {
+        ComponentType::FinishedProduct => "FinishedProduct",
+        ComponentType::SemiFinished => "SemiFinished",
+        ComponentType::RawMaterial => "RawMaterial",
+        ComponentType::Packaging => "Packaging",
+        ComponentType::Service => "Service",
+    }
+}
+
+/// Parse `from/to` where either side may be empty; dates are `YYYY-MM-DD` or RFC 3339
+fn parse_effective_dates(value: &str) -> Result<Effectivity> {
+    let (from, to) = value.split_once('/').unwrap_or((value, ""));
+    Ok((parse_date(from)?, parse_date(to)?))
+}
+
+fn parse_date(value: &str) -> Result<Option<DateTime<Utc>>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc()));
+    }
+
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| Some(dt.with_timezone(&Utc)))
+        .map_err(|_| BomError::SerializationError(format!("Invalid effective date: {}", value)))
+}
+
+fn format_effective_dates(from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> String {
+    let format = |date: Option<DateTime<Utc>>| {
+        date.map(|d| d.to_rfc3339_opts(SecondsFormat::Secs, true))
+            .unwrap_or_default()
+    };
+    format!("{}/{}", format(from), format(to))
+}
+
+fn xml_error(error: quick_xml::Error) -> BomError {
+    BomError::SerializationError(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const FIXTURE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<BOM>
+  <Assembly id="BIKE" description="City Bike" uom="EA" component_type="FinishedProduct">
+    <Item id="WHEEL" description="Wheel &amp; Tyre" quantity="2" component_type="SemiFinished"/>
+    <Item id="BELL" quantity="1" effective_dates="2025-01-01/2025-12-31"/>
+    <Assembly id="FRAME" description="Frame" quantity="1" component_type="SemiFinished">
+      <Item id="TUBE" description="Steel tube" quantity="2.5" uom="M"/>
+    </Assembly>
+  </Assembly>
+  <Assembly id="WHEEL">
+    <Item id="SPOKE" description="Spoke" quantity="36" component_type="RawMaterial"/>
+  </Assembly>
+</BOM>"#;
+
+    fn item_summary(data: &BomData) -> Vec<(String, String, Decimal, u32, Effectivity)> {
+        let mut items: Vec<_> = data
+            .bom_items
+            .iter()
+            .map(|item| {
+                (
+                    item.parent_id.as_str().to_string(),
+                    item.child_id.as_str().to_string(),
+                    item.quantity.normalize(),
+                    item.sequence,
+                    (item.effective_from, item.effective_to),
+                )
+            })
+            .collect();
+        items.sort();
+        items
+    }
+
+    fn component_summary(data: &BomData) -> Vec<(String, String, String, &'static str)> {
+        let mut components: Vec<_> = data
+            .components
+            .iter()
+            .map(|c| {
+                (
+                    c.id.as_str().to_string(),
+                    c.description.clone(),
+                    c.uom.clone(),
+                    component_type_name(c.component_type),
+                )
+            })
+            .collect();
+        components.sort();
+        components
+    }
+
+    #[test]
+    fn test_import_jt_xml() {
+        let data = JtBomImporter::from_xml(FIXTURE.as_bytes()).unwrap();
+
+        assert_eq!(data.components.len(), 6);
+        assert_eq!(data.bom_items.len(), 5);
+
+        let wheel = data.components.iter().find(|c| c.id.as_str() == "WHEEL").unwrap();
+        assert_eq!(wheel.description, "Wheel & Tyre");
+        assert_eq!(wheel.component_type, ComponentType::SemiFinished);
+
+        let tube = data.components.iter().find(|c| c.id.as_str() == "TUBE").unwrap();
+        assert_eq!(tube.uom, "M");
+        assert_eq!(tube.component_type, ComponentType::RawMaterial);
+
+        // The nested assembly is an item of its parent
+        assert!(data
+            .bom_items
+            .iter()
+            .any(|item| item.parent_id.as_str() == "BIKE" && item.child_id.as_str() == "FRAME"));
+
+        let bell = data.bom_items.iter().find(|item| item.child_id.as_str() == "BELL").unwrap();
+        assert_eq!(bell.effective_from, Some(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()));
+        assert_eq!(bell.effective_to, Some(Utc.with_ymd_and_hms(2025, 12, 31, 0, 0, 0).unwrap()));
+        assert_eq!(bell.sequence, 20);
+    }
+
+    #[test]
+    fn test_jt_round_trip() {
+        let imported = JtBomImporter::from_xml(FIXTURE.as_bytes()).unwrap();
+
+        let mut xml = Vec::new();
+        JtBomExporter::to_xml(&imported, &mut xml).unwrap();
+        let reimported = JtBomImporter::from_xml(xml.as_slice()).unwrap();
+
+        assert_eq!(item_summary(&reimported), item_summary(&imported));
+        assert_eq!(component_summary(&reimported), component_summary(&imported));
+    }
+
+    #[test]
+    fn test_item_outside_assembly_is_rejected() {
+        let result = JtBomImporter::from_xml(r#"<BOM><Item id="X" quantity="1"/></BOM>"#.as_bytes());
+        assert!(matches!(result, Err(BomError::SerializationError(_))));
+    }
+}
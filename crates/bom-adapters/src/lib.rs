@@ -1,10 +1,26 @@
 // PLM/ERP adapters placeholder
 // TODO: Implement SAP and Oracle adapters
 
+use bom_core::{BomItem, Component};
+
 #[cfg(feature = "sap")]
 pub mod sap;
 
 #[cfg(feature = "oracle")]
 pub mod oracle;
 
+#[cfg(feature = "jt")]
+pub mod jt;
+
+#[cfg(feature = "arena")]
+pub mod arena;
+
 pub mod rest;
+
+/// BOM data exchanged with external PLM/ERP systems
+/// Importers produce it, exporters consume it
+#[derive(Debug, Clone, Default)]
+pub struct BomData {
+    pub components: Vec<Component>,
+    pub bom_items: Vec<BomItem>,
+}
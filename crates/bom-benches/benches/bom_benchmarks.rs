@@ -1,4 +1,6 @@
+use bom_cache::TieredCache;
 use bom_calc::costing::CostCalculator;
+use bom_calc::engine::BomEngine;
 use bom_calc::explosion::ExplosionCalculator;
 use bom_calc::where_used::WhereUsedAnalyzer;
 use bom_core::repository::memory::InMemoryRepository;
@@ -7,6 +9,7 @@ use bom_graph::BomGraph;
 use chrono::Utc;
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use rust_decimal::Decimal;
+use std::sync::Arc;
 
 // Helper function to create a component
 fn create_component(id: &str, description: &str, cost: i32) -> Component {
@@ -18,6 +21,7 @@ fn create_component(id: &str, description: &str, cost: i32) -> Component {
         standard_cost: Some(Decimal::from(cost)),
         lead_time_days: Some(7),
         procurement_type: ProcurementType::Make,
+        preferred_vendor: None,
         organization: "PLANT-01".to_string(),
         version: 0,
         created_at: Utc::now(),
@@ -49,7 +53,7 @@ fn create_bom_item(parent: &str, child: &str, qty: i32) -> BomItem {
 
 // Create a simple 2-level BOM structure for benchmarking
 fn create_simple_bom() -> (InMemoryRepository, ComponentId) {
-    let mut repo = InMemoryRepository::new();
+    let repo = InMemoryRepository::new();
 
     // Create components
     repo.add_component(create_component("BIKE", "Bicycle", 500));
@@ -58,9 +62,9 @@ fn create_simple_bom() -> (InMemoryRepository, ComponentId) {
     repo.add_component(create_component("WHEEL2", "Rear Wheel", 50));
 
     // Create BOM items
-    repo.add_bom_item(create_bom_item("BIKE", "FRAME", 1));
-    repo.add_bom_item(create_bom_item("BIKE", "WHEEL1", 1));
-    repo.add_bom_item(create_bom_item("BIKE", "WHEEL2", 1));
+    repo.add_bom_item(create_bom_item("BIKE", "FRAME", 1)).unwrap();
+    repo.add_bom_item(create_bom_item("BIKE", "WHEEL1", 1)).unwrap();
+    repo.add_bom_item(create_bom_item("BIKE", "WHEEL2", 1)).unwrap();
 
     (repo, ComponentId::new("BIKE"))
 }
@@ -92,7 +96,7 @@ fn create_deep_bom(levels: usize, children_per_level: usize) -> (InMemoryReposit
                 10,
             ));
 
-            repo.add_bom_item(create_bom_item(parent_id, &child_id, 1));
+            repo.add_bom_item(create_bom_item(parent_id, &child_id, 1)).unwrap();
 
             add_children(repo, &child_id, level + 1, max_levels, children_per_level, counter);
         }
@@ -142,6 +146,51 @@ fn bench_explosion(c: &mut Criterion) {
     group.finish();
 }
 
+// Benchmark explosion with precomputed node levels vs. computing them on every call
+fn bench_precomputed_levels(c: &mut Criterion) {
+    let mut group = c.benchmark_group("precomputed_levels");
+
+    for &(levels, width) in &[(2, 5), (3, 4), (4, 3)] {
+        let (repo, root_id) = create_deep_bom(levels, width);
+        let cold_graph = BomGraph::from_repository(&repo).unwrap();
+        let mut warm_graph = BomGraph::from_repository(&repo).unwrap();
+        warm_graph.precompute_levels();
+
+        group.bench_with_input(
+            BenchmarkId::new("cold_explode", format!("L{}W{}", levels, width)),
+            &levels,
+            |b, _| {
+                let calculator = ExplosionCalculator::new(&cold_graph);
+                b.iter(|| black_box(calculator.explode(&root_id, Decimal::from(10)).unwrap()))
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("precomputed_explode", format!("L{}W{}", levels, width)),
+            &levels,
+            |b, _| {
+                let calculator = ExplosionCalculator::new(&warm_graph);
+                b.iter(|| black_box(calculator.explode(&root_id, Decimal::from(10)).unwrap()))
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("precompute_then_explode", format!("L{}W{}", levels, width)),
+            &levels,
+            |b, _| {
+                b.iter(|| {
+                    let mut graph = BomGraph::from_repository(&repo).unwrap();
+                    graph.precompute_levels();
+                    let calculator = ExplosionCalculator::new(&graph);
+                    black_box(calculator.explode(&root_id, Decimal::from(10)).unwrap())
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
 // Benchmark cost calculation
 fn bench_costing(c: &mut Criterion) {
     let mut group = c.benchmark_group("costing");
@@ -174,11 +223,126 @@ fn bench_where_used(c: &mut Criterion) {
     });
 }
 
+fn bench_batch_where_used(c: &mut Criterion) {
+    let (repo, root_id) = create_deep_bom(4, 4);
+    let graph = BomGraph::from_component(&repo, &root_id, None).unwrap();
+    let analyzer = WhereUsedAnalyzer::new(&graph);
+
+    let component_ids: Vec<ComponentId> = (1..=50).map(|i| ComponentId::new(format!("C{:06}", i))).collect();
+
+    let mut group = c.benchmark_group("batch_where_used");
+    group.bench_function("batch", |b| {
+        b.iter(|| black_box(analyzer.analyze_batch(&component_ids).unwrap()))
+    });
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            for id in &component_ids {
+                black_box(analyzer.analyze(id).unwrap());
+            }
+        })
+    });
+    group.finish();
+}
+
+// Benchmark 8 threads exploding in parallel: costs looked up in the shared cache after
+// every explosion vs. collected per thread and written back once per explosion
+fn bench_thread_local_cache(c: &mut Criterion) {
+    const THREADS: usize = 8;
+    const EXPLOSIONS_PER_THREAD: usize = 20;
+
+    let (repo, root_id) = create_deep_bom(3, 4);
+    let cache = Arc::new(TieredCache::memory_only());
+
+    let mut shared = BomEngine::new(repo.clone()).unwrap();
+    shared.calculate_cost_incremental(&[]).unwrap();
+    for (id, cost) in shared.calculate_all_costs().unwrap() {
+        cache.put_cost(id, cost, ComponentType::SemiFinished);
+    }
+
+    let mut local = BomEngine::new(repo)
+        .unwrap()
+        .with_cost_cache(cache.clone())
+        .with_thread_local_cache();
+    local.calculate_cost_incremental(&[]).unwrap();
+
+    let mut group = c.benchmark_group("thread_local_cache");
+    group.bench_function("shared_cache", |b| {
+        b.iter(|| {
+            std::thread::scope(|scope| {
+                for _ in 0..THREADS {
+                    scope.spawn(|| {
+                        for _ in 0..EXPLOSIONS_PER_THREAD {
+                            let result = shared.explode(&root_id, Decimal::from(10)).unwrap();
+                            for item in &result.items {
+                                black_box(cache.get_cost(&item.component_id));
+                            }
+                        }
+                    });
+                }
+            })
+        })
+    });
+    group.bench_function("thread_local", |b| {
+        b.iter(|| {
+            std::thread::scope(|scope| {
+                for _ in 0..THREADS {
+                    scope.spawn(|| {
+                        for _ in 0..EXPLOSIONS_PER_THREAD {
+                            black_box(local.explode(&root_id, Decimal::from(10)).unwrap());
+                        }
+                    });
+                }
+            })
+        })
+    });
+    group.finish();
+}
+
+// Benchmark exploding 20 separate products one after another vs. with `explode_batch`
+fn bench_explode_batch(c: &mut Criterion) {
+    // PRODUCT-00..19 -> 10 assemblies -> 20 parts each, nothing shared between products
+    let repo = InMemoryRepository::new();
+    let mut requests = Vec::new();
+    for p in 0..20 {
+        let product = format!("PRODUCT-{:02}", p);
+        repo.add_component(create_component(&product, "Product", 0));
+        for a in 0..10 {
+            let assembly = format!("ASSY-{:02}-{:02}", p, a);
+            repo.add_component(create_component(&assembly, "Assembly", 0));
+            repo.add_bom_item(create_bom_item(&product, &assembly, 1)).unwrap();
+            for part in 0..20 {
+                let part = format!("PART-{:02}-{:02}-{:02}", p, a, part);
+                repo.add_component(create_component(&part, "Part", 10));
+                repo.add_bom_item(create_bom_item(&assembly, &part, 2)).unwrap();
+            }
+        }
+        requests.push((ComponentId::new(product), Decimal::from(10)));
+    }
+    let engine = BomEngine::new(repo).unwrap();
+
+    let mut group = c.benchmark_group("explode_batch");
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            for (root, quantity) in &requests {
+                black_box(engine.explode(root, *quantity).unwrap());
+            }
+        })
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| black_box(engine.explode_batch(&requests).unwrap()))
+    });
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_graph_construction,
     bench_explosion,
+    bench_precomputed_levels,
     bench_costing,
-    bench_where_used
+    bench_where_used,
+    bench_batch_where_used,
+    bench_thread_local_cache,
+    bench_explode_batch
 );
 criterion_main!(benches);
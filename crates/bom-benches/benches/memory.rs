@@ -0,0 +1,245 @@
+use bom_calc::engine::BomEngine;
+use bom_core::repository::memory::InMemoryRepository;
+use bom_core::{BomItem, Component, ComponentId, ComponentType, ProcurementType};
+use bom_graph::BomGraph;
+use chrono::Utc;
+use criterion::measurement::{Measurement, ValueFormatter};
+use criterion::{black_box, criterion_group, criterion_main, Criterion, SamplingMode, Throughput};
+use rust_decimal::Decimal;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+// Own bench target so the counting allocator does not slow down the other benchmarks
+#[global_allocator]
+static ALLOCATOR: PeakAllocator = PeakAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+// System allocator that tracks the peak number of live heap bytes
+struct PeakAllocator;
+
+unsafe impl GlobalAlloc for PeakAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(allocated, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+// Peak heap bytes allocated while running `f`, above what was live before
+fn peak_bytes<T>(f: impl FnOnce() -> T) -> usize {
+    let baseline = ALLOCATED.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    black_box(f());
+    PEAK.load(Ordering::Relaxed) - baseline
+}
+
+// Heap bytes a routine leaves allocated, as a criterion measurement
+// Use it with `iter_with_large_drop`, which drops the outputs after measuring
+struct RetainedBytes;
+
+impl Measurement for RetainedBytes {
+    type Intermediate = usize;
+    type Value = usize;
+
+    fn start(&self) -> usize {
+        ALLOCATED.load(Ordering::Relaxed)
+    }
+
+    fn end(&self, start: usize) -> usize {
+        ALLOCATED.load(Ordering::Relaxed).saturating_sub(start)
+    }
+
+    fn add(&self, v1: &usize, v2: &usize) -> usize {
+        v1 + v2
+    }
+
+    fn zero(&self) -> usize {
+        0
+    }
+
+    fn to_f64(&self, value: &usize) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        &BytesFormatter
+    }
+}
+
+struct BytesFormatter;
+
+impl ValueFormatter for BytesFormatter {
+    fn scale_values(&self, typical_value: f64, values: &mut [f64]) -> &'static str {
+        let (factor, unit) = if typical_value >= 1024.0 * 1024.0 {
+            (1024.0 * 1024.0, "MiB")
+        } else if typical_value >= 1024.0 {
+            (1024.0, "KiB")
+        } else {
+            (1.0, "B")
+        };
+        for value in values {
+            *value /= factor;
+        }
+        unit
+    }
+
+    fn scale_throughputs(&self, _typical_value: f64, _throughput: &Throughput, _values: &mut [f64]) -> &'static str {
+        "B"
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        "B"
+    }
+}
+
+fn create_component(id: &str) -> Component {
+    Component {
+        id: ComponentId::new(id),
+        description: id.to_string(),
+        component_type: ComponentType::FinishedProduct,
+        uom: "EA".to_string(),
+        standard_cost: Some(Decimal::from(10)),
+        lead_time_days: Some(7),
+        procurement_type: ProcurementType::Make,
+        preferred_vendor: None,
+        organization: "PLANT-01".to_string(),
+        version: 0,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+fn create_bom_item(parent: &str, child: &str) -> BomItem {
+    BomItem {
+        id: uuid::Uuid::new_v4(),
+        parent_id: ComponentId::new(parent),
+        child_id: ComponentId::new(child),
+        quantity: Decimal::ONE,
+        scrap_factor: Decimal::ZERO,
+        sequence: 10,
+        operation_sequence: None,
+        is_phantom: false,
+        effective_from: None,
+        effective_to: None,
+        alternative_group: None,
+        alternative_priority: None,
+        reference_designator: None,
+        position: None,
+        notes: None,
+        version: 0,
+    }
+}
+
+// ROOT -> `levels` layers of `width` parts, every part used by every part of the layer above:
+// few components but width^levels paths, which `explode` keeps and the streaming explosion does not
+fn create_shared_bom(levels: usize, width: usize) -> (InMemoryRepository, ComponentId) {
+    let repo = InMemoryRepository::new();
+    repo.add_component(create_component("ROOT"));
+
+    let mut parents = vec!["ROOT".to_string()];
+    for level in 0..levels {
+        let layer: Vec<String> = (0..width).map(|i| format!("L{}-P{:03}", level, i)).collect();
+        for child in &layer {
+            repo.add_component(create_component(child));
+            for parent in &parents {
+                repo.add_bom_item(create_bom_item(parent, child)).unwrap();
+            }
+        }
+        parents = layer;
+    }
+
+    (repo, ComponentId::new("ROOT"))
+}
+
+fn bench_bulk_explode(c: &mut Criterion) {
+    let (repo, root) = create_shared_bom(5, 8);
+    let engine = BomEngine::new(repo).unwrap();
+    let quantity = Decimal::from(100);
+
+    let in_memory = peak_bytes(|| {
+        let explosion = engine.explode(&root, quantity).unwrap();
+        explosion.to_bom_csv().len()
+    });
+    let streaming = peak_bytes(|| engine.bulk_explode_to_csv(&root, quantity, std::io::sink()).unwrap());
+    println!(
+        "bulk_explode: peak heap bytes explode + to_bom_csv {} vs bulk_explode_to_csv {}",
+        in_memory, streaming
+    );
+
+    let mut group = c.benchmark_group("bulk_explode");
+    group.bench_function("explode_to_bom_csv", |b| {
+        b.iter(|| black_box(engine.explode(&root, quantity).unwrap().to_bom_csv()))
+    });
+    group.bench_function("bulk_explode_to_csv", |b| {
+        b.iter(|| black_box(engine.bulk_explode_to_csv(&root, quantity, std::io::sink()).unwrap()))
+    });
+    group.finish();
+}
+
+// ROOT -> 100 assemblies -> 99 parts each: 10,001 nodes, every ID string allocated separately
+// as items loaded from a repository are
+fn build_10k_graph() -> BomGraph {
+    let mut graph = BomGraph::new();
+    for a in 0..100 {
+        let assembly = format!("ASSY-{:05}", a);
+        graph.add_bom_item(create_bom_item("ROOT", &assembly)).unwrap();
+        for p in 0..99 {
+            graph
+                .add_bom_item(create_bom_item(&assembly, &format!("PART-{:07}", a * 99 + p)))
+                .unwrap();
+        }
+    }
+    graph
+}
+
+// Heap bytes held by a 10k-node graph with interned component IDs vs. the same graph with
+// a separate copy of the ID in every node and BOM item
+fn bench_graph_memory(c: &mut Criterion<RetainedBytes>) {
+    let mut group = c.benchmark_group("graph_memory_10k");
+    // Criterion budgets warm-up and measurement in the measurement's unit, here bytes:
+    // a single warm-up graph and a few graphs per sample. Every sample is the same, so
+    // plots (which need some spread) are off
+    group
+        .sampling_mode(SamplingMode::Flat)
+        .sample_size(10)
+        .warm_up_time(Duration::from_nanos(1))
+        .measurement_time(Duration::from_nanos(400_000_000));
+
+    group.bench_function("interned_ids", |b| b.iter_with_large_drop(build_10k_graph));
+    group.bench_function("separate_ids", |b| {
+        b.iter_with_large_drop(|| {
+            let mut graph = build_10k_graph();
+            let arena = graph.arena_mut();
+            for index in 0..arena.nodes().len() {
+                let node = arena.node_mut(bom_graph::NodeIndex(index)).unwrap();
+                node.component_id = ComponentId::new(node.component_id.as_str());
+            }
+            for index in 0..arena.edges().len() {
+                let item = &mut arena.edge_mut(bom_graph::EdgeIndex(index)).unwrap().bom_item;
+                item.parent_id = ComponentId::new(item.parent_id.as_str());
+                item.child_id = ComponentId::new(item.child_id.as_str());
+            }
+            graph
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_bulk_explode);
+criterion_group! {
+    name = graph_memory;
+    config = Criterion::default().with_measurement(RetainedBytes).without_plots();
+    targets = bench_graph_memory
+}
+criterion_main!(benches, graph_memory);
@@ -3,20 +3,54 @@
 //! Provides two-tier caching for BOM calculations:
 //! - L1: Fast in-memory cache using moka
 //! - L2: Persistent cache using redb
+//! - L3: Shared cache using Redis (optional, `redis` feature)
+//!
+//! Lookups can be exported as Prometheus metrics (optional, `metrics` feature)
+
+// redb's error types are large; boxing every variant would only add noise here
+#![allow(clippy::result_large_err)]
 
 pub mod memory_cache;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod persistent_cache;
+#[cfg(feature = "redis")]
+pub mod redis_cache;
 
 pub use memory_cache::*;
+#[cfg(feature = "metrics")]
+pub use metrics::*;
 pub use persistent_cache::*;
+#[cfg(feature = "redis")]
+pub use redis_cache::*;
 
-use bom_core::{ComponentId, CostBreakdown, ExplosionResult};
+use bom_core::{ComponentId, ComponentType, CostBreakdown, CostInvalidation, ExplosionResult};
 use rust_decimal::Decimal;
 
-/// Combined cache with L1 (memory) and L2 (persistent) tiers
+/// Combined cache with L1 (memory), L2 (persistent) and optional L3 (Redis) tiers
 pub struct TieredCache {
     memory: MemoryCache,
     persistent: Option<PersistentCache>,
+    #[cfg(feature = "redis")]
+    redis: Option<RedisCache>,
+    #[cfg(feature = "metrics")]
+    metrics: Option<metrics::CacheMetrics>,
+}
+
+/// Kind of value a cache lookup was for
+#[derive(Debug, Clone, Copy)]
+enum CacheKind {
+    Cost,
+    Explosion,
+}
+
+/// Tier that answered a cache lookup
+#[derive(Debug, Clone, Copy)]
+enum CacheTier {
+    L1,
+    L2,
+    #[cfg(feature = "redis")]
+    L3,
 }
 
 impl TieredCache {
@@ -25,6 +59,10 @@ impl TieredCache {
         Self {
             memory: MemoryCache::new(),
             persistent: None,
+            #[cfg(feature = "redis")]
+            redis: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
         }
     }
 
@@ -36,44 +74,134 @@ impl TieredCache {
         Ok(Self {
             memory: MemoryCache::with_config(memory_config),
             persistent: Some(PersistentCache::new(persistent_path)?),
+            #[cfg(feature = "redis")]
+            redis: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
         })
     }
 
+    /// Create a tiered cache with memory cache and a shared Redis cache
+    #[cfg(feature = "redis")]
+    pub fn with_redis(redis_url: &str) -> Result<Self, RedisCacheError> {
+        Ok(Self::memory_only().with_redis_cache(RedisCache::new(redis_url)?))
+    }
+
+    /// Add a Redis cache as L3 (e.g. on top of `with_persistent`)
+    #[cfg(feature = "redis")]
+    pub fn with_redis_cache(mut self, redis: RedisCache) -> Self {
+        self.redis = Some(redis);
+        self
+    }
+
+    /// Export lookup metrics (hits, misses, hit rates, entry counts) through `registry`
+    /// Fails if metrics with the same names are already registered
+    #[cfg(feature = "metrics")]
+    pub fn with_prometheus_metrics(mut self, registry: &prometheus::Registry) -> prometheus::Result<Self> {
+        self.metrics = Some(metrics::CacheMetrics::register(registry)?);
+        Ok(self)
+    }
+
+    /// Metrics of the registry passed to `with_prometheus_metrics`, in the Prometheus text
+    /// format (for a `/metrics` endpoint). Empty if metrics are not enabled
+    #[cfg(feature = "metrics")]
+    pub fn metrics_text(&self) -> String {
+        let Some(metrics) = &self.metrics else {
+            return String::new();
+        };
+        self.memory.run_maintenance();
+        let stats = self.memory.stats();
+        metrics.set_entry_counts(stats.cost_entry_count, stats.explosion_entry_count);
+        metrics.text()
+    }
+
     /// Get cost with L1/L2 cache lookup
     pub fn get_cost(&self, component_id: &ComponentId) -> Option<CostBreakdown> {
         // Try L1 first
         if let Some(cost) = self.memory.get_cost(component_id) {
+            self.record_lookup(CacheKind::Cost, Some(CacheTier::L1));
             return Some(cost);
         }
 
         // Try L2 if available
         if let Some(persistent) = &self.persistent {
             if let Ok(Some(cost)) = persistent.get_cost(component_id) {
-                // Promote to L1
-                self.memory.put_cost(component_id.clone(), cost.clone());
+                // Promote to L1 (type unknown here, so the global TTL applies)
+                self.memory.put_cost_with_default_ttl(component_id.clone(), cost.clone());
+                self.record_lookup(CacheKind::Cost, Some(CacheTier::L2));
                 return Some(cost);
             }
         }
 
+        // Try L3 if available
+        #[cfg(feature = "redis")]
+        if let Some(redis) = &self.redis {
+            if let Ok(Some(cost)) = redis.get_cost(component_id) {
+                // Promote to L2 and L1 (global TTL, as above)
+                if let Some(persistent) = &self.persistent {
+                    let _ = persistent.put_cost(component_id, &cost);
+                }
+                self.memory.put_cost_with_default_ttl(component_id.clone(), cost.clone());
+                self.record_lookup(CacheKind::Cost, Some(CacheTier::L3));
+                return Some(cost);
+            }
+        }
+
+        self.record_lookup(CacheKind::Cost, None);
         None
     }
 
-    /// Put cost into both L1 and L2 caches
-    pub fn put_cost(&self, component_id: ComponentId, cost: CostBreakdown) {
-        self.memory.put_cost(component_id.clone(), cost.clone());
+    /// Put cost into all cache tiers
+    /// `component_type` selects the L1 TTL (see `CacheConfig::ttl_by_component_type`)
+    pub fn put_cost(&self, component_id: ComponentId, cost: CostBreakdown, component_type: ComponentType) {
+        self.memory.put_cost(component_id.clone(), cost.clone(), component_type);
 
         if let Some(persistent) = &self.persistent {
             let _ = persistent.put_cost(&component_id, &cost);
         }
+
+        #[cfg(feature = "redis")]
+        if let Some(redis) = &self.redis {
+            let _ = redis.put_cost(&component_id, &cost);
+        }
     }
 
-    /// Invalidate cost in both caches
+    /// Put several costs into all cache tiers
+    /// The component types are unknown here, so L1 uses the global TTL
+    pub fn put_cost_batch(&self, costs: &[(ComponentId, CostBreakdown)]) {
+        for (component_id, cost) in costs {
+            self.memory.put_cost_with_default_ttl(component_id.clone(), cost.clone());
+
+            if let Some(persistent) = &self.persistent {
+                let _ = persistent.put_cost(component_id, cost);
+            }
+
+            #[cfg(feature = "redis")]
+            if let Some(redis) = &self.redis {
+                let _ = redis.put_cost(component_id, cost);
+            }
+        }
+    }
+
+    /// Invalidate cost in all cache tiers
     pub fn invalidate_cost(&self, component_id: &ComponentId) {
         self.memory.invalidate_cost(component_id);
 
         if let Some(persistent) = &self.persistent {
             let _ = persistent.remove_cost(component_id);
         }
+
+        #[cfg(feature = "redis")]
+        if let Some(redis) = &self.redis {
+            let _ = redis.remove_cost(component_id);
+        }
+    }
+
+    /// Invalidate the costs of several components in all cache tiers
+    pub fn invalidate_cost_batch(&self, component_ids: &[ComponentId]) {
+        for component_id in component_ids {
+            self.invalidate_cost(component_id);
+        }
     }
 
     /// Get explosion with L1/L2 cache lookup
@@ -84,6 +212,7 @@ impl TieredCache {
     ) -> Option<ExplosionResult> {
         // Try L1 first
         if let Some(result) = self.memory.get_explosion(component_id, quantity) {
+            self.record_lookup(CacheKind::Explosion, Some(CacheTier::L1));
             return Some(result);
         }
 
@@ -93,14 +222,31 @@ impl TieredCache {
                 // Promote to L1
                 self.memory
                     .put_explosion(component_id.clone(), *quantity, result.clone());
+                self.record_lookup(CacheKind::Explosion, Some(CacheTier::L2));
                 return Some(result);
             }
         }
 
+        // Try L3 if available
+        #[cfg(feature = "redis")]
+        if let Some(redis) = &self.redis {
+            if let Ok(Some(result)) = redis.get_explosion(component_id, quantity) {
+                // Promote to L2 and L1
+                if let Some(persistent) = &self.persistent {
+                    let _ = persistent.put_explosion(component_id, *quantity, &result);
+                }
+                self.memory
+                    .put_explosion(component_id.clone(), *quantity, result.clone());
+                self.record_lookup(CacheKind::Explosion, Some(CacheTier::L3));
+                return Some(result);
+            }
+        }
+
+        self.record_lookup(CacheKind::Explosion, None);
         None
     }
 
-    /// Put explosion into both L1 and L2 caches
+    /// Put explosion into all cache tiers
     pub fn put_explosion(
         &self,
         component_id: ComponentId,
@@ -113,6 +259,11 @@ impl TieredCache {
         if let Some(persistent) = &self.persistent {
             let _ = persistent.put_explosion(&component_id, quantity, &result);
         }
+
+        #[cfg(feature = "redis")]
+        if let Some(redis) = &self.redis {
+            let _ = redis.put_explosion(&component_id, quantity, &result);
+        }
     }
 
     /// Clear all caches
@@ -122,8 +273,28 @@ impl TieredCache {
         if let Some(persistent) = &self.persistent {
             let _ = persistent.clear_all();
         }
+
+        #[cfg(feature = "redis")]
+        if let Some(redis) = &self.redis {
+            let _ = redis.clear_all();
+        }
+    }
+
+    /// Count a lookup in the Prometheus metrics (if enabled)
+    #[cfg(feature = "metrics")]
+    fn record_lookup(&self, kind: CacheKind, tier: Option<CacheTier>) {
+        if let Some(metrics) = &self.metrics {
+            // Entry counts are sampled now and then; `metrics_text` refreshes them on every scrape
+            if metrics.record_lookup(kind, tier) {
+                let stats = self.memory.stats();
+                metrics.set_entry_counts(stats.cost_entry_count, stats.explosion_entry_count);
+            }
+        }
     }
 
+    #[cfg(not(feature = "metrics"))]
+    fn record_lookup(&self, _kind: CacheKind, _tier: Option<CacheTier>) {}
+
     /// Get combined cache statistics
     pub fn stats(&self) -> TieredCacheStats {
         let memory_stats = self.memory.stats();
@@ -135,12 +306,26 @@ impl TieredCache {
         TieredCacheStats {
             memory: memory_stats,
             persistent: persistent_stats,
+            #[cfg(feature = "redis")]
+            redis: self.redis.as_ref().and_then(|r| r.stats().ok()),
         }
     }
 }
 
+impl CostInvalidation for TieredCache {
+    fn invalidate_cost_batch(&self, component_ids: &[ComponentId]) {
+        TieredCache::invalidate_cost_batch(self, component_ids)
+    }
+
+    fn store_cost_batch(&self, costs: &[(ComponentId, CostBreakdown)]) {
+        TieredCache::put_cost_batch(self, costs)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TieredCacheStats {
     pub memory: CacheStats,
     pub persistent: Option<PersistentCacheStats>,
+    #[cfg(feature = "redis")]
+    pub redis: Option<RedisCacheStats>,
 }
@@ -1,7 +1,9 @@
-use bom_core::{ComponentId, CostBreakdown, ExplosionResult};
+use bom_core::{ComponentId, ComponentType, CostBreakdown, ExplosionResult};
 use moka::sync::Cache;
+use moka::Expiry;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 /// Cache key types
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
@@ -20,13 +22,39 @@ pub enum CacheKey {
 /// Provides fast access to frequently used BOM calculation results
 pub struct MemoryCache {
     /// Cost calculation cache
-    cost_cache: Cache<ComponentId, CostBreakdown>,
+    cost_cache: Cache<ComponentId, CostEntry>,
 
     /// Explosion result cache
     explosion_cache: Cache<String, ExplosionResult>,
 
     /// Configuration
-    _config: CacheConfig,
+    config: CacheConfig,
+}
+
+/// Cached cost together with the TTL chosen for it on insert
+#[derive(Clone)]
+struct CostEntry {
+    cost: CostBreakdown,
+    ttl: Duration,
+}
+
+/// Expires each cost entry after its own TTL
+struct CostExpiry;
+
+impl Expiry<ComponentId, CostEntry> for CostExpiry {
+    fn expire_after_create(&self, _key: &ComponentId, entry: &CostEntry, _created_at: Instant) -> Option<Duration> {
+        Some(entry.ttl)
+    }
+
+    fn expire_after_update(
+        &self,
+        _key: &ComponentId,
+        entry: &CostEntry,
+        _updated_at: Instant,
+        _duration_until_expiry: Option<Duration>,
+    ) -> Option<Duration> {
+        Some(entry.ttl)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -37,9 +65,18 @@ pub struct CacheConfig {
     /// Maximum number of entries in explosion cache
     pub max_explosion_entries: u64,
 
+    /// Maximum total weight of the explosion cache (alternative to `max_explosion_entries`)
+    /// Each explosion weighs `items.len() + 1`, so large BOMs are evicted before
+    /// they can starve many small results
+    pub max_explosion_weight: Option<u64>,
+
     /// Time-to-live for cache entries
     pub ttl: Duration,
 
+    /// Cost TTL per component type, overriding `ttl`
+    /// (e.g. short for raw materials whose prices change often)
+    pub ttl_by_component_type: HashMap<ComponentType, Duration>,
+
     /// Time-to-idle (evict if not accessed)
     pub tti: Duration,
 }
@@ -49,7 +86,9 @@ impl Default for CacheConfig {
         Self {
             max_cost_entries: 10_000,
             max_explosion_entries: 5_000,
+            max_explosion_weight: None,
             ttl: Duration::from_secs(3600), // 1 hour
+            ttl_by_component_type: HashMap::new(),
             tti: Duration::from_secs(1800), // 30 minutes
         }
     }
@@ -63,22 +102,33 @@ impl MemoryCache {
 
     /// Create a new memory cache with custom configuration
     pub fn with_config(config: CacheConfig) -> Self {
+        // TTL is per entry (see `CostExpiry`) so overrides may exceed the global TTL
         let cost_cache = Cache::builder()
             .max_capacity(config.max_cost_entries)
-            .time_to_live(config.ttl)
+            .expire_after(CostExpiry)
             .time_to_idle(config.tti)
             .build();
 
-        let explosion_cache = Cache::builder()
-            .max_capacity(config.max_explosion_entries)
+        let explosion_builder = Cache::builder()
             .time_to_live(config.ttl)
-            .time_to_idle(config.tti)
-            .build();
+            .time_to_idle(config.tti);
+
+        let explosion_cache = match config.max_explosion_weight {
+            Some(max_weight) => explosion_builder
+                .max_capacity(max_weight)
+                .weigher(|_key: &String, value: &ExplosionResult| {
+                    Self::explosion_weight(value)
+                })
+                .build(),
+            None => explosion_builder
+                .max_capacity(config.max_explosion_entries)
+                .build(),
+        };
 
         Self {
             cost_cache,
             explosion_cache,
-            _config: config,
+            config,
         }
     }
 
@@ -86,12 +136,20 @@ impl MemoryCache {
 
     /// Get cached cost breakdown
     pub fn get_cost(&self, component_id: &ComponentId) -> Option<CostBreakdown> {
-        self.cost_cache.get(component_id)
+        self.cost_cache.get(component_id).map(|entry| entry.cost)
     }
 
     /// Put cost breakdown into cache
-    pub fn put_cost(&self, component_id: ComponentId, cost: CostBreakdown) {
-        self.cost_cache.insert(component_id, cost);
+    /// The entry expires after the TTL configured for `component_type` (or the global TTL)
+    pub fn put_cost(&self, component_id: ComponentId, cost: CostBreakdown, component_type: ComponentType) {
+        let ttl = self.ttl_for(component_type);
+        self.cost_cache.insert(component_id, CostEntry { cost, ttl });
+    }
+
+    /// Put cost breakdown into cache with the global TTL (component type unknown)
+    pub(crate) fn put_cost_with_default_ttl(&self, component_id: ComponentId, cost: CostBreakdown) {
+        let ttl = self.config.ttl;
+        self.cost_cache.insert(component_id, CostEntry { cost, ttl });
     }
 
     /// Invalidate cost cache for a component
@@ -159,9 +217,24 @@ impl MemoryCache {
 
     // Helper methods
 
+    fn ttl_for(&self, component_type: ComponentType) -> Duration {
+        self.config
+            .ttl_by_component_type
+            .get(&component_type)
+            .copied()
+            .unwrap_or(self.config.ttl)
+    }
+
     fn make_explosion_key(component_id: &ComponentId, quantity: &rust_decimal::Decimal) -> String {
         format!("{}:{}", component_id.as_str(), quantity)
     }
+
+    /// Weight of an explosion result in the weighted explosion cache (total items + 1)
+    fn explosion_weight(result: &ExplosionResult) -> u32 {
+        u32::try_from(result.items.len())
+            .unwrap_or(u32::MAX)
+            .saturating_add(1)
+    }
 }
 
 impl Default for MemoryCache {
@@ -204,7 +277,7 @@ mod tests {
         assert!(cache.get_cost(&component_id).is_none());
 
         // Put and get
-        cache.put_cost(component_id.clone(), cost.clone());
+        cache.put_cost(component_id.clone(), cost.clone(), ComponentType::FinishedProduct);
         let cached = cache.get_cost(&component_id).unwrap();
         assert_eq!(cached.total_cost, Decimal::from(100));
 
@@ -253,7 +326,7 @@ mod tests {
             calculated_at: Utc::now(),
         };
 
-        cache.put_cost(component_id.clone(), cost);
+        cache.put_cost(component_id.clone(), cost, ComponentType::FinishedProduct);
 
         // Run pending tasks to ensure cache is updated
         cache.run_maintenance();
@@ -287,10 +360,89 @@ mod tests {
             calculated_at: Utc::now(),
         };
 
-        cache.put_cost(component_id.clone(), cost);
+        cache.put_cost(component_id.clone(), cost, ComponentType::FinishedProduct);
         assert!(cache.get_cost(&component_id).is_some());
 
         cache.clear_all();
         assert!(cache.get_cost(&component_id).is_none());
     }
+
+    fn create_explosion(component_id: &ComponentId, item_count: usize) -> ExplosionResult {
+        let items = (0..item_count)
+            .map(|i| bom_core::ExplosionItem {
+                component_id: ComponentId::new(format!("{}-{}", component_id.as_str(), i)),
+                total_quantity: Decimal::ONE,
+                level: 1,
+                paths: vec![],
+                is_phantom: false,
+            })
+            .collect();
+
+        ExplosionResult {
+            root_component: component_id.clone(),
+            items,
+            unique_component_count: item_count,
+            max_depth: 1,
+            calculated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_weighted_explosion_eviction() {
+        let cache = MemoryCache::with_config(CacheConfig {
+            max_explosion_weight: Some(10_050),
+            ..CacheConfig::default()
+        });
+
+        let large_id = ComponentId::new("LARGE");
+        let quantity = Decimal::ONE;
+        cache.put_explosion(large_id.clone(), quantity, create_explosion(&large_id, 10_000));
+        cache.run_maintenance();
+
+        // Many small explosions (weight 2 each) push the cache over its weight limit
+        let small_ids: Vec<ComponentId> = (0..50)
+            .map(|i| ComponentId::new(format!("SMALL-{}", i)))
+            .collect();
+        for id in &small_ids {
+            cache.put_explosion(id.clone(), quantity, create_explosion(id, 1));
+            cache.get_explosion(id, &quantity);
+        }
+        cache.run_maintenance();
+
+        assert!(cache.get_explosion(&large_id, &quantity).is_none());
+        assert!(small_ids
+            .iter()
+            .all(|id| cache.get_explosion(id, &quantity).is_some()));
+    }
+
+    #[test]
+    fn test_ttl_by_component_type() {
+        let cache = MemoryCache::with_config(CacheConfig {
+            ttl_by_component_type: HashMap::from([(ComponentType::RawMaterial, Duration::from_millis(100))]),
+            ..CacheConfig::default()
+        });
+
+        let cost = |id: &ComponentId| CostBreakdown {
+            component_id: id.clone(),
+            material_cost: Decimal::from(100),
+            labor_cost: Decimal::ZERO,
+            overhead_cost: Decimal::ZERO,
+            subcontract_cost: Decimal::ZERO,
+            total_cost: Decimal::from(100),
+            calculated_at: Utc::now(),
+        };
+
+        let raw = ComponentId::new("RAW-001");
+        let product = ComponentId::new("FG-001");
+        cache.put_cost(raw.clone(), cost(&raw), ComponentType::RawMaterial);
+        cache.put_cost(product.clone(), cost(&product), ComponentType::FinishedProduct);
+        assert!(cache.get_cost(&raw).is_some());
+
+        std::thread::sleep(Duration::from_millis(300));
+        cache.run_maintenance();
+
+        // Raw material expired after its short TTL, the product uses the global 1h TTL
+        assert!(cache.get_cost(&raw).is_none());
+        assert!(cache.get_cost(&product).is_some());
+    }
 }
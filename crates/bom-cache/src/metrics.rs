@@ -0,0 +1,236 @@
+//! Prometheus metrics for `TieredCache` (`metrics` feature)
+
+use crate::{CacheKind, CacheStats, CacheTier};
+use prometheus::core::Collector;
+use prometheus::proto::MetricFamily;
+use prometheus::{Encoder, Gauge, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub const HITS_TOTAL: &str = "bom_cache_hits_total";
+pub const MISSES_TOTAL: &str = "bom_cache_misses_total";
+pub const L1_HIT_RATE: &str = "bom_cache_l1_hit_rate";
+pub const L2_HIT_RATE: &str = "bom_cache_l2_hit_rate";
+pub const ENTRY_COUNT: &str = "bom_cache_entry_count";
+
+/// Label separating cost and explosion lookups
+const KIND_LABEL: &str = "kind";
+
+/// Entry counts are read from the memory cache every this many lookups (and on every scrape)
+const ENTRY_COUNT_SAMPLE_INTERVAL: u64 = 1024;
+
+impl CacheKind {
+    fn label(self) -> &'static str {
+        match self {
+            CacheKind::Cost => "cost",
+            CacheKind::Explosion => "explosion",
+        }
+    }
+}
+
+/// Cache metrics registered in a Prometheus registry
+pub(crate) struct CacheMetrics {
+    registry: Registry,
+    hits: IntCounterVec,
+    misses: IntCounterVec,
+    l1_hit_rate: Gauge,
+    l2_hit_rate: Gauge,
+    entry_count: IntGaugeVec,
+    lookups: AtomicU64,
+    l1_hits: AtomicU64,
+    l2_hits: AtomicU64,
+}
+
+impl CacheMetrics {
+    /// Create the metrics and register them in `registry`
+    /// Either all metrics are registered or, on error, none of them
+    pub(crate) fn register(registry: &Registry) -> prometheus::Result<Self> {
+        let metrics = Self {
+            registry: registry.clone(),
+            hits: IntCounterVec::new(Opts::new(HITS_TOTAL, "Cache lookups answered by any tier"), &[KIND_LABEL])?,
+            misses: IntCounterVec::new(Opts::new(MISSES_TOTAL, "Cache lookups not found in any tier"), &[KIND_LABEL])?,
+            l1_hit_rate: Gauge::new(L1_HIT_RATE, "Share of lookups answered by the memory cache")?,
+            l2_hit_rate: Gauge::new(L2_HIT_RATE, "Share of lookups answered by the persistent cache")?,
+            entry_count: IntGaugeVec::new(Opts::new(ENTRY_COUNT, "Entries in the memory cache"), &[KIND_LABEL])?,
+            lookups: AtomicU64::new(0),
+            l1_hits: AtomicU64::new(0),
+            l2_hits: AtomicU64::new(0),
+        };
+
+        for (index, collector) in metrics.collectors().into_iter().enumerate() {
+            if let Err(e) = registry.register(collector) {
+                for registered in metrics.collectors().into_iter().take(index) {
+                    let _ = registry.unregister(registered);
+                }
+                return Err(e);
+            }
+        }
+
+        Ok(metrics)
+    }
+
+    fn collectors(&self) -> Vec<Box<dyn Collector>> {
+        vec![
+            Box::new(self.hits.clone()),
+            Box::new(self.misses.clone()),
+            Box::new(self.l1_hit_rate.clone()),
+            Box::new(self.l2_hit_rate.clone()),
+            Box::new(self.entry_count.clone()),
+        ]
+    }
+
+    /// Count a lookup answered by `tier` (`None` = miss)
+    /// Returns whether the entry counts are due to be sampled (see `set_entry_counts`)
+    pub(crate) fn record_lookup(&self, kind: CacheKind, tier: Option<CacheTier>) -> bool {
+        let lookups = self.lookups.fetch_add(1, Ordering::Relaxed) + 1;
+
+        match tier {
+            Some(tier) => {
+                self.hits.with_label_values(&[kind.label()]).inc();
+                match tier {
+                    CacheTier::L1 => self.l1_hits.fetch_add(1, Ordering::Relaxed),
+                    CacheTier::L2 => self.l2_hits.fetch_add(1, Ordering::Relaxed),
+                    #[cfg(feature = "redis")]
+                    CacheTier::L3 => 0,
+                };
+            }
+            None => self.misses.with_label_values(&[kind.label()]).inc(),
+        }
+
+        self.l1_hit_rate.set(self.l1_hits.load(Ordering::Relaxed) as f64 / lookups as f64);
+        self.l2_hit_rate.set(self.l2_hits.load(Ordering::Relaxed) as f64 / lookups as f64);
+
+        lookups.is_multiple_of(ENTRY_COUNT_SAMPLE_INTERVAL)
+    }
+
+    /// Update the memory cache entry counts
+    pub(crate) fn set_entry_counts(&self, cost: u64, explosion: u64) {
+        let as_gauge = |count: u64| i64::try_from(count).unwrap_or(i64::MAX);
+        self.entry_count
+            .with_label_values(&[CacheKind::Cost.label()])
+            .set(as_gauge(cost));
+        self.entry_count
+            .with_label_values(&[CacheKind::Explosion.label()])
+            .set(as_gauge(explosion));
+    }
+
+    /// All metrics of the registry in the Prometheus text format
+    pub(crate) fn text(&self) -> String {
+        let mut buffer = Vec::new();
+        // Encoding gathered families into a Vec cannot fail
+        let _ = TextEncoder::new().encode(&self.registry.gather(), &mut buffer);
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+impl CacheStats {
+    /// Read cache statistics from metrics registered with `TieredCache::with_prometheus_metrics`
+    /// Missing metrics count as zero
+    pub fn from_prometheus(registry: &Registry) -> CacheStats {
+        let families = registry.gather();
+        let hit_rate = |kind: CacheKind| {
+            let hits = metric_value(&families, HITS_TOTAL, kind);
+            let lookups = hits + metric_value(&families, MISSES_TOTAL, kind);
+            if lookups > 0.0 {
+                hits / lookups
+            } else {
+                0.0
+            }
+        };
+
+        CacheStats {
+            cost_entry_count: metric_value(&families, ENTRY_COUNT, CacheKind::Cost) as u64,
+            cost_hit_rate: hit_rate(CacheKind::Cost),
+            explosion_entry_count: metric_value(&families, ENTRY_COUNT, CacheKind::Explosion) as u64,
+            explosion_hit_rate: hit_rate(CacheKind::Explosion),
+        }
+    }
+}
+
+/// Value of the counter or gauge `name` labelled with `kind`
+fn metric_value(families: &[MetricFamily], name: &str, kind: CacheKind) -> f64 {
+    families
+        .iter()
+        .filter(|family| family.get_name() == name)
+        .flat_map(|family| family.get_metric())
+        .filter(|metric| {
+            metric
+                .get_label()
+                .iter()
+                .any(|label| label.get_name() == KIND_LABEL && label.get_value() == kind.label())
+        })
+        .map(|metric| metric.get_counter().get_value() + metric.get_gauge().get_value())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ENTRY_COUNT, KIND_LABEL};
+    use crate::{CacheStats, TieredCache};
+    use bom_core::{ComponentId, ComponentType, CostBreakdown};
+    use chrono::Utc;
+    use prometheus::{IntGaugeVec, Opts};
+    use rust_decimal::Decimal;
+
+    /// Value of a sample line `name{labels} value` in the text output
+    fn sample(text: &str, series: &str) -> f64 {
+        text.lines()
+            .find_map(|line| line.strip_prefix(series)?.strip_prefix(' '))
+            .unwrap_or_else(|| panic!("{} not in output:\n{}", series, text))
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_prometheus_metrics() {
+        let registry = prometheus::Registry::new();
+        let cache = TieredCache::memory_only().with_prometheus_metrics(&registry).unwrap();
+
+        let component_id = ComponentId::new("TEST-001");
+        let cost = CostBreakdown {
+            component_id: component_id.clone(),
+            material_cost: Decimal::from(100),
+            labor_cost: Decimal::ZERO,
+            overhead_cost: Decimal::ZERO,
+            subcontract_cost: Decimal::ZERO,
+            total_cost: Decimal::from(100),
+            calculated_at: Utc::now(),
+        };
+
+        // One miss, then three hits
+        assert!(cache.get_cost(&component_id).is_none());
+        cache.put_cost(component_id.clone(), cost, ComponentType::RawMaterial);
+        for _ in 0..3 {
+            assert!(cache.get_cost(&component_id).is_some());
+        }
+        assert!(cache.get_explosion(&component_id, &Decimal::ONE).is_none());
+
+        let text = cache.metrics_text();
+        assert_eq!(sample(&text, r#"bom_cache_hits_total{kind="cost"}"#), 3.0);
+        assert_eq!(sample(&text, r#"bom_cache_misses_total{kind="cost"}"#), 1.0);
+        assert_eq!(sample(&text, r#"bom_cache_misses_total{kind="explosion"}"#), 1.0);
+        assert_eq!(sample(&text, "bom_cache_l1_hit_rate"), 0.6);
+        assert_eq!(sample(&text, "bom_cache_l2_hit_rate"), 0.0);
+        assert_eq!(sample(&text, r#"bom_cache_entry_count{kind="cost"}"#), 1.0);
+
+        let stats = CacheStats::from_prometheus(&registry);
+        assert_eq!(stats.cost_hit_rate, 0.75);
+        assert_eq!(stats.explosion_hit_rate, 0.0);
+        assert_eq!(stats.cost_entry_count, 1);
+
+        // Names are taken
+        assert!(TieredCache::memory_only().with_prometheus_metrics(&registry).is_err());
+    }
+
+    #[test]
+    fn test_failed_registration_registers_nothing() {
+        // Only the last metric's name is taken
+        let registry = prometheus::Registry::new();
+        let taken = IntGaugeVec::new(Opts::new(ENTRY_COUNT, "Entries in the memory cache"), &[KIND_LABEL]).unwrap();
+        registry.register(Box::new(taken.clone())).unwrap();
+        assert!(TieredCache::memory_only().with_prometheus_metrics(&registry).is_err());
+
+        // The metrics registered before the failure were removed again
+        registry.unregister(Box::new(taken)).unwrap();
+        TieredCache::memory_only().with_prometheus_metrics(&registry).unwrap();
+    }
+}
@@ -1,9 +1,14 @@
-use bom_core::{ComponentId, CostBreakdown, ExplosionResult};
-use redb::{Database, ReadableTable, ReadableTableMetadata, TableDefinition};
+use bom_core::{AuditEntry, AuditLog, BomError, BomItem, Component, ComponentId, CostBreakdown, ExplosionResult};
+use chrono::{DateTime, Utc};
+use redb::{Database, ReadableTable, ReadableTableMetadata, TableDefinition, WriteTransaction};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
 use std::path::Path;
 
 /// Persistent cache using redb
-/// Survives application restarts
+/// Survives application restarts. Can also store the BOM data itself
+/// (components and BOM items) so it does not have to be re-imported,
+/// and an audit log of changes made to it
 pub struct PersistentCache {
     db: Database,
 }
@@ -11,36 +16,98 @@ pub struct PersistentCache {
 // Define table schemas
 const COST_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("cost_cache");
 const EXPLOSION_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("explosion_cache");
+const COMPONENT_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("components");
+const BOM_ITEM_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("bom_items");
+// Keyed by timestamp in nanoseconds (bumped by 1 when two entries share a timestamp)
+const BOM_AUDIT_TABLE: TableDefinition<i64, &[u8]> = TableDefinition::new("bom_audit");
+const META_TABLE: TableDefinition<&str, u32> = TableDefinition::new("meta");
+const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// One schema upgrade step; `MIGRATIONS[n]` takes the schema from version `n` to `n + 1`
+pub type Migration = fn(&WriteTransaction) -> Result<(), PersistentCacheError>;
+
+/// All schema upgrades in order. Append a migration whenever the table layout or the
+/// stored serialization changes; never edit one that has shipped
+const MIGRATIONS: &[Migration] = &[create_initial_tables];
+
+/// Schema version written by this build
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// Version 1: the cost, explosion, BOM data and audit tables
+fn create_initial_tables(write_txn: &WriteTransaction) -> Result<(), PersistentCacheError> {
+    write_txn.open_table(COST_TABLE)?;
+    write_txn.open_table(EXPLOSION_TABLE)?;
+    write_txn.open_table(COMPONENT_TABLE)?;
+    write_txn.open_table(BOM_ITEM_TABLE)?;
+    write_txn.open_table(BOM_AUDIT_TABLE)?;
+    Ok(())
+}
 
 impl PersistentCache {
     /// Create or open a persistent cache at the given path
+    /// Databases written by an older version are migrated to `SCHEMA_VERSION`
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, PersistentCacheError> {
-        let db = Database::create(path)?;
-
-        // Initialize tables
-        let write_txn = db.begin_write()?;
-        {
-            let _ = write_txn.open_table(COST_TABLE)?;
-            let _ = write_txn.open_table(EXPLOSION_TABLE)?;
-        }
-        write_txn.commit()?;
-
-        Ok(Self { db })
+        Self::open(Database::create(path)?, MIGRATIONS)
     }
 
     /// Create an in-memory persistent cache (for testing)
     pub fn in_memory() -> Result<Self, PersistentCacheError> {
         let db = Database::builder().create_with_backend(redb::backends::InMemoryBackend::new())?;
+        Self::open(db, MIGRATIONS)
+    }
 
-        // Initialize tables
-        let write_txn = db.begin_write()?;
-        {
-            let _ = write_txn.open_table(COST_TABLE)?;
-            let _ = write_txn.open_table(EXPLOSION_TABLE)?;
+    /// Bring `db` up to the last of `migrations`
+    fn open(db: Database, migrations: &[Migration]) -> Result<Self, PersistentCacheError> {
+        let cache = Self { db };
+        let current = cache.schema_version()?;
+        cache.run_migrations(migrations, current, migrations.len() as u32)?;
+        Ok(cache)
+    }
+
+    // Schema operations
+
+    /// Schema version stored in the database (0 for a database without one)
+    pub fn schema_version(&self) -> Result<u32, PersistentCacheError> {
+        let read_txn = self.db.begin_read()?;
+        let table = match read_txn.open_table(META_TABLE) {
+            Ok(table) => table,
+            Err(redb::TableError::TableDoesNotExist(_)) => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(table.get(SCHEMA_VERSION_KEY)?.map(|version| version.value()).unwrap_or(0))
+    }
+
+    /// Apply the migrations taking the schema from `old_version` to `new_version`
+    /// Each migration runs in its own transaction together with the version bump, so an
+    /// interrupted upgrade resumes where it stopped. Downgrades are not supported
+    pub fn migrate(&self, old_version: u32, new_version: u32) -> Result<(), PersistentCacheError> {
+        self.run_migrations(MIGRATIONS, old_version, new_version)
+    }
+
+    fn run_migrations(
+        &self,
+        migrations: &[Migration],
+        old_version: u32,
+        new_version: u32,
+    ) -> Result<(), PersistentCacheError> {
+        let supported = migrations.len() as u32;
+        if old_version > new_version || new_version > supported {
+            return Err(PersistentCacheError::UnsupportedSchemaVersion {
+                found: old_version.max(new_version),
+                supported,
+            });
         }
-        write_txn.commit()?;
 
-        Ok(Self { db })
+        for version in old_version..new_version {
+            let write_txn = self.db.begin_write()?;
+            migrations[version as usize](&write_txn)?;
+            {
+                let mut meta = write_txn.open_table(META_TABLE)?;
+                meta.insert(SCHEMA_VERSION_KEY, version + 1)?;
+            }
+            write_txn.commit()?;
+        }
+        Ok(())
     }
 
     // Cost cache operations
@@ -173,6 +240,191 @@ impl PersistentCache {
         })
     }
 
+    // BOM data operations
+
+    /// Replace the stored BOM data with `components` and `bom_items`
+    /// Cached costs and explosions are cleared since they may no longer match the data
+    pub fn store_bom_data(&self, components: &[Component], bom_items: &[BomItem]) -> Result<(), PersistentCacheError> {
+        self.clear_all()?;
+
+        let write_txn = self.db.begin_write()?;
+        {
+            write_txn.delete_table(COMPONENT_TABLE)?;
+            write_txn.delete_table(BOM_ITEM_TABLE)?;
+
+            let mut component_table = write_txn.open_table(COMPONENT_TABLE)?;
+            for component in components {
+                let bytes = rmp_serde::to_vec(component)?;
+                component_table.insert(component.id.as_str(), bytes.as_slice())?;
+            }
+
+            let mut bom_item_table = write_txn.open_table(BOM_ITEM_TABLE)?;
+            for item in bom_items {
+                let bytes = rmp_serde::to_vec(item)?;
+                bom_item_table.insert(item.id.to_string().as_str(), bytes.as_slice())?;
+            }
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    /// Load all stored components
+    pub fn load_components(&self) -> Result<Vec<Component>, PersistentCacheError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(COMPONENT_TABLE)?;
+
+        let mut components = Vec::new();
+        for item in table.iter()? {
+            let (_, value) = item?;
+            components.push(rmp_serde::from_slice(value.value())?);
+        }
+        Ok(components)
+    }
+
+    /// Load all stored BOM items
+    pub fn load_bom_items(&self) -> Result<Vec<BomItem>, PersistentCacheError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(BOM_ITEM_TABLE)?;
+
+        let mut bom_items = Vec::new();
+        for item in table.iter()? {
+            let (_, value) = item?;
+            bom_items.push(rmp_serde::from_slice(value.value())?);
+        }
+        Ok(bom_items)
+    }
+
+    // Audit log operations
+
+    /// Append an audit entry
+    pub fn log_audit(&self, entry: &AuditEntry) -> Result<(), PersistentCacheError> {
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut table = write_txn.open_table(BOM_AUDIT_TABLE)?;
+            let timestamp_ns = timestamp_key(&entry.timestamp);
+            // Keep keys unique and in insertion order
+            let key = match table.last()? {
+                Some((last, _)) => timestamp_ns.max(last.value().saturating_add(1)),
+                None => timestamp_ns,
+            };
+            let bytes = rmp_serde::to_vec(entry)?;
+            table.insert(key, bytes.as_slice())?;
+        }
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    /// Audit entries in chronological order
+    /// Optionally only entries for `component_id`, made at or after `since`, and at most `limit` of them
+    pub fn query_audit(
+        &self,
+        component_id: Option<&ComponentId>,
+        since: Option<DateTime<Utc>>,
+        limit: Option<usize>,
+    ) -> Result<Vec<AuditEntry>, PersistentCacheError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(BOM_AUDIT_TABLE)?;
+
+        let start = since.as_ref().map(timestamp_key).unwrap_or(i64::MIN);
+        let limit = limit.unwrap_or(usize::MAX);
+
+        let mut entries = Vec::new();
+        for item in table.range(start..)? {
+            if entries.len() >= limit {
+                break;
+            }
+            let (_, value) = item?;
+            let entry: AuditEntry = rmp_serde::from_slice(value.value())?;
+            if component_id.is_none_or(|id| entry.component_id == *id) {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    // Export / import operations
+
+    /// Export all cost and explosion entries as a JSON array
+    /// Used for backups and for moving cache files between machines or redb versions
+    pub fn export_to_json(&self, writer: impl Write) -> Result<(), PersistentCacheError> {
+        let entries = self.export_entries()?;
+        serde_json::to_writer(writer, &entries)?;
+        Ok(())
+    }
+
+    /// Import entries previously written by `export_to_json`
+    /// Returns the number of entries imported
+    pub fn import_from_json(&self, reader: impl Read) -> Result<usize, PersistentCacheError> {
+        let entries: Vec<CacheExportEntry> = serde_json::from_reader(reader)?;
+        self.import_entries(entries)
+    }
+
+    /// Export all cost and explosion entries in MessagePack format (compact alternative to JSON)
+    pub fn export_to_msgpack(&self, mut writer: impl Write) -> Result<(), PersistentCacheError> {
+        let entries = self.export_entries()?;
+        rmp_serde::encode::write(&mut writer, &entries)?;
+        Ok(())
+    }
+
+    /// Import entries previously written by `export_to_msgpack`
+    /// Returns the number of entries imported
+    pub fn import_from_msgpack(&self, reader: impl Read) -> Result<usize, PersistentCacheError> {
+        let entries: Vec<CacheExportEntry> = rmp_serde::from_read(reader)?;
+        self.import_entries(entries)
+    }
+
+    /// Read every entry of every table into export records
+    fn export_entries(&self) -> Result<Vec<CacheExportEntry>, PersistentCacheError> {
+        let read_txn = self.db.begin_read()?;
+        let cost_table = read_txn.open_table(COST_TABLE)?;
+        let explosion_table = read_txn.open_table(EXPLOSION_TABLE)?;
+
+        let mut entries = Vec::new();
+
+        for item in cost_table.iter()? {
+            let (key, value) = item?;
+            entries.push(CacheExportEntry::Cost {
+                key: key.value().to_string(),
+                value: rmp_serde::from_slice(value.value())?,
+            });
+        }
+
+        for item in explosion_table.iter()? {
+            let (key, value) = item?;
+            entries.push(CacheExportEntry::Explosion {
+                key: key.value().to_string(),
+                value: rmp_serde::from_slice(value.value())?,
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Write export records back into their tables in a single transaction
+    fn import_entries(&self, entries: Vec<CacheExportEntry>) -> Result<usize, PersistentCacheError> {
+        let count = entries.len();
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut cost_table = write_txn.open_table(COST_TABLE)?;
+            let mut explosion_table = write_txn.open_table(EXPLOSION_TABLE)?;
+
+            for entry in entries {
+                match entry {
+                    CacheExportEntry::Cost { key, value } => {
+                        let bytes = rmp_serde::to_vec(&value)?;
+                        cost_table.insert(key.as_str(), bytes.as_slice())?;
+                    }
+                    CacheExportEntry::Explosion { key, value } => {
+                        let bytes = rmp_serde::to_vec(&value)?;
+                        explosion_table.insert(key.as_str(), bytes.as_slice())?;
+                    }
+                }
+            }
+        }
+        write_txn.commit()?;
+        Ok(count)
+    }
+
     // Compact the database to reclaim space
     pub fn compact(&mut self) -> Result<(), PersistentCacheError> {
         self.db.compact()?;
@@ -186,6 +438,30 @@ impl PersistentCache {
     }
 }
 
+impl AuditLog for PersistentCache {
+    fn log_audit(&self, entry: &AuditEntry) -> bom_core::Result<()> {
+        PersistentCache::log_audit(self, entry).map_err(|e| BomError::CacheError(e.to_string()))
+    }
+}
+
+/// Audit table key (dates outside the nanosecond range are clamped)
+fn timestamp_key(timestamp: &DateTime<Utc>) -> i64 {
+    timestamp
+        .timestamp_nanos_opt()
+        .unwrap_or(if timestamp.timestamp() < 0 { i64::MIN } else { i64::MAX })
+}
+
+/// A single cache entry in export/import format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CacheExportEntry {
+    /// Entry from the cost table
+    Cost { key: String, value: CostBreakdown },
+
+    /// Entry from the explosion table (key is `component_id:quantity`)
+    Explosion { key: String, value: ExplosionResult },
+}
+
 #[derive(Debug, Clone)]
 pub struct PersistentCacheStats {
     pub cost_entry_count: u64,
@@ -217,6 +493,12 @@ pub enum PersistentCacheError {
 
     #[error("Deserialization error: {0}")]
     Deserialization(#[from] rmp_serde::decode::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Unsupported schema version {found} (this build supports up to {supported})")]
+    UnsupportedSchemaVersion { found: u32, supported: u32 },
 }
 
 #[cfg(test)]
@@ -323,4 +605,239 @@ mod tests {
         cache.clear_all().unwrap();
         assert!(cache.get_cost(&component_id).unwrap().is_none());
     }
+
+    fn populate_export_fixture(cache: &PersistentCache) {
+        for i in 0..6 {
+            let component_id = ComponentId::new(format!("COST-{:03}", i));
+            let cost = CostBreakdown {
+                component_id: component_id.clone(),
+                material_cost: Decimal::from(10 * i),
+                labor_cost: Decimal::ZERO,
+                overhead_cost: Decimal::ZERO,
+                subcontract_cost: Decimal::ZERO,
+                total_cost: Decimal::from(10 * i),
+                calculated_at: Utc::now(),
+            };
+            cache.put_cost(&component_id, &cost).unwrap();
+        }
+
+        for i in 0..4 {
+            let component_id = ComponentId::new(format!("EXP-{:03}", i));
+            let result = ExplosionResult {
+                root_component: component_id.clone(),
+                items: vec![],
+                unique_component_count: i,
+                max_depth: 0,
+                calculated_at: Utc::now(),
+            };
+            cache
+                .put_explosion(&component_id, Decimal::from(i + 1), &result)
+                .unwrap();
+        }
+    }
+
+    fn assert_export_fixture(cache: &PersistentCache) {
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.cost_entry_count, 6);
+        assert_eq!(stats.explosion_entry_count, 4);
+
+        for i in 0..6 {
+            let cost = cache
+                .get_cost(&ComponentId::new(format!("COST-{:03}", i)))
+                .unwrap()
+                .unwrap();
+            assert_eq!(cost.total_cost, Decimal::from(10 * i));
+        }
+
+        for i in 0..4 {
+            let result = cache
+                .get_explosion(&ComponentId::new(format!("EXP-{:03}", i)), &Decimal::from(i + 1))
+                .unwrap()
+                .unwrap();
+            assert_eq!(result.unique_component_count, i);
+        }
+    }
+
+    #[test]
+    fn test_export_import_json() {
+        let source = PersistentCache::in_memory().unwrap();
+        populate_export_fixture(&source);
+
+        let mut buffer = Vec::new();
+        source.export_to_json(&mut buffer).unwrap();
+
+        let target = PersistentCache::in_memory().unwrap();
+        let imported = target.import_from_json(buffer.as_slice()).unwrap();
+
+        assert_eq!(imported, 10);
+        assert_export_fixture(&target);
+    }
+
+    #[test]
+    fn test_export_import_msgpack() {
+        let source = PersistentCache::in_memory().unwrap();
+        populate_export_fixture(&source);
+
+        let mut buffer = Vec::new();
+        source.export_to_msgpack(&mut buffer).unwrap();
+
+        let target = PersistentCache::in_memory().unwrap();
+        let imported = target.import_from_msgpack(buffer.as_slice()).unwrap();
+
+        assert_eq!(imported, 10);
+        assert_export_fixture(&target);
+    }
+
+    #[test]
+    fn test_store_bom_data() {
+        let cache = PersistentCache::in_memory().unwrap();
+        let component = |id: &str| Component {
+            id: ComponentId::new(id),
+            description: id.to_string(),
+            component_type: bom_core::ComponentType::RawMaterial,
+            uom: "EA".to_string(),
+            standard_cost: Some(Decimal::from(5)),
+            lead_time_days: None,
+            procurement_type: bom_core::ProcurementType::Buy,
+            preferred_vendor: Some("ACME".to_string()),
+            organization: "ORG01".to_string(),
+            version: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let item = BomItem {
+            id: uuid::Uuid::new_v4(),
+            parent_id: ComponentId::new("A"),
+            child_id: ComponentId::new("B"),
+            quantity: Decimal::from(2),
+            scrap_factor: Decimal::ZERO,
+            sequence: 10,
+            operation_sequence: None,
+            is_phantom: false,
+            effective_from: None,
+            effective_to: None,
+            alternative_group: None,
+            alternative_priority: None,
+            reference_designator: None,
+            position: None,
+            notes: None,
+            version: 0,
+        };
+
+        cache.store_bom_data(&[component("A"), component("B")], std::slice::from_ref(&item)).unwrap();
+        assert_eq!(cache.load_components().unwrap().len(), 2);
+
+        // Storing again replaces the previous data
+        cache.store_bom_data(&[component("A")], std::slice::from_ref(&item)).unwrap();
+        let components = cache.load_components().unwrap();
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].preferred_vendor.as_deref(), Some("ACME"));
+
+        let bom_items = cache.load_bom_items().unwrap();
+        assert_eq!(bom_items.len(), 1);
+        assert_eq!(bom_items[0].id, item.id);
+        assert_eq!(bom_items[0].quantity, Decimal::from(2));
+    }
+
+    #[test]
+    fn test_fresh_database_is_migrated() {
+        let cache = PersistentCache::in_memory().unwrap();
+        assert_eq!(cache.schema_version().unwrap(), SCHEMA_VERSION);
+
+        // The initial migration created the tables
+        assert_eq!(cache.stats().unwrap().cost_entry_count, 0);
+        assert!(cache.load_components().unwrap().is_empty());
+        assert!(cache.query_audit(None, None, None).unwrap().is_empty());
+
+        // Migrating to the current version again is a no-op; downgrades are rejected
+        cache.migrate(SCHEMA_VERSION, SCHEMA_VERSION).unwrap();
+        assert!(cache.migrate(SCHEMA_VERSION, 0).is_err());
+        assert!(cache.migrate(0, SCHEMA_VERSION + 1).is_err());
+    }
+
+    static MIGRATION_RUNS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+    fn counting_migration(write_txn: &WriteTransaction) -> Result<(), PersistentCacheError> {
+        MIGRATION_RUNS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        create_initial_tables(write_txn)
+    }
+
+    const COUNTING_MIGRATIONS: [Migration; 3] = [counting_migration; 3];
+
+    #[test]
+    fn test_migrated_database_skips_migrations() {
+        let runs = || MIGRATION_RUNS.load(std::sync::atomic::Ordering::SeqCst);
+        let db = Database::builder()
+            .create_with_backend(redb::backends::InMemoryBackend::new())
+            .unwrap();
+
+        // Fresh: runs every migration from version 0
+        let cache = PersistentCache::open(db, &COUNTING_MIGRATIONS[..2]).unwrap();
+        assert_eq!(runs(), 2);
+        assert_eq!(cache.schema_version().unwrap(), 2);
+
+        // Reopened at the same version: nothing to do
+        let cache = PersistentCache::open(cache.db, &COUNTING_MIGRATIONS[..2]).unwrap();
+        assert_eq!(runs(), 2);
+
+        // A newer build only runs the new step
+        let cache = PersistentCache::open(cache.db, &COUNTING_MIGRATIONS).unwrap();
+        assert_eq!(runs(), 3);
+        assert_eq!(cache.schema_version().unwrap(), 3);
+
+        // An older build cannot open it
+        assert!(matches!(
+            PersistentCache::open(cache.db, MIGRATIONS),
+            Err(PersistentCacheError::UnsupportedSchemaVersion { found: 3, supported: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_audit_log() {
+        let cache = PersistentCache::in_memory().unwrap();
+        let start = Utc::now();
+        let entry = |id: &str, new_cost: i64| AuditEntry {
+            // All entries share one timestamp; keys must still stay unique
+            timestamp: start,
+            operation: bom_core::AuditOperation::UpdateComponentCost {
+                old_cost: None,
+                new_cost: Decimal::from(new_cost),
+            },
+            component_id: ComponentId::new(id),
+            bom_item: None,
+        };
+
+        cache.log_audit(&entry("A", 1)).unwrap();
+        cache.log_audit(&entry("B", 2)).unwrap();
+        cache.log_audit(&entry("A", 3)).unwrap();
+
+        assert_eq!(cache.query_audit(None, None, None).unwrap().len(), 3);
+
+        let a = ComponentId::new("A");
+        let costs: Vec<_> = cache
+            .query_audit(Some(&a), None, None)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.operation)
+            .collect();
+        assert_eq!(
+            costs,
+            vec![
+                bom_core::AuditOperation::UpdateComponentCost {
+                    old_cost: None,
+                    new_cost: Decimal::from(1)
+                },
+                bom_core::AuditOperation::UpdateComponentCost {
+                    old_cost: None,
+                    new_cost: Decimal::from(3)
+                },
+            ]
+        );
+
+        assert_eq!(cache.query_audit(Some(&a), None, Some(1)).unwrap().len(), 1);
+        assert!(cache
+            .query_audit(None, Some(start + chrono::Duration::hours(1)), None)
+            .unwrap()
+            .is_empty());
+    }
 }
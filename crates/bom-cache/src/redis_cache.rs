@@ -0,0 +1,198 @@
+use bom_core::{ComponentId, CostBreakdown, ExplosionResult};
+use redis::Commands;
+use std::sync::Mutex;
+
+/// Shared cache using Redis
+/// Lets several processes share calculation results. Values are stored as JSON
+/// so that services written in other languages can read them too
+pub struct RedisCache {
+    connection: Mutex<redis::Connection>,
+    prefix: String,
+}
+
+/// Default key prefix (`bom:cost:<id>`, `bom:explosion:<id>:<qty>`)
+pub const DEFAULT_REDIS_PREFIX: &str = "bom";
+
+impl RedisCache {
+    /// Connect to Redis (e.g. `redis://127.0.0.1/`)
+    pub fn new(redis_url: &str) -> Result<Self, RedisCacheError> {
+        Self::with_prefix(redis_url, DEFAULT_REDIS_PREFIX)
+    }
+
+    /// Connect to Redis using a custom key prefix (e.g. one per BOM dataset)
+    pub fn with_prefix(redis_url: &str, prefix: &str) -> Result<Self, RedisCacheError> {
+        let client = redis::Client::open(redis_url)?;
+        let connection = client.get_connection()?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+            prefix: prefix.to_string(),
+        })
+    }
+
+    // Cost cache operations
+
+    /// Get cached cost breakdown
+    pub fn get_cost(&self, component_id: &ComponentId) -> Result<Option<CostBreakdown>, RedisCacheError> {
+        self.get_json(&self.cost_key(component_id))
+    }
+
+    /// Put cost breakdown into cache
+    pub fn put_cost(&self, component_id: &ComponentId, cost: &CostBreakdown) -> Result<(), RedisCacheError> {
+        self.put_json(&self.cost_key(component_id), cost)
+    }
+
+    /// Remove cost from cache
+    pub fn remove_cost(&self, component_id: &ComponentId) -> Result<(), RedisCacheError> {
+        let mut connection = self.connection.lock().unwrap();
+        connection.del::<_, ()>(self.cost_key(component_id))?;
+        Ok(())
+    }
+
+    // Explosion cache operations
+
+    /// Get cached explosion result
+    pub fn get_explosion(
+        &self,
+        component_id: &ComponentId,
+        quantity: &rust_decimal::Decimal,
+    ) -> Result<Option<ExplosionResult>, RedisCacheError> {
+        self.get_json(&self.explosion_key(component_id, quantity))
+    }
+
+    /// Put explosion result into cache
+    pub fn put_explosion(
+        &self,
+        component_id: &ComponentId,
+        quantity: rust_decimal::Decimal,
+        result: &ExplosionResult,
+    ) -> Result<(), RedisCacheError> {
+        self.put_json(&self.explosion_key(component_id, &quantity), result)
+    }
+
+    // General operations
+
+    /// Remove all entries under this cache's prefix
+    pub fn clear_all(&self) -> Result<(), RedisCacheError> {
+        let keys = self.keys_matching(&format!("{}:*", self.prefix))?;
+        if !keys.is_empty() {
+            let mut connection = self.connection.lock().unwrap();
+            connection.del::<_, ()>(keys)?;
+        }
+        Ok(())
+    }
+
+    /// Get cache statistics
+    pub fn stats(&self) -> Result<RedisCacheStats, RedisCacheError> {
+        Ok(RedisCacheStats {
+            cost_entry_count: self.keys_matching(&format!("{}:cost:*", self.prefix))?.len() as u64,
+            explosion_entry_count: self.keys_matching(&format!("{}:explosion:*", self.prefix))?.len() as u64,
+        })
+    }
+
+    fn get_json<T: serde::de::DeserializeOwned>(&self, key: &str) -> Result<Option<T>, RedisCacheError> {
+        let mut connection = self.connection.lock().unwrap();
+        let value: Option<String> = connection.get(key)?;
+        Ok(value.map(|json| serde_json::from_str(&json)).transpose()?)
+    }
+
+    fn put_json<T: serde::Serialize>(&self, key: &str, value: &T) -> Result<(), RedisCacheError> {
+        let json = serde_json::to_string(value)?;
+        let mut connection = self.connection.lock().unwrap();
+        connection.set::<_, _, ()>(key, json)?;
+        Ok(())
+    }
+
+    /// Collect keys with SCAN (does not block the server like KEYS)
+    fn keys_matching(&self, pattern: &str) -> Result<Vec<String>, RedisCacheError> {
+        let mut connection = self.connection.lock().unwrap();
+        let keys: Vec<String> = connection.scan_match(pattern)?.collect();
+        Ok(keys)
+    }
+
+    fn cost_key(&self, component_id: &ComponentId) -> String {
+        format!("{}:cost:{}", self.prefix, component_id.as_str())
+    }
+
+    fn explosion_key(&self, component_id: &ComponentId, quantity: &rust_decimal::Decimal) -> String {
+        format!("{}:explosion:{}:{}", self.prefix, component_id.as_str(), quantity)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RedisCacheStats {
+    pub cost_entry_count: u64,
+    pub explosion_entry_count: u64,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RedisCacheError {
+    #[error("Redis error: {0}")]
+    Redis(#[from] redis::RedisError),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+
+    // These tests need a running Redis server:
+    // REDIS_URL=redis://127.0.0.1/ cargo test -p bom-cache --features redis -- --ignored
+    fn connect(prefix: &str) -> RedisCache {
+        let url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1/".to_string());
+        let cache = RedisCache::with_prefix(&url, prefix).expect("Redis server not reachable");
+        cache.clear_all().unwrap();
+        cache
+    }
+
+    fn create_test_cost(id: &str) -> CostBreakdown {
+        CostBreakdown {
+            component_id: ComponentId::new(id),
+            material_cost: Decimal::from(100),
+            labor_cost: Decimal::from(50),
+            overhead_cost: Decimal::from(25),
+            subcontract_cost: Decimal::ZERO,
+            total_cost: Decimal::from(175),
+            calculated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    #[ignore = "requires a Redis server (REDIS_URL)"]
+    fn test_redis_cost_cache() {
+        let cache = connect("bom-test-cost");
+        let id = ComponentId::new("TEST-001");
+
+        assert!(cache.get_cost(&id).unwrap().is_none());
+
+        cache.put_cost(&id, &create_test_cost("TEST-001")).unwrap();
+        let cached = cache.get_cost(&id).unwrap().unwrap();
+        assert_eq!(cached.total_cost, Decimal::from(175));
+        assert_eq!(cache.stats().unwrap().cost_entry_count, 1);
+
+        cache.remove_cost(&id).unwrap();
+        assert!(cache.get_cost(&id).unwrap().is_none());
+    }
+
+    #[test]
+    #[ignore = "requires a Redis server (REDIS_URL)"]
+    fn test_redis_promotes_to_lower_tiers() {
+        let url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1/".to_string());
+        let shared = connect(DEFAULT_REDIS_PREFIX);
+        let id = ComponentId::new("SHARED-001");
+
+        // Written by another process
+        shared.put_cost(&id, &create_test_cost("SHARED-001")).unwrap();
+
+        let tiered = crate::TieredCache::with_redis(&url).unwrap();
+        assert!(tiered.get_cost(&id).is_some());
+
+        // Now served from L1 even after Redis was cleared
+        shared.clear_all().unwrap();
+        assert!(tiered.get_cost(&id).is_some());
+    }
+}
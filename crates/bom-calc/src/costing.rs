@@ -1,8 +1,24 @@
-use bom_core::{BomRepository, ComponentId, CostBreakdown, Result};
-use bom_graph::{level_grouping, BomGraph, NodeIndex};
+use bom_core::{BomRepository, Component, ComponentId, ComponentType, CostBreakdown, Result};
+use bom_graph::{level_grouping, topological_sort, BomGraph, NodeIndex};
 use rayon::prelude::*;
 use rust_decimal::Decimal;
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+use crate::ExplosionCalculator;
+
+/// Breakdown of a cost made up of material only
+fn material_cost_breakdown(component_id: ComponentId, material_cost: Decimal) -> CostBreakdown {
+    CostBreakdown {
+        component_id,
+        material_cost,
+        labor_cost: Decimal::ZERO, // TODO: implement
+        overhead_cost: Decimal::ZERO, // TODO: implement
+        subcontract_cost: Decimal::ZERO, // TODO: implement
+        total_cost: material_cost,
+        calculated_at: chrono::Utc::now(),
+    }
+}
 
 /// Cost calculation engine
 pub struct CostCalculator<'a, R: BomRepository> {
@@ -28,15 +44,7 @@ impl<'a, R: BomRepository> CostCalculator<'a, R> {
             if !n.dirty {
                 if let Some(cached_cost) = n.cache.total_material_cost {
                     // Return cached result
-                    return Ok(CostBreakdown {
-                        component_id: component_id.clone(),
-                        material_cost: cached_cost,
-                        labor_cost: Decimal::ZERO, // TODO: implement
-                        overhead_cost: Decimal::ZERO, // TODO: implement
-                        subcontract_cost: Decimal::ZERO, // TODO: implement
-                        total_cost: cached_cost,
-                        calculated_at: chrono::Utc::now(),
-                    });
+                    return Ok(material_cost_breakdown(component_id.clone(), cached_cost));
                 }
             }
         }
@@ -56,8 +64,15 @@ impl<'a, R: BomRepository> CostCalculator<'a, R> {
         &self,
         roots: &[NodeIndex],
     ) -> Result<HashMap<ComponentId, CostBreakdown>> {
-        let mut cost_map: HashMap<ComponentId, CostBreakdown> = HashMap::new();
+        self.calculate_costs_with_overrides(roots, &HashMap::new())
+    }
 
+    /// `calculate_all_costs`, using `cost_overrides` instead of the standard cost where given
+    fn calculate_costs_with_overrides(
+        &self,
+        roots: &[NodeIndex],
+        cost_overrides: &HashMap<ComponentId, Decimal>,
+    ) -> Result<HashMap<ComponentId, CostBreakdown>> {
         // Get component IDs and load all components in batch
         let component_ids: Vec<ComponentId> = self
             .graph
@@ -73,56 +88,101 @@ impl<'a, R: BomRepository> CostCalculator<'a, R> {
             .map(|c| (c.id.clone(), c))
             .collect();
 
-        // Process level by level (bottom-up)
         let levels = level_grouping(self.graph.arena(), roots);
+        Ok(self.rollup(levels, &component_data, cost_overrides, |_| true))
+    }
+
+    /// Recalculate only nodes that are dirty or have no cached cost
+    /// Clean nodes contribute their cached `NodeCache::total_material_cost`, and only the
+    /// components being recalculated are loaded from the repository.
+    /// Returns the recalculated costs (storing them back is up to the caller)
+    pub fn calculate_costs_incremental(&self) -> Result<HashMap<ComponentId, CostBreakdown>> {
+        let arena = self.graph.arena();
+        let needs_update = |node_idx: &NodeIndex| {
+            arena
+                .node(*node_idx)
+                .map(|n| n.dirty || n.cache.total_material_cost.is_none())
+                .unwrap_or(false)
+        };
+
+        // Process level by level (bottom-up)
+        let levels = level_grouping(arena, self.graph.roots());
+
+        let stale_ids: Vec<ComponentId> = levels
+            .iter()
+            .flatten()
+            .filter(|node_idx| needs_update(node_idx))
+            .filter_map(|&node_idx| arena.node(node_idx).map(|n| n.component_id.clone()))
+            .collect();
+        if stale_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let component_data: HashMap<ComponentId, _> = self
+            .repository
+            .get_components(&stale_ids)?
+            .into_iter()
+            .map(|c| (c.id.clone(), c))
+            .collect();
+
+        Ok(self.rollup(levels, &component_data, &HashMap::new(), needs_update))
+    }
+
+    /// Roll costs up level by level (leaves first) for the nodes `recalculate` accepts
+    /// Nodes of each level are processed in parallel. Children that are not recalculated
+    /// contribute their cached `NodeCache::total_material_cost`; nodes without component
+    /// data are skipped
+    fn rollup(
+        &self,
+        levels: Vec<Vec<NodeIndex>>,
+        component_data: &HashMap<ComponentId, Component>,
+        cost_overrides: &HashMap<ComponentId, Decimal>,
+        recalculate: impl Fn(&NodeIndex) -> bool + Sync,
+    ) -> HashMap<ComponentId, CostBreakdown> {
+        let arena = self.graph.arena();
+        let mut cost_map: HashMap<ComponentId, CostBreakdown> = HashMap::new();
 
         for level_nodes in levels {
-            // Process all nodes in this level in parallel
             let level_costs: Vec<_> = level_nodes
                 .par_iter()
+                .filter(|node_idx| recalculate(node_idx))
                 .filter_map(|&node_idx| {
-                    let node = self.graph.arena().node(node_idx)?;
+                    let node = arena.node(node_idx)?;
                     let component = component_data.get(&node.component_id)?;
 
                     // Get own material cost
-                    let own_cost = component.standard_cost.unwrap_or(Decimal::ZERO);
+                    let own_cost = cost_overrides
+                        .get(&node.component_id)
+                        .copied()
+                        .unwrap_or_else(|| component.standard_cost.unwrap_or(Decimal::ZERO));
 
-                    // Sum up children's costs
-                    let children_cost: Decimal = self
-                        .graph
-                        .arena()
+                    // Children were either recalculated at a lower level or are clean and cached
+                    let children_cost: Decimal = arena
                         .children(node_idx)
                         .filter_map(|(child_idx, edge)| {
-                            let child_node = self.graph.arena().node(child_idx)?;
-                            let child_cost_breakdown = cost_map.get(&child_node.component_id)?;
+                            let child_node = arena.node(child_idx)?;
+                            let child_cost = match cost_map.get(&child_node.component_id) {
+                                Some(breakdown) => breakdown.total_cost,
+                                None if !recalculate(&child_idx) => child_node.cache.total_material_cost?,
+                                None => return None,
+                            };
 
                             // Child total cost * quantity
-                            Some(child_cost_breakdown.total_cost * edge.effective_quantity)
+                            Some(child_cost * edge.effective_quantity)
                         })
                         .sum();
 
-                    let total_material_cost = own_cost + children_cost;
-
                     Some((
                         node.component_id.clone(),
-                        CostBreakdown {
-                            component_id: node.component_id.clone(),
-                            material_cost: total_material_cost,
-                            labor_cost: Decimal::ZERO, // TODO: implement
-                            overhead_cost: Decimal::ZERO, // TODO: implement
-                            subcontract_cost: Decimal::ZERO, // TODO: implement
-                            total_cost: total_material_cost,
-                            calculated_at: chrono::Utc::now(),
-                        },
+                        material_cost_breakdown(node.component_id.clone(), own_cost + children_cost),
                     ))
                 })
                 .collect();
 
-            // Add to cost map
             cost_map.extend(level_costs);
         }
 
-        Ok(cost_map)
+        cost_map
     }
 
     /// Calculate cost rollup (total cost for producing a quantity)
@@ -135,6 +195,299 @@ impl<'a, R: BomRepository> CostCalculator<'a, R> {
         Ok(cost_breakdown.total_cost * quantity)
     }
 
+    /// Cost of `root_id` if the components in `new_costs` had those standard costs (what-if)
+    /// Nothing is written to the repository or the graph cache
+    pub fn simulate_cost_change(
+        &self,
+        root_id: &ComponentId,
+        new_costs: &HashMap<ComponentId, Decimal>,
+    ) -> Result<CostBreakdown> {
+        let node = self
+            .graph
+            .find_node(root_id)
+            .ok_or_else(|| bom_core::BomError::ComponentNotFound(root_id.as_str().to_string()))?;
+
+        self.calculate_costs_with_overrides(&[node], new_costs)?
+            .remove(root_id)
+            .ok_or_else(|| bom_core::BomError::CalculationError("Cost not found".to_string()))
+    }
+
+    /// How strongly the cost of `root_id` reacts to the price of each leaf component
+    /// Each leaf's standard cost is raised by `delta_pct` percent on its own;
+    /// `elasticity = (root cost change in percent) / delta_pct`, so a leaf making up half of
+    /// the root cost has an elasticity of 0.5. Sorted by elasticity, highest first
+    pub fn calculate_cost_sensitivity(
+        &self,
+        root_id: &ComponentId,
+        delta_pct: Decimal,
+    ) -> Result<Vec<CostSensitivity>> {
+        if delta_pct.is_zero() {
+            return Err(bom_core::BomError::CalculationError(
+                "cost sensitivity needs a non-zero change".to_string(),
+            ));
+        }
+
+        let root_cost = self.simulate_cost_change(root_id, &HashMap::new())?.total_cost;
+        let root = self.graph.find_node(root_id).unwrap();
+        let arena = self.graph.arena();
+
+        let leaf_ids: Vec<ComponentId> = level_grouping(arena, &[root])
+            .into_iter()
+            .flatten()
+            .filter(|&node| arena.children(node).next().is_none())
+            .filter_map(|node| arena.node(node).map(|n| n.component_id.clone()))
+            .collect();
+        let leaves = self.repository.get_components(&leaf_ids)?;
+
+        let factor = Decimal::ONE + delta_pct / Decimal::from(100);
+        let mut sensitivities = Vec::with_capacity(leaves.len());
+        for leaf in leaves {
+            let base_cost = leaf.standard_cost.unwrap_or(Decimal::ZERO);
+            let perturbed_cost = base_cost * factor;
+            let new_root_cost = self
+                .simulate_cost_change(root_id, &HashMap::from([(leaf.id.clone(), perturbed_cost)]))?
+                .total_cost;
+
+            let elasticity = if root_cost.is_zero() {
+                Decimal::ZERO
+            } else {
+                (new_root_cost - root_cost) / root_cost * Decimal::from(100) / delta_pct
+            };
+
+            sensitivities.push(CostSensitivity {
+                component_id: leaf.id,
+                base_cost,
+                perturbed_cost,
+                elasticity,
+            });
+        }
+
+        sensitivities.sort_by(|a, b| {
+            b.elasticity
+                .cmp(&a.elasticity)
+                .then_with(|| a.component_id.as_str().cmp(b.component_id.as_str()))
+        });
+        Ok(sensitivities)
+    }
+
+    /// Cost of every component of `root_id` for a heatmap view
+    /// `cost` is what the component contributes to one unit of the root (unit cost times
+    /// quantity per root); `cost_pct` is its share of the root cost, which also picks the
+    /// color from green (0%) to red (100%). Nodes are sorted by level, then component ID
+    pub fn generate_cost_heatmap(&self, root_id: &ComponentId) -> Result<CostHeatmapData> {
+        let root = self
+            .graph
+            .find_node(root_id)
+            .ok_or_else(|| bom_core::BomError::ComponentNotFound(root_id.as_str().to_string()))?;
+
+        let costs = self.calculate_all_costs(&[root])?;
+        let unit_cost = |id: &ComponentId| costs.get(id).map(|c| c.total_cost).unwrap_or(Decimal::ZERO);
+        let root_cost = unit_cost(root_id);
+
+        let explosion = ExplosionCalculator::new(self.graph).explode(root_id, Decimal::ONE)?;
+        let arena = self.graph.arena();
+
+        let mut nodes = Vec::with_capacity(explosion.items.len());
+        let mut parent_edges = Vec::new();
+        let mut seen_edges = HashSet::new();
+
+        for item in &explosion.items {
+            let cost = unit_cost(&item.component_id) * item.total_quantity;
+            let cost_pct = if root_cost.is_zero() {
+                Decimal::ZERO
+            } else {
+                cost / root_cost * Decimal::from(100)
+            };
+            nodes.push(HeatmapNode {
+                component_id: item.component_id.clone(),
+                level: item.level,
+                cost,
+                color_hex: heatmap_color(cost_pct),
+                cost_pct,
+            });
+
+            if let Some(node) = self.graph.find_node(&item.component_id) {
+                for (_, edge) in arena.children(node) {
+                    let pair = (edge.bom_item.parent_id.clone(), edge.bom_item.child_id.clone());
+                    if seen_edges.insert(pair.clone()) {
+                        parent_edges.push(pair);
+                    }
+                }
+            }
+        }
+
+        nodes.sort_by(|a, b| {
+            a.level
+                .cmp(&b.level)
+                .then_with(|| a.component_id.as_str().cmp(b.component_id.as_str()))
+        });
+        parent_edges.sort_by(|a, b| (a.0.as_str(), a.1.as_str()).cmp(&(b.0.as_str(), b.1.as_str())));
+
+        Ok(CostHeatmapData { nodes, parent_edges })
+    }
+
+    /// Suggest a simpler structure for `root_id`'s BOM: its minimum spanning arborescence with
+    /// edges weighted by the child's rolled-up cost times the quantity
+    /// See `BomGraph::minimum_spanning_tree`; nothing is changed
+    pub fn suggest_bom_simplification(&self, root_id: &ComponentId) -> Result<BomGraph> {
+        let root = self
+            .graph
+            .find_node(root_id)
+            .ok_or_else(|| bom_core::BomError::ComponentNotFound(root_id.as_str().to_string()))?;
+
+        let cost_map: HashMap<ComponentId, Decimal> = self
+            .calculate_all_costs(&[root])?
+            .into_iter()
+            .map(|(id, cost)| (id, cost.total_cost))
+            .collect();
+
+        self.graph.minimum_spanning_tree(root_id, &cost_map)
+    }
+
+    /// Material cost of `root_id` plus the cost of its BOM's complexity (複雜度成本):
+    /// every distinct component, every BOM item and every level below the root is
+    /// charged at the rate from `config`. Drivers are listed as unique components,
+    /// BOM items, then levels
+    pub fn calculate_weighted_complexity_cost(
+        &self,
+        root_id: &ComponentId,
+        config: ComplexityConfig,
+    ) -> Result<ComplexityCostAnalysis> {
+        let root = self
+            .graph
+            .find_node(root_id)
+            .ok_or_else(|| bom_core::BomError::ComponentNotFound(root_id.as_str().to_string()))?;
+
+        let direct_material_cost = self
+            .calculate_all_costs(&[root])?
+            .get(root_id)
+            .map(|cost| cost.total_cost)
+            .unwrap_or(Decimal::ZERO);
+
+        // Components and items of the root's BOM; shared parts count once
+        let arena = self.graph.arena();
+        let mut components = HashSet::from([root]);
+        let mut bom_items = 0;
+        let mut stack = vec![root];
+        while let Some(current) = stack.pop() {
+            for (child, _) in arena.children(current) {
+                bom_items += 1;
+                if components.insert(child) {
+                    stack.push(child);
+                }
+            }
+        }
+
+        // Levels below the root (longest path); children come before their parents
+        let mut depth: HashMap<NodeIndex, usize> = HashMap::new();
+        for node in topological_sort(arena, &[root]) {
+            let below = arena.children(node).map(|(child, _)| depth[&child] + 1).max().unwrap_or(0);
+            depth.insert(node, below);
+        }
+        let levels = depth.get(&root).copied().unwrap_or(0);
+
+        let complexity_drivers: Vec<ComplexityDriver> = [
+            (ComplexityFactor::UniqueComponents, components.len(), config.cost_per_unique_component),
+            (ComplexityFactor::BomItems, bom_items, config.cost_per_bom_item),
+            (ComplexityFactor::Levels, levels, config.cost_per_level),
+        ]
+        .into_iter()
+        .map(|(factor, count, unit_cost)| ComplexityDriver {
+            factor,
+            count,
+            unit_cost,
+            cost: unit_cost * Decimal::from(count),
+        })
+        .collect();
+
+        let complexity_cost = complexity_drivers.iter().map(|driver| driver.cost).sum();
+        Ok(ComplexityCostAnalysis {
+            direct_material_cost,
+            complexity_cost,
+            total_cost: direct_material_cost + complexity_cost,
+            complexity_drivers,
+        })
+    }
+
+    /// Cost of `root_id` split by component type (成本類別分析)
+    /// Every component of the flattened BOM, the root included, adds its own standard
+    /// cost times its quantity per root to its type; `total_pct` is that type's share
+    /// of the sum over all types. Component lists are sorted by ID
+    pub fn calculate_cost_by_category(&self, root_id: &ComponentId) -> Result<CostByCategory> {
+        let quantities = ExplosionCalculator::new(self.graph).flatten(root_id)?;
+        let component_ids: Vec<ComponentId> = quantities.keys().cloned().collect();
+
+        let mut categories: HashMap<ComponentType, CategoryCost> = HashMap::new();
+        for component in self.repository.get_components(&component_ids)? {
+            let quantity = quantities.get(&component.id).copied().unwrap_or(Decimal::ZERO);
+            let category = categories.entry(component.component_type).or_insert_with(|| CategoryCost {
+                total_cost: Decimal::ZERO,
+                total_pct: Decimal::ZERO,
+                component_count: 0,
+                components: Vec::new(),
+            });
+            category.total_cost += component.standard_cost.unwrap_or(Decimal::ZERO) * quantity;
+            category.component_count += 1;
+            category.components.push(component.id);
+        }
+
+        let total_cost: Decimal = categories.values().map(|category| category.total_cost).sum();
+        for category in categories.values_mut() {
+            if !total_cost.is_zero() {
+                category.total_pct = category.total_cost / total_cost * Decimal::from(100);
+            }
+            category.components.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        }
+
+        Ok(CostByCategory { categories })
+    }
+
+    /// Cost of the material lost to scrap when producing `production_qty` of `root_id` (損耗成本)
+    /// Each BOM item with a scrap factor wastes `quantity * scrap_factor` of its child per
+    /// unit of the parent, valued at the child's standard cost; parent quantities come from
+    /// the explosion and include the scrap of higher levels. Components are listed by scrap
+    /// cost, highest first
+    pub fn analyze_scrap_cost(&self, root_id: &ComponentId, production_qty: Decimal) -> Result<ScrapCostAnalysis> {
+        let explosion = ExplosionCalculator::new(self.graph).explode(root_id, production_qty)?;
+        let arena = self.graph.arena();
+
+        let mut scrap_quantities: HashMap<ComponentId, Decimal> = HashMap::new();
+        for item in &explosion.items {
+            let Some(node) = self.graph.find_node(&item.component_id) else {
+                continue;
+            };
+            for (_, edge) in arena.children(node) {
+                let bom_item = &edge.bom_item;
+                if bom_item.scrap_factor > Decimal::ZERO {
+                    *scrap_quantities.entry(bom_item.child_id.clone()).or_default() +=
+                        bom_item.quantity * bom_item.scrap_factor * item.total_quantity;
+                }
+            }
+        }
+
+        let component_ids: Vec<ComponentId> = scrap_quantities.keys().cloned().collect();
+        let standard_costs: HashMap<ComponentId, Decimal> = self
+            .repository
+            .get_components(&component_ids)?
+            .into_iter()
+            .map(|component| (component.id, component.standard_cost.unwrap_or(Decimal::ZERO)))
+            .collect();
+
+        let mut scrap_by_component: Vec<(ComponentId, Decimal, Decimal)> = scrap_quantities
+            .into_iter()
+            .map(|(component_id, scrap_quantity)| {
+                let unit_cost = standard_costs.get(&component_id).copied().unwrap_or(Decimal::ZERO);
+                (component_id, scrap_quantity, scrap_quantity * unit_cost)
+            })
+            .collect();
+        scrap_by_component.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.as_str().cmp(b.0.as_str())));
+
+        Ok(ScrapCostAnalysis {
+            total_scrap_cost: scrap_by_component.iter().map(|(_, _, cost)| *cost).sum(),
+            scrap_by_component,
+        })
+    }
+
     /// Calculate where the cost comes from (cost breakdown by component)
     pub fn analyze_cost_drivers(
         &self,
@@ -171,10 +524,135 @@ impl<'a, R: BomRepository> CostCalculator<'a, R> {
             .collect();
 
         // Sort by cost (descending)
-        drivers.sort_by(|a, b| b.cost.cmp(&a.cost));
+        drivers.sort_by_key(|d| std::cmp::Reverse(d.cost));
 
         Ok(drivers)
     }
+
+    /// Compare the cost of each alternative in `alternative_group` under `parent_id`
+    /// Results are ordered by priority; the first entry is the primary alternative
+    pub fn compare_alternative_costs(
+        &self,
+        parent_id: &ComponentId,
+        alternative_group: &str,
+    ) -> Result<Vec<AlternativeCostComparison>> {
+        let node = self
+            .graph
+            .find_node(parent_id)
+            .ok_or_else(|| bom_core::BomError::ComponentNotFound(parent_id.as_str().to_string()))?;
+
+        let cost_map = self.calculate_all_costs(&[node])?;
+
+        // (component, priority, unit cost, cost per parent)
+        let mut alternatives: Vec<(ComponentId, u32, Decimal, Decimal)> = self
+            .graph
+            .arena()
+            .children(node)
+            .filter(|(_, edge)| edge.bom_item.alternative_group.as_deref() == Some(alternative_group))
+            .map(|(_, edge)| {
+                let child_id = edge.bom_item.child_id.clone();
+                let unit_cost = cost_map
+                    .get(&child_id)
+                    .map(|c| c.total_cost)
+                    .unwrap_or(Decimal::ZERO);
+                let priority = edge.bom_item.alternative_priority.unwrap_or(u32::MAX);
+                (child_id, priority, unit_cost, unit_cost * edge.effective_quantity)
+            })
+            .collect();
+
+        alternatives.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.as_str().cmp(b.0.as_str())));
+
+        let baseline = alternatives.first().map(|a| a.3).unwrap_or(Decimal::ZERO);
+
+        Ok(alternatives
+            .into_iter()
+            .map(|(alternative_component_id, alternative_priority, unit_cost, extended_cost)| {
+                AlternativeCostComparison {
+                    alternative_component_id,
+                    alternative_priority,
+                    unit_cost,
+                    total_bom_cost_impact: extended_cost - baseline,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Cost of one alternative within an alternative group (替代料成本比較)
+#[derive(Debug, Clone)]
+pub struct AlternativeCostComparison {
+    pub alternative_component_id: ComponentId,
+    /// Priority of the BOM item (`u32::MAX` if not set)
+    pub alternative_priority: u32,
+    /// Total cost of one unit of the alternative component
+    pub unit_cost: Decimal,
+    /// Change in the parent's cost when this alternative is used instead of the
+    /// primary (highest priority) one
+    pub total_bom_cost_impact: Decimal,
+}
+
+/// Rates charged for the structure of a BOM (more SKUs, suppliers and transactions)
+#[derive(Debug, Clone, Copy)]
+pub struct ComplexityConfig {
+    pub cost_per_unique_component: Decimal,
+    pub cost_per_bom_item: Decimal,
+    pub cost_per_level: Decimal,
+}
+
+/// What a complexity cost is charged for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ComplexityFactor {
+    /// Distinct components, including the root
+    UniqueComponents,
+    /// Parent/child items; a shared component's items are counted once
+    BomItems,
+    /// Levels below the root
+    Levels,
+}
+
+/// One line of a complexity cost analysis
+#[derive(Debug, Clone, Serialize)]
+pub struct ComplexityDriver {
+    pub factor: ComplexityFactor,
+    pub count: usize,
+    pub unit_cost: Decimal,
+    /// `count` times `unit_cost`
+    pub cost: Decimal,
+}
+
+/// Material cost plus complexity cost of a BOM (total cost of ownership)
+#[derive(Debug, Clone, Serialize)]
+pub struct ComplexityCostAnalysis {
+    /// Rolled-up material cost of one unit of the root
+    pub direct_material_cost: Decimal,
+    pub complexity_cost: Decimal,
+    pub total_cost: Decimal,
+    pub complexity_drivers: Vec<ComplexityDriver>,
+}
+
+/// Cost of the components of one type within a BOM
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryCost {
+    /// Standard cost times quantity per root, summed over the type's components
+    pub total_cost: Decimal,
+    /// Share of the BOM's total cost in percent
+    pub total_pct: Decimal,
+    pub component_count: usize,
+    pub components: Vec<ComponentId>,
+}
+
+/// Cost of a BOM by component type; types without components are left out
+#[derive(Debug, Clone, Serialize)]
+pub struct CostByCategory {
+    pub categories: HashMap<ComponentType, CategoryCost>,
+}
+
+/// Material waste cost of producing a quantity of a BOM
+#[derive(Debug, Clone, Serialize)]
+pub struct ScrapCostAnalysis {
+    pub total_scrap_cost: Decimal,
+    /// `(component, scrap quantity, scrap cost)`
+    pub scrap_by_component: Vec<(ComponentId, Decimal, Decimal)>,
 }
 
 /// Cost driver analysis result
@@ -185,6 +663,50 @@ pub struct CostDriver {
     pub percentage: Decimal,
 }
 
+/// Effect of a leaf component's price on the root cost (成本敏感度)
+#[derive(Debug, Clone)]
+pub struct CostSensitivity {
+    pub component_id: ComponentId,
+    /// Standard cost of the leaf
+    pub base_cost: Decimal,
+    /// Standard cost after the change
+    pub perturbed_cost: Decimal,
+    /// Relative root cost change per relative leaf cost change
+    pub elasticity: Decimal,
+}
+
+/// One component of a cost heatmap
+#[derive(Debug, Clone, Serialize)]
+pub struct HeatmapNode {
+    pub component_id: ComponentId,
+    /// BOM level below the root (root = 0)
+    pub level: usize,
+    /// Cost contributed to one unit of the root
+    pub cost: Decimal,
+    /// Share of the root cost in percent
+    pub cost_pct: Decimal,
+    /// `#rrggbb`, green for 0% to red for 100%
+    pub color_hex: String,
+}
+
+/// Cost heatmap of a BOM, ready to serialize for a graph visualization (D3.js, React Flow)
+#[derive(Debug, Clone, Serialize)]
+pub struct CostHeatmapData {
+    pub nodes: Vec<HeatmapNode>,
+    /// `(parent, child)` pairs, one per connected pair
+    pub parent_edges: Vec<(ComponentId, ComponentId)>,
+}
+
+/// Interpolate from green (0%) to red (100%); values outside are clamped
+fn heatmap_color(cost_pct: Decimal) -> String {
+    use rust_decimal::prelude::ToPrimitive;
+
+    let share = (cost_pct / Decimal::from(100)).to_f64().unwrap_or(0.0).clamp(0.0, 1.0);
+    let red = (255.0 * share).round() as u8;
+    let green = (255.0 * (1.0 - share)).round() as u8;
+    format!("#{:02x}{:02x}00", red, green)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -202,6 +724,7 @@ mod tests {
             standard_cost: Some(Decimal::from(cost)),
             lead_time_days: Some(7),
             procurement_type: ProcurementType::Make,
+            preferred_vendor: None,
             organization: "ORG01".to_string(),
             version: 0,
             created_at: Utc::now(),
@@ -241,8 +764,8 @@ mod tests {
         repo.add_component(create_test_component("B", 50));
         repo.add_component(create_test_component("C", 30));
 
-        repo.add_bom_item(create_test_bom_item("A", "B", 2));
-        repo.add_bom_item(create_test_bom_item("A", "C", 1));
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
 
         let graph = BomGraph::from_repository(&repo).unwrap();
         let calc = CostCalculator::new(&graph, &repo);
@@ -265,9 +788,9 @@ mod tests {
         repo.add_component(create_test_component("C", 30));
         repo.add_component(create_test_component("D", 10));
 
-        repo.add_bom_item(create_test_bom_item("A", "B", 2));
-        repo.add_bom_item(create_test_bom_item("A", "C", 1));
-        repo.add_bom_item(create_test_bom_item("B", "D", 3));
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 3)).unwrap();
 
         let graph = BomGraph::from_repository(&repo).unwrap();
         let calc = CostCalculator::new(&graph, &repo);
@@ -279,6 +802,182 @@ mod tests {
         assert_eq!(cost_a.total_cost, Decimal::from(290));
     }
 
+    #[test]
+    fn test_cost_sensitivity() {
+        let repo = InMemoryRepository::new();
+
+        // A (cost 0) -> B (cost 0, qty 1) -> D (cost 25, qty 2)
+        //            -> C (cost 30, qty 1), E (cost 20, qty 1)
+        // Root cost 100: D makes up 50%, C 30%, E 20%
+        repo.add_component(create_test_component("A", 0));
+        repo.add_component(create_test_component("B", 0));
+        repo.add_component(create_test_component("C", 30));
+        repo.add_component(create_test_component("D", 25));
+        repo.add_component(create_test_component("E", 20));
+
+        repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "E", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 2)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let calc = CostCalculator::new(&graph, &repo);
+        let a = ComponentId::new("A");
+
+        // What-if does not change the stored cost
+        let what_if = calc
+            .simulate_cost_change(&a, &HashMap::from([(ComponentId::new("D"), Decimal::from(30))]))
+            .unwrap();
+        assert_eq!(what_if.total_cost, Decimal::from(110));
+        assert_eq!(calc.calculate_cost(&a).unwrap().total_cost, Decimal::from(100));
+
+        let sensitivities = calc.calculate_cost_sensitivity(&a, Decimal::from(10)).unwrap();
+        let summary: Vec<(&str, Decimal)> = sensitivities
+            .iter()
+            .map(|s| (s.component_id.as_str(), s.elasticity))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("D", Decimal::new(5, 1)),
+                ("C", Decimal::new(3, 1)),
+                ("E", Decimal::new(2, 1)),
+            ]
+        );
+        assert_eq!(sensitivities[0].base_cost, Decimal::from(25));
+        assert_eq!(sensitivities[0].perturbed_cost, Decimal::new(275, 1));
+
+        assert!(calc.calculate_cost_sensitivity(&a, Decimal::ZERO).is_err());
+    }
+
+    #[test]
+    fn test_cost_heatmap() {
+        let repo = InMemoryRepository::new();
+
+        // Same BOM as test_cost_sensitivity: D 50%, C 30%, E 20% of a root cost of 100
+        repo.add_component(create_test_component("A", 0));
+        repo.add_component(create_test_component("B", 0));
+        repo.add_component(create_test_component("C", 30));
+        repo.add_component(create_test_component("D", 25));
+        repo.add_component(create_test_component("E", 20));
+
+        repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "E", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 2)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let calc = CostCalculator::new(&graph, &repo);
+
+        let heatmap = calc.generate_cost_heatmap(&ComponentId::new("A")).unwrap();
+        let node = |id: &str| heatmap.nodes.iter().find(|n| n.component_id.as_str() == id).unwrap();
+        let red = |n: &HeatmapNode| u8::from_str_radix(&n.color_hex[1..3], 16).unwrap();
+
+        assert_eq!(heatmap.nodes.len(), 5);
+        assert_eq!(node("A").color_hex, "#ff0000");
+        assert_eq!(node("D").level, 2);
+        assert_eq!(node("D").cost, Decimal::from(50));
+        assert_eq!(node("D").cost_pct, Decimal::from(50));
+        assert_eq!(node("E").color_hex, "#33cc00");
+
+        // Below the root, the most expensive leaf is the reddest
+        let leaves = ["C", "D", "E"].map(node);
+        let reddest = leaves.iter().max_by_key(|n| red(n)).unwrap();
+        assert_eq!(reddest.component_id, ComponentId::new("D"));
+
+        let edges: Vec<(&str, &str)> = heatmap
+            .parent_edges
+            .iter()
+            .map(|(parent, child)| (parent.as_str(), child.as_str()))
+            .collect();
+        assert_eq!(edges, vec![("A", "B"), ("A", "C"), ("A", "E"), ("B", "D")]);
+    }
+
+    #[test]
+    fn test_suggest_bom_simplification() {
+        let repo = InMemoryRepository::new();
+
+        // R -> X (10) -> A (5)
+        //   -> Y (50) -> A (x2)
+        // Rolled up: X 15, Y 60. Weights: X-A 5, Y-A 10, R-X 15, R-Y 60; A keeps X-A
+        for (id, cost) in [("R", 0), ("X", 10), ("Y", 50), ("A", 5), ("OTHER", 0), ("Z", 1)] {
+            repo.add_component(create_test_component(id, cost));
+        }
+        repo.add_bom_item(create_test_bom_item("R", "X", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("R", "Y", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("X", "A", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("Y", "A", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("OTHER", "Z", 1)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let calc = CostCalculator::new(&graph, &repo);
+
+        let suggestion = calc.suggest_bom_simplification(&ComponentId::new("R")).unwrap();
+
+        // Only R's BOM, every component still under R, the expensive Y -> A link cut
+        assert_eq!(suggestion.stats().node_count, 4);
+        let mut edges: Vec<(&str, &str)> = suggestion
+            .iter_edges()
+            .map(|(_, _, item)| (item.parent_id.as_str(), item.child_id.as_str()))
+            .collect();
+        edges.sort();
+        assert_eq!(edges, vec![("R", "X"), ("R", "Y"), ("X", "A")]);
+        for id in ["X", "Y", "A"] {
+            assert_eq!(suggestion.path_exists(&ComponentId::new("R"), &ComponentId::new(id)), Some(true));
+        }
+    }
+
+    #[test]
+    fn test_weighted_complexity_cost() {
+        let config = ComplexityConfig {
+            cost_per_unique_component: Decimal::from(10),
+            cost_per_bom_item: Decimal::from(2),
+            cost_per_level: Decimal::from(5),
+        };
+        let analyze = |second_fastener: &str| {
+            // A -> B -> SCREW, A -> C -> second fastener
+            let repo = InMemoryRepository::new();
+            for id in ["A", "B", "C", "SCREW", "BOLT"] {
+                repo.add_component(create_test_component(id, 1));
+            }
+            repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+            repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+            repo.add_bom_item(create_test_bom_item("B", "SCREW", 4)).unwrap();
+            repo.add_bom_item(create_test_bom_item("C", second_fastener, 4)).unwrap();
+
+            let graph = BomGraph::from_repository(&repo).unwrap();
+            CostCalculator::new(&graph, &repo)
+                .calculate_weighted_complexity_cost(&ComponentId::new("A"), config)
+                .unwrap()
+        };
+
+        // Material: A, B and C at 1 plus 2 x 4 fasteners at 1
+        // Complexity: 5 components, 4 items, 2 levels: 50 + 8 + 10
+        let distinct = analyze("BOLT");
+        assert_eq!(distinct.direct_material_cost, Decimal::from(11));
+        assert_eq!(distinct.complexity_cost, Decimal::from(68));
+        assert_eq!(distinct.total_cost, Decimal::from(79));
+        let counts: Vec<(ComplexityFactor, usize)> = distinct
+            .complexity_drivers
+            .iter()
+            .map(|driver| (driver.factor, driver.count))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![
+                (ComplexityFactor::UniqueComponents, 5),
+                (ComplexityFactor::BomItems, 4),
+                (ComplexityFactor::Levels, 2)
+            ]
+        );
+
+        // Sharing the screw removes one unique component
+        let shared = analyze("SCREW");
+        assert_eq!(shared.direct_material_cost, Decimal::from(11));
+        assert_eq!(shared.complexity_cost, Decimal::from(58));
+        assert!(shared.complexity_cost < distinct.complexity_cost);
+    }
+
     #[test]
     fn test_cost_rollup() {
         let repo = InMemoryRepository::new();
@@ -288,7 +987,7 @@ mod tests {
         repo.add_component(create_test_component("A", 100));
         repo.add_component(create_test_component("B", 50));
 
-        repo.add_bom_item(create_test_bom_item("A", "B", 2));
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
 
         let graph = BomGraph::from_repository(&repo).unwrap();
         let calc = CostCalculator::new(&graph, &repo);
@@ -299,4 +998,137 @@ mod tests {
 
         assert_eq!(rollup, Decimal::from(2000));
     }
+
+    #[test]
+    fn test_compare_alternative_costs() {
+        let repo = InMemoryRepository::new();
+
+        // A uses 3x either ALT-A ($10, primary) or ALT-B ($15)
+        repo.add_component(create_test_component("A", 100));
+        repo.add_component(create_test_component("ALT-A", 10));
+        repo.add_component(create_test_component("ALT-B", 15));
+
+        let mut primary = create_test_bom_item("A", "ALT-A", 3);
+        primary.alternative_group = Some("G1".to_string());
+        primary.alternative_priority = Some(1);
+        let mut secondary = create_test_bom_item("A", "ALT-B", 3);
+        secondary.alternative_group = Some("G1".to_string());
+        secondary.alternative_priority = Some(2);
+        repo.add_bom_item(secondary).unwrap();
+        repo.add_bom_item(primary).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let calc = CostCalculator::new(&graph, &repo);
+
+        let comparison = calc
+            .compare_alternative_costs(&ComponentId::new("A"), "G1")
+            .unwrap();
+
+        assert_eq!(comparison.len(), 2);
+        assert_eq!(comparison[0].alternative_component_id.as_str(), "ALT-A");
+        assert_eq!(comparison[0].unit_cost, Decimal::from(10));
+        assert_eq!(comparison[0].total_bom_cost_impact, Decimal::ZERO);
+        assert_eq!(comparison[1].alternative_component_id.as_str(), "ALT-B");
+        assert_eq!(comparison[1].unit_cost, Decimal::from(15));
+
+        // ($15 - $10) * 3
+        assert_eq!(
+            comparison[1].total_bom_cost_impact - comparison[0].total_bom_cost_impact,
+            Decimal::from(15)
+        );
+    }
+
+    #[test]
+    fn test_cost_by_category() {
+        let repo = InMemoryRepository::new();
+
+        // BIKE (FG, 40) -> FRAME (semi, 30, qty 1) -> TUBE (raw, 5, qty 4)
+        //               -> WHEEL (semi, 10, qty 2) -> SPOKE (raw, 1, qty 20)
+        // Rolled-up cost: 40 + (30 + 20) + 2 * (10 + 20) = 150
+        let typed = |id: &str, cost: i32, component_type: ComponentType| Component {
+            component_type,
+            ..create_test_component(id, cost)
+        };
+        repo.add_component(typed("BIKE", 40, ComponentType::FinishedProduct));
+        repo.add_component(typed("FRAME", 30, ComponentType::SemiFinished));
+        repo.add_component(typed("WHEEL", 10, ComponentType::SemiFinished));
+        repo.add_component(typed("TUBE", 5, ComponentType::RawMaterial));
+        repo.add_component(typed("SPOKE", 1, ComponentType::RawMaterial));
+        repo.add_bom_item(create_test_bom_item("BIKE", "FRAME", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("BIKE", "WHEEL", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("FRAME", "TUBE", 4)).unwrap();
+        repo.add_bom_item(create_test_bom_item("WHEEL", "SPOKE", 20)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let calc = CostCalculator::new(&graph, &repo);
+        let breakdown = calc.calculate_cost_by_category(&ComponentId::new("BIKE")).unwrap();
+
+        // Raw: 4 * 5 + 40 * 1 = 60; semi-finished: 30 + 2 * 10 = 50
+        let raw = &breakdown.categories[&ComponentType::RawMaterial];
+        let semi = &breakdown.categories[&ComponentType::SemiFinished];
+        let finished = &breakdown.categories[&ComponentType::FinishedProduct];
+        assert_eq!(raw.total_cost, Decimal::from(60));
+        assert_eq!(semi.total_cost, Decimal::from(50));
+        assert_eq!(finished.total_cost, Decimal::from(40));
+        assert_eq!(breakdown.categories.len(), 3);
+
+        let root_cost = calc.calculate_cost(&ComponentId::new("BIKE")).unwrap().total_cost;
+        assert_eq!(raw.total_cost + semi.total_cost + finished.total_cost, root_cost);
+
+        assert_eq!(raw.total_pct, Decimal::from(40));
+        assert_eq!(raw.component_count, 2);
+        assert_eq!(raw.components, vec![ComponentId::new("SPOKE"), ComponentId::new("TUBE")]);
+
+        assert!(calc.calculate_cost_by_category(&ComponentId::new("MISSING")).is_err());
+    }
+
+    #[test]
+    fn test_scrap_cost() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B (qty 1, 10% scrap) -> C ($100, qty 10, 5% scrap)
+        //   -> D ($2, qty 3, no scrap)
+        repo.add_component(create_test_component("A", 0));
+        repo.add_component(create_test_component("B", 20));
+        repo.add_component(create_test_component("C", 100));
+        repo.add_component(create_test_component("D", 2));
+        repo.add_bom_item(BomItem {
+            scrap_factor: Decimal::new(10, 2),
+            ..create_test_bom_item("A", "B", 1)
+        })
+        .unwrap();
+        repo.add_bom_item(BomItem {
+            scrap_factor: Decimal::new(5, 2),
+            ..create_test_bom_item("B", "C", 10)
+        })
+        .unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "D", 3)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let calc = CostCalculator::new(&graph, &repo);
+
+        // 10 x 5% of C per B: 0.5 units at $100 = $50 per B
+        let single = calc.analyze_scrap_cost(&ComponentId::new("B"), Decimal::ONE).unwrap();
+        assert_eq!(single.total_scrap_cost, Decimal::from(50));
+        assert_eq!(
+            single.scrap_by_component,
+            vec![(ComponentId::new("C"), Decimal::new(5, 1), Decimal::from(50))]
+        );
+
+        // 2 A need 2.2 B (0.2 scrapped, $4) and waste 2.2 * 0.5 = 1.1 C ($110)
+        let analysis = calc.analyze_scrap_cost(&ComponentId::new("A"), Decimal::from(2)).unwrap();
+        let scrap: Vec<(&str, Decimal, Decimal)> = analysis
+            .scrap_by_component
+            .iter()
+            .map(|(id, quantity, cost)| (id.as_str(), quantity.normalize(), cost.normalize()))
+            .collect();
+        assert_eq!(
+            scrap,
+            vec![
+                ("C", Decimal::new(11, 1), Decimal::from(110)),
+                ("B", Decimal::new(2, 1), Decimal::from(4))
+            ]
+        );
+        assert_eq!(analysis.total_scrap_cost.normalize(), Decimal::from(114));
+    }
 }
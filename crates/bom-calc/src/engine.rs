@@ -1,21 +1,64 @@
-use bom_core::{BomRepository, ComponentId, CostBreakdown, ExplosionResult, Result, WhereUsedResult};
+use bom_core::{
+    AuditEntry, AuditLog, AuditOperation, BatchUpdateResult, BomRepository, Component, ComponentId, CostBreakdown,
+    CostInvalidation, ExplosionResult, Result, WhereUsedResult,
+};
 use bom_graph::BomGraph;
+use rayon::prelude::*;
 use rust_decimal::Decimal;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
 
-use crate::{CostCalculator, ExplosionCalculator, WhereUsedAnalyzer, ImpactAnalysis, SharedComponent};
+use crate::{
+    BomMaintainer, BomValidator, CascadeDeleteResult, CostCalculator, ExplosionCalculator, ImpactAnalysis,
+    MaintenanceChange, ProductionPlanner, SharedComponent, SubstitutableComponent, SubstitutionAnalyzer,
+    WhereUsedAnalyzer,
+};
+
+thread_local! {
+    /// Costs collected by the current thread's explosion (see `BomEngine::with_thread_local_cache`)
+    static LOCAL_COSTS: RefCell<HashMap<ComponentId, CostBreakdown>> = RefCell::new(HashMap::new());
+
+    /// Costs the current thread has stored in each engine's cost cache
+    static STORED_COSTS: RefCell<Vec<StoredCosts>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Costs one thread has stored in a cost cache, so unchanged costs are not stored again
+struct StoredCosts {
+    /// Dropped once the engine owning the cache is gone
+    cache: Weak<dyn CostInvalidation>,
+    /// `BomEngine::cost_generation` the costs were stored in
+    generation: u64,
+    costs: HashMap<ComponentId, Decimal>,
+}
 
 /// Unified calculation engine that combines all BOM calculations
 /// This is the main entry point for BOM computations
 pub struct BomEngine<R: BomRepository> {
     graph: BomGraph,
     repository: R,
+    /// Receives an entry for every change made through the engine
+    audit_log: Option<Box<dyn AuditLog>>,
+    /// Cached costs dropped when component costs change
+    cost_cache: Option<Arc<dyn CostInvalidation>>,
+    /// Collect costs per thread during `explode` and store them in `cost_cache` afterwards
+    thread_local_cache: bool,
+    /// Incremented whenever costs are dropped from `cost_cache`
+    cost_generation: u64,
 }
 
 impl<R: BomRepository> BomEngine<R> {
     /// Create a new BOM engine from a repository
     pub fn new(repository: R) -> Result<Self> {
         let graph = BomGraph::from_repository(&repository)?;
-        Ok(Self { graph, repository })
+        Ok(Self {
+            graph,
+            repository,
+            audit_log: None,
+            cost_cache: None,
+            thread_local_cache: false,
+            cost_generation: 0,
+        })
     }
 
     /// Create engine for a specific component (loads only its BOM tree)
@@ -25,7 +68,56 @@ impl<R: BomRepository> BomEngine<R> {
         effective_date: Option<chrono::DateTime<chrono::Utc>>,
     ) -> Result<Self> {
         let graph = BomGraph::from_component(&repository, component_id, effective_date)?;
-        Ok(Self { graph, repository })
+        Ok(Self {
+            graph,
+            repository,
+            audit_log: None,
+            cost_cache: None,
+            thread_local_cache: false,
+            cost_generation: 0,
+        })
+    }
+
+    /// Copy of this engine whose graph has all root products under one virtual root
+    /// (see `BomGraph::add_virtual_root`), for analyzing a product family as one BOM.
+    /// The audit log and cost cache are not carried over
+    pub fn as_unified_graph(&self, virtual_root_id: &ComponentId) -> Result<BomEngine<R>>
+    where
+        R: Clone,
+    {
+        let mut graph = self.graph.clone();
+        graph.add_virtual_root(virtual_root_id)?;
+        Ok(BomEngine {
+            graph,
+            repository: self.repository.clone(),
+            audit_log: None,
+            cost_cache: None,
+            thread_local_cache: self.thread_local_cache,
+            cost_generation: 0,
+        })
+    }
+
+    /// Record changes made through the engine (e.g. in a `PersistentCache`)
+    pub fn with_audit_log(mut self, audit_log: impl AuditLog + 'static) -> Self {
+        self.audit_log = Some(Box::new(audit_log));
+        self
+    }
+
+    /// Invalidate costs in `cost_cache` (e.g. a shared `TieredCache`) when component costs change
+    pub fn with_cost_cache(mut self, cost_cache: impl CostInvalidation + 'static) -> Self {
+        self.cost_cache = Some(Arc::new(cost_cache));
+        self
+    }
+
+    /// Collect the cached costs of exploded components in a thread-local map during each
+    /// `explode` call and store them in `cost_cache` in one batch when the call ends.
+    /// Parallel explosions then do not contend on the shared cache's locks. Each thread
+    /// only stores costs it has not stored yet or that changed since, and stores all of
+    /// them again after the engine dropped costs from the cache; costs the cache evicts
+    /// on its own are not restored until they change
+    pub fn with_thread_local_cache(mut self) -> Self {
+        self.thread_local_cache = true;
+        self
     }
 
     /// Get graph statistics
@@ -38,9 +130,156 @@ impl<R: BomRepository> BomEngine<R> {
     /// Explode BOM to calculate material requirements
     pub fn explode(&self, component_id: &ComponentId, quantity: Decimal) -> Result<ExplosionResult> {
         let calculator = ExplosionCalculator::new(&self.graph);
+        let Some(cost_cache) = self.cost_cache.as_ref().filter(|_| self.thread_local_cache) else {
+            return calculator.explode(component_id, quantity);
+        };
+
+        LOCAL_COSTS.with(|local_cache| {
+            let mut local_cache = local_cache.borrow_mut();
+            local_cache.clear();
+            let result = calculator.explode_with_local_cache(component_id, quantity, &mut local_cache);
+            self.store_changed_costs(cost_cache, local_cache.drain());
+            result
+        })
+    }
+
+    /// Store the costs the current thread has not stored in `cost_cache` yet (in this
+    /// generation) or stored with a different total
+    fn store_changed_costs(
+        &self,
+        cost_cache: &Arc<dyn CostInvalidation>,
+        costs: impl Iterator<Item = (ComponentId, CostBreakdown)>,
+    ) {
+        STORED_COSTS.with(|stored| {
+            let mut stored = stored.borrow_mut();
+            stored.retain(|entry| entry.cache.strong_count() > 0);
+
+            let position = stored
+                .iter()
+                .position(|entry| std::ptr::addr_eq(entry.cache.as_ptr(), Arc::as_ptr(cost_cache)));
+            let entry = match position {
+                Some(position) => &mut stored[position],
+                None => {
+                    stored.push(StoredCosts {
+                        cache: Arc::downgrade(cost_cache),
+                        generation: self.cost_generation,
+                        costs: HashMap::new(),
+                    });
+                    stored.last_mut().unwrap()
+                }
+            };
+            if entry.generation != self.cost_generation {
+                entry.generation = self.cost_generation;
+                entry.costs.clear();
+            }
+
+            let changed: Vec<(ComponentId, CostBreakdown)> = costs
+                .filter(|(id, cost)| entry.costs.get(id) != Some(&cost.total_cost))
+                .collect();
+            if changed.is_empty() {
+                return;
+            }
+            for (id, cost) in &changed {
+                entry.costs.insert(id.clone(), cost.total_cost);
+            }
+            cost_cache.store_cost_batch(&changed);
+        })
+    }
+
+    /// Drop the cached costs of `component_ids` from `cost_cache`
+    fn invalidate_cached_costs(&mut self, component_ids: &[ComponentId]) {
+        if let Some(cost_cache) = &self.cost_cache {
+            cost_cache.invalidate_cost_batch(component_ids);
+            self.cost_generation += 1;
+        }
+    }
+
+    /// Explode BOM with explicit options (e.g. quantity rounding)
+    pub fn explode_with_config(
+        &self,
+        component_id: &ComponentId,
+        quantity: Decimal,
+        config: crate::ExplosionConfig,
+    ) -> Result<ExplosionResult> {
+        let calculator = ExplosionCalculator::new_with_config(&self.graph, config);
         calculator.explode(component_id, quantity)
     }
 
+    /// Explode BOM for one organization (plant), see `ExplosionOptions`
+    /// Organization codes are loaded from the repository
+    pub fn explode_with_options(
+        &self,
+        component_id: &ComponentId,
+        quantity: Decimal,
+        options: &crate::ExplosionOptions,
+    ) -> Result<ExplosionResult> {
+        let organizations = if options.organization_filter.is_some() {
+            let ids: Vec<ComponentId> = self.graph.iter_nodes().map(|(_, id)| id.clone()).collect();
+            self.repository
+                .get_components(&ids)?
+                .into_iter()
+                .map(|component| (component.id, component.organization))
+                .collect()
+        } else {
+            std::collections::HashMap::new()
+        };
+
+        let calculator = ExplosionCalculator::new(&self.graph);
+        calculator.explode_with_options(component_id, quantity, options, &organizations)
+    }
+
+    /// Explode BOM with work center hours from the routings in the repository
+    pub fn explode_with_routing(
+        &self,
+        component_id: &ComponentId,
+        quantity: Decimal,
+    ) -> Result<crate::RoutedExplosionResult> {
+        let mut routings = std::collections::HashMap::new();
+        for (_, id) in self.graph.iter_nodes() {
+            if let Some(routing) = self.repository.get_routing(id)? {
+                routings.insert(id.clone(), routing);
+            }
+        }
+
+        let calculator = ExplosionCalculator::new(&self.graph);
+        calculator.explode_with_routing(component_id, quantity, &routings)
+    }
+
+    /// Write the explosion as CSV (`ExplosionResult::to_bom_csv` format) while it is computed,
+    /// without collecting the items first; rows follow `ExplosionCalculator::explode_to_sink` order
+    /// Returns the number of data rows written
+    pub fn bulk_explode_to_csv<W: std::io::Write>(
+        &self,
+        root_id: &ComponentId,
+        quantity: Decimal,
+        mut writer: W,
+    ) -> Result<usize> {
+        let io_error = |e: std::io::Error| bom_core::BomError::SerializationError(e.to_string());
+        writeln!(writer, "{}", bom_core::BOM_CSV_HEADER).map_err(io_error)?;
+
+        let calculator = ExplosionCalculator::new(&self.graph);
+        let rows = calculator.explode_to_sink(root_id, quantity, |item| {
+            writeln!(writer, "{}", item.to_bom_csv_row()).map_err(io_error)
+        })?;
+        writer.flush().map_err(io_error)?;
+        Ok(rows)
+    }
+
+    /// Explode several products at once, one explosion per `(root, quantity)` request
+    /// The explosions run in parallel on the shared graph; results come back in request
+    /// order. If any request fails (e.g. an unknown root), the error of the first failing one
+    /// in request order is returned. Thread-local cost collection (`with_thread_local_cache`)
+    /// does not apply here
+    pub fn explode_batch(&self, requests: &[(ComponentId, Decimal)]) -> Result<Vec<ExplosionResult>> {
+        let graph = &self.graph;
+        // Collected per request first: rayon would return whichever error it hit first
+        let results: Vec<Result<ExplosionResult>> = requests
+            .par_iter()
+            .map(|(component_id, quantity)| ExplosionCalculator::new(graph).explode(component_id, *quantity))
+            .collect();
+        results.into_iter().collect()
+    }
+
     /// Single-level explosion (immediate children only)
     pub fn explode_single_level(
         &self,
@@ -57,6 +296,123 @@ impl<R: BomRepository> BomEngine<R> {
         calculator.flatten(component_id)
     }
 
+    /// Calculate net requirements given available inventory (gross - on hand, floored at zero)
+    pub fn calculate_net_requirements(
+        &self,
+        root_id: &ComponentId,
+        production_qty: Decimal,
+        inventory: &std::collections::HashMap<ComponentId, Decimal>,
+    ) -> Result<crate::NetRequirementsResult> {
+        let calculator = ExplosionCalculator::new(&self.graph);
+        calculator.calculate_net_requirements(root_id, production_qty, inventory)
+    }
+
+    /// Analyze BOM depth (levels, deepest components, manufacturing steps)
+    pub fn analyze_depth_profile(&self, root_id: &ComponentId) -> Result<crate::DepthProfile> {
+        let calculator = ExplosionCalculator::new(&self.graph);
+        calculator.analyze_depth_profile(root_id)
+    }
+
+    // === Production Planning ===
+
+    /// Generate work orders (and purchase requisitions for leaves) scheduled by lead time
+    pub fn generate_work_orders(
+        &self,
+        root_id: &ComponentId,
+        production_qty: Decimal,
+        start_date: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<crate::WorkOrder>> {
+        let planner = ProductionPlanner::new(&self.graph, &self.repository);
+        planner.generate_work_orders(root_id, production_qty, start_date)
+    }
+
+    /// Purchased component with the longest cumulative lead time (when buying must start)
+    pub fn calculate_procurement_lead_time(&self, root_id: &ComponentId) -> Result<crate::ProcurementLeadTime> {
+        let planner = ProductionPlanner::new(&self.graph, &self.repository);
+        planner.calculate_procurement_lead_time(root_id)
+    }
+
+    /// Group purchased components of a production plan by preferred vendor
+    pub fn calculate_procurement_summary(
+        &self,
+        root_id: &ComponentId,
+        production_qty: Decimal,
+    ) -> Result<crate::ProcurementSummary> {
+        let planner = ProductionPlanner::new(&self.graph, &self.repository);
+        planner.calculate_procurement_summary(root_id, production_qty)
+    }
+
+    /// Minimum inventory levels for a daily demand of `root_id` (see `SafetyStockConfig`)
+    pub fn calculate_safety_stock_requirements(
+        &self,
+        root_id: &ComponentId,
+        daily_demand: Decimal,
+        safety_configs: &[crate::SafetyStockConfig],
+    ) -> Result<crate::SafetyStockReport> {
+        let planner = ProductionPlanner::new(&self.graph, &self.repository);
+        planner.calculate_safety_stock_requirements(root_id, daily_demand, safety_configs)
+    }
+
+    /// Simulate a production run under daily capacity limits (see `CapacityConstraint`)
+    pub fn simulate_production_run(
+        &self,
+        root_id: &ComponentId,
+        target_qty: Decimal,
+        start_date: chrono::DateTime<chrono::Utc>,
+        constraints: &[crate::CapacityConstraint],
+    ) -> Result<crate::ProductionSimulation> {
+        let planner = ProductionPlanner::new(&self.graph, &self.repository);
+        planner.simulate_production_run(root_id, target_qty, start_date, constraints)
+    }
+
+    /// Cumulative lead time (longest chain of component lead times) of `root_id`
+    pub fn calculate_lead_time(&self, root_id: &ComponentId) -> Result<crate::LeadTimeResult> {
+        let planner = ProductionPlanner::new(&self.graph, &self.repository);
+        planner.calculate_lead_time(root_id)
+    }
+
+    /// First article inspection plan (AS9102) for one unit of `root_id`
+    pub fn calculate_first_article_inspection_plan(&self, root_id: &ComponentId) -> Result<crate::FaiPlan> {
+        let planner = ProductionPlanner::new(&self.graph, &self.repository);
+        planner.calculate_first_article_inspection_plan(root_id)
+    }
+
+    /// Alternative routes of a component (see `BomRepository::get_alternative_routes`)
+    pub fn find_alternative_routes(&self, component_id: &ComponentId) -> Result<Vec<bom_core::AlternativeRoute>> {
+        let planner = ProductionPlanner::new(&self.graph, &self.repository);
+        planner.find_alternative_routes(component_id)
+    }
+
+    /// Best alternative route of a component by `criteria` (see `RoutingCriteria`)
+    pub fn select_optimal_route(
+        &self,
+        component_id: &ComponentId,
+        criteria: crate::RoutingCriteria,
+    ) -> Result<bom_core::AlternativeRoute> {
+        let planner = ProductionPlanner::new(&self.graph, &self.repository);
+        planner.select_optimal_route(component_id, criteria)
+    }
+
+    /// How much of one planned unit of `root_id` can be produced given limited stock of some components
+    pub fn simulate_shortage(
+        &self,
+        root_id: &ComponentId,
+        unavailable: &[(ComponentId, Decimal)],
+    ) -> Result<crate::ShortageImpact> {
+        let planner = ProductionPlanner::new(&self.graph, &self.repository);
+        planner.simulate_shortage(root_id, unavailable)
+    }
+
+    /// Demand per day and ABC class of components from their demand history
+    pub fn calculate_component_velocity(
+        &self,
+        data: &[crate::VelocityData],
+        lookback_days: u32,
+    ) -> Result<Vec<crate::ComponentVelocity>> {
+        let planner = ProductionPlanner::new(&self.graph, &self.repository);
+        planner.calculate_component_velocity(data, lookback_days)
+    }
+
     // === Cost Calculation ===
 
     /// Calculate cost breakdown for a component
@@ -71,6 +427,27 @@ impl<R: BomRepository> BomEngine<R> {
         calculator.calculate_all_costs(self.graph.roots())
     }
 
+    /// Recalculate costs after `dirty_ids` changed, reusing cached costs of everything else
+    /// Marks the given components and their ancestors dirty, recalculates only those (and
+    /// nodes without a cached cost), then caches the results and marks them clean.
+    /// Returns the recalculated costs
+    pub fn calculate_cost_incremental(
+        &mut self,
+        dirty_ids: &[ComponentId],
+    ) -> Result<std::collections::HashMap<ComponentId, CostBreakdown>> {
+        for component_id in dirty_ids {
+            self.graph.mark_dirty(component_id)?;
+        }
+
+        let costs = CostCalculator::new(&self.graph, &self.repository).calculate_costs_incremental()?;
+
+        for (component_id, cost) in &costs {
+            self.graph.cache_material_cost(component_id, cost.total_cost)?;
+        }
+
+        Ok(costs)
+    }
+
     /// Calculate total cost for producing a quantity
     pub fn calculate_rollup(&self, component_id: &ComponentId, quantity: Decimal) -> Result<Decimal> {
         let calculator = CostCalculator::new(&self.graph, &self.repository);
@@ -83,6 +460,74 @@ impl<R: BomRepository> BomEngine<R> {
         calculator.analyze_cost_drivers(component_id)
     }
 
+    /// Compare the cost impact of each alternative in an alternative group
+    pub fn compare_alternative_costs(
+        &self,
+        parent_id: &ComponentId,
+        alternative_group: &str,
+    ) -> Result<Vec<crate::AlternativeCostComparison>> {
+        let calculator = CostCalculator::new(&self.graph, &self.repository);
+        calculator.compare_alternative_costs(parent_id, alternative_group)
+    }
+
+    /// Cost of `root_id` with some standard costs replaced (nothing is stored)
+    pub fn simulate_cost_change(
+        &self,
+        root_id: &ComponentId,
+        new_costs: &std::collections::HashMap<ComponentId, Decimal>,
+    ) -> Result<CostBreakdown> {
+        let calculator = CostCalculator::new(&self.graph, &self.repository);
+        calculator.simulate_cost_change(root_id, new_costs)
+    }
+
+    /// Cost elasticity of `root_id` to each leaf component price (see `CostSensitivity`)
+    pub fn calculate_cost_sensitivity(
+        &self,
+        root_id: &ComponentId,
+        delta_pct: Decimal,
+    ) -> Result<Vec<crate::CostSensitivity>> {
+        let calculator = CostCalculator::new(&self.graph, &self.repository);
+        calculator.calculate_cost_sensitivity(root_id, delta_pct)
+    }
+
+    /// Per-component cost shares with heatmap colors and edges for a graph visualization
+    pub fn generate_cost_heatmap(&self, root_id: &ComponentId) -> Result<crate::CostHeatmapData> {
+        let calculator = CostCalculator::new(&self.graph, &self.repository);
+        calculator.generate_cost_heatmap(root_id)
+    }
+
+    /// Suggest a lower-cost spanning tree of `root_id`'s BOM (fewer items, same components)
+    pub fn suggest_bom_simplification(&self, root_id: &ComponentId) -> Result<BomGraph> {
+        let calculator = CostCalculator::new(&self.graph, &self.repository);
+        calculator.suggest_bom_simplification(root_id)
+    }
+
+    /// Material cost of `root_id` plus the cost of its BOM's structural complexity
+    pub fn calculate_weighted_complexity_cost(
+        &self,
+        root_id: &ComponentId,
+        complexity_config: crate::ComplexityConfig,
+    ) -> Result<crate::ComplexityCostAnalysis> {
+        let calculator = CostCalculator::new(&self.graph, &self.repository);
+        calculator.calculate_weighted_complexity_cost(root_id, complexity_config)
+    }
+
+    /// Cost of `root_id`'s BOM split by component type
+    pub fn calculate_cost_by_category(&self, root_id: &ComponentId) -> Result<crate::CostByCategory> {
+        let calculator = CostCalculator::new(&self.graph, &self.repository);
+        calculator.calculate_cost_by_category(root_id)
+    }
+
+    /// Cost of the material scrapped when producing `production_qty` of `root_id`
+    pub fn analyze_scrap_cost(
+        &self,
+        root_id: &ComponentId,
+        production_qty: Decimal,
+    ) -> Result<crate::ScrapCostAnalysis> {
+        let calculator = CostCalculator::new(&self.graph, &self.repository);
+        calculator.analyze_scrap_cost(root_id, production_qty)
+    }
+
     // === Where-Used Analysis ===
 
     /// Find where a component is used
@@ -91,6 +536,21 @@ impl<R: BomRepository> BomEngine<R> {
         analyzer.analyze(component_id)
     }
 
+    /// Find the assemblies using a component at most `max_levels` levels above it
+    pub fn where_used_with_depth(&self, component_id: &ComponentId, max_levels: usize) -> Result<WhereUsedResult> {
+        let analyzer = WhereUsedAnalyzer::new(&self.graph);
+        analyzer.analyze_with_depth(component_id, max_levels)
+    }
+
+    /// Find where each of several components is used, sharing one upward traversal
+    pub fn batch_where_used(
+        &self,
+        component_ids: &[ComponentId],
+    ) -> Result<std::collections::HashMap<ComponentId, WhereUsedResult>> {
+        let analyzer = WhereUsedAnalyzer::new(&self.graph);
+        analyzer.analyze_batch(component_ids)
+    }
+
     /// Find root assemblies that use a component
     pub fn find_root_assemblies(&self, component_id: &ComponentId) -> Result<Vec<ComponentId>> {
         let analyzer = WhereUsedAnalyzer::new(&self.graph);
@@ -109,6 +569,283 @@ impl<R: BomRepository> BomEngine<R> {
         analyzer.find_shared_components(assembly_ids)
     }
 
+    /// Measure component sharing across a product family (1.0 = all unique, 0.0 = fully shared)
+    pub fn calculate_commonality_index(&self, root_ids: &[ComponentId]) -> Result<crate::CommonalityAnalysis> {
+        let analyzer = WhereUsedAnalyzer::new(&self.graph);
+        analyzer.calculate_commonality_index(root_ids)
+    }
+
+    /// Widely used and single-parent components (flagged from `HIGH_RISK_MIN_IN_DEGREE` parents)
+    pub fn supply_chain_risk_report(&self) -> Result<crate::SupplyChainRiskReport> {
+        let analyzer = WhereUsedAnalyzer::new(&self.graph);
+        Ok(analyzer.supply_chain_risk_report(crate::HIGH_RISK_MIN_IN_DEGREE))
+    }
+
+    /// Rank components by how many root products use them (standardization candidates)
+    pub fn calculate_component_usage_frequency(&self) -> Result<Vec<crate::ComponentUsageFrequency>> {
+        let analyzer = WhereUsedAnalyzer::new(&self.graph);
+        analyzer.calculate_usage_frequency()
+    }
+
+    /// Find equivalent components (AML entries, customer part numbers, substitutes)
+    pub fn find_cross_references(&self, component_id: &ComponentId) -> Result<Vec<bom_core::CrossReference>> {
+        self.repository.get_cross_references(component_id)
+    }
+
+    /// Find alternatives, cross-referenced equivalents and successors of a component, cheapest first
+    pub fn find_substitutable_components(&self, component_id: &ComponentId) -> Result<Vec<SubstitutableComponent>> {
+        let analyzer = SubstitutionAnalyzer::new(&self.graph, &self.repository);
+        analyzer.find_substitutable_components(component_id)
+    }
+
+    /// Group BOM components with near-identical descriptions (0.9 catches punctuation and case)
+    pub fn find_duplicate_components(&self, similarity_threshold: f64) -> Result<Vec<crate::DuplicateGroup>> {
+        let analyzer = SubstitutionAnalyzer::new(&self.graph, &self.repository);
+        analyzer.find_duplicate_components(similarity_threshold)
+    }
+
+    // === Reporting ===
+
+    /// Run the explosion and the sections selected in `options` for `root_id` in one call
+    /// See `BomReport::to_markdown` for a printable document
+    pub fn generate_report(
+        &self,
+        root_id: &ComponentId,
+        quantity: Decimal,
+        options: crate::ReportOptions,
+    ) -> Result<crate::BomReport> {
+        let explosion = self.explode(root_id, quantity)?;
+
+        let cost = if options.include_cost {
+            Some(self.calculate_cost(root_id)?)
+        } else {
+            None
+        };
+        let where_used = if options.include_where_used {
+            Some(self.where_used(root_id)?)
+        } else {
+            None
+        };
+        let lead_time = if options.include_lead_times {
+            Some(self.calculate_lead_time(root_id)?)
+        } else {
+            None
+        };
+        let analytics = if options.include_analytics {
+            let arena = self.graph.arena();
+            let leaf_count = explosion
+                .items
+                .iter()
+                .filter_map(|item| self.graph.find_node(&item.component_id))
+                .filter(|&node| arena.children(node).next().is_none())
+                .count();
+            Some(crate::BomAnalytics {
+                depth_profile: self.analyze_depth_profile(root_id)?,
+                leaf_count,
+            })
+        } else {
+            None
+        };
+
+        Ok(crate::BomReport {
+            root_component: root_id.clone(),
+            quantity,
+            explosion,
+            cost,
+            where_used,
+            lead_time,
+            analytics,
+        })
+    }
+
+    /// Save a multi-sheet analysis workbook for `quantity` of `root_id` (see `WORKBOOK_SHEETS`):
+    /// indented BOM, cost per component, where-used paths, structure analytics with a
+    /// complexity score, and bought components with vendor and cost
+    #[cfg(feature = "excel")]
+    pub fn export_to_excel_workbook(
+        &self,
+        root_id: &ComponentId,
+        quantity: Decimal,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<()> {
+        crate::excel::export_workbook(self, root_id, quantity, path.as_ref())
+    }
+
+    // === BOM Maintenance ===
+
+    /// Renumber a parent's BOM items as `step, 2*step, ...` to close sequence gaps
+    /// Returns the `(item id, new sequence)` pairs that changed
+    pub fn resequence_bom(&mut self, component_id: &ComponentId, step: u32) -> Result<Vec<(uuid::Uuid, u32)>> {
+        self.maintain(|maintainer| maintainer.resequence_bom(component_id, step))
+    }
+
+    /// Insert a new BOM item directly after the item with sequence `after_sequence`
+    pub fn insert_bom_item_at_position(
+        &mut self,
+        parent_id: &ComponentId,
+        child_id: &ComponentId,
+        quantity: Decimal,
+        after_sequence: u32,
+    ) -> Result<bom_core::BomItem> {
+        self.maintain(|maintainer| {
+            maintainer.insert_bom_item_at_position(parent_id, child_id, quantity, after_sequence)
+        })
+    }
+
+    /// Delete a component and every BOM item that uses it (see `BomMaintainer::cascade_delete`)
+    pub fn cascade_delete(&mut self, component_id: &ComponentId, recursive: bool) -> Result<CascadeDeleteResult> {
+        let mut maintainer = BomMaintainer::new(&mut self.graph, &self.repository);
+        let result = maintainer.cascade_delete(component_id, recursive)?;
+
+        self.audit(
+            AuditOperation::CascadeDelete {
+                deleted_bom_items: result.deleted_bom_items.clone(),
+                deleted_components: result.deleted_components.clone(),
+            },
+            component_id,
+            None,
+        )?;
+        Ok(result)
+    }
+
+    /// Repair common BOM data problems (see `BomMaintainer::auto_fix`)
+    /// Cached costs of every component are dropped if anything changed
+    pub fn auto_detect_and_fix(&mut self, issues: &[crate::AutoFixIssue]) -> Result<crate::AutoFixReport> {
+        let report = self.maintain(|maintainer| maintainer.auto_fix(issues))?;
+
+        if report.fixed_issues.iter().any(|(_, fixed)| *fixed > 0) {
+            let component_ids: Vec<ComponentId> = self.graph.iter_nodes().map(|(_, id)| id.clone()).collect();
+            self.invalidate_cached_costs(&component_ids);
+        }
+        Ok(report)
+    }
+
+    /// Change a component's standard cost and mark it and its ancestors for recalculation
+    /// Their entries in the cost cache are dropped
+    pub fn update_component_cost(&mut self, component_id: &ComponentId, new_cost: Decimal) -> Result<()> {
+        let mut component = self.repository.get_component(component_id)?;
+        let old_cost = component.standard_cost.replace(new_cost);
+        component.version += 1;
+        component.updated_at = chrono::Utc::now();
+        self.repository.insert_component(component)?;
+
+        // Components outside any BOM are not in the graph
+        let mut invalidated = vec![component_id.clone()];
+        if self.graph.find_node(component_id).is_some() {
+            self.graph.mark_dirty(component_id)?;
+            invalidated.extend(self.graph.ancestors(component_id)?);
+        }
+        self.invalidate_cached_costs(&invalidated);
+
+        self.audit(AuditOperation::UpdateComponentCost { old_cost, new_cost }, component_id, None)
+    }
+
+    /// Set the standard cost of many components at once (e.g. a supplier price list import)
+    /// Updated components and their ancestors are marked dirty and dropped from the cost cache
+    pub fn batch_update_costs(&mut self, updates: &[(ComponentId, Decimal)]) -> Result<BatchUpdateResult> {
+        // Old costs are only needed for the audit trail
+        let old_costs: std::collections::HashMap<ComponentId, Option<Decimal>> = match &self.audit_log {
+            Some(_) => updates
+                .iter()
+                .filter_map(|(id, _)| self.repository.get_component(id).ok())
+                .map(|component| (component.id, component.standard_cost))
+                .collect(),
+            None => std::collections::HashMap::new(),
+        };
+
+        let result = self.repository.batch_update_costs(updates)?;
+
+        let mut invalidated: Vec<ComponentId> = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        for (id, new_cost) in updates {
+            if result.not_found.contains(id) || result.version_conflicts.contains(id) {
+                continue;
+            }
+
+            // Components outside any BOM are not in the graph
+            if self.graph.find_node(id).is_some() {
+                self.graph.mark_dirty(id)?;
+                for ancestor in self.graph.ancestors(id)? {
+                    if seen.insert(ancestor.clone()) {
+                        invalidated.push(ancestor);
+                    }
+                }
+            }
+            if seen.insert(id.clone()) {
+                invalidated.push(id.clone());
+            }
+
+            if self.audit_log.is_some() {
+                let old_cost = old_costs.get(id).copied().flatten();
+                self.audit(
+                    AuditOperation::UpdateComponentCost {
+                        old_cost,
+                        new_cost: *new_cost,
+                    },
+                    id,
+                    None,
+                )?;
+            }
+        }
+
+        self.invalidate_cached_costs(&invalidated);
+
+        Ok(result)
+    }
+
+    /// Copy the direct BOM items of one component to another (e.g. a new revision)
+    /// Returns the number of items copied
+    pub fn copy_bom(&mut self, source_component: &ComponentId, target_component: &ComponentId) -> Result<usize> {
+        self.maintain(|maintainer| maintainer.copy_bom(source_component, target_component))
+    }
+
+    /// Copy the entire structure below a component, duplicating sub-assemblies under new IDs
+    pub fn deep_copy_bom(
+        &mut self,
+        source_component: &ComponentId,
+        target_component: &ComponentId,
+        rename: impl Fn(&ComponentId) -> ComponentId,
+    ) -> Result<usize> {
+        self.maintain(|maintainer| maintainer.deep_copy_bom(source_component, target_component, rename))
+    }
+
+    /// Run a maintenance operation and audit every change it wrote, also if it failed partway
+    fn maintain<T>(&mut self, operation: impl FnOnce(&mut BomMaintainer<'_, R>) -> Result<T>) -> Result<T> {
+        let mut maintainer = BomMaintainer::new(&mut self.graph, &self.repository);
+        let result = operation(&mut maintainer);
+
+        for change in maintainer.into_changes() {
+            let (operation, item) = match change {
+                MaintenanceChange::ComponentInserted(id) => {
+                    self.audit(AuditOperation::InsertComponent, &id, None)?;
+                    continue;
+                }
+                MaintenanceChange::ItemInserted(item) => (AuditOperation::InsertBomItem, item),
+                MaintenanceChange::ItemUpdated(item) => (AuditOperation::UpdateBomItem, item),
+                MaintenanceChange::ItemRemoved(item) => (AuditOperation::RemoveBomItem, item),
+            };
+            self.audit(operation, &item.parent_id.clone(), Some(item))?;
+        }
+        result
+    }
+
+    fn audit(
+        &self,
+        operation: AuditOperation,
+        component_id: &ComponentId,
+        bom_item: Option<bom_core::BomItem>,
+    ) -> Result<()> {
+        let Some(audit_log) = &self.audit_log else {
+            return Ok(());
+        };
+        audit_log.log_audit(&AuditEntry {
+            timestamp: chrono::Utc::now(),
+            operation,
+            component_id: component_id.clone(),
+            bom_item,
+        })
+    }
+
     // === Graph Operations ===
 
     /// Get the underlying graph (for advanced operations)
@@ -121,11 +858,58 @@ impl<R: BomRepository> BomEngine<R> {
         &self.repository
     }
 
+    /// The `top_n` components with the highest betweenness centrality (bottleneck parts shared
+    /// by many assemblies), highest first; ties are ordered by component ID
+    pub fn most_critical_components(&self, top_n: usize) -> Result<Vec<(ComponentId, f64)>> {
+        let mut ranked: Vec<(ComponentId, f64)> = self.graph.betweenness_centrality().into_iter().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.as_str().cmp(b.0.as_str())));
+        ranked.truncate(top_n);
+        Ok(ranked)
+    }
+
+    /// Find components by description (see `BomRepository::search_components`)
+    pub fn search(&self, query: &str) -> Result<Vec<Component>> {
+        self.repository.search_components(query)
+    }
+
+    /// Find BOM items whose notes contain `pattern` (see `BomRepository::search_bom_item_notes`)
+    pub fn find_items_with_notes(&self, pattern: &str) -> Result<Vec<bom_core::BomItem>> {
+        self.repository.search_bom_item_notes(pattern)
+    }
+
     /// Validate the BOM for circular dependencies and other issues
     pub fn validate(&self) -> Result<()> {
         bom_graph::validate_graph(self.graph.arena())
     }
 
+    /// Check repository data for missing components and overlapping effectivity
+    pub fn validate_completeness(&self) -> Result<Vec<crate::ValidationIssue>> {
+        BomValidator::new(&self.repository).validate_completeness()
+    }
+
+    /// Check reference designators (missing, duplicated, count vs quantity) of a parent's BOM
+    pub fn validate_reference_designators(
+        &self,
+        component_id: &ComponentId,
+    ) -> Result<crate::ReferenceDesignatorReport> {
+        BomValidator::new(&self.repository).validate_reference_designators(component_id)
+    }
+
+    /// Find fractional quantities of discrete components (raw materials counted in `EA`)
+    pub fn validate_discrete_quantities(&self) -> Result<Vec<crate::DiscreteQuantityError>> {
+        BomValidator::new(&self.repository).validate_discrete_quantities()
+    }
+
+    /// Warn about sub-graphs that are disconnected from the main BOM
+    pub fn validate_connectivity(&self, multi_product: bool) -> Vec<crate::ValidationIssue> {
+        BomValidator::<R>::validate_connectivity(&self.graph, multi_product)
+    }
+
+    /// Find BOM items for the same parent/child pair with overlapping effectivity
+    pub fn find_effectivity_overlaps(&self) -> Result<Vec<crate::EffectivityOverlap>> {
+        BomValidator::new(&self.repository).find_effectivity_overlaps()
+    }
+
     /// Mark a component as dirty for incremental recomputation
     pub fn mark_dirty(&mut self, component_id: &ComponentId) -> Result<()> {
         self.graph.mark_dirty(component_id)
@@ -153,6 +937,7 @@ mod tests {
             standard_cost: Some(Decimal::from(cost)),
             lead_time_days: Some(7),
             procurement_type: ProcurementType::Make,
+            preferred_vendor: None,
             organization: "ORG01".to_string(),
             version: 0,
             created_at: Utc::now(),
@@ -193,9 +978,9 @@ mod tests {
         repo.add_component(create_test_component("C", 30));
         repo.add_component(create_test_component("D", 10));
 
-        repo.add_bom_item(create_test_bom_item("A", "B", 2));
-        repo.add_bom_item(create_test_bom_item("A", "C", 1));
-        repo.add_bom_item(create_test_bom_item("B", "D", 3));
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 3)).unwrap();
 
         let engine = BomEngine::new(repo).unwrap();
 
@@ -215,6 +1000,83 @@ mod tests {
         assert!(engine.validate().is_ok());
     }
 
+    #[test]
+    fn test_explode_with_organization_filter() {
+        let repo = InMemoryRepository::new();
+
+        // A, B, D in PLANT-A; C, E in PLANT-B
+        // A -> B -> D
+        //   -> C -> E
+        let plants = [("A", "PLANT-A"), ("B", "PLANT-A"), ("C", "PLANT-B"), ("D", "PLANT-A"), ("E", "PLANT-B")];
+        for (id, organization) in plants {
+            let mut component = create_test_component(id, 10);
+            component.organization = organization.to_string();
+            repo.add_component(component);
+        }
+        repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("C", "E", 1)).unwrap();
+
+        let engine = BomEngine::new(repo).unwrap();
+        let options = crate::ExplosionOptions {
+            organization_filter: Some("PLANT-A".to_string()),
+            include_inter_org: false,
+        };
+        let explosion = engine
+            .explode_with_options(&ComponentId::new("A"), Decimal::ONE, &options)
+            .unwrap();
+
+        let mut ids: Vec<&str> = explosion.items.iter().map(|item| item.component_id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["A", "B", "D"]);
+    }
+
+    #[test]
+    fn test_find_cross_references() {
+        let repo = InMemoryRepository::new();
+
+        repo.add_component(create_test_component("A", 100));
+        repo.add_component(create_test_component("RES-10K", 1));
+        repo.add_bom_item(create_test_bom_item("A", "RES-10K", 4)).unwrap();
+
+        // Internal part RES-10K is approved from two manufacturers
+        repo.add_cross_reference(
+            ComponentId::new("RES-10K"),
+            CrossReference {
+                equivalent_id: ComponentId::new("RC0603FR-0710KL"),
+                relationship_type: CrossReferenceType::ManufacturerEquivalent,
+                source: "Yageo".to_string(),
+            },
+        );
+        repo.add_cross_reference(
+            ComponentId::new("RES-10K"),
+            CrossReference {
+                equivalent_id: ComponentId::new("CRCW060310K0FKEA"),
+                relationship_type: CrossReferenceType::ManufacturerEquivalent,
+                source: "Vishay".to_string(),
+            },
+        );
+
+        let engine = BomEngine::new(repo).unwrap();
+
+        let references = engine
+            .find_cross_references(&ComponentId::new("RES-10K"))
+            .unwrap();
+        assert_eq!(references.len(), 2);
+        assert!(references
+            .iter()
+            .all(|r| r.relationship_type == CrossReferenceType::ManufacturerEquivalent));
+        assert!(references.iter().any(|r| r.source == "Yageo"));
+        assert!(references.iter().any(|r| r.source == "Vishay"));
+
+        // Components without references return an empty list
+        assert!(engine
+            .find_cross_references(&ComponentId::new("A"))
+            .unwrap()
+            .is_empty());
+    }
+
     #[test]
     fn test_validation_catches_cycles() {
         let repo = InMemoryRepository::new();
@@ -223,11 +1085,616 @@ mod tests {
         repo.add_component(create_test_component("B", 50));
 
         // Create a cycle: A -> B -> A
-        repo.add_bom_item(create_test_bom_item("A", "B", 1));
-        repo.add_bom_item(create_test_bom_item("B", "A", 1));
+        repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "A", 1)).unwrap();
 
         // Should fail to create engine due to cycle
         let result = BomEngine::new(repo);
         assert!(result.is_err());
     }
+
+    /// Records which components are loaded
+    struct SpyRepository {
+        inner: InMemoryRepository,
+        loaded: std::sync::Mutex<Vec<ComponentId>>,
+    }
+
+    impl SpyRepository {
+        fn take_loaded(&self) -> Vec<String> {
+            let mut loaded: Vec<String> = self
+                .loaded
+                .lock()
+                .unwrap()
+                .drain(..)
+                .map(|id| id.as_str().to_string())
+                .collect();
+            loaded.sort();
+            loaded
+        }
+    }
+
+    impl BomRepository for SpyRepository {
+        fn get_component(&self, id: &ComponentId) -> Result<Component> {
+            self.loaded.lock().unwrap().push(id.clone());
+            self.inner.get_component(id)
+        }
+
+        fn get_components(&self, ids: &[ComponentId]) -> Result<Vec<Component>> {
+            self.loaded.lock().unwrap().extend(ids.iter().cloned());
+            self.inner.get_components(ids)
+        }
+
+        fn get_bom_header(
+            &self,
+            component_id: &ComponentId,
+            alternative: Option<&str>,
+            effective_date: Option<chrono::DateTime<Utc>>,
+        ) -> Result<BomHeader> {
+            self.inner.get_bom_header(component_id, alternative, effective_date)
+        }
+
+        fn get_bom_items(
+            &self,
+            component_id: &ComponentId,
+            effective_date: Option<chrono::DateTime<Utc>>,
+        ) -> Result<Vec<BomItem>> {
+            self.inner.get_bom_items(component_id, effective_date)
+        }
+
+        fn get_all_bom_items(&self) -> Result<Vec<BomItem>> {
+            self.inner.get_all_bom_items()
+        }
+
+        fn find_parents(&self, component_id: &ComponentId) -> Result<Vec<BomItem>> {
+            self.inner.find_parents(component_id)
+        }
+    }
+
+    #[test]
+    fn test_calculate_cost_incremental() {
+        let repo = InMemoryRepository::new();
+
+        // A (100) -> B (50, qty 2) -> D (10, qty 3)
+        //         -> C (30, qty 1)
+        repo.add_component(create_test_component("A", 100));
+        repo.add_component(create_test_component("B", 50));
+        repo.add_component(create_test_component("C", 30));
+        repo.add_component(create_test_component("D", 10));
+
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 3)).unwrap();
+
+        let spy = SpyRepository {
+            inner: repo,
+            loaded: std::sync::Mutex::new(Vec::new()),
+        };
+        let mut engine = BomEngine::new(spy).unwrap();
+        let a = ComponentId::new("A");
+
+        // Nothing cached yet: everything is computed once
+        engine.repository().take_loaded();
+        let costs = engine.calculate_cost_incremental(&[]).unwrap();
+        assert_eq!(costs.len(), 4);
+        // A = 100 + 2 * (50 + 3 * 10) + 30
+        assert_eq!(costs[&a].total_cost, Decimal::from(290));
+        engine.repository().take_loaded();
+
+        // Nothing changed: nothing is recomputed or loaded
+        assert!(engine.calculate_cost_incremental(&[]).unwrap().is_empty());
+        assert!(engine.repository().take_loaded().is_empty());
+
+        // D gets more expensive: only D and its ancestors are recomputed
+        engine.repository().inner.add_component(create_test_component("D", 20));
+        let costs = engine.calculate_cost_incremental(&[ComponentId::new("D")]).unwrap();
+        assert_eq!(engine.repository().take_loaded(), vec!["A", "B", "D"]);
+        assert_eq!(costs.len(), 3);
+        assert_eq!(costs[&a].total_cost, Decimal::from(350));
+
+        // The cached result is served without touching the repository
+        assert_eq!(engine.calculate_cost(&a).unwrap().total_cost, Decimal::from(350));
+        assert!(engine.repository().take_loaded().is_empty());
+    }
+
+    #[test]
+    fn test_update_component_cost_is_audited() {
+        let repo = InMemoryRepository::new();
+
+        // A (100) -> B (50, qty 2) -> D (10, qty 3)
+        repo.add_component(create_test_component("A", 100));
+        repo.add_component(create_test_component("B", 50));
+        repo.add_component(create_test_component("D", 10));
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 3)).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.db");
+
+        let a = ComponentId::new("A");
+        let d = ComponentId::new("D");
+        {
+            let cache = bom_cache::PersistentCache::new(&path).unwrap();
+            let mut engine = BomEngine::new(repo).unwrap().with_audit_log(cache);
+
+            engine.calculate_cost_incremental(&[]).unwrap();
+            engine.update_component_cost(&d, Decimal::from(20)).unwrap();
+            engine.update_component_cost(&ComponentId::new("B"), Decimal::from(40)).unwrap();
+            engine.update_component_cost(&d, Decimal::from(25)).unwrap();
+
+            // A = 100 + 2 * (40 + 3 * 25)
+            let costs = engine.calculate_cost_incremental(&[]).unwrap();
+            assert_eq!(costs[&a].total_cost, Decimal::from(330));
+        }
+
+        // The history survives reopening the database
+        let cache = bom_cache::PersistentCache::new(&path).unwrap();
+        assert_eq!(cache.query_audit(None, None, None).unwrap().len(), 3);
+
+        let operations: Vec<AuditOperation> = cache
+            .query_audit(Some(&d), None, None)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.operation)
+            .collect();
+        assert_eq!(
+            operations,
+            vec![
+                AuditOperation::UpdateComponentCost {
+                    old_cost: Some(Decimal::from(10)),
+                    new_cost: Decimal::from(20),
+                },
+                AuditOperation::UpdateComponentCost {
+                    old_cost: Some(Decimal::from(20)),
+                    new_cost: Decimal::from(25),
+                },
+            ]
+        );
+    }
+
+    /// Audit log keeping entries in memory
+    #[derive(Clone, Default)]
+    struct MemoryAuditLog(std::sync::Arc<std::sync::Mutex<Vec<AuditEntry>>>);
+
+    impl AuditLog for MemoryAuditLog {
+        fn log_audit(&self, entry: &AuditEntry) -> Result<()> {
+            self.0.lock().unwrap().push(entry.clone());
+            Ok(())
+        }
+    }
+
+    impl MemoryAuditLog {
+        fn take_operations(&self) -> Vec<AuditOperation> {
+            self.0.lock().unwrap().drain(..).map(|entry| entry.operation).collect()
+        }
+    }
+
+    #[test]
+    fn test_maintenance_is_audited() {
+        let repo = InMemoryRepository::new();
+        for id in ["A", "B", "C", "D"] {
+            repo.add_component(create_test_component(id, 10));
+        }
+
+        // A -> B (seq 10), A -> C (seq 50), B -> D
+        repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        let mut c = create_test_bom_item("A", "C", 1);
+        c.sequence = 50;
+        repo.add_bom_item(c).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 1)).unwrap();
+
+        let log = MemoryAuditLog::default();
+        let mut engine = BomEngine::new(repo).unwrap().with_audit_log(log.clone());
+        let a = ComponentId::new("A");
+
+        // Only C is renumbered (50 -> 20)
+        engine.resequence_bom(&a, 10).unwrap();
+        let entries = log.0.lock().unwrap().clone();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].operation, AuditOperation::UpdateBomItem);
+        assert_eq!(entries[0].component_id, a);
+        assert_eq!(entries[0].bom_item.as_ref().unwrap().sequence, 20);
+        log.take_operations();
+
+        engine.copy_bom(&a, &ComponentId::new("A2")).unwrap();
+        assert_eq!(log.take_operations(), vec![AuditOperation::InsertBomItem; 2]);
+
+        // B is duplicated as B-COPY: one new component and three copied items
+        engine
+            .deep_copy_bom(&a, &ComponentId::new("A3"), |id| ComponentId::new(format!("{}-COPY", id.as_str())))
+            .unwrap();
+        assert_eq!(
+            log.take_operations(),
+            vec![
+                AuditOperation::InsertBomItem,
+                AuditOperation::InsertBomItem,
+                AuditOperation::InsertComponent,
+                AuditOperation::InsertBomItem,
+            ]
+        );
+
+        // The gap left by resequencing with step 100 is closed again
+        engine.resequence_bom(&a, 100).unwrap();
+        assert_eq!(log.take_operations().len(), 2);
+        let report = engine.auto_detect_and_fix(&[crate::AutoFixIssue::ResequenceGaps]).unwrap();
+        assert_eq!(report.fixed_issues[0].1, 2);
+        assert_eq!(log.take_operations(), vec![AuditOperation::UpdateBomItem; 2]);
+    }
+
+    #[test]
+    fn test_batch_update_costs() {
+        let repo = InMemoryRepository::new();
+
+        // P (10) -> 100 children (1 each, qty 1)
+        repo.add_component(create_test_component("P", 10));
+        let mut updates = Vec::new();
+        for i in 0..100 {
+            let id = format!("C-{:03}", i);
+            repo.add_component(create_test_component(&id, 1));
+            repo.add_bom_item(create_test_bom_item("P", &id, 1)).unwrap();
+            updates.push((ComponentId::new(id), Decimal::from(2)));
+        }
+        updates.push((ComponentId::new("MISSING"), Decimal::from(2)));
+
+        let p = ComponentId::new("P");
+        let cache = std::sync::Arc::new(bom_cache::TieredCache::memory_only());
+        let mut engine = BomEngine::new(repo).unwrap().with_cost_cache(cache.clone());
+
+        let before = engine.calculate_cost(&p).unwrap();
+        assert_eq!(before.total_cost, Decimal::from(110));
+        cache.put_cost(p.clone(), before, ComponentType::FinishedProduct);
+
+        let result = engine.batch_update_costs(&updates).unwrap();
+        assert_eq!(result.updated, 100);
+        assert_eq!(result.not_found, vec![ComponentId::new("MISSING")]);
+        assert!(result.version_conflicts.is_empty());
+
+        // The ancestor's cached cost is gone and recalculation sees every new cost
+        assert!(cache.get_cost(&p).is_none());
+        assert_eq!(engine.calculate_cost(&p).unwrap().total_cost, Decimal::from(210));
+        let child = engine.repository().get_component(&ComponentId::new("C-042")).unwrap();
+        assert_eq!(child.standard_cost, Some(Decimal::from(2)));
+        assert_eq!(child.version, 1);
+    }
+
+    #[test]
+    fn test_explode_with_thread_local_cache() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B (x2) -> C (x3)
+        for id in ["A", "B", "C"] {
+            repo.add_component(create_test_component(id, 10));
+        }
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "C", 3)).unwrap();
+        let a = ComponentId::new("A");
+
+        let plain = BomEngine::new(repo.clone()).unwrap().explode(&a, Decimal::from(5)).unwrap();
+
+        let cache = std::sync::Arc::new(bom_cache::TieredCache::memory_only());
+        let mut engine = BomEngine::new(repo)
+            .unwrap()
+            .with_cost_cache(cache.clone())
+            .with_thread_local_cache();
+        engine.calculate_cost_incremental(&[]).unwrap();
+
+        let results: Vec<ExplosionResult> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| engine.explode(&a, Decimal::from(5)).unwrap()))
+                .collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+
+        let quantities = |result: &ExplosionResult| {
+            let mut quantities: Vec<(String, Decimal, usize)> = result
+                .items
+                .iter()
+                .map(|item| (item.component_id.as_str().to_string(), item.total_quantity, item.level))
+                .collect();
+            quantities.sort();
+            quantities
+        };
+        for result in &results {
+            assert_eq!(quantities(result), quantities(&plain));
+        }
+
+        // The collected costs were written back to the shared cache
+        assert_eq!(cache.get_cost(&a).unwrap().total_cost, Decimal::from(90));
+        assert_eq!(cache.get_cost(&ComponentId::new("C")).unwrap().total_cost, Decimal::from(10));
+    }
+
+    /// Cost cache recording the components of every stored batch
+    #[derive(Clone, Default)]
+    struct RecordingCostCache(std::sync::Arc<std::sync::Mutex<Vec<Vec<String>>>>);
+
+    impl CostInvalidation for RecordingCostCache {
+        fn invalidate_cost_batch(&self, _component_ids: &[ComponentId]) {}
+
+        fn store_cost_batch(&self, costs: &[(ComponentId, CostBreakdown)]) {
+            let mut ids: Vec<String> = costs.iter().map(|(id, _)| id.as_str().to_string()).collect();
+            ids.sort();
+            self.0.lock().unwrap().push(ids);
+        }
+    }
+
+    #[test]
+    fn test_thread_local_cache_stores_only_changed_costs() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B -> C
+        for id in ["A", "B", "C"] {
+            repo.add_component(create_test_component(id, 10));
+        }
+        repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "C", 1)).unwrap();
+        let a = ComponentId::new("A");
+
+        let cache = RecordingCostCache::default();
+        let mut engine = BomEngine::new(repo)
+            .unwrap()
+            .with_cost_cache(cache.clone())
+            .with_thread_local_cache();
+        engine.calculate_cost_incremental(&[]).unwrap();
+        let take_batches = || std::mem::take(&mut *cache.0.lock().unwrap());
+
+        engine.explode(&a, Decimal::ONE).unwrap();
+        assert_eq!(take_batches(), vec![vec!["A", "B", "C"]]);
+
+        // Nothing changed
+        engine.explode(&a, Decimal::from(5)).unwrap();
+        assert!(take_batches().is_empty());
+
+        // Dropped costs are stored again once recalculated
+        engine.update_component_cost(&ComponentId::new("C"), Decimal::from(20)).unwrap();
+        engine.explode(&a, Decimal::ONE).unwrap();
+        assert!(take_batches().is_empty());
+        engine.calculate_cost_incremental(&[]).unwrap();
+        engine.explode(&a, Decimal::ONE).unwrap();
+        assert_eq!(take_batches(), vec![vec!["A", "B", "C"]]);
+    }
+
+    #[test]
+    fn test_find_items_with_notes() {
+        let repo = InMemoryRepository::new();
+        for id in ["A", "B", "C", "D"] {
+            repo.add_component(create_test_component(id, 1));
+        }
+        let notes = [("B", Some("Use only with ECO-1234 approval")), ("C", Some("Torque to 40 Nm")), ("D", None)];
+        for (child, note) in notes {
+            let mut item = create_test_bom_item("A", child, 1);
+            item.notes = note.map(str::to_string);
+            repo.add_bom_item(item).unwrap();
+        }
+
+        let engine = BomEngine::new(repo).unwrap();
+        let items = engine.find_items_with_notes("eco-1234").unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].child_id.as_str(), "B");
+        assert!(engine.find_items_with_notes("ECO-9999").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_is_case_insensitive() {
+        let repo = InMemoryRepository::new();
+        let descriptions = [("TUBE-001", "Aluminum Tube 25mm"), ("TUBE-002", "Steel tube"), ("BOLT-001", "Bolt M6")];
+        for (id, description) in descriptions {
+            let mut component = create_test_component(id, 1);
+            component.description = description.to_string();
+            repo.add_component(component);
+        }
+
+        let engine = BomEngine::new(repo).unwrap();
+        let ids = |query: &str| -> Vec<String> {
+            engine
+                .search(query)
+                .unwrap()
+                .into_iter()
+                .map(|component| component.id.as_str().to_string())
+                .collect()
+        };
+
+        assert_eq!(ids("aluminum tube"), vec!["TUBE-001"]);
+        assert_eq!(ids("TUBE"), vec!["TUBE-001", "TUBE-002"]);
+        assert!(ids("copper").is_empty());
+    }
+
+    #[test]
+    fn test_generate_report_sections() {
+        let repo = InMemoryRepository::new();
+
+        // A (cost 100) -> B (cost 50, qty 2) -> D (cost 10, qty 3)
+        //              -> C (cost 30, qty 1)
+        repo.add_component(create_test_component("A", 100));
+        repo.add_component(create_test_component("B", 50));
+        repo.add_component(create_test_component("C", 30));
+        repo.add_component(create_test_component("D", 10));
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 3)).unwrap();
+
+        let engine = BomEngine::new(repo).unwrap();
+        let a = ComponentId::new("A");
+
+        // Explosion only
+        let report = engine.generate_report(&a, Decimal::from(10), crate::ReportOptions::default()).unwrap();
+        assert_eq!(report.explosion.items.len(), 4);
+        assert!(report.cost.is_none());
+        assert!(report.where_used.is_none());
+        assert!(report.lead_time.is_none());
+        assert!(report.analytics.is_none());
+
+        let markdown = report.to_markdown();
+        assert!(markdown.starts_with("# BOM Report: A\n"));
+        assert!(markdown.contains("| 2 | D | 60 |"));
+        assert!(!markdown.contains("## Cost"));
+        assert!(!markdown.contains("## Lead Time"));
+
+        // Each option adds exactly its own section
+        let options = crate::ReportOptions {
+            include_cost: true,
+            include_lead_times: true,
+            ..Default::default()
+        };
+        let report = engine.generate_report(&a, Decimal::from(10), options).unwrap();
+        assert!(report.cost.is_some());
+        assert!(report.where_used.is_none());
+        assert_eq!(report.lead_time.as_ref().unwrap().total_lead_time_days, 21);
+        assert!(report.analytics.is_none());
+
+        let report = engine.generate_report(&a, Decimal::from(10), crate::ReportOptions::all()).unwrap();
+        assert!(report.where_used.unwrap().used_in.is_empty());
+        let analytics = report.analytics.as_ref().unwrap();
+        assert_eq!(analytics.depth_profile.max_depth, 2);
+        assert_eq!(analytics.leaf_count, 2);
+
+        let markdown = engine
+            .generate_report(&a, Decimal::from(10), crate::ReportOptions::all())
+            .unwrap()
+            .to_markdown();
+        for section in ["## Material Explosion", "## Cost", "## Where Used", "## Lead Time", "## Analytics"] {
+            assert!(markdown.contains(section), "missing {}", section);
+        }
+        assert!(markdown.contains("Critical path: A → B → D"));
+
+        assert!(engine
+            .generate_report(&ComponentId::new("MISSING"), Decimal::ONE, crate::ReportOptions::all())
+            .is_err());
+    }
+
+    #[test]
+    fn test_explode_batch_keeps_request_order() {
+        let repo = InMemoryRepository::new();
+
+        // Products P0..P9, each Pn -> PART-n (n + 1)
+        let requests: Vec<(ComponentId, Decimal)> = (0..10)
+            .map(|n| {
+                repo.add_bom_item(create_test_bom_item(&format!("P{}", n), &format!("PART-{}", n), n + 1))
+                    .unwrap();
+                (ComponentId::new(format!("P{}", n)), Decimal::from(10 - n))
+            })
+            .collect();
+        let engine = BomEngine::new(repo).unwrap();
+
+        let results = engine.explode_batch(&requests).unwrap();
+        assert_eq!(results.len(), requests.len());
+        for (n, ((root, quantity), result)) in requests.iter().zip(&results).enumerate() {
+            assert_eq!(&result.root_component, root);
+            assert!(result.compare(&engine.explode(root, *quantity).unwrap()).is_empty());
+            let part = result.items.iter().find(|item| item.component_id != *root).unwrap();
+            assert_eq!(part.total_quantity, *quantity * Decimal::from(n + 1));
+        }
+
+        // Several failing requests: always the error of the first one
+        let mut with_missing = requests.clone();
+        with_missing.insert(3, (ComponentId::new("MISSING-1"), Decimal::ONE));
+        with_missing.extend((2..20).map(|n| (ComponentId::new(format!("MISSING-{}", n)), Decimal::ONE)));
+        for _ in 0..5 {
+            let error = engine.explode_batch(&with_missing).unwrap_err();
+            assert!(matches!(&error, BomError::ComponentNotFound(id) if id == "MISSING-1"), "{}", error);
+        }
+        assert!(engine.explode_batch(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_bulk_explode_to_csv_matches_explode() {
+        let repo = InMemoryRepository::new();
+
+        // Diamond with a shared part on two levels:
+        // A -> B (2) -> D (3) -> E (2)
+        //   -> C (1) -> D (1)
+        //            -> E (5)
+        for id in ["A", "B", "C", "D", "E"] {
+            repo.add_component(create_test_component(id, 10));
+        }
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 3)).unwrap();
+        repo.add_bom_item(create_test_bom_item("C", "D", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("C", "E", 5)).unwrap();
+        repo.add_bom_item(create_test_bom_item("D", "E", 2)).unwrap();
+
+        let engine = BomEngine::new(repo).unwrap();
+        let a = ComponentId::new("A");
+
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        let rows = engine.bulk_explode_to_csv(&a, Decimal::from(10), &mut cursor).unwrap();
+        let streamed = String::from_utf8(cursor.into_inner()).unwrap();
+
+        let explosion = engine.explode(&a, Decimal::from(10)).unwrap();
+        assert_eq!(rows, explosion.items.len());
+
+        // Same header and rows; only the row order may differ
+        let sorted_lines = |csv: &str| {
+            let mut lines: Vec<String> = csv.lines().map(str::to_string).collect();
+            lines[1..].sort();
+            lines
+        };
+        let streamed_lines = sorted_lines(&streamed);
+        assert_eq!(streamed_lines, sorted_lines(&explosion.to_bom_csv()));
+        assert_eq!(streamed_lines[0], BOM_CSV_HEADER);
+        assert_eq!(streamed_lines.len(), rows + 1);
+        // E: 10 * (2*3*2 + 1*1*2 + 1*5) = 190, longest path A -> B -> D -> E
+        assert!(streamed_lines.contains(&"3,E,190".to_string()));
+
+        assert!(matches!(
+            engine.bulk_explode_to_csv(&ComponentId::new("MISSING"), Decimal::ONE, std::io::sink()),
+            Err(BomError::ComponentNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_most_critical_components() {
+        let repo = InMemoryRepository::new();
+        for id in ["A", "B", "C", "D", "E"] {
+            repo.add_component(create_test_component(id, 10));
+        }
+        for (parent, child) in [("A", "B"), ("A", "C"), ("B", "D"), ("C", "D"), ("D", "E")] {
+            repo.add_bom_item(create_test_bom_item(parent, child, 1)).unwrap();
+        }
+        let engine = BomEngine::new(repo).unwrap();
+
+        let critical = engine.most_critical_components(3).unwrap();
+        let ids: Vec<&str> = critical.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["D", "B", "C"]);
+        assert!(critical[0].1 > critical[1].1);
+        assert!(engine.most_critical_components(0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_as_unified_graph_keeps_shared_components() {
+        let repo = InMemoryRepository::new();
+
+        // Two products sharing FRAME (and TUBE below it)
+        for id in ["BIKE", "TRIKE", "FRAME", "TUBE", "BASKET"] {
+            repo.add_component(create_test_component(id, 10));
+        }
+        repo.add_bom_item(create_test_bom_item("BIKE", "FRAME", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("TRIKE", "FRAME", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("TRIKE", "BASKET", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("FRAME", "TUBE", 2)).unwrap();
+
+        let engine = BomEngine::new(repo).unwrap();
+        let unified = engine.as_unified_graph(&ComponentId::new("FAMILY")).unwrap();
+        assert_eq!(unified.graph().roots().len(), 1);
+        assert_eq!(unified.stats().node_count, engine.stats().node_count + 1);
+        // The engine's own graph is untouched
+        assert_eq!(engine.graph().roots().len(), 2);
+
+        let products = [ComponentId::new("BIKE"), ComponentId::new("TRIKE")];
+        let shared_ids = |engine: &BomEngine<InMemoryRepository>| {
+            let mut ids: Vec<String> = engine
+                .find_shared_components(&products)
+                .unwrap()
+                .into_iter()
+                .map(|shared| shared.component_id.as_str().to_string())
+                .collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(shared_ids(&unified), shared_ids(&engine));
+        assert_eq!(shared_ids(&unified), ["FRAME", "TUBE"]);
+
+        // The whole family explodes from the virtual root
+        let explosion = unified.explode(&ComponentId::new("FAMILY"), Decimal::ONE).unwrap();
+        assert_eq!(explosion.unique_component_count, 6);
+    }
 }
@@ -0,0 +1,333 @@
+use bom_core::{BomError, BomRepository, Component, ComponentId, ProcurementType, Result};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use rust_xlsxwriter::{Color, Format, Workbook, Worksheet, XlsxError};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::{BomEngine, ComplexityConfig, ReportOptions};
+
+/// Sheet names of `BomEngine::export_to_excel_workbook`, in workbook order
+pub const WORKBOOK_SHEETS: [&str; 5] = ["Explosion", "Cost Breakdown", "Where Used", "Analytics", "Procurement"];
+
+/// Fill of every other data row
+const STRIPE_COLOR: u32 = 0xF2F2F2;
+
+/// Cell formats shared by all sheets
+struct Formats {
+    header: Format,
+    text: [Format; 2],
+    quantity: [Format; 2],
+    cost: [Format; 2],
+}
+
+impl Formats {
+    fn new() -> Self {
+        let striped = |format: Format, row: usize| {
+            if row == 1 {
+                format.set_background_color(Color::RGB(STRIPE_COLOR))
+            } else {
+                format
+            }
+        };
+        Self {
+            header: Format::new().set_bold(),
+            text: [0, 1].map(|row| striped(Format::new(), row)),
+            quantity: [0, 1].map(|row| striped(Format::new().set_num_format("0.####"), row)),
+            cost: [0, 1].map(|row| striped(Format::new().set_num_format("#,##0.00"), row)),
+        }
+    }
+}
+
+/// Cell of a data row
+enum Cell {
+    Text(String),
+    Quantity(Decimal),
+    Cost(Decimal),
+}
+
+/// Write a sheet: bold header row, data rows with alternating fill, columns fitted to content
+fn write_sheet(
+    workbook: &mut Workbook,
+    formats: &Formats,
+    name: &str,
+    headers: &[&str],
+    rows: Vec<Vec<Cell>>,
+) -> std::result::Result<(), XlsxError> {
+    let sheet: &mut Worksheet = workbook.add_worksheet();
+    sheet.set_name(name)?;
+    for (col, header) in headers.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, *header, &formats.header)?;
+    }
+    for (index, row) in rows.into_iter().enumerate() {
+        let (row_num, stripe) = (index as u32 + 1, index % 2);
+        for (col, cell) in row.into_iter().enumerate() {
+            let col = col as u16;
+            match cell {
+                Cell::Text(text) => sheet.write_string_with_format(row_num, col, text, &formats.text[stripe])?,
+                Cell::Quantity(value) => {
+                    sheet.write_number_with_format(row_num, col, number(value), &formats.quantity[stripe])?
+                }
+                Cell::Cost(value) => {
+                    sheet.write_number_with_format(row_num, col, number(value), &formats.cost[stripe])?
+                }
+            };
+        }
+    }
+    sheet.autofit();
+    Ok(())
+}
+
+fn number(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+fn text(value: impl Into<String>) -> Cell {
+    Cell::Text(value.into())
+}
+
+/// Build and save the workbook of `BomEngine::export_to_excel_workbook`
+pub(crate) fn export_workbook<R: BomRepository>(
+    engine: &BomEngine<R>,
+    root_id: &ComponentId,
+    quantity: Decimal,
+    path: &Path,
+) -> Result<()> {
+    let options = ReportOptions {
+        include_analytics: true,
+        ..Default::default()
+    };
+    let report = engine.generate_report(root_id, quantity, options)?;
+    let graph = engine.graph();
+    let arena = graph.arena();
+
+    let ids: Vec<ComponentId> = report.explosion.items.iter().map(|item| item.component_id.clone()).collect();
+    let components: HashMap<ComponentId, Component> = ids
+        .iter()
+        .filter_map(|id| engine.repository().get_component(id).ok())
+        .map(|component| (component.id.clone(), component))
+        .collect();
+    let describe = |id: &ComponentId| components.get(id).map(|c| c.description.clone()).unwrap_or_default();
+    let costs = engine.calculate_all_costs()?;
+
+    // Explosion: indented BOM, one row per occurrence in BOM order (by sequence, then ID)
+    let mut explosion_rows = Vec::new();
+    let root = graph
+        .find_node(root_id)
+        .ok_or_else(|| BomError::ComponentNotFound(root_id.as_str().to_string()))?;
+    let mut stack = vec![(root, 0usize, quantity)];
+    while let Some((node, level, node_qty)) = stack.pop() {
+        let Some(data) = arena.node(node) else { continue };
+        let id = &data.component_id;
+        explosion_rows.push(vec![
+            text(level.to_string()),
+            text(format!("{}{}", "  ".repeat(level), id.as_str())),
+            text(describe(id)),
+            Cell::Quantity(node_qty),
+            text(components.get(id).map(|c| c.uom.clone()).unwrap_or_default()),
+        ]);
+        let mut children: Vec<_> = arena.children(node).collect();
+        children.sort_by_key(|(_, edge)| (edge.bom_item.sequence, edge.bom_item.child_id.as_str()));
+        // Reversed so the stack pops the first child first
+        for (child, edge) in children.into_iter().rev() {
+            stack.push((child, level + 1, node_qty * edge.effective_quantity));
+        }
+    }
+
+    // Components ordered by level, then ID
+    let mut items: Vec<_> = report.explosion.items.iter().collect();
+    items.sort_by(|a, b| a.level.cmp(&b.level).then_with(|| a.component_id.as_str().cmp(b.component_id.as_str())));
+
+    let cost_rows = items
+        .iter()
+        .filter_map(|item| {
+            let cost = costs.get(&item.component_id)?;
+            Some(vec![
+                text(item.component_id.as_str()),
+                Cell::Quantity(item.total_quantity),
+                Cell::Cost(cost.material_cost),
+                Cell::Cost(cost.labor_cost),
+                Cell::Cost(cost.overhead_cost),
+                Cell::Cost(cost.subcontract_cost),
+                Cell::Cost(cost.total_cost),
+                Cell::Cost(cost.total_cost * item.total_quantity),
+            ])
+        })
+        .collect();
+
+    // Where used: every path from the root to each component below it
+    let mut where_used_rows = Vec::new();
+    for item in items.iter().filter(|item| item.level > 0) {
+        let mut paths: Vec<String> = item
+            .paths
+            .iter()
+            .map(|path| path.iter().map(|id| id.as_str()).collect::<Vec<_>>().join(" > "))
+            .collect();
+        paths.sort();
+        for path in paths {
+            let parent = path.rsplit(" > ").nth(1).unwrap_or_default().to_string();
+            where_used_rows.push(vec![text(item.component_id.as_str()), text(parent), text(path)]);
+        }
+    }
+
+    // Analytics: structure statistics and a complexity score (one point per unique
+    // component, BOM item and level)
+    let unit_rates = ComplexityConfig {
+        cost_per_unique_component: Decimal::ONE,
+        cost_per_bom_item: Decimal::ONE,
+        cost_per_level: Decimal::ONE,
+    };
+    let complexity = engine.calculate_weighted_complexity_cost(root_id, unit_rates)?;
+    let analytics = report.analytics.as_ref();
+    let count = |value: usize| Cell::Quantity(Decimal::from(value));
+    let mut analytics_rows = vec![
+        vec![text("Root component"), text(root_id.as_str())],
+        vec![text("Quantity"), Cell::Quantity(quantity)],
+        vec![text("Unique components"), count(report.explosion.unique_component_count)],
+        vec![text("Maximum depth"), count(report.explosion.max_depth)],
+    ];
+    if let Some(analytics) = analytics {
+        analytics_rows.push(vec![text("Leaf components"), count(analytics.leaf_count)]);
+        analytics_rows.push(vec![
+            text("Average depth"),
+            Cell::Quantity(Decimal::try_from(analytics.depth_profile.average_depth).unwrap_or_default()),
+        ]);
+    }
+    for driver in &complexity.complexity_drivers {
+        analytics_rows.push(vec![text(format!("{:?}", driver.factor)), count(driver.count)]);
+    }
+    analytics_rows.push(vec![text("Complexity score"), Cell::Quantity(complexity.complexity_cost)]);
+
+    // Procurement: bought components with vendor and cost
+    let procurement_rows = items
+        .iter()
+        .filter_map(|item| {
+            let component = components.get(&item.component_id)?;
+            if component.procurement_type != ProcurementType::Buy {
+                return None;
+            }
+            let unit_cost = component.standard_cost.unwrap_or(Decimal::ZERO);
+            Some(vec![
+                text(item.component_id.as_str()),
+                text(component.description.clone()),
+                text(component.preferred_vendor.clone().unwrap_or_default()),
+                Cell::Quantity(item.total_quantity),
+                Cell::Cost(unit_cost),
+                Cell::Cost(unit_cost * item.total_quantity),
+            ])
+        })
+        .collect();
+
+    let formats = Formats::new();
+    let mut workbook = Workbook::new();
+    let sheets: [(&[&str], Vec<Vec<Cell>>); 5] = [
+        (&["Level", "Component", "Description", "Quantity", "UoM"], explosion_rows),
+        (
+            &["Component", "Quantity", "Material", "Labor", "Overhead", "Subcontract", "Unit Cost", "Extended Cost"],
+            cost_rows,
+        ),
+        (&["Component", "Parent", "Path"], where_used_rows),
+        (&["Metric", "Value"], analytics_rows),
+        (&["Component", "Description", "Vendor", "Quantity", "Unit Cost", "Extended Cost"], procurement_rows),
+    ];
+    let written: std::result::Result<(), XlsxError> = (|| {
+        for (name, (headers, rows)) in WORKBOOK_SHEETS.into_iter().zip(sheets) {
+            write_sheet(&mut workbook, &formats, name, headers, rows)?;
+        }
+        workbook.save(path)
+    })();
+    written.map_err(|e| BomError::SerializationError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bom_core::repository::memory::InMemoryRepository;
+    use bom_core::*;
+    use calamine::{open_workbook, Reader, Xlsx};
+    use chrono::Utc;
+
+    fn create_test_component(id: &str, cost: i32, procurement_type: ProcurementType) -> Component {
+        Component {
+            id: ComponentId::new(id),
+            description: format!("Component {}", id),
+            component_type: ComponentType::FinishedProduct,
+            uom: "EA".to_string(),
+            standard_cost: Some(Decimal::from(cost)),
+            lead_time_days: Some(7),
+            procurement_type,
+            preferred_vendor: Some("ACME".to_string()),
+            organization: "ORG01".to_string(),
+            version: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn create_test_bom_item(parent: &str, child: &str, qty: i32) -> BomItem {
+        BomItem {
+            id: uuid::Uuid::new_v4(),
+            parent_id: ComponentId::new(parent),
+            child_id: ComponentId::new(child),
+            quantity: Decimal::from(qty),
+            scrap_factor: Decimal::ZERO,
+            sequence: 10,
+            operation_sequence: None,
+            is_phantom: false,
+            effective_from: None,
+            effective_to: None,
+            alternative_group: None,
+            alternative_priority: None,
+            reference_designator: None,
+            position: None,
+            notes: None,
+            version: 0,
+        }
+    }
+
+    #[test]
+    fn test_export_to_excel_workbook() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B (2) -> D (3), A -> C (1) -> D (1); C and D are bought
+        repo.add_component(create_test_component("A", 100, ProcurementType::Make));
+        repo.add_component(create_test_component("B", 50, ProcurementType::Make));
+        repo.add_component(create_test_component("C", 30, ProcurementType::Buy));
+        repo.add_component(create_test_component("D", 10, ProcurementType::Buy));
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 3)).unwrap();
+        repo.add_bom_item(create_test_bom_item("C", "D", 1)).unwrap();
+        let engine = BomEngine::new(repo).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bom.xlsx");
+        engine
+            .export_to_excel_workbook(&ComponentId::new("A"), Decimal::from(10), &path)
+            .unwrap();
+
+        let mut workbook: Xlsx<_> = open_workbook(&path).unwrap();
+        assert_eq!(workbook.sheet_names(), WORKBOOK_SHEETS);
+
+        let mut height = |sheet: &str| workbook.worksheet_range(sheet).unwrap().height();
+        // Header plus A, B, B/D, C, C/D
+        assert_eq!(height("Explosion"), 6);
+        // Header plus one row per unique component
+        assert_eq!(height("Cost Breakdown"), 5);
+        // B, C and D (reached through B and through C)
+        assert_eq!(height("Where Used"), 5);
+        // Header plus root, quantity, unique components, depth, leaves, average depth,
+        // three complexity drivers and the score
+        assert_eq!(height("Analytics"), 11);
+        // Header plus C and D
+        assert_eq!(height("Procurement"), 3);
+
+        let explosion = workbook.worksheet_range("Explosion").unwrap();
+        assert_eq!(explosion.get_value((2, 1)).unwrap().to_string(), "  B");
+        assert_eq!(explosion.get_value((3, 3)).unwrap().to_string(), "60");
+
+        assert!(engine
+            .export_to_excel_workbook(&ComponentId::new("MISSING"), Decimal::ONE, dir.path().join("x.xlsx"))
+            .is_err());
+    }
+}
@@ -1,18 +1,114 @@
-use bom_core::{ComponentId, ExplosionItem, ExplosionResult, Result};
+use bom_core::{BomItem, ComponentId, CostBreakdown, ExplosionItem, ExplosionResult, Result, Routing};
 use bom_graph::{level_grouping, BomGraph, NodeIndex};
+use chrono::{DateTime, Utc};
 use rayon::prelude::*;
-use rust_decimal::Decimal;
-use std::collections::HashMap;
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Net material requirements after subtracting available inventory (淨需求)
+#[derive(Debug, Clone)]
+pub struct NetRequirementsResult {
+    /// Total quantity needed per component for the production quantity
+    pub gross_requirements: HashMap<ComponentId, Decimal>,
+    /// Inventory on hand for the components in this BOM
+    pub available_inventory: HashMap<ComponentId, Decimal>,
+    /// max(0, gross - available) per component
+    pub net_requirements: HashMap<ComponentId, Decimal>,
+}
+
+/// Depth statistics of a BOM structure (used for planning horizons)
+#[derive(Debug, Clone)]
+pub struct DepthProfile {
+    /// Deepest level below the root (root = 0)
+    pub max_depth: usize,
+    /// Mean level over all unique components, root included
+    pub average_depth: f64,
+    /// Number of unique components per level
+    pub depth_histogram: HashMap<usize, usize>,
+    /// Components at the deepest level
+    pub nodes_at_max_depth: Vec<ComponentId>,
+    /// Components that are manufactured (have children and are not phantom)
+    pub total_manufacturing_steps: usize,
+}
+
+/// How exploded quantities are rounded
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QuantityRounding {
+    /// Keep full precision
+    #[default]
+    None,
+    /// Round to the given number of decimal places (half away from zero)
+    RoundTo(u32),
+    /// Round up to the given number of decimal places (`CeilTo(0)` = whole units)
+    CeilTo(u32),
+}
+
+impl QuantityRounding {
+    /// Round a quantity according to this mode
+    pub fn apply(&self, quantity: Decimal) -> Decimal {
+        match *self {
+            QuantityRounding::None => quantity,
+            QuantityRounding::RoundTo(dp) => {
+                quantity.round_dp_with_strategy(dp, RoundingStrategy::MidpointAwayFromZero)
+            }
+            QuantityRounding::CeilTo(dp) => quantity.round_dp_with_strategy(dp, RoundingStrategy::ToPositiveInfinity),
+        }
+    }
+}
+
+/// Explosion options
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExplosionConfig {
+    pub quantity_rounding: QuantityRounding,
+    /// Only follow BOM items effective at this date (`BomItem::is_effective_at`), overriding
+    /// the date the graph was built for. `None` follows every item in the graph
+    pub effective_date: Option<DateTime<Utc>>,
+}
+
+/// Organization filter for multi-plant explosions
+#[derive(Debug, Clone, Default)]
+pub struct ExplosionOptions {
+    /// Only explode components of this organization (plant)
+    pub organization_filter: Option<String>,
+    /// Keep children of other organizations as purchased leaves (inter-org transfers)
+    /// instead of dropping them; their own BOMs are never exploded
+    pub include_inter_org: bool,
+}
+
+/// Explosion with the capacity it needs per work center (工時負荷)
+#[derive(Debug, Clone)]
+pub struct RoutedExplosionResult {
+    pub explosion: ExplosionResult,
+    /// Setup plus run hours per work center
+    pub work_center_hours: HashMap<String, Decimal>,
+    /// BOM items whose `operation_sequence` is not an operation of the parent's routing
+    pub unmatched_operation_items: Vec<Uuid>,
+}
 
 /// Material explosion calculator
 /// Explodes a BOM to calculate total quantities needed
 pub struct ExplosionCalculator<'a> {
     graph: &'a BomGraph,
+    config: ExplosionConfig,
 }
 
 impl<'a> ExplosionCalculator<'a> {
     pub fn new(graph: &'a BomGraph) -> Self {
-        Self { graph }
+        Self::new_with_config(graph, ExplosionConfig::default())
+    }
+
+    pub fn new_with_config(graph: &'a BomGraph, config: ExplosionConfig) -> Self {
+        Self { graph, config }
+    }
+
+    /// Whether the explosion follows `item` at the configured effective date
+    /// Items of a graph built for that same date were already filtered when loading
+    fn is_effective(&self, item: &BomItem) -> bool {
+        match self.config.effective_date {
+            Some(date) if self.graph.effective_date() != Some(date) => item.is_effective_at(&date),
+            _ => true,
+        }
     }
 
     /// Explode BOM for a component with given quantity
@@ -21,30 +117,259 @@ impl<'a> ExplosionCalculator<'a> {
         &self,
         component_id: &ComponentId,
         quantity: Decimal,
+    ) -> Result<ExplosionResult> {
+        self.explode_pruned(component_id, quantity, &HashSet::new(), &HashSet::new())
+    }
+
+    /// Explode BOM, handing each item to `sink` as soon as its quantity is final instead of
+    /// collecting them (流式展開). Quantities and levels match `explode`, but items carry no
+    /// `paths` and arrive grouped by the graph's level grouping rather than sorted by level;
+    /// only per-node quantities and depths of the levels not yet emitted are kept in memory.
+    /// Returns the number of items emitted; an error from `sink` stops the explosion
+    pub fn explode_to_sink<F>(&self, component_id: &ComponentId, quantity: Decimal, mut sink: F) -> Result<usize>
+    where
+        F: FnMut(ExplosionItem) -> Result<()>,
+    {
+        let root = self
+            .graph
+            .find_node(component_id)
+            .ok_or_else(|| bom_core::BomError::ComponentNotFound(component_id.as_str().to_string()))?;
+        let arena = self.graph.arena();
+
+        // Flowing and reported quantities as in `explode`, phantoms are left out the same way
+        let mut quantities: HashMap<NodeIndex, Decimal> = HashMap::from([(root, quantity)]);
+        let mut reported: HashMap<NodeIndex, Decimal> = HashMap::from([(root, quantity)]);
+        // Longest path from the root, the same level `explode` reports
+        let mut depths: HashMap<NodeIndex, usize> = HashMap::from([(root, 0)]);
+        // Deepest level a node reached through a phantom item passes its children on to
+        let mut phantom_depths: HashMap<NodeIndex, usize> = HashMap::new();
+
+        let levels = self
+            .graph
+            .cached_level_grouping(&[root])
+            .unwrap_or_else(|| level_grouping(arena, &[root]));
+
+        let mut emitted = 0;
+        // Roots first: all parents of a level precede it, so its quantities are final
+        for level_nodes in levels.iter().rev() {
+            for node in level_nodes {
+                let Some(mut node_qty) = quantities.remove(node) else {
+                    continue;
+                };
+                node_qty = self.config.quantity_rounding.apply(node_qty);
+                let depth = depths.remove(node);
+                let attached = depth.max(phantom_depths.remove(node)).unwrap_or(0);
+
+                for (child_node, edge) in arena.children(*node).filter(|(_, edge)| self.is_effective(&edge.bom_item)) {
+                    let child_qty = edge.effective_quantity * node_qty;
+                    *quantities.entry(child_node).or_insert(Decimal::ZERO) += child_qty;
+                    if edge.bom_item.is_phantom {
+                        let child_depth = phantom_depths.entry(child_node).or_insert(0);
+                        *child_depth = (*child_depth).max(attached);
+                    } else {
+                        *reported.entry(child_node).or_insert(Decimal::ZERO) += child_qty;
+                        let child_depth = depths.entry(child_node).or_insert(0);
+                        *child_depth = (*child_depth).max(attached + 1);
+                    }
+                }
+
+                let Some(reported_qty) = reported.remove(node) else {
+                    continue;
+                };
+                if let Some(component) = arena.node(*node) {
+                    sink(ExplosionItem {
+                        component_id: component.component_id.clone(),
+                        total_quantity: self.config.quantity_rounding.apply(reported_qty),
+                        level: depth.unwrap_or(0),
+                        paths: Vec::new(),
+                        is_phantom: false,
+                    })?;
+                    emitted += 1;
+                }
+            }
+        }
+
+        Ok(emitted)
+    }
+
+    /// Explode BOM, skipping children outside `options.organization_filter`
+    /// `organizations` maps component IDs to organization codes; components missing
+    /// from it are treated as belonging to the filtered organization. The root is
+    /// always exploded
+    pub fn explode_with_options(
+        &self,
+        component_id: &ComponentId,
+        quantity: Decimal,
+        options: &ExplosionOptions,
+        organizations: &HashMap<ComponentId, String>,
+    ) -> Result<ExplosionResult> {
+        let Some(organization) = &options.organization_filter else {
+            return self.explode(component_id, quantity);
+        };
+
+        let root = self
+            .graph
+            .find_node(component_id)
+            .ok_or_else(|| bom_core::BomError::ComponentNotFound(component_id.as_str().to_string()))?;
+
+        let foreign: HashSet<NodeIndex> = self
+            .graph
+            .iter_nodes()
+            .filter(|(node, id)| {
+                *node != root && organizations.get(*id).is_some_and(|org| org != organization)
+            })
+            .map(|(node, _)| node)
+            .collect();
+
+        if options.include_inter_org {
+            self.explode_pruned(component_id, quantity, &HashSet::new(), &foreign)
+        } else {
+            self.explode_pruned(component_id, quantity, &foreign, &HashSet::new())
+        }
+    }
+
+    /// Explode BOM and collect the cached costs of the exploded components in `local_cache`
+    /// Components whose node holds a clean `NodeCache::total_material_cost` (see
+    /// `BomEngine::calculate_cost_incremental`) are added unless already present, so one
+    /// thread can gather costs without touching a shared cache. The explosion itself is
+    /// the same as `explode`
+    pub fn explode_with_local_cache(
+        &self,
+        component_id: &ComponentId,
+        quantity: Decimal,
+        local_cache: &mut HashMap<ComponentId, CostBreakdown>,
+    ) -> Result<ExplosionResult> {
+        let result = self.explode(component_id, quantity)?;
+
+        for item in &result.items {
+            if local_cache.contains_key(&item.component_id) {
+                continue;
+            }
+            let cached_cost = self
+                .graph
+                .find_node(&item.component_id)
+                .and_then(|node| self.graph.arena().node(node))
+                .filter(|node| !node.dirty)
+                .and_then(|node| node.cache.total_material_cost);
+            if let Some(cost) = cached_cost {
+                local_cache.insert(
+                    item.component_id.clone(),
+                    CostBreakdown {
+                        component_id: item.component_id.clone(),
+                        material_cost: cost,
+                        labor_cost: Decimal::ZERO,
+                        overhead_cost: Decimal::ZERO,
+                        subcontract_cost: Decimal::ZERO,
+                        total_cost: cost,
+                        calculated_at: chrono::Utc::now(),
+                    },
+                );
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Explode BOM and add up the routing hours of every manufactured component
+    /// Each operation of a component's routing costs `setup_time_hours` once plus
+    /// `run_time_per_unit_hours` for every unit required. Phantoms are not produced and
+    /// components without a routing add no hours
+    pub fn explode_with_routing(
+        &self,
+        component_id: &ComponentId,
+        quantity: Decimal,
+        routings: &HashMap<ComponentId, Routing>,
+    ) -> Result<RoutedExplosionResult> {
+        let explosion = self.explode(component_id, quantity)?;
+        let arena = self.graph.arena();
+
+        let mut work_center_hours: HashMap<String, Decimal> = HashMap::new();
+        let mut unmatched_operation_items = Vec::new();
+
+        for item in explosion.items.iter().filter(|item| !item.is_phantom) {
+            let Some(routing) = routings.get(&item.component_id) else {
+                continue;
+            };
+
+            for operation in &routing.operations {
+                *work_center_hours.entry(operation.work_center.clone()).or_default() +=
+                    operation.setup_time_hours + operation.run_time_per_unit_hours * item.total_quantity;
+            }
+
+            let Some(node) = self.graph.find_node(&item.component_id) else {
+                continue;
+            };
+            for (_, edge) in arena.children(node) {
+                if let Some(sequence) = &edge.bom_item.operation_sequence {
+                    if !routing.operations.iter().any(|operation| &operation.sequence == sequence) {
+                        unmatched_operation_items.push(edge.bom_item.id);
+                    }
+                }
+            }
+        }
+
+        Ok(RoutedExplosionResult {
+            explosion,
+            work_center_hours,
+            unmatched_operation_items,
+        })
+    }
+
+    /// Explosion that never reaches `excluded` nodes and does not descend below `leaves`
+    fn explode_pruned(
+        &self,
+        component_id: &ComponentId,
+        quantity: Decimal,
+        excluded: &HashSet<NodeIndex>,
+        leaves: &HashSet<NodeIndex>,
     ) -> Result<ExplosionResult> {
         let node = self
             .graph
             .find_node(component_id)
             .ok_or_else(|| bom_core::BomError::ComponentNotFound(component_id.as_str().to_string()))?;
 
+        // `quantities` is what flows down to a node's children, `reported` only what reaches it
+        // through non-phantom items. Phantoms are transparent: a node reached through a phantom
+        // item passes its quantity on to its children without appearing itself, and its paths
+        // end at its parent so the children are attributed there
         let mut quantities: HashMap<NodeIndex, Decimal> = HashMap::new();
+        let mut reported: HashMap<NodeIndex, Decimal> = HashMap::new();
         let mut paths: HashMap<NodeIndex, Vec<Vec<NodeIndex>>> = HashMap::new();
 
         // Initialize root
         quantities.insert(node, quantity);
+        reported.insert(node, quantity);
         paths.insert(node, vec![vec![node]]);
 
-        // Get level grouping for parallel processing
-        let levels = level_grouping(self.graph.arena(), &[node]);
+        // Get level grouping for parallel processing (precomputed levels if available)
+        let levels = self
+            .graph
+            .cached_level_grouping(&[node])
+            .unwrap_or_else(|| level_grouping(self.graph.arena(), &[node]));
 
         // Process each level from top to bottom (reverse of level_grouping order)
         // Level grouping returns [level 0 = leaves, ..., level N = roots]
         // We need to process from roots to leaves
-        for (_level_idx, level_nodes) in levels.iter().rev().enumerate() {
+        for level_nodes in levels.iter().rev() {
+            // All parents of this level were processed, so its quantities are final
+            if self.config.quantity_rounding != QuantityRounding::None {
+                for node in level_nodes {
+                    for quantities in [&mut quantities, &mut reported] {
+                        if let Some(quantity) = quantities.get_mut(node) {
+                            *quantity = self.config.quantity_rounding.apply(*quantity);
+                        }
+                    }
+                }
+            }
+
             // Process all nodes in this level in parallel
             let level_results: Vec<_> = level_nodes
                 .par_iter()
                 .filter_map(|&parent_node| {
+                    if leaves.contains(&parent_node) {
+                        return None;
+                    }
+
                     // Get quantity for this parent
                     let parent_qty = quantities.get(&parent_node)?;
 
@@ -53,15 +378,20 @@ impl<'a> ExplosionCalculator<'a> {
                         .graph
                         .arena()
                         .children(parent_node)
+                        .filter(|(child_node, edge)| {
+                            !excluded.contains(child_node) && self.is_effective(&edge.bom_item)
+                        })
                         .map(|(child_node, edge)| {
                             let child_qty = edge.effective_quantity * parent_qty;
 
-                            // Build paths: prepend parent to all parent's paths
+                            // Build paths: append child to all parent's paths (phantoms stay off them)
                             let mut child_paths = Vec::new();
                             if let Some(parent_paths) = paths.get(&parent_node) {
                                 for parent_path in parent_paths {
                                     let mut new_path = parent_path.clone();
-                                    new_path.push(child_node);
+                                    if !edge.bom_item.is_phantom {
+                                        new_path.push(child_node);
+                                    }
                                     child_paths.push(new_path);
                                 }
                             }
@@ -76,34 +406,43 @@ impl<'a> ExplosionCalculator<'a> {
 
             // Aggregate results (must be done sequentially due to HashMap)
             for (_parent_node, children_data) in level_results {
-                for (child_node, child_qty, child_paths, _is_phantom) in children_data {
+                for (child_node, child_qty, child_paths, is_phantom) in children_data {
                     // Accumulate quantity
                     *quantities.entry(child_node).or_insert(Decimal::ZERO) += child_qty;
+                    if !is_phantom {
+                        *reported.entry(child_node).or_insert(Decimal::ZERO) += child_qty;
+                    }
 
                     // Accumulate paths
-                    paths.entry(child_node).or_insert_with(Vec::new).extend(child_paths);
+                    paths.entry(child_node).or_default().extend(child_paths);
                 }
             }
         }
 
         // Build result
-        let mut items: Vec<ExplosionItem> = quantities
+        let mut items: Vec<ExplosionItem> = reported
             .into_iter()
             .filter_map(|(node_idx, total_quantity)| {
                 let node = self.graph.arena().node(node_idx)?;
 
+                // Paths through a phantom item end above the node and are not its own
+                let node_paths: Vec<Vec<NodeIndex>> = paths
+                    .remove(&node_idx)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|path| path.last() == Some(&node_idx))
+                    .collect();
+
                 // Calculate level (max path length - 1)
-                let level = paths
-                    .get(&node_idx)
-                    .and_then(|p| p.iter().map(|path| path.len()).max())
+                let level = node_paths
+                    .iter()
+                    .map(|path| path.len())
+                    .max()
                     .map(|len| len.saturating_sub(1))
                     .unwrap_or(0);
 
                 // Convert NodeIndex paths to ComponentId paths
-                let component_paths: Vec<Vec<ComponentId>> = paths
-                    .get(&node_idx)
-                    .cloned()
-                    .unwrap_or_default()
+                let component_paths: Vec<Vec<ComponentId>> = node_paths
                     .into_iter()
                     .filter_map(|path| {
                         let comp_path: Vec<ComponentId> = path
@@ -121,7 +460,8 @@ impl<'a> ExplosionCalculator<'a> {
                     total_quantity,
                     level,
                     paths: component_paths,
-                    is_phantom: false, // TODO: get from component data
+                    // Phantoms never make it into the result
+                    is_phantom: false,
                 })
             })
             .collect();
@@ -158,6 +498,7 @@ impl<'a> ExplosionCalculator<'a> {
             .graph
             .arena()
             .children(node)
+            .filter(|(_, edge)| self.is_effective(&edge.bom_item))
             .map(|(child_node, edge)| {
                 let child = self.graph.arena().node(child_node).unwrap();
                 let total_quantity = edge.effective_quantity * quantity;
@@ -188,6 +529,83 @@ impl<'a> ExplosionCalculator<'a> {
 
         Ok(flattened)
     }
+
+    /// Calculate net requirements for producing `production_qty` of a component
+    /// Inventory is netted per component; stock of a sub-assembly does not reduce
+    /// the requirements of its children
+    pub fn calculate_net_requirements(
+        &self,
+        root_id: &ComponentId,
+        production_qty: Decimal,
+        inventory: &HashMap<ComponentId, Decimal>,
+    ) -> Result<NetRequirementsResult> {
+        let gross_requirements: HashMap<ComponentId, Decimal> = self
+            .flatten(root_id)?
+            .into_iter()
+            .map(|(component_id, quantity)| (component_id, quantity * production_qty))
+            .collect();
+
+        let mut available_inventory = HashMap::new();
+        let mut net_requirements = HashMap::new();
+
+        for (component_id, gross) in &gross_requirements {
+            let available = inventory.get(component_id).copied().unwrap_or(Decimal::ZERO);
+            if available > Decimal::ZERO {
+                available_inventory.insert(component_id.clone(), available);
+            }
+            net_requirements.insert(component_id.clone(), (*gross - available).max(Decimal::ZERO));
+        }
+
+        Ok(NetRequirementsResult {
+            gross_requirements,
+            available_inventory,
+            net_requirements,
+        })
+    }
+
+    /// Analyze how deep the BOM below `root_id` is
+    /// Each component is counted once, at its deepest level (same as `explode`).
+    /// Phantoms are left out like in `explode`, their children count one level up
+    pub fn analyze_depth_profile(&self, root_id: &ComponentId) -> Result<DepthProfile> {
+        let result = self.explode(root_id, Decimal::ONE)?;
+        let arena = self.graph.arena();
+
+        let in_bom: HashMap<NodeIndex, usize> = result
+            .items
+            .iter()
+            .filter_map(|item| Some((self.graph.find_node(&item.component_id)?, item.level)))
+            .collect();
+
+        let mut depth_histogram: HashMap<usize, usize> = HashMap::new();
+        let mut total_manufacturing_steps = 0;
+
+        for (&node, &level) in &in_bom {
+            *depth_histogram.entry(level).or_insert(0) += 1;
+
+            if arena.children(node).any(|(_, edge)| self.is_effective(&edge.bom_item)) {
+                total_manufacturing_steps += 1;
+            }
+        }
+
+        let mut nodes_at_max_depth: Vec<ComponentId> = result
+            .items
+            .iter()
+            .filter(|item| item.level == result.max_depth)
+            .map(|item| item.component_id.clone())
+            .collect();
+        nodes_at_max_depth.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        let level_sum: usize = result.items.iter().map(|item| item.level).sum();
+        let average_depth = level_sum as f64 / result.unique_component_count as f64;
+
+        Ok(DepthProfile {
+            max_depth: result.max_depth,
+            average_depth,
+            depth_histogram,
+            nodes_at_max_depth,
+            total_manufacturing_steps,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -197,6 +615,7 @@ mod tests {
     use bom_core::*;
     use bom_graph::BomGraph;
     use chrono::Utc;
+    use std::str::FromStr;
 
     fn create_test_component(id: &str) -> Component {
         Component {
@@ -207,6 +626,7 @@ mod tests {
             standard_cost: Some(Decimal::from(100)),
             lead_time_days: Some(7),
             procurement_type: ProcurementType::Make,
+            preferred_vendor: None,
             organization: "ORG01".to_string(),
             version: 0,
             created_at: Utc::now(),
@@ -235,6 +655,15 @@ mod tests {
         }
     }
 
+    // Exact decimals for quantities and scrap factors that an i32 cannot express
+    fn create_scrap_bom_item(parent: &str, child: &str, qty: &str, scrap_factor: &str) -> BomItem {
+        BomItem {
+            quantity: Decimal::from_str(qty).unwrap(),
+            scrap_factor: Decimal::from_str(scrap_factor).unwrap(),
+            ..create_test_bom_item(parent, child, 1)
+        }
+    }
+
     #[test]
     fn test_simple_explosion() {
         let repo = InMemoryRepository::new();
@@ -245,8 +674,8 @@ mod tests {
         repo.add_component(create_test_component("B"));
         repo.add_component(create_test_component("C"));
 
-        repo.add_bom_item(create_test_bom_item("A", "B", 2));
-        repo.add_bom_item(create_test_bom_item("A", "C", 3));
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 3)).unwrap();
 
         let graph = BomGraph::from_repository(&repo).unwrap();
         let calc = ExplosionCalculator::new(&graph);
@@ -282,10 +711,10 @@ mod tests {
         repo.add_component(create_test_component("C"));
         repo.add_component(create_test_component("D"));
 
-        repo.add_bom_item(create_test_bom_item("A", "B", 2));
-        repo.add_bom_item(create_test_bom_item("A", "C", 1));
-        repo.add_bom_item(create_test_bom_item("B", "D", 3));
-        repo.add_bom_item(create_test_bom_item("C", "D", 2));
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 3)).unwrap();
+        repo.add_bom_item(create_test_bom_item("C", "D", 2)).unwrap();
 
         let graph = BomGraph::from_repository(&repo).unwrap();
         let calc = ExplosionCalculator::new(&graph);
@@ -316,9 +745,9 @@ mod tests {
         repo.add_component(create_test_component("C"));
         repo.add_component(create_test_component("D"));
 
-        repo.add_bom_item(create_test_bom_item("A", "B", 2));
-        repo.add_bom_item(create_test_bom_item("A", "C", 3));
-        repo.add_bom_item(create_test_bom_item("B", "D", 5));
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 3)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 5)).unwrap();
 
         let graph = BomGraph::from_repository(&repo).unwrap();
         let calc = ExplosionCalculator::new(&graph);
@@ -333,4 +762,450 @@ mod tests {
         assert!(result.iter().any(|item| item.component_id.as_str() == "C"));
         assert!(!result.iter().any(|item| item.component_id.as_str() == "D"));
     }
+
+    #[test]
+    fn test_calculate_net_requirements() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B (qty 2) -> C (qty 4)
+        repo.add_component(create_test_component("A"));
+        repo.add_component(create_test_component("B"));
+        repo.add_component(create_test_component("C"));
+
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "C", 4)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let calculator = ExplosionCalculator::new(&graph);
+
+        let mut inventory = HashMap::new();
+        inventory.insert(ComponentId::new("C"), Decimal::from(5));
+        inventory.insert(ComponentId::new("B"), Decimal::from(10));
+
+        // Producing 1 A needs 2 B and 8 C
+        let result = calculator
+            .calculate_net_requirements(&ComponentId::new("A"), Decimal::ONE, &inventory)
+            .unwrap();
+
+        let c = ComponentId::new("C");
+        assert_eq!(result.gross_requirements.get(&c), Some(&Decimal::from(8)));
+        assert_eq!(result.available_inventory.get(&c), Some(&Decimal::from(5)));
+        assert_eq!(result.net_requirements.get(&c), Some(&Decimal::from(3)));
+
+        // Surplus stock never yields a negative requirement
+        assert_eq!(result.net_requirements.get(&ComponentId::new("B")), Some(&Decimal::ZERO));
+        assert_eq!(result.net_requirements.get(&ComponentId::new("A")), Some(&Decimal::ONE));
+    }
+
+    #[test]
+    fn test_explode_lazy_graph() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B (qty 2) -> C (qty 3); X -> Y is never loaded
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "C", 3)).unwrap();
+        repo.add_bom_item(create_test_bom_item("X", "Y", 1)).unwrap();
+
+        let mut lazy = bom_graph::LazyBomGraph::new(std::sync::Arc::new(repo));
+        let graph = lazy.load_subtree(&ComponentId::new("A")).unwrap();
+        assert!(graph.find_node(&ComponentId::new("X")).is_none());
+
+        let calculator = ExplosionCalculator::new(graph);
+        let flattened = calculator.flatten(&ComponentId::new("A")).unwrap();
+        assert_eq!(flattened.get(&ComponentId::new("C")), Some(&Decimal::from(6)));
+    }
+
+    #[test]
+    fn test_analyze_depth_profile() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B -> C -> E
+        // A -> C
+        // A -> D (phantom) -> F
+        repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("C", "E", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        let mut phantom = create_test_bom_item("A", "D", 1);
+        phantom.is_phantom = true;
+        repo.add_bom_item(phantom).unwrap();
+        repo.add_bom_item(create_test_bom_item("D", "F", 1)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let calculator = ExplosionCalculator::new(&graph);
+        let root = ComponentId::new("A");
+
+        let profile = calculator.analyze_depth_profile(&root).unwrap();
+        let explosion = calculator.explode(&root, Decimal::ONE).unwrap();
+
+        // C is counted once, at its deepest level (via B); phantom D is skipped and F moves up to A
+        assert_eq!(profile.max_depth, 3);
+        assert_eq!(profile.nodes_at_max_depth, vec![ComponentId::new("E")]);
+        assert_eq!(profile.depth_histogram.get(&1), Some(&2));
+        assert_eq!(profile.depth_histogram.get(&2), Some(&1));
+        assert_eq!(
+            profile.depth_histogram.values().sum::<usize>(),
+            explosion.unique_component_count
+        );
+        assert!((profile.average_depth - 1.4).abs() < f64::EPSILON);
+
+        // A, B and C are manufactured; E and F are leaves
+        assert_eq!(profile.total_manufacturing_steps, 3);
+    }
+
+    #[test]
+    fn test_explode_with_quantity_rounding() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B (qty 2, scrap 0.000005 => 2.00001 per A) -> C (qty 1)
+        let mut item = create_test_bom_item("A", "B", 2);
+        item.scrap_factor = Decimal::new(5, 6);
+        repo.add_bom_item(item).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "C", 1)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let root = ComponentId::new("A");
+        let quantity_of = |config: ExplosionConfig, id: &str| {
+            ExplosionCalculator::new_with_config(&graph, config)
+                .explode(&root, Decimal::ONE)
+                .unwrap()
+                .items
+                .into_iter()
+                .find(|item| item.component_id.as_str() == id)
+                .unwrap()
+                .total_quantity
+        };
+
+        assert_eq!(quantity_of(ExplosionConfig::default(), "B"), Decimal::new(200001, 5));
+
+        // Whole units for purchasing; C inherits the rounded-up quantity of B
+        let ceil = ExplosionConfig {
+            quantity_rounding: QuantityRounding::CeilTo(0),
+            ..Default::default()
+        };
+        assert_eq!(quantity_of(ceil, "B"), Decimal::from(3));
+        assert_eq!(quantity_of(ceil, "C"), Decimal::from(3));
+
+        let round = ExplosionConfig {
+            quantity_rounding: QuantityRounding::RoundTo(1),
+            ..Default::default()
+        };
+        assert_eq!(quantity_of(round, "B"), Decimal::new(20, 1));
+    }
+
+    #[test]
+    fn test_explode_at_effective_date() {
+        use chrono::TimeZone;
+
+        let repo = InMemoryRepository::new();
+        let cutoff = Utc.with_ymd_and_hms(2024, 7, 1, 0, 0, 0).unwrap();
+
+        // A -> B (qty 2) -> D (qty 1) until the cutoff, A -> C (qty 3) from the cutoff on
+        let mut old = create_test_bom_item("A", "B", 2);
+        old.effective_to = Some(cutoff - chrono::Duration::seconds(1));
+        let mut new = create_test_bom_item("A", "C", 3);
+        new.effective_from = Some(cutoff);
+        repo.add_bom_item(old).unwrap();
+        repo.add_bom_item(new).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 1)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let root = ComponentId::new("A");
+        let explode_at = |date: Option<DateTime<Utc>>| {
+            let config = ExplosionConfig {
+                effective_date: date,
+                ..Default::default()
+            };
+            let mut items: Vec<(String, Decimal)> = ExplosionCalculator::new_with_config(&graph, config)
+                .explode(&root, Decimal::from(10))
+                .unwrap()
+                .items
+                .into_iter()
+                .map(|item| (item.component_id.as_str().to_string(), item.total_quantity))
+                .collect();
+            items.sort();
+            items
+        };
+        let item = |id: &str, qty: i32| (id.to_string(), Decimal::from(qty));
+
+        assert_eq!(
+            explode_at(Some(cutoff - chrono::Duration::days(30))),
+            vec![item("A", 10), item("B", 20), item("D", 20)]
+        );
+        assert_eq!(explode_at(Some(cutoff)), vec![item("A", 10), item("C", 30)]);
+        // Without a date every item in the graph is followed
+        assert_eq!(
+            explode_at(None),
+            vec![item("A", 10), item("B", 20), item("C", 30), item("D", 20)]
+        );
+
+        // A graph loaded for a date remembers it
+        let dated = BomGraph::from_component(&repo, &root, Some(cutoff)).unwrap();
+        assert_eq!(dated.effective_date(), Some(cutoff));
+        assert_eq!(graph.effective_date(), None);
+    }
+
+    #[test]
+    fn test_explode_serialized_graph() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B (qty 2, scrap 10%) -> D (qty 3)
+        // A -> C (phantom, qty 1) -> D (qty 4)
+        repo.add_bom_item(create_scrap_bom_item("A", "B", "2", "0.10")).unwrap();
+        let mut phantom = create_test_bom_item("A", "C", 1);
+        phantom.is_phantom = true;
+        repo.add_bom_item(phantom).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 3)).unwrap();
+        repo.add_bom_item(create_test_bom_item("C", "D", 4)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let root = ComponentId::new("A");
+        let explode = |graph: &BomGraph| {
+            let mut items: Vec<(String, Decimal, usize)> = ExplosionCalculator::new(graph)
+                .explode(&root, Decimal::from(10))
+                .unwrap()
+                .items
+                .into_iter()
+                .map(|item| (item.component_id.as_str().to_string(), item.total_quantity, item.level))
+                .collect();
+            items.sort();
+            items
+        };
+
+        let restored: BomGraph = serde_json::from_str(&serde_json::to_string(&graph).unwrap()).unwrap();
+        assert_eq!(explode(&restored), explode(&graph));
+        assert_eq!(explode(&graph)[2], ("D".to_string(), Decimal::from(106), 2));
+
+        // The versioned snapshot rebuilds the graph from its BOM items and explodes the same
+        let reloaded = BomGraph::from_json(&graph.to_json().unwrap()).unwrap();
+        assert_eq!(explode(&reloaded), explode(&graph));
+    }
+
+    #[test]
+    fn test_explode_with_organization_filter() {
+        // A (PLANT-A) -> B (PLANT-A) -> D (PLANT-A)
+        //             -> C (PLANT-B) -> E (PLANT-B)
+        let organizations: HashMap<ComponentId, String> = [
+            ("A", "PLANT-A"),
+            ("B", "PLANT-A"),
+            ("C", "PLANT-B"),
+            ("D", "PLANT-A"),
+            ("E", "PLANT-B"),
+        ]
+        .into_iter()
+        .map(|(id, org)| (ComponentId::new(id), org.to_string()))
+        .collect();
+
+        let mut graph = BomGraph::new();
+        graph.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("A", "C", 2)).unwrap();
+        graph.add_bom_item(create_test_bom_item("B", "D", 3)).unwrap();
+        graph.add_bom_item(create_test_bom_item("C", "E", 4)).unwrap();
+
+        let calculator = ExplosionCalculator::new(&graph);
+        let ids = |result: &ExplosionResult| {
+            let mut ids: Vec<String> = result.items.iter().map(|i| i.component_id.as_str().to_string()).collect();
+            ids.sort();
+            ids
+        };
+
+        let mut options = ExplosionOptions {
+            organization_filter: Some("PLANT-A".to_string()),
+            include_inter_org: false,
+        };
+        let result = calculator
+            .explode_with_options(&ComponentId::new("A"), Decimal::ONE, &options, &organizations)
+            .unwrap();
+        assert_eq!(ids(&result), vec!["A", "B", "D"]);
+
+        // Inter-org components stay as leaves, their BOMs are not exploded
+        options.include_inter_org = true;
+        let result = calculator
+            .explode_with_options(&ComponentId::new("A"), Decimal::ONE, &options, &organizations)
+            .unwrap();
+        assert_eq!(ids(&result), vec!["A", "B", "C", "D"]);
+        let c = result.items.iter().find(|i| i.component_id.as_str() == "C").unwrap();
+        assert_eq!(c.total_quantity, Decimal::from(2));
+
+        // No filter: full explosion
+        let result = calculator
+            .explode_with_options(&ComponentId::new("A"), Decimal::ONE, &ExplosionOptions::default(), &organizations)
+            .unwrap();
+        assert_eq!(result.unique_component_count, 5);
+    }
+
+    #[test]
+    fn test_contracted_phantoms_flatten_the_same() {
+        // A -> P1 (phantom, 2) -> P2 (phantom, 3) -> C (4)
+        //                      -> D (5)
+        //   -> C (1)
+        let mut graph = BomGraph::new();
+        let mut p1 = create_test_bom_item("A", "P1", 2);
+        p1.is_phantom = true;
+        let mut p2 = create_test_bom_item("P1", "P2", 3);
+        p2.is_phantom = true;
+        graph.add_bom_item(p1).unwrap();
+        graph.add_bom_item(p2).unwrap();
+        graph.add_bom_item(create_test_bom_item("P2", "C", 4)).unwrap();
+        graph.add_bom_item(create_test_bom_item("P1", "D", 5)).unwrap();
+        graph.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+
+        let mut original = ExplosionCalculator::new(&graph).flatten(&ComponentId::new("A")).unwrap();
+        original.remove(&ComponentId::new("P1"));
+        original.remove(&ComponentId::new("P2"));
+
+        let contracted_graph = graph.contract_phantoms();
+        let contracted = ExplosionCalculator::new(&contracted_graph)
+            .flatten(&ComponentId::new("A"))
+            .unwrap();
+
+        assert_eq!(contracted, original);
+        assert_eq!(contracted[&ComponentId::new("C")], Decimal::from(25));
+        assert_eq!(contracted[&ComponentId::new("D")], Decimal::from(10));
+    }
+
+    #[test]
+    fn test_scrap_compounds_across_levels() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B (qty 2, scrap 10%) -> C (qty 3, scrap 5%)
+        repo.add_bom_item(create_scrap_bom_item("A", "B", "2", "0.10")).unwrap();
+        repo.add_bom_item(create_scrap_bom_item("B", "C", "3", "0.05")).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let result = ExplosionCalculator::new(&graph)
+            .explode(&ComponentId::new("A"), Decimal::from(100))
+            .unwrap();
+        let quantity_of = |id: &str| {
+            result
+                .items
+                .iter()
+                .find(|item| item.component_id.as_str() == id)
+                .unwrap()
+                .total_quantity
+        };
+
+        // B: 100 x 2 x 1.10, C: 100 x 2 x 1.10 x 3 x 1.05, both exact
+        assert_eq!(quantity_of("B"), Decimal::from_str("220").unwrap());
+        assert_eq!(quantity_of("C"), Decimal::from_str("693").unwrap());
+        assert_eq!(
+            quantity_of("C"),
+            Decimal::from(100)
+                * Decimal::from(2)
+                * Decimal::from_str("1.10").unwrap()
+                * Decimal::from(3)
+                * Decimal::from_str("1.05").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_phantom_expansion() {
+        let repo = InMemoryRepository::new();
+
+        // A -> P (phantom, qty 2) -> C (qty 3)
+        let mut phantom = create_test_bom_item("A", "P", 2);
+        phantom.is_phantom = true;
+        repo.add_bom_item(phantom).unwrap();
+        repo.add_bom_item(create_test_bom_item("P", "C", 3)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let calc = ExplosionCalculator::new(&graph);
+        let root = ComponentId::new("A");
+
+        let result = calc.explode(&root, Decimal::from(5)).unwrap();
+        assert_eq!(result.unique_component_count, 2);
+        assert!(result.items.iter().all(|item| item.component_id.as_str() != "P"));
+
+        // C is attributed to A: 5 x 2 x 3, one level below it
+        let c_item = result
+            .items
+            .iter()
+            .find(|item| item.component_id.as_str() == "C")
+            .unwrap();
+        assert_eq!(c_item.total_quantity, Decimal::from(30));
+        assert_eq!(c_item.level, 1);
+        assert_eq!(c_item.paths, vec![vec![root.clone(), ComponentId::new("C")]]);
+
+        let mut streamed = Vec::new();
+        calc.explode_to_sink(&root, Decimal::from(5), |item| {
+            streamed.push((item.component_id.as_str().to_string(), item.total_quantity, item.level));
+            Ok(())
+        })
+        .unwrap();
+        streamed.sort();
+        assert_eq!(
+            streamed,
+            vec![("A".to_string(), Decimal::from(5), 0), ("C".to_string(), Decimal::from(30), 1)]
+        );
+    }
+
+    #[test]
+    fn test_explode_with_precomputed_levels() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B (qty 2) -> C (qty 3), A -> C (qty 1), X -> B (outside A's BOM)
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "C", 3)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("X", "B", 1)).unwrap();
+
+        let mut graph = BomGraph::from_repository(&repo).unwrap();
+        let cold = ExplosionCalculator::new(&graph).flatten(&ComponentId::new("A")).unwrap();
+
+        graph.precompute_levels();
+        let warm = ExplosionCalculator::new(&graph).flatten(&ComponentId::new("A")).unwrap();
+
+        assert_eq!(warm, cold);
+        assert_eq!(warm.get(&ComponentId::new("C")), Some(&Decimal::from(7)));
+    }
+
+    #[test]
+    fn test_explode_with_routing() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B (qty 2) consumed at operation 0010, A -> C (qty 1) at 0030 (not in the routing)
+        let mut a_b = create_test_bom_item("A", "B", 2);
+        a_b.operation_sequence = Some("0010".to_string());
+        let mut a_c = create_test_bom_item("A", "C", 1);
+        a_c.operation_sequence = Some("0030".to_string());
+        let unmatched = a_c.id;
+        repo.add_bom_item(a_b).unwrap();
+        repo.add_bom_item(a_c).unwrap();
+
+        let operation = |sequence: &str, work_center: &str, setup: Decimal, run: Decimal| RoutingOperation {
+            sequence: sequence.to_string(),
+            work_center: work_center.to_string(),
+            setup_time_hours: setup,
+            run_time_per_unit_hours: run,
+        };
+        repo.add_routing(Routing {
+            component_id: ComponentId::new("A"),
+            operations: vec![
+                operation("0010", "ASSY", Decimal::new(15, 1), Decimal::new(25, 2)),
+                operation("0020", "PAINT", Decimal::new(5, 1), Decimal::new(1, 1)),
+            ],
+        });
+        repo.add_routing(Routing {
+            component_id: ComponentId::new("B"),
+            operations: vec![operation("0010", "CNC", Decimal::from(2), Decimal::new(5, 1))],
+        });
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let routings: HashMap<ComponentId, Routing> = graph
+            .iter_nodes()
+            .filter_map(|(_, id)| repo.get_routing(id).unwrap().map(|routing| (id.clone(), routing)))
+            .collect();
+
+        let result = ExplosionCalculator::new(&graph)
+            .explode_with_routing(&ComponentId::new("A"), Decimal::from(10), &routings)
+            .unwrap();
+
+        // A: 10 units, B: 20 units, C has no routing
+        assert_eq!(result.work_center_hours.len(), 3);
+        assert_eq!(result.work_center_hours["ASSY"], Decimal::from(4)); // 1.5 + 10 * 0.25
+        assert_eq!(result.work_center_hours["PAINT"], Decimal::new(15, 1)); // 0.5 + 10 * 0.1
+        assert_eq!(result.work_center_hours["CNC"], Decimal::from(12)); // 2 + 20 * 0.5
+        assert_eq!(result.unmatched_operation_items, vec![unmatched]);
+        assert_eq!(result.explosion.items.len(), 3);
+    }
 }
@@ -2,8 +2,23 @@ pub mod explosion;
 pub mod costing;
 pub mod where_used;
 pub mod engine;
+pub mod maintenance;
+pub mod validation;
+pub mod planning;
+pub mod substitution;
+pub mod report;
+pub mod testing;
+#[cfg(feature = "excel")]
+pub mod excel;
 
 pub use explosion::*;
 pub use costing::*;
 pub use where_used::*;
 pub use engine::*;
+pub use maintenance::*;
+pub use validation::*;
+pub use planning::*;
+pub use substitution::*;
+pub use report::*;
+#[cfg(feature = "excel")]
+pub use excel::*;
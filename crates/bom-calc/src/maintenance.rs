@@ -0,0 +1,1129 @@
+use bom_core::{BomError, BomItem, BomRepository, ComponentId, Result};
+use bom_graph::BomGraph;
+use chrono::{DateTime, Utc};
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Default gap between sequence numbers when new numbers must be assigned
+pub const DEFAULT_SEQUENCE_STEP: u32 = 10;
+
+/// BOM maintenance operations (資料維護)
+/// Applies structural changes to both the repository and the in-memory graph
+pub struct BomMaintainer<'a, R: BomRepository> {
+    graph: &'a mut BomGraph,
+    repository: &'a R,
+    /// Repository writes so far, in order (see `into_changes`)
+    changes: Vec<MaintenanceChange>,
+}
+
+impl<'a, R: BomRepository> BomMaintainer<'a, R> {
+    pub fn new(graph: &'a mut BomGraph, repository: &'a R) -> Self {
+        Self {
+            graph,
+            repository,
+            changes: Vec::new(),
+        }
+    }
+
+    /// Changes written to the repository, including those of an operation that failed partway
+    /// `cascade_delete` is not recorded item by item; its result describes the whole deletion
+    pub fn into_changes(self) -> Vec<MaintenanceChange> {
+        self.changes
+    }
+
+    /// Reassign item sequence numbers of a parent as `step, 2*step, 3*step, ...`
+    /// Returns the `(item id, new sequence)` pairs that actually changed
+    pub fn resequence_bom(&mut self, component_id: &ComponentId, step: u32) -> Result<Vec<(Uuid, u32)>> {
+        if step == 0 {
+            return Err(bom_core::BomError::CalculationError(
+                "Sequence step must be greater than zero".to_string(),
+            ));
+        }
+
+        let items = self.sorted_items(component_id)?;
+        // Check the largest number before renumbering anything
+        if u32::try_from(items.len()).ok().and_then(|count| step.checked_mul(count)).is_none() {
+            return Err(bom_core::BomError::CalculationError(format!(
+                "Sequence step {} is too large for {} items",
+                step,
+                items.len()
+            )));
+        }
+
+        let mut changes = Vec::new();
+
+        for (position, mut item) in items.into_iter().enumerate() {
+            let new_sequence = step * (position as u32 + 1);
+            if item.sequence == new_sequence {
+                continue;
+            }
+
+            item.sequence = new_sequence;
+            self.update_item(&item)?;
+            if self.graph.find_edge_by_item_id(&item.id).is_some() {
+                self.graph.update_bom_item(item.clone())?;
+            }
+            changes.push((item.id, new_sequence));
+        }
+
+        Ok(changes)
+    }
+
+    /// Insert a new BOM item directly after the item with sequence `after_sequence`
+    /// Uses the midpoint of the gap to the next item; if there is no free number
+    /// the parent is resequenced first with `DEFAULT_SEQUENCE_STEP`
+    pub fn insert_bom_item_at_position(
+        &mut self,
+        parent_id: &ComponentId,
+        child_id: &ComponentId,
+        quantity: Decimal,
+        after_sequence: u32,
+    ) -> Result<BomItem> {
+        let mut after_sequence = after_sequence;
+        let mut sequence = Self::free_sequence(&self.sorted_items(parent_id)?, after_sequence);
+
+        if sequence.is_none() {
+            // Find the position of the anchor before renumbering, then translate it
+            let position = self
+                .sorted_items(parent_id)?
+                .iter()
+                .filter(|item| item.sequence <= after_sequence)
+                .count() as u32;
+            self.resequence_bom(parent_id, DEFAULT_SEQUENCE_STEP)?;
+            after_sequence = position * DEFAULT_SEQUENCE_STEP;
+            sequence = Self::free_sequence(&self.sorted_items(parent_id)?, after_sequence);
+        }
+
+        let sequence = sequence.ok_or_else(|| {
+            bom_core::BomError::CalculationError(format!(
+                "No free sequence number after {} in BOM of {}",
+                after_sequence,
+                parent_id.as_str()
+            ))
+        })?;
+
+        let item = BomItem {
+            id: Uuid::new_v4(),
+            parent_id: parent_id.clone(),
+            child_id: child_id.clone(),
+            quantity,
+            scrap_factor: Decimal::ZERO,
+            sequence,
+            operation_sequence: None,
+            is_phantom: false,
+            effective_from: None,
+            effective_to: None,
+            alternative_group: None,
+            alternative_priority: None,
+            reference_designator: None,
+            position: None,
+            notes: None,
+            version: 0,
+        };
+
+        // Add to the graph first so a cycle is rejected before anything is persisted
+        self.graph.add_bom_item(item.clone())?;
+        self.insert_item(item.clone())?;
+
+        Ok(item)
+    }
+
+    /// Copy the direct BOM items of `source_id` to `target_id` with fresh item IDs
+    /// Returns the number of items copied
+    pub fn copy_bom(&mut self, source_id: &ComponentId, target_id: &ComponentId) -> Result<usize> {
+        let mut copied = 0;
+
+        for item in self.sorted_items(source_id)? {
+            self.insert_copy(&item, target_id, &item.child_id)?;
+            copied += 1;
+        }
+
+        Ok(copied)
+    }
+
+    /// Copy the whole structure below `source_id` to `target_id`
+    /// Every sub-assembly is duplicated as a new component named by `rename`;
+    /// leaf components are shared with the original. Returns the number of items copied
+    pub fn deep_copy_bom(
+        &mut self,
+        source_id: &ComponentId,
+        target_id: &ComponentId,
+        rename: impl Fn(&ComponentId) -> ComponentId,
+    ) -> Result<usize> {
+        let all_items = self.repository.get_all_bom_items()?;
+        let assemblies: HashSet<&ComponentId> = all_items.iter().map(|item| &item.parent_id).collect();
+
+        // Map every sub-assembly in the source tree to its new ID
+        let mut new_ids: HashMap<ComponentId, ComponentId> = HashMap::new();
+        new_ids.insert(source_id.clone(), target_id.clone());
+        let mut order = vec![source_id.clone()];
+        let mut next = 0;
+
+        while next < order.len() {
+            let parent = order[next].clone();
+            next += 1;
+            for item in all_items.iter().filter(|item| item.parent_id == parent) {
+                if assemblies.contains(&item.child_id) && !new_ids.contains_key(&item.child_id) {
+                    new_ids.insert(item.child_id.clone(), rename(&item.child_id));
+                    order.push(item.child_id.clone());
+                }
+            }
+        }
+
+        let mut copied = 0;
+        for original in &order {
+            let new_parent = new_ids[original].clone();
+
+            if original != source_id {
+                let mut component = self.repository.get_component(original)?;
+                let now = chrono::Utc::now();
+                component.id = new_parent.clone();
+                component.version = 0;
+                component.created_at = now;
+                component.updated_at = now;
+                self.repository.insert_component(component)?;
+                self.changes.push(MaintenanceChange::ComponentInserted(new_parent.clone()));
+            }
+
+            for item in self.sorted_items(original)? {
+                let new_child = new_ids.get(&item.child_id).unwrap_or(&item.child_id).clone();
+                self.insert_copy(&item, &new_parent, &new_child)?;
+                copied += 1;
+            }
+        }
+
+        Ok(copied)
+    }
+
+    /// Delete a component and every BOM item that uses it
+    /// A component with BOM items of its own is only deleted when `recursive` is set; its
+    /// items are then removed too, and children no longer used anywhere are deleted the same
+    /// way. Children still used by other parents are kept. The graph is rebuilt from the
+    /// repository afterwards
+    pub fn cascade_delete(&mut self, component_id: &ComponentId, recursive: bool) -> Result<CascadeDeleteResult> {
+        let all_items = self.repository.get_all_bom_items()?;
+
+        let exists = self.repository.get_component(component_id).is_ok()
+            || all_items
+                .iter()
+                .any(|item| item.parent_id == *component_id || item.child_id == *component_id);
+        if !exists {
+            return Err(BomError::ComponentNotFound(component_id.as_str().to_string()));
+        }
+
+        let own_items = all_items.iter().filter(|item| item.parent_id == *component_id).count();
+        if own_items > 0 && !recursive {
+            return Err(BomError::RepositoryError(format!(
+                "{} has {} BOM items of its own; delete recursively to remove them",
+                component_id.as_str(),
+                own_items
+            )));
+        }
+
+        // Plan first so nothing is removed if the plan fails
+        let mut deleted_items: HashSet<Uuid> = HashSet::new();
+        let mut deleted_components: Vec<ComponentId> = vec![component_id.clone()];
+        let mut next = 0;
+
+        while next < deleted_components.len() {
+            let current = deleted_components[next].clone();
+            next += 1;
+
+            let mut children = Vec::new();
+            for item in &all_items {
+                if item.child_id == current || item.parent_id == current {
+                    deleted_items.insert(item.id);
+                }
+                if item.parent_id == current {
+                    children.push(item.child_id.clone());
+                }
+            }
+
+            for child in children {
+                let still_used = all_items
+                    .iter()
+                    .any(|item| item.child_id == child && !deleted_items.contains(&item.id));
+                if !still_used && !deleted_components.contains(&child) {
+                    deleted_components.push(child);
+                }
+            }
+        }
+
+        let mut removed: Vec<BomItem> = all_items
+            .into_iter()
+            .filter(|item| deleted_items.contains(&item.id))
+            .collect();
+        removed.sort_by(|a, b| {
+            a.parent_id
+                .as_str()
+                .cmp(b.parent_id.as_str())
+                .then(a.sequence.cmp(&b.sequence))
+        });
+
+        for item in &removed {
+            self.repository.remove_bom_item(&item.id)?;
+        }
+
+        let mut component_deleted = false;
+        let mut removed_components = Vec::new();
+        for component in &deleted_components {
+            match self.repository.remove_component(component) {
+                Ok(_) => {
+                    component_deleted |= component == component_id;
+                    removed_components.push(component.clone());
+                }
+                // Only referenced by BOM items, never stored as a component
+                Err(BomError::ComponentNotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let mut affected_parents: Vec<ComponentId> = removed
+            .iter()
+            .map(|item| item.parent_id.clone())
+            .filter(|parent| !deleted_components.contains(parent))
+            .collect();
+        affected_parents.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        affected_parents.dedup();
+
+        *self.graph = BomGraph::from_repository(self.repository)?;
+
+        Ok(CascadeDeleteResult {
+            deleted_bom_items: removed.iter().map(|item| item.id).collect(),
+            affected_parents,
+            component_deleted,
+            deleted_components: removed_components,
+        })
+    }
+
+    /// Repair the given data problems in order (自動修復)
+    /// A fix that fails is reported in `unfixed_issues` and the remaining ones still run;
+    /// each fix checks all items before changing any. The graph is rebuilt from the
+    /// repository after every fix that changed items
+    pub fn auto_fix(&mut self, issues: &[AutoFixIssue]) -> Result<AutoFixReport> {
+        let mut report = AutoFixReport::default();
+
+        for issue in issues {
+            let outcome = match issue {
+                AutoFixIssue::MergeRedundantPaths => self.merge_redundant_items(),
+                AutoFixIssue::ResequenceGaps => self.resequence_all(),
+                AutoFixIssue::RemoveExpiredItems(before) => self.remove_expired_items(before),
+                AutoFixIssue::NormalizeQuantities(precision) => self.normalize_quantities(*precision),
+            };
+            match outcome {
+                Ok(fixed) => report.fixed_issues.push((issue.clone(), fixed)),
+                Err(e) => report.unfixed_issues.push((issue.clone(), e.to_string())),
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Merge redundant items that are identical apart from quantity into the lowest-sequence one
+    /// (see `RedundantPath::mergeable`); other redundant items are left alone
+    /// Every item is checked against the repository before any is written, and a write that
+    /// fails undoes the earlier ones. Returns the number of items removed
+    fn merge_redundant_items(&mut self) -> Result<usize> {
+        let stored: HashMap<Uuid, BomItem> = self
+            .repository
+            .get_all_bom_items()?
+            .into_iter()
+            .map(|item| (item.id, item))
+            .collect();
+
+        let mut merges = Vec::new();
+        for path in self.graph.detect_redundant_paths().into_iter().filter(|path| path.mergeable) {
+            let unchanged = |item: &BomItem| {
+                stored
+                    .get(&item.id)
+                    .is_some_and(|stored| (stored.version, stored.quantity) == (item.version, item.quantity))
+            };
+            if let Some(item) = path.items.iter().find(|item| !unchanged(item)) {
+                return Err(BomError::RepositoryError(format!(
+                    "BOM item {} changed in the repository; reload before merging",
+                    item.id
+                )));
+            }
+            let (first, duplicates) = path.items.split_first().unwrap();
+            let merged = BomItem {
+                quantity: path.total_quantity,
+                ..first.clone()
+            };
+            merged.validate()?;
+            merges.push((merged, duplicates.to_vec()));
+        }
+
+        let written = self.changes.len();
+        let mut removed = 0;
+        for (merged, duplicates) in &merges {
+            let applied = self
+                .update_item(merged)
+                .and_then(|()| duplicates.iter().try_for_each(|duplicate| self.remove_item(&duplicate.id)));
+            if let Err(e) = applied {
+                return self.abort_since(written, &stored, e);
+            }
+            removed += duplicates.len();
+        }
+        self.reload_if_changed(removed)
+    }
+
+    /// Undo the item writes journaled after the first `written` changes (see `undo_since`)
+    /// because of `error`, then rebuild the graph from whatever the repository holds now
+    fn abort_since(&mut self, written: usize, originals: &HashMap<Uuid, BomItem>, error: BomError) -> Result<usize> {
+        let undone = self.undo_since(written, originals);
+        *self.graph = BomGraph::from_repository(self.repository)?;
+        undone?;
+        Err(error)
+    }
+
+    /// Revert the item writes journaled after the first `written` changes, newest first
+    /// `originals` holds the items as they were before; the undo writes are journaled too
+    fn undo_since(&mut self, written: usize, originals: &HashMap<Uuid, BomItem>) -> Result<()> {
+        let undo: Vec<MaintenanceChange> = self.changes[written..].iter().rev().cloned().collect();
+        for change in undo {
+            match change {
+                MaintenanceChange::ItemUpdated(item) => {
+                    let original = originals
+                        .get(&item.id)
+                        .ok_or_else(|| BomError::BomItemNotFound(item.id.to_string()))?;
+                    self.update_item(original)?;
+                }
+                MaintenanceChange::ItemRemoved(item) => self.insert_item(item)?,
+                MaintenanceChange::ItemInserted(item) => self.remove_item(&item.id)?,
+                MaintenanceChange::ComponentInserted(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Resequence every parent with `DEFAULT_SEQUENCE_STEP`
+    /// Returns the number of items renumbered
+    fn resequence_all(&mut self) -> Result<usize> {
+        let mut parents: Vec<ComponentId> = self
+            .repository
+            .get_all_bom_items()?
+            .into_iter()
+            .map(|item| item.parent_id)
+            .collect();
+        parents.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        parents.dedup();
+
+        let mut renumbered = 0;
+        for parent in &parents {
+            renumbered += self.resequence_bom(parent, DEFAULT_SEQUENCE_STEP)?.len();
+        }
+        Ok(renumbered)
+    }
+
+    /// Remove items whose effectivity ended before `before`
+    /// A removal that fails undoes the earlier ones. Returns the number of items removed
+    fn remove_expired_items(&mut self, before: &DateTime<Utc>) -> Result<usize> {
+        let expired: Vec<Uuid> = self
+            .repository
+            .get_all_bom_items()?
+            .into_iter()
+            .filter(|item| item.effective_to.is_some_and(|to| to < *before))
+            .map(|item| item.id)
+            .collect();
+
+        let written = self.changes.len();
+        for id in &expired {
+            if let Err(e) = self.remove_item(id) {
+                // Removed items are journaled whole, so no originals are needed to undo
+                return self.abort_since(written, &HashMap::new(), e);
+            }
+        }
+        self.reload_if_changed(expired.len())
+    }
+
+    /// Round quantities to `precision` decimal places (half away from zero)
+    /// Fails without changing anything if a quantity would round to zero, and a write that
+    /// fails undoes the earlier ones. Returns the number of items changed
+    fn normalize_quantities(&mut self, precision: u32) -> Result<usize> {
+        let mut originals = HashMap::new();
+        let mut changed = Vec::new();
+        for item in self.repository.get_all_bom_items()? {
+            let rounded = item
+                .quantity
+                .round_dp_with_strategy(precision, RoundingStrategy::MidpointAwayFromZero);
+            if rounded == item.quantity {
+                continue;
+            }
+            if rounded.is_zero() {
+                return Err(BomError::InvalidQuantity(format!(
+                    "{} of {} in {} rounds to zero at {} decimal places",
+                    item.quantity,
+                    item.child_id.as_str(),
+                    item.parent_id.as_str(),
+                    precision
+                )));
+            }
+            changed.push(BomItem {
+                quantity: rounded,
+                ..item.clone()
+            });
+            originals.insert(item.id, item);
+        }
+
+        let written = self.changes.len();
+        for item in &changed {
+            if let Err(e) = self.update_item(item) {
+                return self.abort_since(written, &originals, e);
+            }
+        }
+        self.reload_if_changed(changed.len())
+    }
+
+    /// Rebuild the graph from the repository if `changed` items were modified
+    fn reload_if_changed(&mut self, changed: usize) -> Result<usize> {
+        if changed > 0 {
+            *self.graph = BomGraph::from_repository(self.repository)?;
+        }
+        Ok(changed)
+    }
+
+    /// Store a copy of `item` under new parent/child IDs with a fresh item ID
+    fn insert_copy(&mut self, item: &BomItem, parent_id: &ComponentId, child_id: &ComponentId) -> Result<()> {
+        let copy = BomItem {
+            id: Uuid::new_v4(),
+            parent_id: parent_id.clone(),
+            child_id: child_id.clone(),
+            version: 0,
+            ..item.clone()
+        };
+
+        // Add to the graph first so a cycle is rejected before anything is persisted
+        self.graph.add_bom_item(copy.clone())?;
+        self.insert_item(copy)
+    }
+
+    fn insert_item(&mut self, item: BomItem) -> Result<()> {
+        self.repository.insert_bom_item(item.clone())?;
+        self.changes.push(MaintenanceChange::ItemInserted(item));
+        Ok(())
+    }
+
+    fn update_item(&mut self, item: &BomItem) -> Result<()> {
+        self.repository.update_bom_item(item)?;
+        self.changes.push(MaintenanceChange::ItemUpdated(item.clone()));
+        Ok(())
+    }
+
+    fn remove_item(&mut self, id: &Uuid) -> Result<()> {
+        let removed = self.repository.remove_bom_item(id)?;
+        self.changes.push(MaintenanceChange::ItemRemoved(removed));
+        Ok(())
+    }
+
+    /// All items of a parent (regardless of effectivity), ordered by sequence
+    fn sorted_items(&self, parent_id: &ComponentId) -> Result<Vec<BomItem>> {
+        let mut items: Vec<BomItem> = self
+            .repository
+            .get_all_bom_items()?
+            .into_iter()
+            .filter(|item| item.parent_id == *parent_id)
+            .collect();
+        items.sort_by_key(|item| item.sequence);
+        Ok(items)
+    }
+
+    /// Pick a sequence number strictly between `after_sequence` and the next used number
+    fn free_sequence(items: &[BomItem], after_sequence: u32) -> Option<u32> {
+        match items.iter().map(|item| item.sequence).find(|&seq| seq > after_sequence) {
+            Some(next) if next - after_sequence >= 2 => Some(after_sequence + (next - after_sequence) / 2),
+            Some(_) => None,
+            None => after_sequence.checked_add(DEFAULT_SEQUENCE_STEP),
+        }
+    }
+}
+
+/// A repository write made by `BomMaintainer`, for the audit trail
+#[derive(Debug, Clone)]
+pub enum MaintenanceChange {
+    /// Component created (by `deep_copy_bom`)
+    ComponentInserted(ComponentId),
+    /// BOM item added
+    ItemInserted(BomItem),
+    /// BOM item changed, holding the new values
+    ItemUpdated(BomItem),
+    /// BOM item removed, holding the removed item
+    ItemRemoved(BomItem),
+}
+
+/// Outcome of `BomMaintainer::cascade_delete`
+#[derive(Debug, Clone)]
+pub struct CascadeDeleteResult {
+    /// Removed BOM items, ordered by parent and sequence
+    pub deleted_bom_items: Vec<Uuid>,
+    /// Remaining parents that lost BOM items
+    pub affected_parents: Vec<ComponentId>,
+    /// Whether the component itself was removed from the repository
+    pub component_deleted: bool,
+    /// All components removed from the repository (the component and orphaned children)
+    pub deleted_components: Vec<ComponentId>,
+}
+
+/// Data problem `BomMaintainer::auto_fix` can repair
+#[derive(Debug, Clone, PartialEq)]
+pub enum AutoFixIssue {
+    /// Parent/child pairs linked by more than one item used the same way: merged, quantities summed
+    MergeRedundantPaths,
+    /// Uneven sequence numbers: every parent is renumbered with `DEFAULT_SEQUENCE_STEP`
+    ResequenceGaps,
+    /// Items whose `effective_to` lies before the date are removed
+    RemoveExpiredItems(DateTime<Utc>),
+    /// Quantities are rounded to the given number of decimal places
+    NormalizeQuantities(u32),
+}
+
+/// Outcome of `BomMaintainer::auto_fix`
+#[derive(Debug, Clone, Default)]
+pub struct AutoFixReport {
+    /// Applied fixes with the number of BOM items each changed or removed
+    pub fixed_issues: Vec<(AutoFixIssue, usize)>,
+    /// Fixes that could not be applied, with the reason
+    pub unfixed_issues: Vec<(AutoFixIssue, String)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{create_test_bom_item, create_test_component};
+    use bom_core::repository::memory::InMemoryRepository;
+    use bom_core::*;
+    use chrono::Utc;
+
+    /// One `child` per `parent` at `sequence`
+    fn item_at(parent: &str, child: &str, sequence: u32) -> BomItem {
+        BomItem {
+            sequence,
+            ..create_test_bom_item(parent, child, 1)
+        }
+    }
+
+    fn sequences(repo: &InMemoryRepository, parent: &str) -> Vec<u32> {
+        let mut sequences: Vec<u32> = repo
+            .get_all_bom_items()
+            .unwrap()
+            .into_iter()
+            .filter(|item| item.parent_id.as_str() == parent)
+            .map(|item| item.sequence)
+            .collect();
+        sequences.sort();
+        sequences
+    }
+
+    #[test]
+    fn test_resequence_after_deletion() {
+        let repo = InMemoryRepository::new();
+        for id in ["A", "B", "C", "D", "E"] {
+            repo.add_component(create_test_component(id));
+        }
+
+        repo.add_bom_item(item_at("A", "B", 10)).unwrap();
+        repo.add_bom_item(item_at("A", "C", 20)).unwrap();
+        let removed = item_at("A", "D", 30);
+        let removed_id = removed.id;
+        repo.add_bom_item(removed).unwrap();
+        repo.add_bom_item(item_at("A", "E", 40)).unwrap();
+
+        repo.remove_bom_item(&removed_id).unwrap();
+        assert_eq!(sequences(&repo, "A"), vec![10, 20, 40]);
+
+        let mut graph = BomGraph::from_repository(&repo).unwrap();
+        let mut maintainer = BomMaintainer::new(&mut graph, &repo);
+        let changes = maintainer.resequence_bom(&ComponentId::new("A"), 10).unwrap();
+
+        // Only the item after the gap is renumbered
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].1, 30);
+        assert_eq!(sequences(&repo, "A"), vec![10, 20, 30]);
+
+        // The graph edge follows the repository
+        let edge = graph.find_edge_by_item_id(&changes[0].0).unwrap();
+        assert_eq!(graph.arena().edge(edge).unwrap().bom_item.sequence, 30);
+
+        // A step whose multiples overflow is rejected before any item changes
+        let mut maintainer = BomMaintainer::new(&mut graph, &repo);
+        assert!(maintainer.resequence_bom(&ComponentId::new("A"), u32::MAX / 2).is_err());
+        assert_eq!(sequences(&repo, "A"), vec![10, 20, 30]);
+    }
+
+    #[test]
+    fn test_insert_bom_item_at_position() {
+        let repo = InMemoryRepository::new();
+        for id in ["A", "B", "C", "D", "E"] {
+            repo.add_component(create_test_component(id));
+        }
+
+        repo.add_bom_item(item_at("A", "B", 10)).unwrap();
+        repo.add_bom_item(item_at("A", "C", 20)).unwrap();
+
+        let mut graph = BomGraph::from_repository(&repo).unwrap();
+        let mut maintainer = BomMaintainer::new(&mut graph, &repo);
+
+        // Room between 10 and 20: use the midpoint
+        let item = maintainer
+            .insert_bom_item_at_position(&ComponentId::new("A"), &ComponentId::new("D"), Decimal::ONE, 10)
+            .unwrap();
+        assert_eq!(item.sequence, 15);
+
+        // Next free number is the midpoint of 15 and 20
+        let next = maintainer
+            .insert_bom_item_at_position(&ComponentId::new("A"), &ComponentId::new("E"), Decimal::ONE, 15)
+            .unwrap();
+        assert_eq!(next.sequence, 17);
+
+        assert_eq!(sequences(&repo, "A"), vec![10, 15, 17, 20]);
+        assert_eq!(graph.stats().edge_count, 4);
+    }
+
+    #[test]
+    fn test_insert_without_gap_resequences() {
+        let repo = InMemoryRepository::new();
+        for id in ["A", "B", "C", "D"] {
+            repo.add_component(create_test_component(id));
+        }
+
+        repo.add_bom_item(item_at("A", "B", 10)).unwrap();
+        repo.add_bom_item(item_at("A", "C", 11)).unwrap();
+
+        let mut graph = BomGraph::from_repository(&repo).unwrap();
+        let mut maintainer = BomMaintainer::new(&mut graph, &repo);
+
+        let item = maintainer
+            .insert_bom_item_at_position(&ComponentId::new("A"), &ComponentId::new("D"), Decimal::ONE, 10)
+            .unwrap();
+
+        // 10, 11 is renumbered to 10, 20 and the new item lands in between
+        assert_eq!(item.sequence, 15);
+        assert_eq!(sequences(&repo, "A"), vec![10, 15, 20]);
+    }
+
+    #[test]
+    fn test_copy_bom() {
+        let repo = InMemoryRepository::new();
+        for id in ["A", "A2", "B", "C"] {
+            repo.add_component(create_test_component(id));
+        }
+
+        repo.add_bom_item(item_at("A", "B", 10)).unwrap();
+        repo.add_bom_item(item_at("A", "C", 20)).unwrap();
+
+        let mut graph = BomGraph::from_repository(&repo).unwrap();
+        let mut maintainer = BomMaintainer::new(&mut graph, &repo);
+        let copied = maintainer.copy_bom(&ComponentId::new("A"), &ComponentId::new("A2")).unwrap();
+        assert_eq!(copied, 2);
+
+        let originals = repo.get_bom_items(&ComponentId::new("A"), None).unwrap();
+        let mut copies = repo.get_bom_items(&ComponentId::new("A2"), None).unwrap();
+        assert_eq!(copies.len(), 2);
+        assert_eq!(sequences(&repo, "A2"), vec![10, 20]);
+
+        // Copies get fresh IDs
+        for copy in &copies {
+            assert!(originals.iter().all(|original| original.id != copy.id));
+        }
+
+        // Changing the copy leaves the original alone
+        copies[0].quantity = Decimal::from(5);
+        repo.update_bom_item(&copies[0]).unwrap();
+        assert!(repo
+            .get_bom_items(&ComponentId::new("A"), None)
+            .unwrap()
+            .iter()
+            .all(|item| item.quantity == Decimal::ONE));
+
+        assert!(graph.find_node(&ComponentId::new("A2")).is_some());
+    }
+
+    #[test]
+    fn test_deep_copy_bom() {
+        let repo = InMemoryRepository::new();
+        for id in ["A", "A2", "B", "C", "D"] {
+            repo.add_component(create_test_component(id));
+        }
+
+        // A -> B -> D
+        //   -> C
+        repo.add_bom_item(item_at("A", "B", 10)).unwrap();
+        repo.add_bom_item(item_at("A", "C", 20)).unwrap();
+        repo.add_bom_item(item_at("B", "D", 10)).unwrap();
+
+        let mut graph = BomGraph::from_repository(&repo).unwrap();
+        let mut maintainer = BomMaintainer::new(&mut graph, &repo);
+        let copied = maintainer
+            .deep_copy_bom(&ComponentId::new("A"), &ComponentId::new("A2"), |id| {
+                ComponentId::new(format!("{}-R2", id.as_str()))
+            })
+            .unwrap();
+        assert_eq!(copied, 3);
+
+        // The sub-assembly is duplicated, the leaves are shared
+        assert!(repo.get_component(&ComponentId::new("B-R2")).is_ok());
+        let children: Vec<String> = repo
+            .get_bom_items(&ComponentId::new("A2"), None)
+            .unwrap()
+            .into_iter()
+            .map(|item| item.child_id.as_str().to_string())
+            .collect();
+        assert!(children.contains(&"B-R2".to_string()));
+        assert!(children.contains(&"C".to_string()));
+        assert_eq!(repo.get_bom_items(&ComponentId::new("B-R2"), None).unwrap().len(), 1);
+
+        // The original structure is unchanged
+        assert_eq!(repo.get_bom_items(&ComponentId::new("B"), None).unwrap().len(), 1);
+        assert_eq!(repo.get_all_bom_items().unwrap().len(), 6);
+    }
+
+    /// Every remaining BOM item points at components that still exist
+    fn assert_referential_integrity(repo: &InMemoryRepository) {
+        for item in repo.get_all_bom_items().unwrap() {
+            assert!(repo.get_component(&item.parent_id).is_ok(), "dangling parent {}", item.parent_id.as_str());
+            assert!(repo.get_component(&item.child_id).is_ok(), "dangling child {}", item.child_id.as_str());
+        }
+    }
+
+    #[test]
+    fn test_cascade_delete_leaf() {
+        let repo = InMemoryRepository::new();
+        for id in ["A", "B", "C", "D"] {
+            repo.add_component(create_test_component(id));
+        }
+
+        // D is used in A and B
+        repo.add_bom_item(item_at("A", "B", 10)).unwrap();
+        repo.add_bom_item(item_at("A", "D", 20)).unwrap();
+        repo.add_bom_item(item_at("B", "D", 10)).unwrap();
+        repo.add_bom_item(item_at("B", "C", 20)).unwrap();
+
+        let mut graph = BomGraph::from_repository(&repo).unwrap();
+        let mut maintainer = BomMaintainer::new(&mut graph, &repo);
+        let result = maintainer.cascade_delete(&ComponentId::new("D"), false).unwrap();
+
+        assert!(result.component_deleted);
+        assert_eq!(result.deleted_bom_items.len(), 2);
+        assert_eq!(result.affected_parents, vec![ComponentId::new("A"), ComponentId::new("B")]);
+        assert!(repo.get_component(&ComponentId::new("D")).is_err());
+        assert_eq!(repo.get_all_bom_items().unwrap().len(), 2);
+        assert_referential_integrity(&repo);
+
+        assert!(graph.find_node(&ComponentId::new("D")).is_none());
+        assert_eq!(graph.stats().edge_count, 2);
+    }
+
+    #[test]
+    fn test_cascade_delete_recursive() {
+        let repo = InMemoryRepository::new();
+        for id in ["A", "B", "C", "D", "E"] {
+            repo.add_component(create_test_component(id));
+        }
+
+        // A -> B -> C
+        //        -> D
+        // E -> D
+        repo.add_bom_item(item_at("A", "B", 10)).unwrap();
+        repo.add_bom_item(item_at("B", "C", 10)).unwrap();
+        repo.add_bom_item(item_at("B", "D", 20)).unwrap();
+        repo.add_bom_item(item_at("E", "D", 10)).unwrap();
+
+        let mut graph = BomGraph::from_repository(&repo).unwrap();
+        let mut maintainer = BomMaintainer::new(&mut graph, &repo);
+
+        // B has children of its own
+        assert!(maintainer.cascade_delete(&ComponentId::new("B"), false).is_err());
+        assert!(maintainer.cascade_delete(&ComponentId::new("MISSING"), true).is_err());
+        assert_eq!(repo.get_all_bom_items().unwrap().len(), 4);
+
+        let result = maintainer.cascade_delete(&ComponentId::new("B"), true).unwrap();
+
+        // C was only used by B and goes too; D is still used by E
+        assert!(result.component_deleted);
+        assert_eq!(result.deleted_components, vec![ComponentId::new("B"), ComponentId::new("C")]);
+        assert_eq!(result.deleted_bom_items.len(), 3);
+        assert_eq!(result.affected_parents, vec![ComponentId::new("A")]);
+        assert!(repo.get_component(&ComponentId::new("D")).is_ok());
+        assert_eq!(repo.get_all_bom_items().unwrap().len(), 1);
+        assert_referential_integrity(&repo);
+
+        assert!(graph.find_node(&ComponentId::new("C")).is_none());
+        assert_eq!(graph.stats().edge_count, 1);
+    }
+
+    #[test]
+    fn test_auto_fix() {
+        let repo = InMemoryRepository::new();
+        for id in ["A", "B", "C", "D", "E"] {
+            repo.add_component(create_test_component(id));
+        }
+
+        // A -> B twice (merged), C (expired), D (qty 1.005); sequences 10, 15, 40, 70
+        repo.add_bom_item(item_at("A", "B", 10)).unwrap();
+        repo.add_bom_item(BomItem {
+            quantity: Decimal::from(2),
+            ..item_at("A", "B", 15)
+        })
+        .unwrap();
+        repo.add_bom_item(BomItem {
+            effective_to: Some(Utc::now() - chrono::Duration::days(30)),
+            ..item_at("A", "C", 40)
+        })
+        .unwrap();
+        repo.add_bom_item(BomItem {
+            quantity: Decimal::new(1005, 3),
+            ..item_at("A", "D", 70)
+        })
+        .unwrap();
+        repo.add_bom_item(item_at("D", "E", 5)).unwrap();
+
+        let mut graph = BomGraph::from_repository(&repo).unwrap();
+        let mut maintainer = BomMaintainer::new(&mut graph, &repo);
+        let issues = [
+            AutoFixIssue::RemoveExpiredItems(Utc::now()),
+            AutoFixIssue::MergeRedundantPaths,
+            AutoFixIssue::NormalizeQuantities(2),
+            AutoFixIssue::ResequenceGaps,
+        ];
+        let report = maintainer.auto_fix(&issues).unwrap();
+
+        let counts: Vec<usize> = report.fixed_issues.iter().map(|(_, count)| *count).collect();
+        assert_eq!(counts, vec![1, 1, 1, 2]);
+        assert!(report.unfixed_issues.is_empty());
+
+        // Clean: nothing left to fix
+        let items = repo.get_all_bom_items().unwrap();
+        assert_eq!(items.len(), 3);
+        assert_eq!(sequences(&repo, "A"), vec![10, 20]);
+        assert_eq!(sequences(&repo, "D"), vec![10]);
+        let quantity = |child: &str| items.iter().find(|item| item.child_id.as_str() == child).unwrap().quantity;
+        assert_eq!(quantity("B"), Decimal::from(3));
+        assert_eq!(quantity("D"), Decimal::new(101, 2));
+        assert!(graph.detect_redundant_paths().is_empty());
+        assert!(graph.find_node(&ComponentId::new("C")).is_none());
+
+        let mut maintainer = BomMaintainer::new(&mut graph, &repo);
+        let rerun = maintainer.auto_fix(&issues).unwrap();
+        assert!(rerun.fixed_issues.iter().all(|(_, count)| *count == 0));
+
+        // A quantity that rounds to zero blocks the whole fix
+        repo.add_bom_item(BomItem {
+            quantity: Decimal::new(4, 1),
+            ..item_at("D", "C", 20)
+        })
+        .unwrap();
+        let report = maintainer.auto_fix(&[AutoFixIssue::NormalizeQuantities(0)]).unwrap();
+        assert!(report.fixed_issues.is_empty());
+        assert_eq!(report.unfixed_issues.len(), 1);
+        assert!(report.unfixed_issues[0].1.contains("rounds to zero"));
+        let items = repo.get_all_bom_items().unwrap();
+        assert!(items.iter().any(|item| item.quantity == Decimal::new(101, 2)));
+    }
+
+    /// Repository whose `update_bom_item` and `remove_bom_item` fail for one item
+    struct FailingWriteRepository {
+        inner: InMemoryRepository,
+        failing_id: Uuid,
+    }
+
+    impl BomRepository for FailingWriteRepository {
+        fn get_component(&self, id: &ComponentId) -> Result<Component> {
+            self.inner.get_component(id)
+        }
+
+        fn get_components(&self, ids: &[ComponentId]) -> Result<Vec<Component>> {
+            self.inner.get_components(ids)
+        }
+
+        fn get_bom_header(
+            &self,
+            component_id: &ComponentId,
+            alternative: Option<&str>,
+            effective_date: Option<DateTime<Utc>>,
+        ) -> Result<BomHeader> {
+            self.inner.get_bom_header(component_id, alternative, effective_date)
+        }
+
+        fn get_bom_items(
+            &self,
+            component_id: &ComponentId,
+            effective_date: Option<DateTime<Utc>>,
+        ) -> Result<Vec<BomItem>> {
+            self.inner.get_bom_items(component_id, effective_date)
+        }
+
+        fn get_all_bom_items(&self) -> Result<Vec<BomItem>> {
+            self.inner.get_all_bom_items()
+        }
+
+        fn find_parents(&self, component_id: &ComponentId) -> Result<Vec<BomItem>> {
+            self.inner.find_parents(component_id)
+        }
+
+        fn insert_bom_item(&self, item: BomItem) -> Result<()> {
+            self.inner.insert_bom_item(item)
+        }
+
+        fn update_bom_item(&self, item: &BomItem) -> Result<()> {
+            if item.id == self.failing_id {
+                return Err(BomError::RepositoryError("update failed".to_string()));
+            }
+            self.inner.update_bom_item(item)
+        }
+
+        fn remove_bom_item(&self, id: &Uuid) -> Result<BomItem> {
+            if *id == self.failing_id {
+                return Err(BomError::RepositoryError("remove failed".to_string()));
+            }
+            self.inner.remove_bom_item(id)
+        }
+    }
+
+    #[test]
+    fn test_merge_redundant_items_keeps_distinct_usages() {
+        let repo = InMemoryRepository::new();
+        for id in ["A", "B", "C"] {
+            repo.add_component(create_test_component(id));
+        }
+
+        // A -> B: two plain items (merged) and one in an alternative group (kept)
+        repo.add_bom_item(item_at("A", "B", 10)).unwrap();
+        repo.add_bom_item(item_at("A", "B", 20)).unwrap();
+        repo.add_bom_item(BomItem {
+            alternative_group: Some("ALT".to_string()),
+            ..item_at("A", "B", 30)
+        })
+        .unwrap();
+        // A -> C: the same usage with different scrap factors (kept)
+        repo.add_bom_item(item_at("A", "C", 40)).unwrap();
+        repo.add_bom_item(BomItem {
+            scrap_factor: Decimal::new(5, 2),
+            ..item_at("A", "C", 50)
+        })
+        .unwrap();
+
+        let mut graph = BomGraph::from_repository(&repo).unwrap();
+        let mut maintainer = BomMaintainer::new(&mut graph, &repo);
+        let report = maintainer.auto_fix(&[AutoFixIssue::MergeRedundantPaths]).unwrap();
+        assert_eq!(report.fixed_issues[0].1, 1);
+
+        let changes = maintainer.into_changes();
+        assert_eq!(changes.len(), 2);
+        assert!(matches!(&changes[0], MaintenanceChange::ItemUpdated(item) if item.quantity == Decimal::from(2)));
+        assert!(matches!(&changes[1], MaintenanceChange::ItemRemoved(item) if item.sequence == 20));
+        assert_eq!(sequences(&repo, "A"), vec![10, 30, 40, 50]);
+    }
+
+    #[test]
+    fn test_merge_redundant_items_undoes_on_failure() {
+        let inner = InMemoryRepository::new();
+        for id in ["A", "B", "C"] {
+            inner.add_component(create_test_component(id));
+        }
+        inner.add_bom_item(item_at("A", "B", 10)).unwrap();
+        inner.add_bom_item(item_at("A", "B", 20)).unwrap();
+        inner.add_bom_item(item_at("A", "C", 30)).unwrap();
+        let failing = item_at("A", "C", 40);
+        inner.add_bom_item(failing.clone()).unwrap();
+        let before = item_snapshot(&inner);
+
+        let repo = FailingWriteRepository {
+            inner,
+            failing_id: failing.id,
+        };
+        let mut graph = BomGraph::from_repository(&repo).unwrap();
+        let mut maintainer = BomMaintainer::new(&mut graph, &repo);
+        let report = maintainer.auto_fix(&[AutoFixIssue::MergeRedundantPaths]).unwrap();
+        assert!(report.fixed_issues.is_empty());
+        assert!(report.unfixed_issues[0].1.contains("remove failed"));
+
+        // A -> B was merged before A -> C failed; both pairs are back as they were
+        assert_eq!(item_snapshot(&repo), before);
+
+        // The merge of A -> B, the update of A -> C and their undo are all journaled
+        let changes = maintainer.into_changes();
+        assert_eq!(changes.len(), 6);
+        assert!(matches!(&changes[5], MaintenanceChange::ItemUpdated(item) if item.sequence == 10));
+    }
+
+    /// Snapshot of the stored items by sequence, to compare before and after a failed fix
+    fn item_snapshot(repo: &impl BomRepository) -> Vec<(Uuid, u32, Decimal)> {
+        let mut items: Vec<(Uuid, u32, Decimal)> = repo
+            .get_all_bom_items()
+            .unwrap()
+            .into_iter()
+            .map(|item| (item.id, item.sequence, item.quantity))
+            .collect();
+        items.sort_by_key(|item| item.1);
+        items
+    }
+
+    #[test]
+    fn test_normalize_quantities_undoes_on_failure() {
+        let inner = InMemoryRepository::new();
+        for id in ["A", "B", "C"] {
+            inner.add_component(create_test_component(id));
+        }
+        inner
+            .add_bom_item(BomItem {
+                quantity: Decimal::new(1004, 3),
+                ..item_at("A", "B", 10)
+            })
+            .unwrap();
+        let failing = BomItem {
+            quantity: Decimal::new(2006, 3),
+            ..item_at("A", "C", 20)
+        };
+        inner.add_bom_item(failing.clone()).unwrap();
+        let before = item_snapshot(&inner);
+
+        let repo = FailingWriteRepository {
+            inner,
+            failing_id: failing.id,
+        };
+        let mut graph = BomGraph::from_repository(&repo).unwrap();
+        let mut maintainer = BomMaintainer::new(&mut graph, &repo);
+        let report = maintainer.auto_fix(&[AutoFixIssue::NormalizeQuantities(2)]).unwrap();
+        assert!(report.unfixed_issues[0].1.contains("update failed"));
+
+        // A -> B was rounded before A -> C failed, and is back as it was
+        assert_eq!(item_snapshot(&repo), before);
+        assert_eq!(maintainer.into_changes().len(), 2);
+        let b = graph.find_node(&ComponentId::new("B")).unwrap();
+        let quantities: Vec<Decimal> = graph.arena().parents(b).map(|(_, edge)| edge.bom_item.quantity).collect();
+        assert_eq!(quantities, vec![Decimal::new(1004, 3)]);
+    }
+
+    #[test]
+    fn test_remove_expired_items_undoes_on_failure() {
+        let inner = InMemoryRepository::new();
+        for id in ["A", "B", "C", "D"] {
+            inner.add_component(create_test_component(id));
+        }
+        let expired = |child: &str, sequence: u32| BomItem {
+            effective_to: Some(Utc::now() - chrono::Duration::days(30)),
+            ..item_at("A", child, sequence)
+        };
+        inner.add_bom_item(expired("B", 10)).unwrap();
+        let failing = expired("C", 20);
+        inner.add_bom_item(failing.clone()).unwrap();
+        inner.add_bom_item(item_at("A", "D", 30)).unwrap();
+        let before = item_snapshot(&inner);
+
+        let repo = FailingWriteRepository {
+            inner,
+            failing_id: failing.id,
+        };
+        let mut graph = BomGraph::from_repository(&repo).unwrap();
+        let mut maintainer = BomMaintainer::new(&mut graph, &repo);
+        let report = maintainer
+            .auto_fix(&[AutoFixIssue::RemoveExpiredItems(Utc::now())])
+            .unwrap();
+        assert!(report.unfixed_issues[0].1.contains("remove failed"));
+
+        // A -> B was removed before A -> C failed, and is back
+        assert_eq!(item_snapshot(&repo), before);
+        assert_eq!(maintainer.into_changes().len(), 2);
+        assert_eq!(graph.arena().edge_count(), 3);
+    }
+}
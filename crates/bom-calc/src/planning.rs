@@ -0,0 +1,1369 @@
+use bom_core::{AlternativeRoute, BomError, BomRepository, ComponentId, ProcurementType, Result};
+use bom_graph::{topological_sort, Arena, BomGraph, NodeIndex};
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::ExplosionCalculator;
+
+/// Kind of order generated for a component
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkOrderType {
+    /// Manufactured component (工單)
+    Production,
+    /// Leaf component that must be bought (請購單)
+    PurchaseRequisition,
+}
+
+/// Planned order for one component of a production plan
+#[derive(Debug, Clone)]
+pub struct WorkOrder {
+    pub id: Uuid,
+    pub component_id: ComponentId,
+    pub order_type: WorkOrderType,
+    pub quantity: Decimal,
+    pub planned_start: DateTime<Utc>,
+    pub planned_finish: DateTime<Utc>,
+    /// Order of the parent that needs this component first (`None` for the root)
+    pub parent_work_order_id: Option<Uuid>,
+}
+
+/// One purchased component of a procurement plan
+#[derive(Debug, Clone)]
+pub struct ProcurementLineItem {
+    pub component_id: ComponentId,
+    pub quantity: Decimal,
+    /// Standard cost (zero if not maintained)
+    pub unit_cost: Decimal,
+    pub extended_cost: Decimal,
+}
+
+/// Purchased components grouped by preferred vendor (採購彙總)
+#[derive(Debug, Clone)]
+pub struct ProcurementSummary {
+    pub by_vendor: HashMap<String, Vec<ProcurementLineItem>>,
+    /// Purchased components without a preferred vendor
+    pub unassigned_vendor: Vec<ProcurementLineItem>,
+    pub total_cost: Decimal,
+}
+
+/// Safety stock parameters of one component (安全庫存)
+#[derive(Debug, Clone)]
+pub struct SafetyStockConfig {
+    pub component_id: ComponentId,
+    /// Days of demand to keep in stock
+    pub safety_stock_days: u32,
+    /// Multiplier for uncertain demand (1.0 = no adjustment)
+    pub demand_variability_factor: f64,
+}
+
+/// Minimum inventory of one component
+#[derive(Debug, Clone)]
+pub struct SafetyStockRequirement {
+    pub component_id: ComponentId,
+    /// Quantity needed per unit of the root
+    pub explosion_quantity: Decimal,
+    /// Daily demand of this component (root daily demand * explosion quantity)
+    pub daily_demand: Decimal,
+    pub safety_stock_days: u32,
+    /// Safety stock to keep on hand
+    pub min_inventory: Decimal,
+}
+
+/// Safety stock levels for the components of a BOM
+#[derive(Debug, Clone)]
+pub struct SafetyStockReport {
+    pub root_component: ComponentId,
+    /// Daily demand of the root
+    pub daily_demand: Decimal,
+    /// Configured components found in the BOM, sorted by component ID
+    pub requirements: Vec<SafetyStockRequirement>,
+    /// Configured components that are not part of the BOM
+    pub not_in_bom: Vec<ComponentId>,
+}
+
+/// Daily capacity limit of a component (產能限制)
+#[derive(Debug, Clone)]
+pub struct CapacityConstraint {
+    pub component_id: ComponentId,
+    /// Most units that can be finished per day
+    pub max_daily_units: Decimal,
+    /// Machine group (work center) doing the production
+    pub machine_group: String,
+}
+
+/// Output of one capacity-constrained component on one day
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledProduction {
+    pub component_id: ComponentId,
+    pub machine_group: String,
+    pub quantity: Decimal,
+}
+
+/// Capacity-constrained production of one day
+#[derive(Debug, Clone)]
+pub struct DaySchedule {
+    pub date: DateTime<Utc>,
+    /// Sorted by component ID
+    pub production: Vec<ScheduledProduction>,
+}
+
+/// Result of a capacity-constrained production run
+#[derive(Debug, Clone)]
+pub struct ProductionSimulation {
+    pub completion_date: DateTime<Utc>,
+    /// Component on the critical path that takes the most days
+    pub bottleneck_component: ComponentId,
+    /// Days with constrained production, in date order
+    pub daily_schedule: Vec<DaySchedule>,
+}
+
+/// Cumulative lead time of a BOM (累計前置時間)
+#[derive(Debug, Clone)]
+pub struct LeadTimeResult {
+    pub root_component: ComponentId,
+    /// Days from the start of the earliest order until the root is finished
+    pub total_lead_time_days: u32,
+    /// Longest lead time chain, root first
+    pub critical_path: Vec<ComponentId>,
+}
+
+/// Purchasing lead time of a BOM (採購前置時間)
+#[derive(Debug, Clone)]
+pub struct ProcurementLeadTime {
+    /// Days from ordering the critical purchased component until the root is finished
+    pub total_days: u32,
+    /// Purchased leaf on the longest lead time chain (the long pole)
+    pub critical_purchased_component: ComponentId,
+    /// Own lead time of every purchased leaf, longest first
+    pub all_purchased_lead_times: Vec<(ComponentId, u32)>,
+}
+
+/// Inspection step of a first article inspection (AS9102)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum InspectionType {
+    /// At the supplier, before shipment
+    Source,
+    /// On arrival of a purchased part
+    Receiving,
+    /// During manufacturing of a made part
+    InProcess,
+    /// Of the finished made part
+    Final,
+}
+
+/// One inspection of a first article inspection plan
+#[derive(Debug, Clone, PartialEq)]
+pub struct FaiInspection {
+    pub component_id: ComponentId,
+    /// BOM level (0 = root)
+    pub level: usize,
+    pub inspection_type: InspectionType,
+    /// Units needed for one first article of the root, rounded up
+    pub expected_qty: usize,
+}
+
+/// First article inspection plan of a product (首件檢驗計畫)
+#[derive(Debug, Clone, Default)]
+pub struct FaiPlan {
+    /// Ordered by level, component ID and inspection type
+    pub inspections: Vec<FaiInspection>,
+}
+
+/// What `ProductionPlanner::select_optimal_route` optimizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoutingCriteria {
+    /// Lowest `cost_factor` (ties: shorter lead time)
+    MinCost,
+    /// Shortest lead time (ties: lower cost)
+    MinLeadTime,
+    /// Fewest operations, i.e. the fewest work centers that can become a bottleneck
+    /// (ties: shorter lead time)
+    MaxCapacity,
+}
+
+/// Recorded demand of one component (e.g. issues from stock)
+#[derive(Debug, Clone)]
+pub struct VelocityData {
+    pub component_id: ComponentId,
+    /// When and how much was demanded
+    pub demand_events: Vec<(DateTime<Utc>, Decimal)>,
+}
+
+/// ABC class of a component by demand (ABC 分類)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VelocityClass {
+    /// Top 20% of components by demand (fast movers)
+    A,
+    /// Next 30%
+    B,
+    /// Remaining 50% (slow movers)
+    C,
+}
+
+/// Demand rate of one component over the lookback window
+#[derive(Debug, Clone)]
+pub struct ComponentVelocity {
+    pub component_id: ComponentId,
+    /// Demand within the lookback window
+    pub total_demand: Decimal,
+    /// `total_demand` divided by the lookback days
+    pub demand_per_day: Decimal,
+    pub velocity_class: VelocityClass,
+}
+
+/// Component that limits production in a shortage simulation
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShortageItem {
+    pub component_id: ComponentId,
+    /// Quantity needed per unit of the root
+    pub needed: Decimal,
+    pub available: Decimal,
+    /// `needed - available`
+    pub shortfall: Decimal,
+}
+
+/// Result of `ProductionPlanner::simulate_shortage` (缺料模擬)
+#[derive(Debug, Clone)]
+pub struct ShortageImpact {
+    /// Root quantity the available stock allows, out of the planned single unit: 1 when nothing
+    /// limits it (none of the unavailable components is used by the root, or their stock
+    /// covers the unit), 0 when a required component has nothing available
+    pub can_produce_qty: Decimal,
+    /// Components short for one unit of the root, ordered by component ID
+    pub shortages: Vec<ShortageItem>,
+    /// (short component, alternative) pairs from the same alternative group under a parent
+    /// in the root's BOM; alternatives that are short themselves are left out
+    pub alternatives_available: Vec<(ComponentId, ComponentId)>,
+}
+
+/// Which way `longest_chains` runs through a BOM
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChainDirection {
+    /// From the leaves up: a component waits for all of its children
+    FromLeaves,
+    /// From the root down: a component waits for all of its parents in the BOM
+    FromRoot,
+}
+
+/// Longest chains of durations through a BOM (critical path method)
+struct LongestChains {
+    /// Nodes of the BOM in the order they were scheduled
+    order: Vec<NodeIndex>,
+    /// Days until each node is done: the longest chain before it plus its own duration
+    finish: HashMap<NodeIndex, i64>,
+    /// The node before each one on its longest chain (the first of equally long ones)
+    previous: HashMap<NodeIndex, NodeIndex>,
+}
+
+impl LongestChains {
+    /// `node` followed by the nodes before it on its longest chain
+    fn chain(&self, node: NodeIndex) -> Vec<NodeIndex> {
+        let mut chain = vec![node];
+        while let Some(&previous) = self.previous.get(chain.last().unwrap()) {
+            chain.push(previous);
+        }
+        chain
+    }
+}
+
+/// Schedule `root`'s BOM in `direction`; `duration(node, ready_day)` is how long a node
+/// takes once everything before it is done (on `ready_day`)
+fn longest_chains(
+    arena: &Arena,
+    root: NodeIndex,
+    direction: ChainDirection,
+    mut duration: impl FnMut(NodeIndex, i64) -> i64,
+) -> LongestChains {
+    let mut order = topological_sort(arena, &[root]);
+    if direction == ChainDirection::FromRoot {
+        order.reverse();
+    }
+
+    let mut finish: HashMap<NodeIndex, i64> = HashMap::new();
+    let mut previous = HashMap::new();
+    for &node in &order {
+        let before: Vec<NodeIndex> = match direction {
+            ChainDirection::FromLeaves => arena.children(node).map(|(child, _)| child).collect(),
+            ChainDirection::FromRoot => arena.parents(node).map(|(parent, _)| parent).collect(),
+        };
+        let latest = before
+            .into_iter()
+            .filter_map(|other| finish.get(&other).map(|&day| (day, other)))
+            .reduce(|latest, other| if other.0 > latest.0 { other } else { latest });
+
+        let ready_day = latest.map_or(0, |(day, _)| day);
+        if let Some((_, other)) = latest {
+            previous.insert(node, other);
+        }
+        finish.insert(node, ready_day + duration(node, ready_day));
+    }
+
+    LongestChains { order, finish, previous }
+}
+
+/// Production planning (生產排程)
+/// Schedules orders along the critical path using component lead times
+pub struct ProductionPlanner<'a, R: BomRepository> {
+    graph: &'a BomGraph,
+    repository: &'a R,
+}
+
+impl<'a, R: BomRepository> ProductionPlanner<'a, R> {
+    pub fn new(graph: &'a BomGraph, repository: &'a R) -> Self {
+        Self { graph, repository }
+    }
+
+    /// Generate one order per component needed to produce `production_qty` of `root_id`
+    /// The longest lead time chain starts at `start_date`; every other order is
+    /// scheduled backward from its parent so it finishes just in time.
+    /// Returned orders are sorted top-down (root first)
+    pub fn generate_work_orders(
+        &self,
+        root_id: &ComponentId,
+        production_qty: Decimal,
+        start_date: DateTime<Utc>,
+    ) -> Result<Vec<WorkOrder>> {
+        let root = self
+            .graph
+            .find_node(root_id)
+            .ok_or_else(|| bom_core::BomError::ComponentNotFound(root_id.as_str().to_string()))?;
+
+        let quantities: HashMap<ComponentId, Decimal> = ExplosionCalculator::new(self.graph)
+            .explode(root_id, production_qty)?
+            .items
+            .into_iter()
+            .map(|item| (item.component_id, item.total_quantity))
+            .collect();
+
+        let arena = self.graph.arena();
+        let mut lead_times: HashMap<NodeIndex, i64> = HashMap::new();
+        let chains = longest_chains(arena, root, ChainDirection::FromLeaves, |node, _| {
+            let lead_time = self.lead_time_days(&arena.node(node).unwrap().component_id);
+            lead_times.insert(node, lead_time);
+            lead_time
+        });
+
+        // Walk top-down: each order finishes when its earliest parent starts
+        let mut orders: Vec<WorkOrder> = Vec::new();
+        let mut order_of: HashMap<NodeIndex, usize> = HashMap::new();
+
+        for &node in chains.order.iter().rev() {
+            let component_id = arena.node(node).unwrap().component_id.clone();
+
+            let (planned_finish, parent_work_order_id) = if node == root {
+                (start_date + Duration::days(chains.finish[&node]), None)
+            } else {
+                arena
+                    .parents(node)
+                    .filter_map(|(parent, _)| order_of.get(&parent).map(|&i| &orders[i]))
+                    .min_by_key(|parent| parent.planned_start)
+                    .map(|parent| (parent.planned_start, Some(parent.id)))
+                    .unwrap_or((start_date, None))
+            };
+
+            let order_type = if arena.children(node).next().is_none() {
+                WorkOrderType::PurchaseRequisition
+            } else {
+                WorkOrderType::Production
+            };
+
+            order_of.insert(node, orders.len());
+            orders.push(WorkOrder {
+                id: Uuid::new_v4(),
+                quantity: quantities.get(&component_id).copied().unwrap_or(Decimal::ZERO),
+                component_id,
+                order_type,
+                planned_start: planned_finish - Duration::days(lead_times[&node]),
+                planned_finish,
+                parent_work_order_id,
+            });
+        }
+
+        Ok(orders)
+    }
+
+    /// Simulate producing `target_qty` of `root_id` from `start_date` under daily capacity limits
+    /// A component starts once all of its children are complete. Unconstrained components
+    /// take their lead time; constrained ones finish at most `max_daily_units` per day and
+    /// never take less than their lead time
+    pub fn simulate_production_run(
+        &self,
+        root_id: &ComponentId,
+        target_qty: Decimal,
+        start_date: DateTime<Utc>,
+        constraints: &[CapacityConstraint],
+    ) -> Result<ProductionSimulation> {
+        let root = self
+            .graph
+            .find_node(root_id)
+            .ok_or_else(|| bom_core::BomError::ComponentNotFound(root_id.as_str().to_string()))?;
+
+        if let Some(constraint) = constraints.iter().find(|c| c.max_daily_units <= Decimal::ZERO) {
+            return Err(BomError::CalculationError(format!(
+                "max daily units of {} must be positive",
+                constraint.component_id.as_str()
+            )));
+        }
+        let capacities: HashMap<&ComponentId, &CapacityConstraint> =
+            constraints.iter().map(|c| (&c.component_id, c)).collect();
+
+        let quantities: HashMap<ComponentId, Decimal> = ExplosionCalculator::new(self.graph)
+            .explode(root_id, target_qty)?
+            .items
+            .into_iter()
+            .map(|item| (item.component_id, item.total_quantity))
+            .collect();
+
+        // Days from the start until each component is complete, and how long it took itself
+        let arena = self.graph.arena();
+        let mut duration: HashMap<NodeIndex, i64> = HashMap::new();
+        let mut production_by_day: BTreeMap<i64, Vec<ScheduledProduction>> = BTreeMap::new();
+
+        let chains = longest_chains(arena, root, ChainDirection::FromLeaves, |node, ready_day| {
+            let component_id = &arena.node(node).unwrap().component_id;
+            let lead_time = self.lead_time_days(component_id);
+
+            let days = match capacities.get(component_id) {
+                Some(constraint) => {
+                    let mut remaining = quantities.get(component_id).copied().unwrap_or(Decimal::ZERO);
+                    let mut day = ready_day;
+                    while remaining > Decimal::ZERO {
+                        let quantity = remaining.min(constraint.max_daily_units);
+                        production_by_day.entry(day).or_default().push(ScheduledProduction {
+                            component_id: component_id.clone(),
+                            machine_group: constraint.machine_group.clone(),
+                            quantity,
+                        });
+                        remaining -= quantity;
+                        day += 1;
+                    }
+                    (day - ready_day).max(lead_time)
+                }
+                None => lead_time,
+            };
+
+            duration.insert(node, days);
+            days
+        });
+
+        // The slowest component on the critical path (latest-finishing children from the root)
+        let bottleneck = chains
+            .chain(root)
+            .into_iter()
+            .reduce(|slowest, node| if duration[&node] > duration[&slowest] { node } else { slowest })
+            .unwrap_or(root);
+
+        let daily_schedule = production_by_day
+            .into_iter()
+            .map(|(day, mut production)| {
+                production.sort_by(|a, b| a.component_id.as_str().cmp(b.component_id.as_str()));
+                DaySchedule {
+                    date: start_date + Duration::days(day),
+                    production,
+                }
+            })
+            .collect();
+
+        Ok(ProductionSimulation {
+            completion_date: start_date + Duration::days(chains.finish[&root]),
+            bottleneck_component: arena.node(bottleneck).unwrap().component_id.clone(),
+            daily_schedule,
+        })
+    }
+
+    /// Cumulative lead time of `root_id`: the longest chain of component lead times
+    /// Components without a lead time count as zero days
+    pub fn calculate_lead_time(&self, root_id: &ComponentId) -> Result<LeadTimeResult> {
+        let root = self
+            .graph
+            .find_node(root_id)
+            .ok_or_else(|| bom_core::BomError::ComponentNotFound(root_id.as_str().to_string()))?;
+
+        let arena = self.graph.arena();
+        let chains = longest_chains(arena, root, ChainDirection::FromLeaves, |node, _| {
+            self.lead_time_days(&arena.node(node).unwrap().component_id)
+        });
+
+        Ok(LeadTimeResult {
+            root_component: root_id.clone(),
+            total_lead_time_days: chains.finish[&root] as u32,
+            critical_path: chains
+                .chain(root)
+                .into_iter()
+                .map(|node| arena.node(node).unwrap().component_id.clone())
+                .collect(),
+        })
+    }
+
+    /// Find the purchased component that determines when procurement must start
+    /// Only `Buy` leaves are considered. Each one's cumulative lead time is its own lead
+    /// time plus those of the assemblies above it on the longest chain to the root
+    pub fn calculate_procurement_lead_time(&self, root_id: &ComponentId) -> Result<ProcurementLeadTime> {
+        let root = self
+            .graph
+            .find_node(root_id)
+            .ok_or_else(|| bom_core::BomError::ComponentNotFound(root_id.as_str().to_string()))?;
+
+        // Longest lead time from the root down to each node, both included
+        let arena = self.graph.arena();
+        let chains = longest_chains(arena, root, ChainDirection::FromRoot, |node, _| {
+            self.lead_time_days(&arena.node(node).unwrap().component_id)
+        });
+
+        let mut purchased = Vec::new();
+        for (&node, &days) in &chains.finish {
+            if arena.children(node).next().is_some() {
+                continue;
+            }
+            let component = self.repository.get_component(&arena.node(node).unwrap().component_id)?;
+            if component.procurement_type == ProcurementType::Buy {
+                purchased.push((component.id, component.lead_time_days.unwrap_or(0), days));
+            }
+        }
+
+        let (critical_purchased_component, _, total_days) = purchased
+            .iter()
+            .max_by(|a, b| a.2.cmp(&b.2).then_with(|| b.0.as_str().cmp(a.0.as_str())))
+            .cloned()
+            .ok_or_else(|| {
+                BomError::CalculationError(format!("{} has no purchased components", root_id.as_str()))
+            })?;
+
+        let mut all_purchased_lead_times: Vec<(ComponentId, u32)> =
+            purchased.into_iter().map(|(id, own, _)| (id, own)).collect();
+        all_purchased_lead_times.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.as_str().cmp(b.0.as_str())));
+
+        Ok(ProcurementLeadTime {
+            total_days: total_days as u32,
+            critical_purchased_component,
+            all_purchased_lead_times,
+        })
+    }
+
+    /// Build a purchase plan for `production_qty` of `root_id`
+    /// Only components with `ProcurementType::Buy` are included; line items are
+    /// sorted by component ID
+    pub fn calculate_procurement_summary(
+        &self,
+        root_id: &ComponentId,
+        production_qty: Decimal,
+    ) -> Result<ProcurementSummary> {
+        let explosion = ExplosionCalculator::new(self.graph).explode(root_id, production_qty)?;
+
+        let mut by_vendor: HashMap<String, Vec<ProcurementLineItem>> = HashMap::new();
+        let mut unassigned_vendor = Vec::new();
+        let mut total_cost = Decimal::ZERO;
+
+        for item in explosion.items {
+            let component = match self.repository.get_component(&item.component_id) {
+                Ok(component) => component,
+                Err(_) => continue,
+            };
+            if component.procurement_type != ProcurementType::Buy {
+                continue;
+            }
+
+            let unit_cost = component.standard_cost.unwrap_or(Decimal::ZERO);
+            let line = ProcurementLineItem {
+                component_id: item.component_id,
+                quantity: item.total_quantity,
+                unit_cost,
+                extended_cost: unit_cost * item.total_quantity,
+            };
+            total_cost += line.extended_cost;
+
+            match component.preferred_vendor {
+                Some(vendor) => by_vendor.entry(vendor).or_default().push(line),
+                None => unassigned_vendor.push(line),
+            }
+        }
+
+        for lines in by_vendor.values_mut() {
+            lines.sort_by(|a, b| a.component_id.as_str().cmp(b.component_id.as_str()));
+        }
+        unassigned_vendor.sort_by(|a, b| a.component_id.as_str().cmp(b.component_id.as_str()));
+
+        Ok(ProcurementSummary {
+            by_vendor,
+            unassigned_vendor,
+            total_cost,
+        })
+    }
+
+    /// Minimum inventory for each configured component of `root_id`
+    /// `safety_stock = safety_stock_days * daily_demand * explosion_quantity * demand_variability_factor`
+    pub fn calculate_safety_stock_requirements(
+        &self,
+        root_id: &ComponentId,
+        daily_demand: Decimal,
+        safety_configs: &[SafetyStockConfig],
+    ) -> Result<SafetyStockReport> {
+        let quantities = ExplosionCalculator::new(self.graph).flatten(root_id)?;
+
+        let mut requirements = Vec::new();
+        let mut not_in_bom = Vec::new();
+
+        for config in safety_configs {
+            let Some(&explosion_quantity) = quantities.get(&config.component_id) else {
+                not_in_bom.push(config.component_id.clone());
+                continue;
+            };
+
+            let variability = Decimal::try_from(config.demand_variability_factor)
+                .ok()
+                .filter(|factor| !factor.is_sign_negative())
+                .ok_or_else(|| {
+                    BomError::CalculationError(format!(
+                        "invalid demand variability factor {} for {}",
+                        config.demand_variability_factor,
+                        config.component_id.as_str()
+                    ))
+                })?;
+
+            let component_demand = daily_demand * explosion_quantity;
+            requirements.push(SafetyStockRequirement {
+                component_id: config.component_id.clone(),
+                explosion_quantity,
+                daily_demand: component_demand,
+                safety_stock_days: config.safety_stock_days,
+                min_inventory: Decimal::from(config.safety_stock_days) * component_demand * variability,
+            });
+        }
+
+        requirements.sort_by(|a, b| a.component_id.as_str().cmp(b.component_id.as_str()));
+
+        Ok(SafetyStockReport {
+            root_component: root_id.clone(),
+            daily_demand,
+            requirements,
+            not_in_bom,
+        })
+    }
+
+    /// First article inspections for one unit of `root_id`, classified by procurement type:
+    /// bought parts get source and receiving inspections, made parts in-process and final
+    /// inspections, parts that can be either get all four. Phantoms are not inspected;
+    /// components missing from the repository are skipped
+    pub fn calculate_first_article_inspection_plan(&self, root_id: &ComponentId) -> Result<FaiPlan> {
+        use rust_decimal::prelude::ToPrimitive;
+
+        let explosion = ExplosionCalculator::new(self.graph).explode(root_id, Decimal::ONE)?;
+        let mut inspections = Vec::new();
+
+        for item in explosion.items.iter().filter(|item| !item.is_phantom) {
+            let Ok(component) = self.repository.get_component(&item.component_id) else {
+                continue;
+            };
+            let inspection_types: &[InspectionType] = match component.procurement_type {
+                ProcurementType::Buy => &[InspectionType::Source, InspectionType::Receiving],
+                ProcurementType::Make => &[InspectionType::InProcess, InspectionType::Final],
+                ProcurementType::Both => &[
+                    InspectionType::Source,
+                    InspectionType::Receiving,
+                    InspectionType::InProcess,
+                    InspectionType::Final,
+                ],
+            };
+            let expected_qty = item.total_quantity.ceil().to_usize().unwrap_or(usize::MAX);
+
+            for &inspection_type in inspection_types {
+                inspections.push(FaiInspection {
+                    component_id: item.component_id.clone(),
+                    level: item.level,
+                    inspection_type,
+                    expected_qty,
+                });
+            }
+        }
+
+        inspections.sort_by(|a, b| {
+            a.level
+                .cmp(&b.level)
+                .then_with(|| a.component_id.as_str().cmp(b.component_id.as_str()))
+                .then(a.inspection_type.cmp(&b.inspection_type))
+        });
+        Ok(FaiPlan { inspections })
+    }
+
+    /// Alternative routes of a component from the repository (flexible manufacturing)
+    pub fn find_alternative_routes(&self, component_id: &ComponentId) -> Result<Vec<AlternativeRoute>> {
+        self.repository.get_alternative_routes(component_id)
+    }
+
+    /// Best alternative route of a component by `criteria`; among equal routes the first
+    /// one in repository order wins. Fails if the component has no alternative routes
+    pub fn select_optimal_route(
+        &self,
+        component_id: &ComponentId,
+        criteria: RoutingCriteria,
+    ) -> Result<AlternativeRoute> {
+        self.find_alternative_routes(component_id)?
+            .into_iter()
+            .min_by(|a, b| match criteria {
+                RoutingCriteria::MinCost => {
+                    a.cost_factor.cmp(&b.cost_factor).then(a.lead_time_days.cmp(&b.lead_time_days))
+                }
+                RoutingCriteria::MinLeadTime => {
+                    a.lead_time_days.cmp(&b.lead_time_days).then(a.cost_factor.cmp(&b.cost_factor))
+                }
+                RoutingCriteria::MaxCapacity => a
+                    .operations
+                    .len()
+                    .cmp(&b.operations.len())
+                    .then(a.lead_time_days.cmp(&b.lead_time_days)),
+            })
+            .ok_or_else(|| {
+                BomError::CalculationError(format!("no alternative routes for component {}", component_id.as_str()))
+            })
+    }
+
+    /// How far limited stock of some components restricts production of `root_id`
+    /// `unavailable` lists `(component_id, available_quantity)`; components not in the root's
+    /// BOM are ignored and components not listed are unlimited. One unit of the root is planned
+    /// and quantities are per unit, so `can_produce_qty` of 0.5 means half of it can be built.
+    /// Stock beyond the planned unit does not raise `can_produce_qty` above 1. A component with
+    /// alternatives (same alternative group under a parent in the root's BOM) limits production
+    /// by the combined stock of the group, and not at all if an alternative is unlimited
+    pub fn simulate_shortage(
+        &self,
+        root_id: &ComponentId,
+        unavailable: &[(ComponentId, Decimal)],
+    ) -> Result<ShortageImpact> {
+        let quantities = ExplosionCalculator::new(self.graph).flatten(root_id)?;
+        let stock: HashMap<&ComponentId, Decimal> =
+            unavailable.iter().map(|(id, available)| (id, *available)).collect();
+
+        let mut can_produce_qty = Decimal::ONE;
+        let mut shortages = Vec::new();
+        let mut alternatives_of: HashMap<&ComponentId, Vec<ComponentId>> = HashMap::new();
+        for (component_id, available) in unavailable {
+            let Some(&needed) = quantities.get(component_id).filter(|needed| !needed.is_zero()) else {
+                continue;
+            };
+            let alternatives = alternatives_of
+                .entry(component_id)
+                .or_insert_with(|| self.alternatives_in_bom(component_id, &quantities));
+
+            let combined = alternatives
+                .iter()
+                .try_fold(*available, |sum, alternative| stock.get(alternative).map(|stock| sum + stock));
+            if let Some(combined) = combined {
+                can_produce_qty = can_produce_qty.min((combined / needed).max(Decimal::ZERO));
+            }
+            if *available < needed {
+                shortages.push(ShortageItem {
+                    component_id: component_id.clone(),
+                    needed,
+                    available: *available,
+                    shortfall: needed - available,
+                });
+            }
+        }
+        shortages.sort_by(|a, b| a.component_id.as_str().cmp(b.component_id.as_str()));
+
+        let short: HashSet<&ComponentId> = shortages.iter().map(|item| &item.component_id).collect();
+        let mut alternatives_available: Vec<(ComponentId, ComponentId)> = Vec::new();
+        for item in &shortages {
+            for alternative in &alternatives_of[&item.component_id] {
+                let pair = (item.component_id.clone(), alternative.clone());
+                if !short.contains(alternative) && !alternatives_available.contains(&pair) {
+                    alternatives_available.push(pair);
+                }
+            }
+        }
+        alternatives_available
+            .sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()).then_with(|| a.1.as_str().cmp(b.1.as_str())));
+
+        Ok(ShortageImpact {
+            can_produce_qty,
+            shortages,
+            alternatives_available,
+        })
+    }
+
+    /// Other members of the alternative groups `component_id` belongs to, under parents that
+    /// are in the BOM described by `quantities` (each listed once, in BOM order)
+    fn alternatives_in_bom(
+        &self,
+        component_id: &ComponentId,
+        quantities: &HashMap<ComponentId, Decimal>,
+    ) -> Vec<ComponentId> {
+        let Some(node) = self.graph.find_node(component_id) else {
+            return Vec::new();
+        };
+
+        let arena = self.graph.arena();
+        let mut alternatives = Vec::new();
+        for (parent, edge) in arena.parents(node) {
+            let Some(group) = &edge.bom_item.alternative_group else {
+                continue;
+            };
+            let in_bom = arena
+                .node(parent)
+                .is_some_and(|parent| quantities.contains_key(&parent.component_id));
+            if !in_bom {
+                continue;
+            }
+            for (_, sibling) in arena.children(parent) {
+                let alternative = &sibling.bom_item.child_id;
+                if sibling.bom_item.alternative_group.as_ref() == Some(group)
+                    && alternative != component_id
+                    && !alternatives.contains(alternative)
+                {
+                    alternatives.push(alternative.clone());
+                }
+            }
+        }
+        alternatives
+    }
+
+    /// Demand rate and ABC class per component from demand events of the last `lookback_days`
+    /// Components are ranked by total demand: the top 20% (rounded up) are class A, the next
+    /// 30% class B, the rest class C. Entries of the same component are combined; the result is
+    /// ordered by demand, highest first (ties by component ID)
+    pub fn calculate_component_velocity(
+        &self,
+        data: &[VelocityData],
+        lookback_days: u32,
+    ) -> Result<Vec<ComponentVelocity>> {
+        if lookback_days == 0 {
+            return Err(BomError::CalculationError(
+                "velocity lookback must be at least one day".to_string(),
+            ));
+        }
+        let since = Utc::now() - Duration::days(lookback_days as i64);
+
+        let mut demand: HashMap<&ComponentId, Decimal> = HashMap::new();
+        for entry in data {
+            let recent: Decimal = entry
+                .demand_events
+                .iter()
+                .filter(|(at, _)| *at >= since)
+                .map(|(_, quantity)| *quantity)
+                .sum();
+            *demand.entry(&entry.component_id).or_insert(Decimal::ZERO) += recent;
+        }
+
+        let mut ranked: Vec<(&ComponentId, Decimal)> = demand.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.as_str().cmp(b.0.as_str())));
+
+        let count = ranked.len();
+        let a_count = (count * 20).div_ceil(100);
+        let ab_count = (count * 50).div_ceil(100);
+        let days = Decimal::from(lookback_days);
+
+        Ok(ranked
+            .into_iter()
+            .enumerate()
+            .map(|(rank, (component_id, total_demand))| ComponentVelocity {
+                component_id: component_id.clone(),
+                total_demand,
+                demand_per_day: total_demand / days,
+                velocity_class: if rank < a_count {
+                    VelocityClass::A
+                } else if rank < ab_count {
+                    VelocityClass::B
+                } else {
+                    VelocityClass::C
+                },
+            })
+            .collect())
+    }
+
+    /// Lead time of a component in days (zero if unknown)
+    fn lead_time_days(&self, component_id: &ComponentId) -> i64 {
+        self.repository
+            .get_component(component_id)
+            .ok()
+            .and_then(|component| component.lead_time_days)
+            .unwrap_or(0) as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{create_test_bom_item, create_test_component};
+    use bom_core::repository::memory::InMemoryRepository;
+    use bom_core::*;
+    use chrono::TimeZone;
+
+    fn component_with_lead_time(id: &str, lead_time_days: u32) -> Component {
+        Component {
+            lead_time_days: Some(lead_time_days),
+            ..create_test_component(id)
+        }
+    }
+
+    #[test]
+    fn test_generate_work_orders() {
+        let repo = InMemoryRepository::new();
+
+        // A (2d) -> B (3d, qty 2) -> C (5d, qty 3)
+        //        -> D (1d, qty 1)
+        repo.add_component(component_with_lead_time("A", 2));
+        repo.add_component(component_with_lead_time("B", 3));
+        repo.add_component(component_with_lead_time("C", 5));
+        repo.add_component(component_with_lead_time("D", 1));
+
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "C", 3)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "D", 1)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let planner = ProductionPlanner::new(&graph, &repo);
+
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let orders = planner
+            .generate_work_orders(&ComponentId::new("A"), Decimal::from(10), start)
+            .unwrap();
+        assert_eq!(orders.len(), 4);
+
+        let order = |id: &str| orders.iter().find(|o| o.component_id.as_str() == id).unwrap();
+        let day = |d: i64| start + Duration::days(d);
+
+        // Critical path C -> B -> A takes 5 + 3 + 2 = 10 days
+        let a = order("A");
+        assert_eq!((a.planned_start, a.planned_finish), (day(8), day(10)));
+        assert_eq!(a.parent_work_order_id, None);
+        assert_eq!(a.quantity, Decimal::from(10));
+
+        let b = order("B");
+        assert_eq!((b.planned_start, b.planned_finish), (day(5), day(8)));
+        assert_eq!(b.parent_work_order_id, Some(a.id));
+        assert_eq!(b.quantity, Decimal::from(20));
+
+        let c = order("C");
+        assert_eq!((c.planned_start, c.planned_finish), (day(0), day(5)));
+        assert_eq!(c.parent_work_order_id, Some(b.id));
+        assert_eq!(c.order_type, WorkOrderType::PurchaseRequisition);
+        assert_eq!(c.quantity, Decimal::from(60));
+
+        // D is off the critical path and finishes just in time for A
+        let d = order("D");
+        assert_eq!((d.planned_start, d.planned_finish), (day(7), day(8)));
+        assert_eq!(d.order_type, WorkOrderType::PurchaseRequisition);
+    }
+
+    #[test]
+    fn test_calculate_procurement_summary() {
+        let repo = InMemoryRepository::new();
+
+        let buy = |id: &str, cost: i64, vendor: Option<&str>| Component {
+            standard_cost: Some(Decimal::from(cost)),
+            procurement_type: ProcurementType::Buy,
+            preferred_vendor: vendor.map(str::to_string),
+            ..component_with_lead_time(id, 1)
+        };
+
+        // A -> B (qty 2, made) -> C (qty 3, ACME)
+        //   -> D (qty 1, ACME)
+        //   -> E (qty 4, no vendor)
+        repo.add_component(component_with_lead_time("A", 1));
+        repo.add_component(component_with_lead_time("B", 1));
+        repo.add_component(buy("C", 5, Some("ACME")));
+        repo.add_component(buy("D", 20, Some("ACME")));
+        repo.add_component(buy("E", 1, None));
+
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "C", 3)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "D", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "E", 4)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let planner = ProductionPlanner::new(&graph, &repo);
+
+        let summary = planner
+            .calculate_procurement_summary(&ComponentId::new("A"), Decimal::from(10))
+            .unwrap();
+
+        // Made components (A, B) are not purchased
+        assert_eq!(summary.by_vendor.len(), 1);
+        let acme = &summary.by_vendor["ACME"];
+        assert_eq!(acme.len(), 2);
+        assert_eq!(acme[0].component_id, ComponentId::new("C"));
+        assert_eq!(acme[0].quantity, Decimal::from(60));
+        assert_eq!(acme[0].extended_cost, Decimal::from(300));
+        assert_eq!(acme[1].component_id, ComponentId::new("D"));
+        assert_eq!(acme[1].quantity, Decimal::from(10));
+
+        let acme_quantity: Decimal = acme.iter().map(|line| line.quantity).sum();
+        assert_eq!(acme_quantity, Decimal::from(70));
+
+        assert_eq!(summary.unassigned_vendor.len(), 1);
+        assert_eq!(summary.unassigned_vendor[0].quantity, Decimal::from(40));
+
+        // 300 + 200 + 40
+        assert_eq!(summary.total_cost, Decimal::from(540));
+    }
+
+    #[test]
+    fn test_calculate_safety_stock_requirements() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B (qty 2) -> C (qty 3)
+        repo.add_component(component_with_lead_time("A", 1));
+        repo.add_component(component_with_lead_time("B", 1));
+        repo.add_component(component_with_lead_time("C", 1));
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "C", 3)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let planner = ProductionPlanner::new(&graph, &repo);
+
+        let config = |id: &str, days: u32, factor: f64| SafetyStockConfig {
+            component_id: ComponentId::new(id),
+            safety_stock_days: days,
+            demand_variability_factor: factor,
+        };
+        let report = planner
+            .calculate_safety_stock_requirements(
+                &ComponentId::new("A"),
+                Decimal::from(10),
+                &[config("C", 2, 1.5), config("A", 5, 1.0), config("X", 1, 1.0)],
+            )
+            .unwrap();
+
+        assert_eq!(report.requirements.len(), 2);
+
+        // 5 days * 10 per day
+        let a = &report.requirements[0];
+        assert_eq!(a.component_id, ComponentId::new("A"));
+        assert_eq!(a.min_inventory, Decimal::from(50));
+
+        // 2 days * 10 per day * 6 per unit * 1.5
+        let c = &report.requirements[1];
+        assert_eq!(c.daily_demand, Decimal::from(60));
+        assert_eq!(c.min_inventory, Decimal::from(180));
+
+        assert_eq!(report.not_in_bom, vec![ComponentId::new("X")]);
+
+        assert!(planner
+            .calculate_safety_stock_requirements(&ComponentId::new("A"), Decimal::from(10), &[config("B", 1, f64::NAN)])
+            .is_err());
+    }
+
+    #[test]
+    fn test_calculate_lead_time() {
+        let repo = InMemoryRepository::new();
+
+        // A (2d) -> B (3d) -> C (5d), A -> D (1d)
+        repo.add_component(component_with_lead_time("A", 2));
+        repo.add_component(component_with_lead_time("B", 3));
+        repo.add_component(component_with_lead_time("C", 5));
+        repo.add_component(component_with_lead_time("D", 1));
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "C", 3)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "D", 1)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let planner = ProductionPlanner::new(&graph, &repo);
+
+        let lead_time = planner.calculate_lead_time(&ComponentId::new("A")).unwrap();
+        assert_eq!(lead_time.total_lead_time_days, 10);
+        assert_eq!(
+            lead_time.critical_path,
+            vec![ComponentId::new("A"), ComponentId::new("B"), ComponentId::new("C")]
+        );
+    }
+
+    #[test]
+    fn test_calculate_procurement_lead_time() {
+        let repo = InMemoryRepository::new();
+        let buy = |id: &str, lead_time_days: u32| Component {
+            procurement_type: ProcurementType::Buy,
+            ..component_with_lead_time(id, lead_time_days)
+        };
+
+        // A (2) -> B (make, 3) -> C (buy, 15)
+        //       -> D (buy, 12)
+        //       -> E (make, 9) -> F (buy, 8)
+        //       -> G (make, 4)
+        repo.add_component(component_with_lead_time("A", 2));
+        repo.add_component(component_with_lead_time("B", 3));
+        repo.add_component(buy("C", 15));
+        repo.add_component(buy("D", 12));
+        repo.add_component(component_with_lead_time("E", 9));
+        repo.add_component(buy("F", 8));
+        repo.add_component(component_with_lead_time("G", 4));
+        for (parent, child) in [("A", "B"), ("B", "C"), ("A", "D"), ("A", "E"), ("E", "F"), ("A", "G")] {
+            repo.add_bom_item(create_test_bom_item(parent, child, 1)).unwrap();
+        }
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let planner = ProductionPlanner::new(&graph, &repo);
+
+        let result = planner.calculate_procurement_lead_time(&ComponentId::new("A")).unwrap();
+
+        // C: 2 + 3 + 15, F: 2 + 9 + 8, D: 2 + 12
+        assert_eq!(result.critical_purchased_component, ComponentId::new("C"));
+        assert_eq!(result.total_days, 20);
+        assert_eq!(
+            result.all_purchased_lead_times,
+            vec![
+                (ComponentId::new("C"), 15),
+                (ComponentId::new("D"), 12),
+                (ComponentId::new("F"), 8),
+            ]
+        );
+
+        // Below B only C is bought; a made leaf has nothing to buy
+        let b = planner.calculate_procurement_lead_time(&ComponentId::new("B")).unwrap();
+        assert_eq!(b.total_days, 18);
+        assert!(planner.calculate_procurement_lead_time(&ComponentId::new("G")).is_err());
+        assert!(planner.calculate_procurement_lead_time(&ComponentId::new("MISSING")).is_err());
+    }
+
+    #[test]
+    fn test_simulate_production_run() {
+        let repo = InMemoryRepository::new();
+
+        // A (2d) -> B (3d, qty 2) -> C (5d, qty 3)
+        //        -> D (1d, qty 1)
+        repo.add_component(component_with_lead_time("A", 2));
+        repo.add_component(component_with_lead_time("B", 3));
+        repo.add_component(component_with_lead_time("C", 5));
+        repo.add_component(component_with_lead_time("D", 1));
+
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "C", 3)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "D", 1)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let planner = ProductionPlanner::new(&graph, &repo);
+
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let day = |d: i64| start + Duration::days(d);
+        let a = ComponentId::new("A");
+
+        // Without constraints the lead times decide: C -> B -> A takes 10 days
+        let unconstrained = planner.simulate_production_run(&a, Decimal::from(10), start, &[]).unwrap();
+        assert_eq!(unconstrained.completion_date, day(10));
+        assert_eq!(unconstrained.bottleneck_component, ComponentId::new("C"));
+        assert!(unconstrained.daily_schedule.is_empty());
+
+        // B needs 20 units at 2 per day: 10 days instead of its 3 day lead time
+        let constraint = CapacityConstraint {
+            component_id: ComponentId::new("B"),
+            max_daily_units: Decimal::from(2),
+            machine_group: "PRESS".to_string(),
+        };
+        let simulation = planner
+            .simulate_production_run(&a, Decimal::from(10), start, std::slice::from_ref(&constraint))
+            .unwrap();
+        assert_eq!(simulation.completion_date, day(17));
+        assert_eq!(simulation.bottleneck_component, ComponentId::new("B"));
+
+        // B runs on days 5..15, right after C is complete
+        assert_eq!(simulation.daily_schedule.len(), 10);
+        assert_eq!(simulation.daily_schedule[0].date, day(5));
+        assert_eq!(simulation.daily_schedule[9].date, day(14));
+        assert!(simulation.daily_schedule.iter().all(|d| d.production
+            == vec![ScheduledProduction {
+                component_id: ComponentId::new("B"),
+                machine_group: "PRESS".to_string(),
+                quantity: Decimal::from(2),
+            }]));
+
+        let zero_capacity = CapacityConstraint {
+            max_daily_units: Decimal::ZERO,
+            ..constraint
+        };
+        assert!(planner
+            .simulate_production_run(&a, Decimal::from(10), start, &[zero_capacity])
+            .is_err());
+    }
+
+    #[test]
+    fn test_first_article_inspection_plan() {
+        let repo = InMemoryRepository::new();
+        let bought = |id: &str| Component {
+            procurement_type: ProcurementType::Buy,
+            ..component_with_lead_time(id, 1)
+        };
+
+        // A (make) -> B (make, qty 2) -> C (buy, qty 1.5)
+        //          -> D (buy, qty 4)
+        repo.add_component(component_with_lead_time("A", 1));
+        repo.add_component(component_with_lead_time("B", 1));
+        repo.add_component(bought("C"));
+        repo.add_component(bought("D"));
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(BomItem {
+            quantity: Decimal::new(15, 1),
+            ..create_test_bom_item("B", "C", 1)
+        })
+        .unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "D", 4)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let planner = ProductionPlanner::new(&graph, &repo);
+        let plan = planner
+            .calculate_first_article_inspection_plan(&ComponentId::new("A"))
+            .unwrap();
+
+        let steps: Vec<(&str, usize, InspectionType, usize)> = plan
+            .inspections
+            .iter()
+            .map(|i| (i.component_id.as_str(), i.level, i.inspection_type, i.expected_qty))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                ("A", 0, InspectionType::InProcess, 1),
+                ("A", 0, InspectionType::Final, 1),
+                ("B", 1, InspectionType::InProcess, 2),
+                ("B", 1, InspectionType::Final, 2),
+                ("D", 1, InspectionType::Source, 4),
+                ("D", 1, InspectionType::Receiving, 4),
+                ("C", 2, InspectionType::Source, 3),
+                ("C", 2, InspectionType::Receiving, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_select_optimal_route() {
+        let repo = InMemoryRepository::new();
+        let frame = ComponentId::new("FRAME");
+        let route = |route_id: &str, operations: &[&str], lead_time_days: u32, cost_factor: Decimal| AlternativeRoute {
+            route_id: route_id.to_string(),
+            operations: operations.iter().map(|op| op.to_string()).collect(),
+            lead_time_days,
+            cost_factor,
+        };
+        repo.add_alternative_route(frame.clone(), route("LINE-A", &["CUT", "WELD", "PAINT"], 5, Decimal::ONE));
+        repo.add_alternative_route(frame.clone(), route("LINE-B", &["ROBOT-WELD", "PAINT"], 3, Decimal::new(125, 2)));
+
+        let graph = BomGraph::new();
+        let planner = ProductionPlanner::new(&graph, &repo);
+        assert_eq!(planner.find_alternative_routes(&frame).unwrap().len(), 2);
+
+        let pick = |criteria| planner.select_optimal_route(&frame, criteria).unwrap().route_id;
+        assert_eq!(pick(RoutingCriteria::MinCost), "LINE-A");
+        assert_eq!(pick(RoutingCriteria::MinLeadTime), "LINE-B");
+        assert_eq!(pick(RoutingCriteria::MaxCapacity), "LINE-B");
+
+        assert!(matches!(
+            planner.select_optimal_route(&ComponentId::new("SEAT"), RoutingCriteria::MinCost),
+            Err(BomError::CalculationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_calculate_component_velocity() {
+        let repo = InMemoryRepository::new();
+        let graph = BomGraph::new();
+        let planner = ProductionPlanner::new(&graph, &repo);
+
+        let now = Utc::now();
+        let days_ago = |days: i64| now - Duration::days(days);
+        let data = |id: &str, events: Vec<(DateTime<Utc>, i64)>| VelocityData {
+            component_id: ComponentId::new(id),
+            demand_events: events.into_iter().map(|(at, qty)| (at, Decimal::from(qty))).collect(),
+        };
+        let velocity = planner
+            .calculate_component_velocity(
+                &[
+                    data("BOLT", vec![(days_ago(1), 400), (days_ago(5), 200), (days_ago(60), 5000)]),
+                    data("FRAME", vec![(days_ago(2), 30)]),
+                    data("WHEEL", vec![(days_ago(3), 120)]),
+                ],
+                30,
+            )
+            .unwrap();
+
+        let classes: Vec<(&str, VelocityClass)> = velocity
+            .iter()
+            .map(|v| (v.component_id.as_str(), v.velocity_class))
+            .collect();
+        assert_eq!(
+            classes,
+            vec![("BOLT", VelocityClass::A), ("WHEEL", VelocityClass::B), ("FRAME", VelocityClass::C)]
+        );
+        // The event outside the 30-day window is ignored
+        assert_eq!(velocity[0].total_demand, Decimal::from(600));
+        assert_eq!(velocity[0].demand_per_day, Decimal::from(20));
+        assert_eq!(velocity[1].demand_per_day, Decimal::from(4));
+
+        assert!(planner.calculate_component_velocity(&[], 0).is_err());
+    }
+
+    #[test]
+    fn test_simulate_shortage() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B (10) ; A -> C (2) or its alternative C2 (group G1) ; A -> D (1)
+        let mut graph = BomGraph::new();
+        graph.add_bom_item(create_test_bom_item("A", "B", 10)).unwrap();
+        for child in ["C", "C2"] {
+            let mut item = create_test_bom_item("A", child, 2);
+            item.alternative_group = Some("G1".to_string());
+            graph.add_bom_item(item).unwrap();
+        }
+        graph.add_bom_item(create_test_bom_item("A", "D", 1)).unwrap();
+        let planner = ProductionPlanner::new(&graph, &repo);
+
+        // 5 of the 10 B needed per unit: half of the planned unit can be built
+        let impact = planner
+            .simulate_shortage(
+                &ComponentId::new("A"),
+                &[
+                    (ComponentId::new("B"), Decimal::from(5)),
+                    (ComponentId::new("C"), Decimal::from(8)),
+                    (ComponentId::new("X"), Decimal::ZERO),
+                ],
+            )
+            .unwrap();
+        assert_eq!(impact.can_produce_qty, Decimal::new(5, 1));
+        assert_eq!(
+            impact.shortages,
+            vec![ShortageItem {
+                component_id: ComponentId::new("B"),
+                needed: Decimal::from(10),
+                available: Decimal::from(5),
+                shortfall: Decimal::from(5),
+            }]
+        );
+        assert!(impact.alternatives_available.is_empty());
+
+        // C short, but its alternative C2 is unlimited and can step in
+        let impact = planner
+            .simulate_shortage(&ComponentId::new("A"), &[(ComponentId::new("C"), Decimal::ONE)])
+            .unwrap();
+        assert_eq!(impact.can_produce_qty, Decimal::ONE);
+        assert_eq!(impact.shortages.len(), 1);
+        assert_eq!(impact.alternatives_available, vec![(ComponentId::new("C"), ComponentId::new("C2"))]);
+
+        // C2 limited too: 1 C + 4 C2 would cover 2.5 units at 2 per unit, but 1 is planned
+        let impact = planner
+            .simulate_shortage(
+                &ComponentId::new("A"),
+                &[(ComponentId::new("C"), Decimal::ONE), (ComponentId::new("C2"), Decimal::from(4))],
+            )
+            .unwrap();
+        assert_eq!(impact.can_produce_qty, Decimal::ONE);
+        assert_eq!(impact.alternatives_available, vec![(ComponentId::new("C"), ComponentId::new("C2"))]);
+
+        // Both short: no alternative is available
+        let impact = planner
+            .simulate_shortage(
+                &ComponentId::new("A"),
+                &[(ComponentId::new("C"), Decimal::ONE), (ComponentId::new("C2"), Decimal::ZERO)],
+            )
+            .unwrap();
+        assert_eq!(impact.can_produce_qty, Decimal::new(5, 1));
+        assert!(impact.alternatives_available.is_empty());
+
+        // No D at all: nothing can be built
+        let impact = planner
+            .simulate_shortage(&ComponentId::new("A"), &[(ComponentId::new("D"), Decimal::ZERO)])
+            .unwrap();
+        assert_eq!(impact.can_produce_qty, Decimal::ZERO);
+
+        let unused = planner.simulate_shortage(&ComponentId::new("A"), &[]).unwrap();
+        assert_eq!(unused.can_produce_qty, Decimal::ONE);
+    }
+}
@@ -0,0 +1,154 @@
+use bom_core::{ComponentId, CostBreakdown, ExplosionResult, WhereUsedResult};
+use rust_decimal::Decimal;
+use std::fmt::Write;
+
+use crate::{DepthProfile, LeadTimeResult};
+
+/// Optional sections of a `BomReport` (the explosion is always included)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReportOptions {
+    pub include_cost: bool,
+    pub include_where_used: bool,
+    pub include_lead_times: bool,
+    pub include_analytics: bool,
+}
+
+impl ReportOptions {
+    /// Every section included
+    pub fn all() -> Self {
+        Self {
+            include_cost: true,
+            include_where_used: true,
+            include_lead_times: true,
+            include_analytics: true,
+        }
+    }
+}
+
+/// Structure statistics of a BOM
+#[derive(Debug, Clone)]
+pub struct BomAnalytics {
+    pub depth_profile: DepthProfile,
+    /// Components without children (purchased or raw material)
+    pub leaf_count: usize,
+}
+
+/// All calculations for one root component in a single result (BOM 報表)
+#[derive(Debug, Clone)]
+pub struct BomReport {
+    pub root_component: ComponentId,
+    pub quantity: Decimal,
+    pub explosion: ExplosionResult,
+    /// Unit cost of the root
+    pub cost: Option<CostBreakdown>,
+    /// Assemblies the root is used in
+    pub where_used: Option<WhereUsedResult>,
+    pub lead_time: Option<LeadTimeResult>,
+    pub analytics: Option<BomAnalytics>,
+}
+
+impl BomReport {
+    /// Render the report as a Markdown document, one section per included part
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        // Writing to a String cannot fail
+        let _ = self.write_markdown(&mut md);
+        md
+    }
+
+    fn write_markdown(&self, md: &mut String) -> std::fmt::Result {
+        writeln!(md, "# BOM Report: {}", self.root_component.as_str())?;
+        writeln!(md)?;
+        writeln!(md, "Quantity: {}", self.quantity)?;
+
+        writeln!(md)?;
+        writeln!(md, "## Material Explosion")?;
+        writeln!(md)?;
+        writeln!(md, "| Level | Component | Quantity |")?;
+        writeln!(md, "|------:|-----------|---------:|")?;
+        let mut items: Vec<_> = self.explosion.items.iter().collect();
+        items.sort_by(|a, b| a.level.cmp(&b.level).then_with(|| a.component_id.as_str().cmp(b.component_id.as_str())));
+        for item in items {
+            writeln!(md, "| {} | {} | {} |", item.level, item.component_id.as_str(), item.total_quantity)?;
+        }
+        writeln!(md)?;
+        writeln!(
+            md,
+            "Unique components: {}, maximum depth: {}",
+            self.explosion.unique_component_count, self.explosion.max_depth
+        )?;
+
+        if let Some(cost) = &self.cost {
+            writeln!(md)?;
+            writeln!(md, "## Cost")?;
+            writeln!(md)?;
+            writeln!(md, "| Element | Unit Cost | Total Cost |")?;
+            writeln!(md, "|---------|----------:|-----------:|")?;
+            for (element, unit_cost) in [
+                ("Material", cost.material_cost),
+                ("Labor", cost.labor_cost),
+                ("Overhead", cost.overhead_cost),
+                ("Subcontract", cost.subcontract_cost),
+                ("**Total**", cost.total_cost),
+            ] {
+                writeln!(md, "| {} | {} | {} |", element, unit_cost, unit_cost * self.quantity)?;
+            }
+        }
+
+        if let Some(where_used) = &self.where_used {
+            writeln!(md)?;
+            writeln!(md, "## Where Used")?;
+            writeln!(md)?;
+            if where_used.used_in.is_empty() {
+                writeln!(md, "Not used in any assembly.")?;
+            } else {
+                writeln!(md, "| Parent | Quantity | Level |")?;
+                writeln!(md, "|--------|---------:|------:|")?;
+                for item in &where_used.used_in {
+                    writeln!(md, "| {} | {} | {} |", item.parent_id.as_str(), item.quantity, item.level)?;
+                }
+            }
+        }
+
+        if let Some(lead_time) = &self.lead_time {
+            writeln!(md)?;
+            writeln!(md, "## Lead Time")?;
+            writeln!(md)?;
+            writeln!(md, "Cumulative lead time: {} days", lead_time.total_lead_time_days)?;
+            writeln!(md)?;
+            writeln!(
+                md,
+                "Critical path: {}",
+                lead_time
+                    .critical_path
+                    .iter()
+                    .map(|id| id.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" → ")
+            )?;
+        }
+
+        if let Some(analytics) = &self.analytics {
+            let profile = &analytics.depth_profile;
+            writeln!(md)?;
+            writeln!(md, "## Analytics")?;
+            writeln!(md)?;
+            writeln!(md, "- Maximum depth: {}", profile.max_depth)?;
+            writeln!(md, "- Average depth: {:.2}", profile.average_depth)?;
+            writeln!(md, "- Manufacturing steps: {}", profile.total_manufacturing_steps)?;
+            writeln!(md, "- Leaf components: {}", analytics.leaf_count)?;
+            writeln!(
+                md,
+                "- Deepest components: {}",
+                profile
+                    .nodes_at_max_depth
+                    .iter()
+                    .map(|id| id.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+        }
+
+        Ok(())
+    }
+}
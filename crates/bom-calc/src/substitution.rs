@@ -0,0 +1,334 @@
+use bom_core::{BomError, BomRepository, Component, ComponentId, CrossReferenceType, Result};
+use bom_graph::BomGraph;
+use rust_decimal::Decimal;
+use std::collections::HashSet;
+
+/// How a substitute relates to the original component
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SubstitutionType {
+    /// 替代料 (Another item in the same alternative group under a parent)
+    AlternativeItem,
+
+    /// 交叉參照 (Equivalent from the cross-reference list)
+    CrossReference,
+
+    /// 新料取代 (Successor in the supersession chain)
+    Supersession,
+}
+
+/// A component that can be used instead of another one
+#[derive(Debug, Clone)]
+pub struct SubstitutableComponent {
+    pub component_id: ComponentId,
+    pub substitution_type: SubstitutionType,
+    /// Substitute standard cost minus original standard cost (None if either is unknown)
+    pub cost_difference: Option<Decimal>,
+    /// Substitute lead time minus original lead time in days (None if either is unknown)
+    pub lead_time_difference: Option<i32>,
+}
+
+/// Components whose descriptions suggest they are the same part (重複料號)
+#[derive(Debug, Clone)]
+pub struct DuplicateGroup {
+    /// First component of the group by ID
+    pub representative: ComponentId,
+    /// Likely duplicates with their similarity to the representative, most similar first
+    pub duplicates: Vec<(ComponentId, f64)>,
+}
+
+/// Substitution analyzer (替代分析)
+/// Collects alternatives, cross-references and supersessions of a component in one view
+pub struct SubstitutionAnalyzer<'a, R: BomRepository> {
+    graph: &'a BomGraph,
+    repository: &'a R,
+}
+
+impl<'a, R: BomRepository> SubstitutionAnalyzer<'a, R> {
+    pub fn new(graph: &'a BomGraph, repository: &'a R) -> Self {
+        Self { graph, repository }
+    }
+
+    /// Find every component that can replace `component_id`, cheapest first
+    /// Substitutes with an unknown cost difference come last
+    pub fn find_substitutable_components(&self, component_id: &ComponentId) -> Result<Vec<SubstitutableComponent>> {
+        let original = self.repository.get_component(component_id)?;
+
+        let mut candidates: Vec<(ComponentId, SubstitutionType)> = Vec::new();
+        candidates.extend(
+            self.alternative_items(component_id)
+                .into_iter()
+                .map(|id| (id, SubstitutionType::AlternativeItem)),
+        );
+
+        let references = self.repository.get_cross_references(component_id)?;
+        candidates.extend(
+            references
+                .into_iter()
+                .filter(|r| r.relationship_type != CrossReferenceType::SupersededBy)
+                .map(|r| (r.equivalent_id, SubstitutionType::CrossReference)),
+        );
+
+        candidates.extend(
+            self.supersession_chain(component_id)?
+                .into_iter()
+                .map(|id| (id, SubstitutionType::Supersession)),
+        );
+
+        let mut seen = HashSet::new();
+        let mut substitutes: Vec<SubstitutableComponent> = candidates
+            .into_iter()
+            .filter(|(id, substitution_type)| id != component_id && seen.insert((id.clone(), *substitution_type)))
+            .map(|(id, substitution_type)| {
+                // Substitutes outside the repository are still listed, without comparison
+                let substitute = self.repository.get_component(&id).ok();
+                SubstitutableComponent {
+                    cost_difference: substitute.as_ref().and_then(|s| cost_difference(&original, s)),
+                    lead_time_difference: substitute.as_ref().and_then(|s| lead_time_difference(&original, s)),
+                    component_id: id,
+                    substitution_type,
+                }
+            })
+            .collect();
+
+        substitutes.sort_by(|a, b| match (a.cost_difference, b.cost_difference) {
+            (Some(x), Some(y)) => x.cmp(&y),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        }
+        .then_with(|| a.component_id.as_str().cmp(b.component_id.as_str())));
+
+        Ok(substitutes)
+    }
+
+    /// Group components of the BOM with near-identical descriptions (see `Component::similarity_score`)
+    /// Components are taken in ID order; each one not yet grouped becomes the representative
+    /// of the components scoring at least `similarity_threshold` against it. Only groups
+    /// with duplicates are returned. Compares every pair, so it is O(n²) in the component count
+    pub fn find_duplicate_components(&self, similarity_threshold: f64) -> Result<Vec<DuplicateGroup>> {
+        if !(0.0..=1.0).contains(&similarity_threshold) {
+            return Err(BomError::CalculationError(format!(
+                "similarity threshold {} is outside 0..1",
+                similarity_threshold
+            )));
+        }
+
+        // Nodes without a component record have no description to compare
+        let mut components: Vec<Component> = self
+            .graph
+            .iter_nodes()
+            .filter_map(|(_, id)| self.repository.get_component(id).ok())
+            .collect();
+        components.sort_by(|a, b| a.id.as_str().cmp(b.id.as_str()));
+
+        let mut grouped = vec![false; components.len()];
+        let mut groups = Vec::new();
+
+        for (i, representative) in components.iter().enumerate() {
+            if grouped[i] {
+                continue;
+            }
+
+            let mut duplicates = Vec::new();
+            for (j, candidate) in components.iter().enumerate().skip(i + 1) {
+                if grouped[j] {
+                    continue;
+                }
+                let score = representative.similarity_score(candidate);
+                if score >= similarity_threshold {
+                    grouped[j] = true;
+                    duplicates.push((candidate.id.clone(), score));
+                }
+            }
+
+            if !duplicates.is_empty() {
+                duplicates.sort_by(|a, b| b.1.total_cmp(&a.1));
+                groups.push(DuplicateGroup {
+                    representative: representative.id.clone(),
+                    duplicates,
+                });
+            }
+        }
+
+        Ok(groups)
+    }
+
+    /// Other items in the alternative groups `component_id` belongs to (under any parent)
+    fn alternative_items(&self, component_id: &ComponentId) -> Vec<ComponentId> {
+        let Some(node) = self.graph.find_node(component_id) else {
+            return Vec::new();
+        };
+        let arena = self.graph.arena();
+
+        arena
+            .parents(node)
+            .filter_map(|(parent, edge)| Some((parent, edge.bom_item.alternative_group.clone()?)))
+            .flat_map(|(parent, group)| {
+                arena
+                    .children(parent)
+                    .filter(move |(_, edge)| edge.bom_item.alternative_group.as_deref() == Some(group.as_str()))
+                    .map(|(_, edge)| edge.bom_item.child_id.clone())
+            })
+            .collect()
+    }
+
+    /// Successors of `component_id`, following `SupersededBy` references transitively
+    fn supersession_chain(&self, component_id: &ComponentId) -> Result<Vec<ComponentId>> {
+        let mut chain = Vec::new();
+        let mut visited = HashSet::from([component_id.clone()]);
+        let mut pending = vec![component_id.clone()];
+
+        while let Some(current) = pending.pop() {
+            for reference in self.repository.get_cross_references(&current)? {
+                if reference.relationship_type == CrossReferenceType::SupersededBy
+                    && visited.insert(reference.equivalent_id.clone())
+                {
+                    chain.push(reference.equivalent_id.clone());
+                    pending.push(reference.equivalent_id);
+                }
+            }
+        }
+
+        Ok(chain)
+    }
+}
+
+fn cost_difference(original: &Component, substitute: &Component) -> Option<Decimal> {
+    Some(substitute.standard_cost? - original.standard_cost?)
+}
+
+fn lead_time_difference(original: &Component, substitute: &Component) -> Option<i32> {
+    Some(substitute.lead_time_days? as i32 - original.lead_time_days? as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{create_test_bom_item, create_test_component};
+    use bom_core::repository::memory::InMemoryRepository;
+    use bom_core::*;
+
+    fn purchased_part(id: &str, cost: i64, lead_time_days: u32) -> Component {
+        Component {
+            component_type: ComponentType::RawMaterial,
+            standard_cost: Some(Decimal::from(cost)),
+            lead_time_days: Some(lead_time_days),
+            procurement_type: ProcurementType::Buy,
+            ..create_test_component(id)
+        }
+    }
+
+    fn alternative_item(parent: &str, child: &str, group: Option<&str>, priority: u32) -> BomItem {
+        BomItem {
+            sequence: 10 * priority,
+            alternative_group: group.map(|g| g.to_string()),
+            alternative_priority: Some(priority),
+            ..create_test_bom_item(parent, child, 1)
+        }
+    }
+
+    fn reference(id: &str, relationship_type: CrossReferenceType) -> CrossReference {
+        CrossReference {
+            equivalent_id: ComponentId::new(id),
+            relationship_type,
+            source: "AML".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_find_substitutable_components() {
+        let repo = InMemoryRepository::new();
+
+        // CAP-100 (cost 10, 5 days) used in board A, alternative CAP-101 in group G1
+        repo.add_component(purchased_part("A", 100, 10));
+        repo.add_component(purchased_part("CAP-100", 10, 5));
+        repo.add_component(purchased_part("CAP-101", 12, 3));
+        repo.add_component(purchased_part("CAP-200", 8, 10));
+        repo.add_component(purchased_part("CAP-300", 11, 5));
+        repo.add_bom_item(alternative_item("A", "CAP-100", Some("G1"), 1)).unwrap();
+        repo.add_bom_item(alternative_item("A", "CAP-101", Some("G1"), 2)).unwrap();
+
+        // Cross-referenced equivalent, and a supersession chain CAP-100 -> CAP-300 -> CAP-400
+        repo.add_cross_reference(
+            ComponentId::new("CAP-100"),
+            reference("CAP-200", CrossReferenceType::Interchangeable),
+        );
+        repo.add_cross_reference(
+            ComponentId::new("CAP-100"),
+            reference("CAP-300", CrossReferenceType::SupersededBy),
+        );
+        repo.add_cross_reference(
+            ComponentId::new("CAP-300"),
+            reference("CAP-400", CrossReferenceType::SupersededBy),
+        );
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let analyzer = SubstitutionAnalyzer::new(&graph, &repo);
+
+        let substitutes = analyzer
+            .find_substitutable_components(&ComponentId::new("CAP-100"))
+            .unwrap();
+
+        let summary: Vec<(&str, SubstitutionType, Option<Decimal>, Option<i32>)> = substitutes
+            .iter()
+            .map(|s| {
+                (
+                    s.component_id.as_str(),
+                    s.substitution_type,
+                    s.cost_difference,
+                    s.lead_time_difference,
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("CAP-200", SubstitutionType::CrossReference, Some(Decimal::from(-2)), Some(5)),
+                ("CAP-300", SubstitutionType::Supersession, Some(Decimal::from(1)), Some(0)),
+                ("CAP-101", SubstitutionType::AlternativeItem, Some(Decimal::from(2)), Some(-2)),
+                // Not in the repository: listed last, without comparison
+                ("CAP-400", SubstitutionType::Supersession, None, None),
+            ]
+        );
+
+        assert!(analyzer
+            .find_substitutable_components(&ComponentId::new("MISSING"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_find_duplicate_components() {
+        let repo = InMemoryRepository::new();
+
+        let descriptions = [
+            ("FRAME-001", "Bicycle Frame"),
+            ("TUBE-001", "Aluminum Tube 6061"),
+            ("TUBE-017", "Aluminum Tube, 6061"),
+            ("TUBE-020", "ALUMINUM TUBE 6061 "),
+            ("TUBE-002", "Steel Tube 4130"),
+        ];
+        for (id, description) in descriptions {
+            let mut component = purchased_part(id, 1, 1);
+            component.description = description.to_string();
+            repo.add_component(component);
+            if id != "FRAME-001" {
+                repo.add_bom_item(alternative_item("FRAME-001", id, None, 1)).unwrap();
+            }
+        }
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let analyzer = SubstitutionAnalyzer::new(&graph, &repo);
+
+        let groups = analyzer.find_duplicate_components(0.9).unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].representative, ComponentId::new("TUBE-001"));
+
+        let duplicates: Vec<&str> = groups[0].duplicates.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(duplicates, vec!["TUBE-020", "TUBE-017"]);
+        assert_eq!(groups[0].duplicates[0].1, 1.0);
+        assert!(groups[0].duplicates[1].1 >= 0.9);
+
+        assert!(analyzer.find_duplicate_components(1.5).is_err());
+    }
+}
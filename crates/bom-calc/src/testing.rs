@@ -0,0 +1,117 @@
+//! Helpers for regression tests of calculation changes, and the fixtures the tests share
+
+use bom_core::{BomItem, Component, ComponentId, ComponentType, ExplosionResult, ProcurementType};
+use chrono::Utc;
+use rust_decimal::Decimal;
+
+/// A finished product made in-house, counted in `EA`, costing 100 with a 7-day lead time
+pub fn create_test_component(id: &str) -> Component {
+    Component {
+        id: ComponentId::new(id),
+        description: format!("Component {}", id),
+        component_type: ComponentType::FinishedProduct,
+        uom: "EA".to_string(),
+        standard_cost: Some(Decimal::from(100)),
+        lead_time_days: Some(7),
+        procurement_type: ProcurementType::Make,
+        preferred_vendor: None,
+        organization: "ORG01".to_string(),
+        version: 0,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+/// `qty` of `child` per `parent` at sequence 10, without scrap, effectivity or alternatives
+pub fn create_test_bom_item(parent: &str, child: &str, qty: i32) -> BomItem {
+    BomItem {
+        id: uuid::Uuid::new_v4(),
+        parent_id: ComponentId::new(parent),
+        child_id: ComponentId::new(child),
+        quantity: Decimal::from(qty),
+        scrap_factor: Decimal::ZERO,
+        sequence: 10,
+        operation_sequence: None,
+        is_phantom: false,
+        effective_from: None,
+        effective_to: None,
+        alternative_group: None,
+        alternative_priority: None,
+        reference_designator: None,
+        position: None,
+        notes: None,
+        version: 0,
+    }
+}
+
+/// Panic with a description of every difference if the explosions require different
+/// components or quantities (see `ExplosionResult::compare`)
+#[track_caller]
+pub fn assert_explosion_equal(a: &ExplosionResult, b: &ExplosionResult) {
+    let diff = a.compare(b);
+    if diff.is_empty() {
+        return;
+    }
+
+    let mut differences = Vec::new();
+    let mut deltas: Vec<_> = diff.quantity_delta.iter().collect();
+    deltas.sort_by(|x, y| x.0.as_str().cmp(y.0.as_str()));
+    for (component_id, delta) in deltas {
+        differences.push(format!("{}: quantity differs by {}", component_id.as_str(), delta));
+    }
+    for component_id in &diff.missing_in_other {
+        differences.push(format!("{}: missing in second explosion", component_id.as_str()));
+    }
+    for component_id in &diff.extra_in_other {
+        differences.push(format!("{}: only in second explosion", component_id.as_str()));
+    }
+
+    panic!(
+        "explosions of {} differ:\n  {}",
+        diff.component_id.as_str(),
+        differences.join("\n  ")
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ExplosionCalculator;
+    use bom_core::repository::memory::InMemoryRepository;
+    use bom_graph::BomGraph;
+
+    fn explode(items: &[(&str, &str, i32)]) -> ExplosionResult {
+        let repo = InMemoryRepository::new();
+        for &(parent, child, qty) in items {
+            repo.add_bom_item(create_test_bom_item(parent, child, qty)).unwrap();
+        }
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        ExplosionCalculator::new(&graph)
+            .explode(&ComponentId::new("A"), Decimal::from(2))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_compare_explosions() {
+        let baseline = explode(&[("A", "B", 2), ("B", "C", 3), ("A", "D", 1)]);
+        assert!(baseline.compare(&baseline.clone()).is_empty());
+        assert_explosion_equal(&baseline, &explode(&[("A", "D", 1), ("A", "B", 2), ("B", "C", 3)]));
+
+        // C per B changed from 3 to 4, D replaced by E
+        let changed = explode(&[("A", "B", 2), ("B", "C", 4), ("A", "E", 1)]);
+        let diff = baseline.compare(&changed);
+        assert_eq!(diff.component_id, ComponentId::new("A"));
+        assert_eq!(diff.quantity_delta.len(), 1);
+        assert_eq!(diff.quantity_delta[&ComponentId::new("C")], Decimal::from(4));
+        assert_eq!(diff.missing_in_other, vec![ComponentId::new("D")]);
+        assert_eq!(diff.extra_in_other, vec![ComponentId::new("E")]);
+    }
+
+    #[test]
+    #[should_panic(expected = "C: quantity differs by 4")]
+    fn test_assert_explosion_equal_reports_quantity_difference() {
+        let baseline = explode(&[("A", "B", 2), ("B", "C", 3)]);
+        let changed = explode(&[("A", "B", 2), ("B", "C", 4)]);
+        assert_explosion_equal(&baseline, &changed);
+    }
+}
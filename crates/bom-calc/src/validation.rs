@@ -0,0 +1,856 @@
+use bom_core::{BomError, BomItem, BomRepository, ComponentId, ComponentType, ReferenceDesignatorList, Result};
+use bom_graph::{find_all_elementary_cycles, Arena, BomGraph};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
+
+/// Severity of a validation issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IssueSeverity {
+    /// Data that makes calculations wrong or impossible
+    Error,
+    /// Suspicious data that should be reviewed
+    Warning,
+}
+
+/// Which validation rule produced an issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValidationCategory {
+    /// BOM items referencing components that do not exist
+    Completeness,
+    /// Overlapping effectivity ranges for the same parent/child pair
+    Effectivity,
+    /// Sub-graphs that are not connected to the rest of the BOM
+    Connectivity,
+    /// Circular references between components
+    Cycle,
+    /// Missing or invalid standard costs
+    Cost,
+    /// BOM items that are entered more than once
+    Duplicate,
+    /// Fractional quantities of components that can only be used whole
+    Quantity,
+    /// BOM items with values that make no sense (see `BomItem::validate`)
+    InvalidItem,
+}
+
+/// A single data quality finding
+#[derive(Debug, Clone)]
+pub struct ValidationIssue {
+    pub severity: IssueSeverity,
+    pub category: ValidationCategory,
+    pub component_id: ComponentId,
+    pub message: String,
+}
+
+/// Two BOM items for the same parent/child pair that are effective at the same time
+#[derive(Debug, Clone)]
+pub struct EffectivityOverlap {
+    pub parent_id: ComponentId,
+    pub child_id: ComponentId,
+    pub item_a: BomItem,
+    pub item_b: BomItem,
+    /// Start of the overlapping period (`DateTime::<Utc>::MIN_UTC` if both items are open-ended)
+    pub overlap_from: DateTime<Utc>,
+    /// End of the overlapping period (`None` = open-ended)
+    pub overlap_to: Option<DateTime<Utc>>,
+}
+
+/// A fractional quantity of a discrete (raw material, `EA`) component
+#[derive(Debug, Clone)]
+pub struct DiscreteQuantityError {
+    pub bom_item_id: Uuid,
+    pub parent_id: ComponentId,
+    pub child_id: ComponentId,
+    pub quantity: Decimal,
+    /// The quantity rounded up to the next whole unit
+    pub rounded_suggestion: Decimal,
+}
+
+/// Reference designator check for one parent's BOM (electronics BOMs, e.g. "R1,R2,R3")
+#[derive(Debug, Clone, Default)]
+pub struct ReferenceDesignatorReport {
+    /// Raw material items without designators in a BOM that uses designators
+    pub missing_designators: Vec<Uuid>,
+    /// Designators placed by more than one item, with the items using them
+    pub duplicate_designators: Vec<(String, Vec<Uuid>)>,
+    /// Items of components counted in `EA` whose designator count differs from the
+    /// rounded quantity
+    pub count_mismatch: Vec<Uuid>,
+    /// Items whose designators are not a valid list (see `ReferenceDesignatorList::parse`)
+    pub invalid_designators: Vec<Uuid>,
+}
+
+impl ReferenceDesignatorReport {
+    /// True if no problems were found
+    pub fn is_clean(&self) -> bool {
+        self.missing_designators.is_empty()
+            && self.duplicate_designators.is_empty()
+            && self.count_mismatch.is_empty()
+            && self.invalid_designators.is_empty()
+    }
+}
+
+/// BOM data quality validator (資料品質檢查)
+/// Works on repository data so that items filtered out of the graph are checked too
+pub struct BomValidator<'a, R: BomRepository> {
+    repository: &'a R,
+}
+
+impl<'a, R: BomRepository> BomValidator<'a, R> {
+    pub fn new(repository: &'a R) -> Self {
+        Self { repository }
+    }
+
+    /// Check that every BOM item references existing components and that
+    /// no parent/child pair has overlapping effectivity
+    pub fn validate_completeness(&self) -> Result<Vec<ValidationIssue>> {
+        let mut issues = self.find_missing_components()?;
+        issues.extend(self.validate_effectivity()?);
+        Ok(issues)
+    }
+
+    /// Components referenced by BOM items that do not exist (errors)
+    pub fn find_missing_components(&self) -> Result<Vec<ValidationIssue>> {
+        let items = self.repository.get_all_bom_items()?;
+        let mut issues = Vec::new();
+
+        // Checked once per ID
+        let mut checked = HashSet::new();
+        for item in &items {
+            for id in [&item.parent_id, &item.child_id] {
+                if checked.insert(id.clone()) && self.repository.get_component(id).is_err() {
+                    issues.push(ValidationIssue {
+                        severity: IssueSeverity::Error,
+                        category: ValidationCategory::Completeness,
+                        component_id: id.clone(),
+                        message: format!("Component {} is referenced by BOM items but does not exist", id.as_str()),
+                    });
+                }
+            }
+        }
+
+        Ok(issues)
+    }
+
+    /// Inverted effectivity ranges (errors) and overlapping effectivity (warnings)
+    pub fn validate_effectivity(&self) -> Result<Vec<ValidationIssue>> {
+        let items = self.repository.get_all_bom_items()?;
+        let mut issues = Vec::new();
+
+        for item in &items {
+            if let (Some(from), Some(to)) = (item.effective_from, item.effective_to) {
+                if to < from {
+                    issues.push(ValidationIssue {
+                        severity: IssueSeverity::Error,
+                        category: ValidationCategory::Effectivity,
+                        component_id: item.parent_id.clone(),
+                        message: format!(
+                            "Item {} ({} -> {}) ends on {} before it starts on {}",
+                            item.id,
+                            item.parent_id.as_str(),
+                            item.child_id.as_str(),
+                            to,
+                            from
+                        ),
+                    });
+                }
+            }
+        }
+
+        for overlap in Self::overlaps_in(&items) {
+            issues.push(ValidationIssue {
+                severity: IssueSeverity::Warning,
+                category: ValidationCategory::Effectivity,
+                component_id: overlap.parent_id.clone(),
+                message: format!(
+                    "Items {} and {} for {} -> {} are both effective{}{}",
+                    overlap.item_a.id,
+                    overlap.item_b.id,
+                    overlap.parent_id.as_str(),
+                    overlap.child_id.as_str(),
+                    Some(overlap.overlap_from)
+                        .filter(|from| *from != DateTime::<Utc>::MIN_UTC)
+                        .map(|from| format!(" from {}", from))
+                        .unwrap_or_default(),
+                    overlap
+                        .overlap_to
+                        .map(|to| format!(" to {}", to))
+                        .unwrap_or_default()
+                ),
+            });
+        }
+
+        Ok(issues)
+    }
+
+    /// Circular references in the repository data (errors, one per cycle)
+    /// Works without a graph, which cannot be built from cyclic data
+    pub fn validate_cycles(&self) -> Result<Vec<ValidationIssue>> {
+        let items = self.repository.get_all_bom_items()?;
+
+        let mut children: HashMap<&ComponentId, Vec<&ComponentId>> = HashMap::new();
+        for item in &items {
+            children.entry(&item.parent_id).or_default().push(&item.child_id);
+        }
+
+        let mut starts: Vec<&ComponentId> = children.keys().copied().collect();
+        starts.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        let mut finished: HashSet<&ComponentId> = HashSet::new();
+        let mut cycles = Vec::new();
+
+        for start in starts {
+            if finished.contains(start) {
+                continue;
+            }
+
+            // Iterative DFS; `path` holds the current chain of (node, next child index)
+            let mut path: Vec<(&ComponentId, usize)> = vec![(start, 0)];
+            let mut on_path: HashSet<&ComponentId> = HashSet::from([start]);
+
+            while let Some((node, next)) = path.last_mut() {
+                let node = *node;
+                let child = children.get(node).and_then(|c| c.get(*next)).copied();
+                *next += 1;
+
+                match child {
+                    Some(child) if on_path.contains(child) => {
+                        let position = path.iter().position(|(n, _)| *n == child).unwrap_or(0);
+                        let mut cycle: Vec<&ComponentId> = path[position..].iter().map(|(n, _)| *n).collect();
+                        cycle.push(child);
+                        cycles.push(cycle);
+                    }
+                    Some(child) if !finished.contains(child) => {
+                        on_path.insert(child);
+                        path.push((child, 0));
+                    }
+                    Some(_) => {}
+                    None => {
+                        on_path.remove(node);
+                        finished.insert(node);
+                        path.pop();
+                    }
+                }
+            }
+        }
+
+        Ok(cycles
+            .into_iter()
+            .map(|cycle| ValidationIssue {
+                severity: IssueSeverity::Error,
+                category: ValidationCategory::Cycle,
+                component_id: cycle[0].clone(),
+                message: format!(
+                    "Circular dependency: {}",
+                    cycle.iter().map(|id| id.as_str()).collect::<Vec<_>>().join(" -> ")
+                ),
+            })
+            .collect())
+    }
+
+    /// Every elementary circuit in the repository data (errors, one per circuit)
+    /// Slower than `validate_cycles`, which reports one cycle per cyclic path, but also
+    /// lists cycles that share components with others
+    pub fn validate_all_cycles(&self) -> Result<Vec<ValidationIssue>> {
+        let mut arena = Arena::new();
+        for item in self.repository.get_all_bom_items()? {
+            let parent = arena.add_node(item.parent_id.clone());
+            let child = arena.add_node(item.child_id.clone());
+            arena.add_edge(parent, child, item);
+        }
+
+        Ok(find_all_elementary_cycles(&arena)
+            .into_iter()
+            .map(|cycle| ValidationIssue {
+                severity: IssueSeverity::Error,
+                category: ValidationCategory::Cycle,
+                component_id: cycle[0].clone(),
+                message: format!(
+                    "Circular dependency: {} -> {}",
+                    cycle.iter().map(|id| id.as_str()).collect::<Vec<_>>().join(" -> "),
+                    cycle[0].as_str()
+                ),
+            })
+            .collect())
+    }
+
+    /// Negative standard costs (errors) and purchased leaf components without a cost (warnings)
+    pub fn validate_costs(&self) -> Result<Vec<ValidationIssue>> {
+        let items = self.repository.get_all_bom_items()?;
+        let parents: HashSet<&ComponentId> = items.iter().map(|item| &item.parent_id).collect();
+
+        let mut ids: Vec<&ComponentId> = items
+            .iter()
+            .flat_map(|item| [&item.parent_id, &item.child_id])
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        ids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        let mut issues = Vec::new();
+        for id in ids {
+            // Missing components are reported by the completeness check
+            let Ok(component) = self.repository.get_component(id) else {
+                continue;
+            };
+
+            match component.standard_cost {
+                Some(cost) if cost < Decimal::ZERO => issues.push(ValidationIssue {
+                    severity: IssueSeverity::Error,
+                    category: ValidationCategory::Cost,
+                    component_id: id.clone(),
+                    message: format!("Component {} has a negative standard cost ({})", id.as_str(), cost),
+                }),
+                None if !parents.contains(id) => issues.push(ValidationIssue {
+                    severity: IssueSeverity::Warning,
+                    category: ValidationCategory::Cost,
+                    component_id: id.clone(),
+                    message: format!("Leaf component {} has no standard cost", id.as_str()),
+                }),
+                _ => {}
+            }
+        }
+
+        Ok(issues)
+    }
+
+    /// Reused item IDs (errors) and the same child entered twice at the same
+    /// sequence of a parent (warnings)
+    pub fn validate_duplicates(&self) -> Result<Vec<ValidationIssue>> {
+        let items = self.repository.get_all_bom_items()?;
+        let mut issues = Vec::new();
+
+        let mut ids: HashMap<Uuid, &BomItem> = HashMap::new();
+        let mut positions: HashSet<(&ComponentId, &ComponentId, u32)> = HashSet::new();
+
+        for item in &items {
+            if ids.insert(item.id, item).is_some() {
+                issues.push(ValidationIssue {
+                    severity: IssueSeverity::Error,
+                    category: ValidationCategory::Duplicate,
+                    component_id: item.parent_id.clone(),
+                    message: format!("BOM item ID {} is used more than once", item.id),
+                });
+            }
+
+            if !positions.insert((&item.parent_id, &item.child_id, item.sequence)) {
+                issues.push(ValidationIssue {
+                    severity: IssueSeverity::Warning,
+                    category: ValidationCategory::Duplicate,
+                    component_id: item.parent_id.clone(),
+                    message: format!(
+                        "{} is listed more than once at sequence {} of {}",
+                        item.child_id.as_str(),
+                        item.sequence,
+                        item.parent_id.as_str()
+                    ),
+                });
+            }
+        }
+
+        Ok(issues)
+    }
+
+    /// BOM items with a fractional quantity of a discrete component
+    /// (raw material counted in `EA`), sorted by parent and child
+    pub fn validate_discrete_quantities(&self) -> Result<Vec<DiscreteQuantityError>> {
+        let items = self.repository.get_all_bom_items()?;
+        let mut errors = Vec::new();
+
+        for item in &items {
+            if item.quantity.fract() == Decimal::ZERO {
+                continue;
+            }
+            // Missing components are reported by the completeness check
+            let Ok(child) = self.repository.get_component(&item.child_id) else {
+                continue;
+            };
+            if child.component_type == ComponentType::RawMaterial && child.uom == "EA" {
+                errors.push(DiscreteQuantityError {
+                    bom_item_id: item.id,
+                    parent_id: item.parent_id.clone(),
+                    child_id: item.child_id.clone(),
+                    quantity: item.quantity,
+                    rounded_suggestion: item.quantity.ceil(),
+                });
+            }
+        }
+
+        errors.sort_by(|a, b| {
+            (a.parent_id.as_str(), a.child_id.as_str()).cmp(&(b.parent_id.as_str(), b.child_id.as_str()))
+        });
+        Ok(errors)
+    }
+
+    /// Fractional quantities of discrete components as validation issues (errors)
+    pub fn validate_quantities(&self) -> Result<Vec<ValidationIssue>> {
+        Ok(self
+            .validate_discrete_quantities()?
+            .into_iter()
+            .map(|error| ValidationIssue {
+                severity: IssueSeverity::Error,
+                category: ValidationCategory::Quantity,
+                component_id: error.parent_id.clone(),
+                message: format!(
+                    "{} needs a whole quantity of {} EA, not {} (suggested: {})",
+                    error.parent_id.as_str(),
+                    error.child_id.as_str(),
+                    error.quantity,
+                    error.rounded_suggestion
+                ),
+            })
+            .collect())
+    }
+
+    /// BOM items rejected by `BomItem::validate` (errors)
+    /// Inverted effectivity ranges are left to `validate_effectivity`
+    pub fn validate_items(&self) -> Result<Vec<ValidationIssue>> {
+        let items = self.repository.get_all_bom_items()?;
+        let mut issues = Vec::new();
+
+        for item in &items {
+            match item.validate() {
+                Ok(()) | Err(BomError::InvalidEffectivityRange { .. }) => {}
+                Err(error) => issues.push(ValidationIssue {
+                    severity: IssueSeverity::Error,
+                    category: ValidationCategory::InvalidItem,
+                    component_id: item.parent_id.clone(),
+                    message: format!("Item {}: {}", item.id, error),
+                }),
+            }
+        }
+
+        Ok(issues)
+    }
+
+    /// Find all pairs of BOM items with the same parent and child whose
+    /// effectivity ranges overlap
+    pub fn find_effectivity_overlaps(&self) -> Result<Vec<EffectivityOverlap>> {
+        let items = self.repository.get_all_bom_items()?;
+        Ok(Self::overlaps_in(&items))
+    }
+
+    /// Check the reference designators of a parent's currently effective items
+    /// A BOM counts as an electronics BOM once any of its items has designators;
+    /// only then are raw material items without designators reported as missing.
+    /// Items of the same alternative group may share designators. Designator counts are
+    /// compared with quantities for components counted in `EA` only
+    pub fn validate_reference_designators(&self, component_id: &ComponentId) -> Result<ReferenceDesignatorReport> {
+        let items = self.repository.get_bom_items(component_id, None)?;
+        let mut report = ReferenceDesignatorReport::default();
+
+        let mut parsed: Vec<(&BomItem, Vec<String>)> = Vec::with_capacity(items.len());
+        for item in &items {
+            let list = item.reference_designator.as_deref().map(ReferenceDesignatorList::parse);
+            match list {
+                Some(Ok(list)) => parsed.push((item, list.designators().to_vec())),
+                Some(Err(_)) => report.invalid_designators.push(item.id),
+                None => parsed.push((item, Vec::new())),
+            }
+        }
+
+        let uses_designators = parsed.iter().any(|(_, designators)| !designators.is_empty());
+        let mut usage: BTreeMap<&str, Vec<&BomItem>> = BTreeMap::new();
+
+        for (item, designators) in &parsed {
+            if designators.is_empty() {
+                if uses_designators
+                    && self
+                        .repository
+                        .get_component(&item.child_id)
+                        .is_ok_and(|child| child.component_type == ComponentType::RawMaterial)
+                {
+                    report.missing_designators.push(item.id);
+                }
+                continue;
+            }
+
+            if Decimal::from(designators.len()) != item.quantity.round()
+                && self
+                    .repository
+                    .get_component(&item.child_id)
+                    .is_ok_and(|child| child.uom == "EA")
+            {
+                report.count_mismatch.push(item.id);
+            }
+
+            for designator in designators {
+                usage.entry(designator.as_str()).or_default().push(item);
+            }
+        }
+
+        for (designator, users) in usage {
+            let distinct_positions: HashSet<_> = users
+                .iter()
+                .map(|item| item.alternative_group.as_deref().ok_or(item.id))
+                .collect();
+            if distinct_positions.len() > 1 {
+                report
+                    .duplicate_designators
+                    .push((designator.to_string(), users.iter().map(|item| item.id).collect()));
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Warn about every connected component besides the largest one
+    /// Pass `multi_product = true` when the graph intentionally holds unrelated products
+    pub fn validate_connectivity(graph: &BomGraph, multi_product: bool) -> Vec<ValidationIssue> {
+        if multi_product {
+            return Vec::new();
+        }
+
+        let mut components = graph.connected_components();
+        if components.len() <= 1 {
+            return Vec::new();
+        }
+
+        components.sort_by_key(|group| std::cmp::Reverse(group.len()));
+        let main_size = components[0].len();
+
+        components
+            .into_iter()
+            .skip(1)
+            .map(|group| ValidationIssue {
+                severity: IssueSeverity::Warning,
+                category: ValidationCategory::Connectivity,
+                component_id: group[0].clone(),
+                message: format!(
+                    "{} component(s) starting at {} are disconnected from the main BOM ({} components)",
+                    group.len(),
+                    group[0].as_str(),
+                    main_size
+                ),
+            })
+            .collect()
+    }
+
+    fn overlaps_in(items: &[BomItem]) -> Vec<EffectivityOverlap> {
+        let mut by_pair: HashMap<(&ComponentId, &ComponentId), Vec<&BomItem>> = HashMap::new();
+        for item in items {
+            by_pair
+                .entry((&item.parent_id, &item.child_id))
+                .or_default()
+                .push(item);
+        }
+
+        let mut overlaps = Vec::new();
+        for group in by_pair.values().filter(|group| group.len() > 1) {
+            for (i, item_a) in group.iter().enumerate() {
+                for item_b in &group[i + 1..] {
+                    if let Some((overlap_from, overlap_to)) = Self::overlap_range(item_a, item_b) {
+                        overlaps.push(EffectivityOverlap {
+                            parent_id: item_a.parent_id.clone(),
+                            child_id: item_a.child_id.clone(),
+                            item_a: (*item_a).clone(),
+                            item_b: (*item_b).clone(),
+                            overlap_from,
+                            overlap_to,
+                        });
+                    }
+                }
+            }
+        }
+
+        overlaps
+    }
+
+    /// Intersection of two effectivity ranges, if not empty
+    fn overlap_range(a: &BomItem, b: &BomItem) -> Option<(DateTime<Utc>, Option<DateTime<Utc>>)> {
+        let from = a
+            .effective_from
+            .max(b.effective_from)
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+
+        let to = match (a.effective_to, b.effective_to) {
+            (Some(x), Some(y)) => Some(x.min(y)),
+            (x, y) => x.or(y),
+        };
+
+        match to {
+            Some(to) if to < from => None,
+            _ => Some((from, to)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{create_test_bom_item, create_test_component};
+    use bom_core::repository::memory::InMemoryRepository;
+    use chrono::{Duration, TimeZone};
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn test_invalid_items_are_reported() {
+        let repo = InMemoryRepository::new();
+        for id in ["A", "B", "C", "D"] {
+            repo.add_component(create_test_component(id));
+        }
+
+        // Loaded without validation so they can be reported here
+        repo.add_bom_item_unchecked(create_test_bom_item("A", "B", 0));
+        let mut inverted = create_test_bom_item("A", "C", 1);
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        inverted.effective_from = Some(start);
+        inverted.effective_to = Some(start - Duration::days(1));
+        repo.add_bom_item_unchecked(inverted);
+        let mut scrap = create_test_bom_item("A", "D", 1);
+        scrap.scrap_factor = Decimal::new(15, 1);
+        repo.add_bom_item_unchecked(scrap);
+
+        let validator = BomValidator::new(&repo);
+        let items = validator.validate_items().unwrap();
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().all(|issue| issue.severity == IssueSeverity::Error
+            && issue.category == ValidationCategory::InvalidItem));
+        assert!(items.iter().any(|issue| issue.message.contains("A -> B")));
+        assert!(items.iter().any(|issue| issue.message.contains("scrap factor")));
+
+        // The inverted range is reported once, by the effectivity check
+        let effectivity = validator.validate_effectivity().unwrap();
+        assert_eq!(effectivity.len(), 1);
+        assert_eq!(effectivity[0].severity, IssueSeverity::Error);
+        assert!(effectivity[0].message.contains("before it starts"));
+
+        // Items added the checked way are rejected
+        assert!(repo.add_bom_item(create_test_bom_item("A", "B", 0)).is_err());
+        assert!(repo.insert_bom_item(create_test_bom_item("A", "B", 0)).is_err());
+    }
+
+    #[test]
+    fn test_find_effectivity_overlaps() {
+        let repo = InMemoryRepository::new();
+        repo.add_component(create_test_component("A"));
+        repo.add_component(create_test_component("B"));
+        repo.add_component(create_test_component("C"));
+
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+
+        // A -> B: days 0..60 (qty 2) and days 30..90 (qty 3) overlap for 30 days
+        let mut old = create_test_bom_item("A", "B", 2);
+        old.effective_from = Some(start);
+        old.effective_to = Some(start + Duration::days(60));
+        let mut new = create_test_bom_item("A", "B", 3);
+        new.effective_from = Some(start + Duration::days(30));
+        new.effective_to = Some(start + Duration::days(90));
+        repo.add_bom_item(old).unwrap();
+        repo.add_bom_item(new).unwrap();
+
+        // A -> C: consecutive ranges do not overlap
+        let mut first = create_test_bom_item("A", "C", 1);
+        first.effective_to = Some(start);
+        let mut second = create_test_bom_item("A", "C", 1);
+        second.effective_from = Some(start + Duration::days(1));
+        repo.add_bom_item(first).unwrap();
+        repo.add_bom_item(second).unwrap();
+
+        let validator = BomValidator::new(&repo);
+        let overlaps = validator.find_effectivity_overlaps().unwrap();
+
+        assert_eq!(overlaps.len(), 1);
+        let overlap = &overlaps[0];
+        assert_eq!(overlap.child_id.as_str(), "B");
+        assert_eq!(overlap.overlap_from, start + Duration::days(30));
+        assert_eq!(overlap.overlap_to, Some(start + Duration::days(60)));
+        assert_eq!(
+            overlap.overlap_to.unwrap() - overlap.overlap_from,
+            Duration::days(30)
+        );
+    }
+
+    #[test]
+    fn test_validate_completeness() {
+        let repo = InMemoryRepository::new();
+        repo.add_component(create_test_component("A"));
+        repo.add_component(create_test_component("B"));
+
+        // X does not exist; A -> B is duplicated without effectivity
+        repo.add_bom_item(create_test_bom_item("A", "X", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+
+        let validator = BomValidator::new(&repo);
+        let issues = validator.validate_completeness().unwrap();
+
+        assert_eq!(issues.len(), 2);
+        assert!(issues.iter().any(|issue| issue.severity == IssueSeverity::Error
+            && issue.category == ValidationCategory::Completeness
+            && issue.component_id.as_str() == "X"));
+        assert!(issues.iter().any(|issue| issue.severity == IssueSeverity::Warning
+            && issue.category == ValidationCategory::Effectivity));
+    }
+
+    #[test]
+    fn test_validate_connectivity() {
+        let mut graph = BomGraph::new();
+        graph.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("B", "C", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("X", "Y", 1)).unwrap();
+
+        let issues = BomValidator::<InMemoryRepository>::validate_connectivity(&graph, false);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].category, ValidationCategory::Connectivity);
+        assert_eq!(issues[0].component_id.as_str(), "X");
+
+        // Explicitly multi-product graphs are not flagged
+        assert!(BomValidator::<InMemoryRepository>::validate_connectivity(&graph, true).is_empty());
+    }
+
+    #[test]
+    fn test_validate_reference_designators() {
+        let repo = InMemoryRepository::new();
+        repo.add_component(create_test_component("PCBA"));
+        for id in ["RES-10K", "RES-1K", "CAP-1U"] {
+            let mut component = create_test_component(id);
+            component.component_type = ComponentType::RawMaterial;
+            repo.add_component(component);
+        }
+
+        let mut r10k = create_test_bom_item("PCBA", "RES-10K", 3);
+        r10k.reference_designator = Some("R1, R2,R3".to_string());
+        let mut r1k = create_test_bom_item("PCBA", "RES-1K", 2);
+        r1k.reference_designator = Some("R1,R4".to_string());
+        let cap = create_test_bom_item("PCBA", "CAP-1U", 1);
+        let (r10k_id, r1k_id, cap_id) = (r10k.id, r1k.id, cap.id);
+        repo.add_bom_item(r10k).unwrap();
+        repo.add_bom_item(r1k).unwrap();
+        repo.add_bom_item(cap).unwrap();
+
+        let validator = BomValidator::new(&repo);
+        let report = validator
+            .validate_reference_designators(&ComponentId::new("PCBA"))
+            .unwrap();
+
+        assert_eq!(report.duplicate_designators.len(), 1);
+        let (designator, items) = &report.duplicate_designators[0];
+        assert_eq!(designator, "R1");
+        assert!(items.contains(&r10k_id) && items.contains(&r1k_id));
+
+        assert_eq!(report.missing_designators, vec![cap_id]);
+        assert!(report.count_mismatch.is_empty());
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn test_reference_designator_count_mismatch() {
+        let repo = InMemoryRepository::new();
+        repo.add_component(create_test_component("PCBA"));
+        repo.add_component(create_test_component("RES-10K"));
+        let mut wire = create_test_component("WIRE");
+        wire.uom = "M".to_string();
+        repo.add_component(wire);
+
+        // 3 designators for 2 resistors; wire is measured in meters and not counted
+        let mut resistors = create_test_bom_item("PCBA", "RES-10K", 2);
+        resistors.reference_designator = Some("R1,R2,R3".to_string());
+        let mut wire_item = create_test_bom_item("PCBA", "WIRE", 2);
+        wire_item.reference_designator = Some("W1".to_string());
+        let resistors_id = resistors.id;
+        assert_eq!(resistors.reference_designator_count().unwrap(), 3);
+        assert!(matches!(repo.insert_bom_item(resistors.clone()), Err(BomError::InvalidBomItem(_))));
+        repo.add_bom_item_unchecked(resistors);
+        repo.add_bom_item(wire_item).unwrap();
+
+        let report = BomValidator::new(&repo)
+            .validate_reference_designators(&ComponentId::new("PCBA"))
+            .unwrap();
+        assert_eq!(report.count_mismatch, vec![resistors_id]);
+        assert!(report.invalid_designators.is_empty());
+
+        // Ranges are not a valid list, but such items can still be stored and are reported
+        let mut invalid = create_test_bom_item("PCBA", "RES-10K", 1);
+        invalid.reference_designator = Some("R1-R3".to_string());
+        let invalid_id = invalid.id;
+        repo.insert_bom_item(invalid).unwrap();
+
+        let report = BomValidator::new(&repo)
+            .validate_reference_designators(&ComponentId::new("PCBA"))
+            .unwrap();
+        assert_eq!(report.invalid_designators, vec![invalid_id]);
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn test_validate_cycles_costs_and_duplicates() {
+        let repo = InMemoryRepository::new();
+        for id in ["A", "B", "C"] {
+            repo.add_component(create_test_component(id));
+        }
+        let mut free = create_test_component("D");
+        free.standard_cost = None;
+        repo.add_component(free);
+
+        // B -> C -> B is a cycle; D is a leaf without a cost; A -> D is entered twice
+        repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("C", "B", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "D", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "D", 1)).unwrap();
+
+        let validator = BomValidator::new(&repo);
+
+        let cycles = validator.validate_cycles().unwrap();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].severity, IssueSeverity::Error);
+        assert!(cycles[0].message.contains("B -> C -> B"));
+
+        let costs = validator.validate_costs().unwrap();
+        assert_eq!(costs.len(), 1);
+        assert_eq!(costs[0].component_id.as_str(), "D");
+
+        let duplicates = validator.validate_duplicates().unwrap();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].severity, IssueSeverity::Warning);
+
+        // C -> A closes a second cycle A -> B -> C -> A through B
+        repo.add_bom_item(create_test_bom_item("C", "A", 1)).unwrap();
+        let all_cycles = validator.validate_all_cycles().unwrap();
+        let messages: Vec<&str> = all_cycles.iter().map(|issue| issue.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec!["Circular dependency: A -> B -> C -> A", "Circular dependency: B -> C -> B"]
+        );
+    }
+
+    #[test]
+    fn test_validate_discrete_quantities() {
+        let repo = InMemoryRepository::new();
+        repo.add_component(create_test_component("A"));
+        for id in ["SCREW", "WIRE", "PAINT"] {
+            let mut raw = create_test_component(id);
+            raw.component_type = ComponentType::RawMaterial;
+            repo.add_component(raw);
+        }
+        let mut wire = repo.get_component(&ComponentId::new("WIRE")).unwrap();
+        wire.uom = "M".to_string();
+        repo.add_component(wire);
+
+        // 1.5 EA of a screw cannot be issued; 1.5 m of wire can
+        let mut screws = create_test_bom_item("A", "SCREW", 1);
+        screws.quantity = Decimal::new(15, 1);
+        let screw_item = screws.id;
+        repo.add_bom_item(screws).unwrap();
+        let mut wire = create_test_bom_item("A", "WIRE", 1);
+        wire.quantity = Decimal::new(15, 1);
+        repo.add_bom_item(wire).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "PAINT", 2)).unwrap();
+
+        let validator = BomValidator::new(&repo);
+        let errors = validator.validate_discrete_quantities().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].bom_item_id, screw_item);
+        assert_eq!(errors[0].child_id.as_str(), "SCREW");
+        assert_eq!(errors[0].quantity, Decimal::new(15, 1));
+        assert_eq!(errors[0].rounded_suggestion, Decimal::from(2));
+
+        let issues = validator.validate_quantities().unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].category, ValidationCategory::Quantity);
+        assert_eq!(issues[0].severity, IssueSeverity::Error);
+    }
+}
@@ -1,9 +1,12 @@
-use bom_core::{ComponentId, WhereUsedItem, WhereUsedResult, Result};
+use bom_core::{ComponentId, ExplosionResult, WhereUsedItem, WhereUsedResult, Result};
 use bom_graph::{find_all_paths, BomGraph, NodeIndex};
 use rayon::prelude::*;
 use rust_decimal::Decimal;
+use serde::Serialize;
 use std::collections::{HashMap, HashSet};
 
+use crate::ExplosionCalculator;
+
 /// Where-used analyzer (反查分析)
 /// Finds all parent assemblies that use a specific component
 pub struct WhereUsedAnalyzer<'a> {
@@ -15,69 +18,192 @@ impl<'a> WhereUsedAnalyzer<'a> {
         Self { graph }
     }
 
-    /// Find all assemblies that use this component
+    /// Find all assemblies that use this component, at any level
     pub fn analyze(&self, component_id: &ComponentId) -> Result<WhereUsedResult> {
+        self.analyze_with_depth(component_id, usize::MAX)
+    }
+
+    /// Multi-level where-used: every assembly at most `max_levels` levels above the component
+    /// Each assembly is listed once with its `level` (1 = direct parent; the fewest levels
+    /// between them if it uses the component at several), the quantity of the component per
+    /// unit of the assembly over all its paths, and its paths from the roots. With 1 only the
+    /// direct parents are listed; with 0 nothing is. Items are ordered by level, then ID
+    pub fn analyze_with_depth(&self, component_id: &ComponentId, max_levels: usize) -> Result<WhereUsedResult> {
         let node = self
             .graph
             .find_node(component_id)
             .ok_or_else(|| bom_core::BomError::ComponentNotFound(component_id.as_str().to_string()))?;
 
-        // Find all parents (immediate)
-        let direct_parents: Vec<(NodeIndex, Decimal)> = self
-            .graph
-            .arena()
-            .parents(node)
-            .map(|(parent_idx, edge)| (parent_idx, edge.effective_quantity))
-            .collect();
+        Ok(WhereUsedResult {
+            component: component_id.clone(),
+            used_in: self.used_in(node, &self.ancestor_levels(node), max_levels),
+            queried_at: chrono::Utc::now(),
+        })
+    }
 
-        // For each parent, find all paths to roots
-        let used_in: Vec<WhereUsedItem> = direct_parents
+    /// Where-used for several components at once
+    /// A single breadth-first pass upward from all inputs finds the level of every ancestor
+    /// above each input, visiting each node's parents once per level for all inputs that reach
+    /// it together. Each result equals what `analyze` returns for that component
+    pub fn analyze_batch(&self, component_ids: &[ComponentId]) -> Result<HashMap<ComponentId, WhereUsedResult>> {
+        let nodes: Vec<NodeIndex> = component_ids
+            .iter()
+            .map(|id| {
+                self.graph
+                    .find_node(id)
+                    .ok_or_else(|| bom_core::BomError::ComponentNotFound(id.as_str().to_string()))
+            })
+            .collect::<Result<_>>()?;
+
+        // Level of each ancestor per input (by position); the frontier groups inputs by node
+        let arena = self.graph.arena();
+        let mut levels: Vec<HashMap<NodeIndex, usize>> = vec![HashMap::new(); nodes.len()];
+        let mut frontier: HashMap<NodeIndex, Vec<usize>> = HashMap::new();
+        for (position, &node) in nodes.iter().enumerate() {
+            frontier.entry(node).or_default().push(position);
+        }
+        let mut level = 0;
+        while !frontier.is_empty() {
+            level += 1;
+            let mut next: HashMap<NodeIndex, Vec<usize>> = HashMap::new();
+            for (current, positions) in frontier {
+                for (parent, _) in arena.parents(current) {
+                    for &position in &positions {
+                        if parent != nodes[position] && !levels[position].contains_key(&parent) {
+                            levels[position].insert(parent, level);
+                            next.entry(parent).or_default().push(position);
+                        }
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        let queried_at = chrono::Utc::now();
+        Ok(nodes
             .par_iter()
-            .flat_map(|&(parent_idx, quantity)| {
-                let parent_node = self.graph.arena().node(parent_idx)?;
-
-                // Find all paths from roots to this parent
-                let mut all_paths_idx = Vec::new();
-                for &root in self.graph.roots() {
-                    let paths = find_all_paths(self.graph.arena(), root, parent_idx);
-                    all_paths_idx.extend(paths);
+            .zip(&levels)
+            .zip(component_ids)
+            .map(|((&node, level_of), component_id)| {
+                let result = WhereUsedResult {
+                    component: component_id.clone(),
+                    used_in: self.used_in(node, level_of, usize::MAX),
+                    queried_at,
+                };
+                (component_id.clone(), result)
+            })
+            .collect())
+    }
+
+    /// Level of every ancestor of `node` (1 = direct parent), the fewest levels between them
+    fn ancestor_levels(&self, node: NodeIndex) -> HashMap<NodeIndex, usize> {
+        let arena = self.graph.arena();
+
+        // Breadth-first upward, so each ancestor is first reached at its lowest level
+        let mut level_of: HashMap<NodeIndex, usize> = HashMap::new();
+        let mut frontier = vec![node];
+        let mut level = 0;
+        while !frontier.is_empty() {
+            level += 1;
+            let mut next = Vec::new();
+            for current in frontier {
+                for (parent, _) in arena.parents(current) {
+                    if parent != node && !level_of.contains_key(&parent) {
+                        level_of.insert(parent, level);
+                        next.push(parent);
+                    }
                 }
+            }
+            frontier = next;
+        }
+        level_of
+    }
+
+    /// Ancestors of `node` up to `max_levels` above it, with their paths from the roots
+    /// `level_of` holds the level of every ancestor, as from `ancestor_levels`
+    fn used_in(&self, node: NodeIndex, level_of: &HashMap<NodeIndex, usize>, max_levels: usize) -> Vec<WhereUsedItem> {
+        let roots: HashSet<NodeIndex> = self.graph.roots().iter().copied().collect();
+        let arena = self.graph.arena();
+
+        let mut quantities: HashMap<NodeIndex, Decimal> = HashMap::from([(node, Decimal::ONE)]);
+        let mut ancestors: Vec<(NodeIndex, usize, Decimal)> = level_of
+            .iter()
+            .filter(|&(_, &level)| level <= max_levels)
+            .map(|(&ancestor, &level)| (ancestor, level, self.quantity_per(ancestor, level_of, &mut quantities)))
+            .collect();
+        ancestors.sort_by(|a, b| {
+            let id = |node: NodeIndex| arena.node(node).map(|node| node.component_id.as_str());
+            a.1.cmp(&b.1).then_with(|| id(a.0).cmp(&id(b.0)))
+        });
+
+        ancestors
+            .par_iter()
+            .filter_map(|&(ancestor, level, quantity)| {
+                let ancestor_node = arena.node(ancestor)?;
 
-                // Convert NodeIndex paths to ComponentId paths
-                let all_paths: Vec<Vec<ComponentId>> = all_paths_idx
+                let mut paths = Vec::new();
+                self.paths_up(ancestor, &roots, &mut Vec::new(), &mut paths);
+                let paths = paths
                     .into_iter()
-                    .filter_map(|path| {
-                        let comp_path: Vec<ComponentId> = path
-                            .into_iter()
-                            .filter_map(|idx| {
-                                self.graph.arena().node(idx).map(|n| n.component_id.clone())
-                            })
-                            .collect();
-                        if comp_path.is_empty() { None } else { Some(comp_path) }
+                    .map(|path| {
+                        path.into_iter()
+                            .filter_map(|idx| arena.node(idx).map(|node| node.component_id.clone()))
+                            .collect()
                     })
                     .collect();
 
-                // Calculate max level
-                let level = all_paths
-                    .iter()
-                    .map(|path| path.len())
-                    .max()
-                    .unwrap_or(1);
-
                 Some(WhereUsedItem {
-                    parent_id: parent_node.component_id.clone(),
+                    parent_id: ancestor_node.component_id.clone(),
                     quantity,
                     level,
-                    paths: all_paths,
+                    paths,
                 })
             })
-            .collect();
+            .collect()
+    }
 
-        Ok(WhereUsedResult {
-            component: component_id.clone(),
-            used_in,
-            queried_at: chrono::Utc::now(),
-        })
+    /// Units of the queried component per unit of `ancestor`, summed over all paths between them
+    /// `quantities` starts with the queried component at 1 and memoizes every ancestor done
+    fn quantity_per(
+        &self,
+        ancestor: NodeIndex,
+        ancestors: &HashMap<NodeIndex, usize>,
+        quantities: &mut HashMap<NodeIndex, Decimal>,
+    ) -> Decimal {
+        if let Some(&quantity) = quantities.get(&ancestor) {
+            return quantity;
+        }
+
+        let mut quantity = Decimal::ZERO;
+        for (child, edge) in self.graph.arena().children(ancestor) {
+            // Children that neither are the component nor contain it add nothing
+            if ancestors.contains_key(&child) || quantities.contains_key(&child) {
+                quantity += edge.effective_quantity * self.quantity_per(child, ancestors, quantities);
+            }
+        }
+        quantities.insert(ancestor, quantity);
+        quantity
+    }
+
+    /// Walk up from `node` collecting the paths (root first) that lead to it from `roots`;
+    /// `path` holds the nodes walked so far
+    fn paths_up(
+        &self,
+        node: NodeIndex,
+        roots: &HashSet<NodeIndex>,
+        path: &mut Vec<NodeIndex>,
+        paths: &mut Vec<Vec<NodeIndex>>,
+    ) {
+        path.push(node);
+
+        if roots.contains(&node) {
+            paths.push(path.iter().rev().copied().collect());
+        }
+        for (parent, _) in self.graph.arena().parents(node) {
+            self.paths_up(parent, roots, path, paths);
+        }
+
+        path.pop();
     }
 
     /// Find all top-level assemblies (roots) that use this component
@@ -159,32 +285,7 @@ impl<'a> WhereUsedAnalyzer<'a> {
         }
 
         // For each assembly, collect all descendant components
-        let assembly_descendants: Vec<HashSet<ComponentId>> = assembly_nodes
-            .par_iter()
-            .map(|&assembly| {
-                let mut descendants = HashSet::new();
-                let mut stack = vec![assembly];
-                let mut visited = HashSet::new();
-
-                while let Some(current) = stack.pop() {
-                    if !visited.insert(current) {
-                        continue;
-                    }
-
-                    if let Some(node) = self.graph.arena().node(current) {
-                        if current != assembly {
-                            descendants.insert(node.component_id.clone());
-                        }
-                    }
-
-                    for (child_idx, _) in self.graph.arena().children(current) {
-                        stack.push(child_idx);
-                    }
-                }
-
-                descendants
-            })
-            .collect();
+        let assembly_descendants = self.assembly_descendants(&assembly_nodes);
 
         // Find components that appear in multiple assemblies
         let mut component_usage: HashMap<ComponentId, Vec<usize>> = HashMap::new();
@@ -193,7 +294,7 @@ impl<'a> WhereUsedAnalyzer<'a> {
             for component_id in descendants {
                 component_usage
                     .entry(component_id.clone())
-                    .or_insert_with(Vec::new)
+                    .or_default()
                     .push(idx);
             }
         }
@@ -217,6 +318,182 @@ impl<'a> WhereUsedAnalyzer<'a> {
 
         Ok(shared)
     }
+
+    /// Commonality index of a product family (1.0 = all components unique, 0.0 = fully shared)
+    /// Counts each distinct component once per product below it:
+    /// `index = (total_instances - shared_instances) / total_instances`, where shared
+    /// instances belong to components in more than one product. A family without
+    /// components has index 1.0
+    pub fn calculate_commonality_index(&self, root_ids: &[ComponentId]) -> Result<CommonalityAnalysis> {
+        let root_nodes: Vec<NodeIndex> = root_ids
+            .iter()
+            .map(|id| {
+                self.graph
+                    .find_node(id)
+                    .ok_or_else(|| bom_core::BomError::ComponentNotFound(id.as_str().to_string()))
+            })
+            .collect::<Result<_>>()?;
+
+        let mut product_counts: HashMap<ComponentId, usize> = HashMap::new();
+        for descendants in self.assembly_descendants(&root_nodes) {
+            for component_id in descendants {
+                *product_counts.entry(component_id).or_insert(0) += 1;
+            }
+        }
+
+        let total_component_instances: usize = product_counts.values().sum();
+        let shared_instances: usize = product_counts.values().filter(|&&count| count > 1).sum();
+        let index = if total_component_instances == 0 {
+            1.0
+        } else {
+            (total_component_instances - shared_instances) as f64 / total_component_instances as f64
+        };
+
+        Ok(CommonalityAnalysis {
+            index,
+            total_distinct_components: product_counts.len(),
+            total_component_instances,
+            shared_components: product_counts.values().filter(|&&count| count > 1).count(),
+        })
+    }
+
+    /// How widely each component is used across all root products (標準化分析)
+    /// Explodes every root for one unit and aggregates. Root products themselves are not
+    /// listed. Sorted by the number of products using the component, most used first
+    pub fn calculate_usage_frequency(&self) -> Result<Vec<ComponentUsageFrequency>> {
+        let root_ids: Vec<ComponentId> = self
+            .graph
+            .iter_root_nodes()
+            .map(|(_, id)| id.clone())
+            .collect();
+
+        let calculator = ExplosionCalculator::new(self.graph);
+        let explosions: Vec<ExplosionResult> = root_ids
+            .par_iter()
+            .map(|root_id| calculator.explode(root_id, Decimal::ONE))
+            .collect::<Result<_>>()?;
+
+        let mut usage: HashMap<ComponentId, (usize, Decimal)> = HashMap::new();
+        for explosion in &explosions {
+            for item in explosion.items.iter().filter(|item| item.component_id != explosion.root_component) {
+                let (products, quantity) = usage.entry(item.component_id.clone()).or_insert((0, Decimal::ZERO));
+                *products += 1;
+                *quantity += item.total_quantity;
+            }
+        }
+
+        let mut frequencies: Vec<ComponentUsageFrequency> = usage
+            .into_iter()
+            .map(|(component_id, (total_root_products, cumulative_quantity_across_products))| {
+                let total_parents = self
+                    .graph
+                    .find_node(&component_id)
+                    .map(|node| {
+                        self.graph
+                            .arena()
+                            .parents(node)
+                            .map(|(parent_idx, _)| parent_idx)
+                            .collect::<HashSet<_>>()
+                            .len()
+                    })
+                    .unwrap_or(0);
+
+                ComponentUsageFrequency {
+                    component_id,
+                    total_parents,
+                    total_root_products,
+                    cumulative_quantity_across_products,
+                }
+            })
+            .collect();
+
+        frequencies.sort_by(|a, b| {
+            b.total_root_products
+                .cmp(&a.total_root_products)
+                .then_with(|| b.cumulative_quantity_across_products.cmp(&a.cumulative_quantity_across_products))
+                .then_with(|| a.component_id.as_str().cmp(b.component_id.as_str()))
+        });
+
+        Ok(frequencies)
+    }
+
+    /// Supply chain exposure of the BOM: in-degree distribution, widely used components
+    /// (at least `min_in_degree` parents) and single-parent components
+    pub fn supply_chain_risk_report(&self, min_in_degree: usize) -> SupplyChainRiskReport {
+        SupplyChainRiskReport {
+            degree_distribution: self.graph.inbound_degree_distribution(),
+            high_risk_components: self.graph.high_risk_components(min_in_degree),
+            single_source_components: self.graph.single_source_components(),
+        }
+    }
+
+    /// Distinct components below each assembly (the assembly itself excluded)
+    fn assembly_descendants(&self, assemblies: &[NodeIndex]) -> Vec<HashSet<ComponentId>> {
+        assemblies
+            .par_iter()
+            .map(|&assembly| {
+                let mut descendants = HashSet::new();
+                let mut stack = vec![assembly];
+                let mut visited = HashSet::new();
+
+                while let Some(current) = stack.pop() {
+                    if !visited.insert(current) {
+                        continue;
+                    }
+
+                    if let Some(node) = self.graph.arena().node(current) {
+                        if current != assembly {
+                            descendants.insert(node.component_id.clone());
+                        }
+                    }
+
+                    for (child_idx, _) in self.graph.arena().children(current) {
+                        stack.push(child_idx);
+                    }
+                }
+
+                descendants
+            })
+            .collect()
+    }
+}
+
+/// Parent count from which `BomEngine::supply_chain_risk_report` flags a component
+pub const HIGH_RISK_MIN_IN_DEGREE: usize = 3;
+
+/// Supply chain risk overview (see `WhereUsedAnalyzer::supply_chain_risk_report`)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SupplyChainRiskReport {
+    /// `{in_degree: component_count}`
+    pub degree_distribution: HashMap<usize, usize>,
+    /// Components with many parents and their parent counts, most used first
+    pub high_risk_components: Vec<(ComponentId, usize)>,
+    /// Components with exactly one parent
+    pub single_source_components: Vec<ComponentId>,
+}
+
+/// Component sharing within a product family
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CommonalityAnalysis {
+    /// 1.0 = every component unique to one product, 0.0 = every component shared
+    pub index: f64,
+    pub total_distinct_components: usize,
+    /// Distinct components per product, summed over the products
+    pub total_component_instances: usize,
+    /// Components used in more than one product
+    pub shared_components: usize,
+}
+
+/// Usage of a component across all root products
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComponentUsageFrequency {
+    pub component_id: ComponentId,
+    /// Distinct assemblies using the component directly
+    pub total_parents: usize,
+    /// Root products whose BOM contains the component at any level
+    pub total_root_products: usize,
+    /// Sum of the quantities needed for one unit of each root product
+    pub cumulative_quantity_across_products: Decimal,
 }
 
 /// Impact analysis result
@@ -239,47 +516,9 @@ pub struct SharedComponent {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::{create_test_bom_item, create_test_component};
     use bom_core::repository::memory::InMemoryRepository;
-    use bom_core::*;
     use bom_graph::BomGraph;
-    use chrono::Utc;
-
-    fn create_test_component(id: &str) -> Component {
-        Component {
-            id: ComponentId::new(id),
-            description: format!("Component {}", id),
-            component_type: ComponentType::FinishedProduct,
-            uom: "EA".to_string(),
-            standard_cost: Some(Decimal::from(100)),
-            lead_time_days: Some(7),
-            procurement_type: ProcurementType::Make,
-            organization: "ORG01".to_string(),
-            version: 0,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
-        }
-    }
-
-    fn create_test_bom_item(parent: &str, child: &str, qty: i32) -> BomItem {
-        BomItem {
-            id: uuid::Uuid::new_v4(),
-            parent_id: ComponentId::new(parent),
-            child_id: ComponentId::new(child),
-            quantity: Decimal::from(qty),
-            scrap_factor: Decimal::ZERO,
-            sequence: 10,
-            operation_sequence: None,
-            is_phantom: false,
-            effective_from: None,
-            effective_to: None,
-            alternative_group: None,
-            alternative_priority: None,
-            reference_designator: None,
-            position: None,
-            notes: None,
-            version: 0,
-        }
-    }
 
     #[test]
     fn test_where_used_simple() {
@@ -291,8 +530,8 @@ mod tests {
         repo.add_component(create_test_component("B"));
         repo.add_component(create_test_component("C"));
 
-        repo.add_bom_item(create_test_bom_item("A", "B", 2));
-        repo.add_bom_item(create_test_bom_item("C", "B", 1));
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("C", "B", 1)).unwrap();
 
         let graph = BomGraph::from_repository(&repo).unwrap();
         let analyzer = WhereUsedAnalyzer::new(&graph);
@@ -311,6 +550,105 @@ mod tests {
             .any(|item| item.parent_id.as_str() == "C"));
     }
 
+    #[test]
+    fn test_analyze_batch_matches_analyze() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B -> D
+        //   -> C -> D
+        // E -> D
+        for id in ["A", "B", "C", "D", "E"] {
+            repo.add_component(create_test_component(id));
+        }
+        repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 3)).unwrap();
+        repo.add_bom_item(create_test_bom_item("C", "D", 4)).unwrap();
+        repo.add_bom_item(create_test_bom_item("E", "D", 5)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let analyzer = WhereUsedAnalyzer::new(&graph);
+
+        let ids: Vec<ComponentId> = ["A", "B", "C", "D", "E"].into_iter().map(ComponentId::new).collect();
+        let batch = analyzer.analyze_batch(&ids).unwrap();
+        assert_eq!(batch.len(), ids.len());
+
+        let summary = |result: &WhereUsedResult| {
+            let mut items: Vec<_> = result
+                .used_in
+                .iter()
+                .map(|item| {
+                    let mut paths = item.paths.clone();
+                    paths.sort_by_key(|path| path.iter().map(|id| id.as_str().to_string()).collect::<Vec<_>>());
+                    (item.parent_id.as_str().to_string(), item.quantity, item.level, paths)
+                })
+                .collect();
+            items.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+            items
+        };
+
+        for id in &ids {
+            let individual = analyzer.analyze(id).unwrap();
+            assert_eq!(batch[id].component, individual.component);
+            assert_eq!(summary(&batch[id]), summary(&individual));
+        }
+        // B, C and E directly, A through B and C: 1 * 3 + 2 * 4
+        let d = &batch[&ComponentId::new("D")].used_in;
+        assert_eq!(d.len(), 4);
+        assert_eq!((d[3].parent_id.as_str(), d[3].level, d[3].quantity), ("A", 2, Decimal::from(11)));
+
+        assert!(analyzer
+            .analyze_batch(&[ComponentId::new("A"), ComponentId::new("MISSING")])
+            .is_err());
+    }
+
+    #[test]
+    fn test_analyze_with_depth() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B -> C -> D (2) -> E (3)
+        for id in ["A", "B", "C", "D", "E"] {
+            repo.add_component(create_test_component(id));
+        }
+        repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("C", "D", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("D", "E", 3)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let analyzer = WhereUsedAnalyzer::new(&graph);
+        let e = ComponentId::new("E");
+        let ids = |ids: &[&str]| -> Vec<ComponentId> { ids.iter().map(|id| ComponentId::new(*id)).collect() };
+
+        // (assembly, level, quantity of E per assembly, paths from the root)
+        let at_depth = |max_levels: usize| -> Vec<(String, usize, Decimal, Vec<Vec<ComponentId>>)> {
+            analyzer
+                .analyze_with_depth(&e, max_levels)
+                .unwrap()
+                .used_in
+                .into_iter()
+                .map(|item| (item.parent_id.as_str().to_string(), item.level, item.quantity, item.paths))
+                .collect()
+        };
+        let d = ("D".to_string(), 1, Decimal::from(3), vec![ids(&["A", "B", "C", "D"])]);
+        let c = ("C".to_string(), 2, Decimal::from(6), vec![ids(&["A", "B", "C"])]);
+        let b = ("B".to_string(), 3, Decimal::from(6), vec![ids(&["A", "B"])]);
+        let a = ("A".to_string(), 4, Decimal::from(6), vec![ids(&["A"])]);
+
+        assert_eq!(at_depth(1), vec![d.clone()]);
+        assert_eq!(at_depth(2), vec![d.clone(), c.clone()]);
+        let unlimited = vec![d, c, b, a];
+        assert_eq!(at_depth(usize::MAX), unlimited);
+        assert_eq!(at_depth(10), unlimited);
+
+        let full = analyzer.analyze(&e).unwrap();
+        assert_eq!(full.used_in.len(), unlimited.len());
+
+        assert!(analyzer.analyze_with_depth(&e, 0).unwrap().used_in.is_empty());
+        // The root is used nowhere at any depth
+        assert!(analyzer.analyze_with_depth(&ComponentId::new("A"), 2).unwrap().used_in.is_empty());
+    }
+
     #[test]
     fn test_find_root_assemblies() {
         let repo = InMemoryRepository::new();
@@ -323,9 +661,9 @@ mod tests {
         repo.add_component(create_test_component("C"));
         repo.add_component(create_test_component("D"));
 
-        repo.add_bom_item(create_test_bom_item("A", "B", 1));
-        repo.add_bom_item(create_test_bom_item("B", "D", 1));
-        repo.add_bom_item(create_test_bom_item("C", "D", 1));
+        repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("C", "D", 1)).unwrap();
 
         let graph = BomGraph::from_repository(&repo).unwrap();
         let analyzer = WhereUsedAnalyzer::new(&graph);
@@ -352,9 +690,9 @@ mod tests {
         repo.add_component(create_test_component("C"));
         repo.add_component(create_test_component("D"));
 
-        repo.add_bom_item(create_test_bom_item("A", "B", 1));
-        repo.add_bom_item(create_test_bom_item("A", "C", 1));
-        repo.add_bom_item(create_test_bom_item("B", "D", 1));
+        repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 1)).unwrap();
 
         let graph = BomGraph::from_repository(&repo).unwrap();
         let analyzer = WhereUsedAnalyzer::new(&graph);
@@ -393,11 +731,11 @@ mod tests {
         repo.add_component(create_test_component("E"));
         repo.add_component(create_test_component("F"));
 
-        repo.add_bom_item(create_test_bom_item("A", "B", 1));
-        repo.add_bom_item(create_test_bom_item("A", "C", 1));
-        repo.add_bom_item(create_test_bom_item("B", "D", 1));
-        repo.add_bom_item(create_test_bom_item("E", "F", 1));
-        repo.add_bom_item(create_test_bom_item("F", "D", 1));
+        repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("E", "F", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("F", "D", 1)).unwrap();
 
         let graph = BomGraph::from_repository(&repo).unwrap();
         let analyzer = WhereUsedAnalyzer::new(&graph);
@@ -411,4 +749,101 @@ mod tests {
             .iter()
             .any(|sc| sc.component_id.as_str() == "D" && sc.used_in_count == 2));
     }
+
+    #[test]
+    fn test_commonality_index() {
+        let repo = InMemoryRepository::new();
+
+        // P1 and P2 share A and B (half their components), P3 shares nothing
+        let family = [
+            ("P1", ["A", "B", "C", "D"]),
+            ("P2", ["A", "B", "E", "F"]),
+            ("P3", ["G", "H", "I", "J"]),
+        ];
+        for (product, children) in family {
+            repo.add_component(create_test_component(product));
+            for child in children {
+                repo.add_component(create_test_component(child));
+                repo.add_bom_item(create_test_bom_item(product, child, 1)).unwrap();
+            }
+        }
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let analyzer = WhereUsedAnalyzer::new(&graph);
+
+        let roots: Vec<ComponentId> = ["P1", "P2", "P3"].into_iter().map(ComponentId::new).collect();
+        let analysis = analyzer.calculate_commonality_index(&roots).unwrap();
+
+        // 12 instances, 4 of them (A and B twice each) shared: (12 - 4) / 12
+        assert_eq!(analysis.total_component_instances, 12);
+        assert_eq!(analysis.total_distinct_components, 10);
+        assert_eq!(analysis.shared_components, 2);
+        assert!((analysis.index - 8.0 / 12.0).abs() < 1e-12);
+
+        // A single product has nothing to share with
+        let single = analyzer.calculate_commonality_index(&roots[..1]).unwrap();
+        assert_eq!(single.index, 1.0);
+
+        assert!(analyzer
+            .calculate_commonality_index(&[ComponentId::new("MISSING")])
+            .is_err());
+    }
+
+    #[test]
+    fn test_supply_chain_risk_report() {
+        let repo = InMemoryRepository::new();
+
+        // BOLT is used by all five products, each product also has its own label
+        repo.add_component(create_test_component("BOLT"));
+        for i in 1..=5 {
+            let (product, label) = (format!("P{}", i), format!("LABEL-{}", i));
+            repo.add_component(create_test_component(&product));
+            repo.add_component(create_test_component(&label));
+            repo.add_bom_item(create_test_bom_item(&product, "BOLT", 4)).unwrap();
+            repo.add_bom_item(create_test_bom_item(&product, &label, 1)).unwrap();
+        }
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let report = WhereUsedAnalyzer::new(&graph).supply_chain_risk_report(HIGH_RISK_MIN_IN_DEGREE);
+
+        assert_eq!(report.high_risk_components, vec![(ComponentId::new("BOLT"), 5)]);
+        assert_eq!(report.single_source_components.len(), 5);
+        assert_eq!(report.degree_distribution[&0], 5);
+        assert_eq!(report.degree_distribution[&5], 1);
+    }
+
+    #[test]
+    fn test_usage_frequency() {
+        let repo = InMemoryRepository::new();
+
+        // P1 -> FRAME -> BOLT (x4)
+        //    -> BOLT (x2)
+        // P2 -> FRAME
+        // P3 -> SEAT -> BOLT
+        for id in ["P1", "P2", "P3", "FRAME", "SEAT", "BOLT"] {
+            repo.add_component(create_test_component(id));
+        }
+        repo.add_bom_item(create_test_bom_item("P1", "FRAME", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("P1", "BOLT", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("FRAME", "BOLT", 4)).unwrap();
+        repo.add_bom_item(create_test_bom_item("P2", "FRAME", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("P3", "SEAT", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("SEAT", "BOLT", 1)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let analyzer = WhereUsedAnalyzer::new(&graph);
+
+        let usage = analyzer.calculate_usage_frequency().unwrap();
+        let ids: Vec<&str> = usage.iter().map(|u| u.component_id.as_str()).collect();
+        assert_eq!(ids, vec!["BOLT", "FRAME", "SEAT"]);
+
+        // Used in all three products: 6 + 4 + 1 bolts
+        assert_eq!(usage[0].total_root_products, 3);
+        assert_eq!(usage[0].total_parents, 3);
+        assert_eq!(usage[0].cumulative_quantity_across_products, Decimal::from(11));
+
+        assert_eq!(usage[1].total_root_products, 2);
+        assert_eq!(usage[1].total_parents, 2);
+        assert_eq!(usage[2].total_root_products, 1);
+    }
 }
@@ -0,0 +1,156 @@
+use anyhow::Result;
+use bom_calc::{BomEngine, DepthProfile};
+use bom_core::ComponentId;
+use colored::*;
+use serde::Serialize;
+
+use crate::data::BomData;
+use crate::output;
+
+#[derive(Debug, Serialize)]
+struct DepthLevelOutput {
+    depth: usize,
+    count: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct DepthProfileOutput {
+    component: String,
+    max_depth: usize,
+    average_depth: f64,
+    total_manufacturing_steps: usize,
+    nodes_at_max_depth: Vec<String>,
+    depth_histogram: Vec<DepthLevelOutput>,
+}
+
+pub fn execute(bom_data: &BomData, component: &str, profile: &str, format: &str) -> Result<String> {
+    match profile {
+        "depth" => depth_profile(bom_data, component, format),
+        _ => anyhow::bail!(rust_i18n::t!("errors.unknown_profile", profile = profile)),
+    }
+}
+
+fn depth_profile(bom_data: &BomData, component: &str, format: &str) -> Result<String> {
+    let engine = BomEngine::new(bom_data.to_repository()?)?;
+    let component_id = ComponentId::new(component);
+    let profile = engine
+        .analyze_depth_profile(&component_id)
+        .map_err(|e| anyhow::anyhow!(rust_i18n::t!("errors.calculation_error", error = e.to_string())))?;
+
+    let histogram = sorted_histogram(&profile);
+
+    match format {
+        "table" => {
+            let mut output = String::new();
+            output.push_str(&format!(
+                "\n{}\n\n",
+                rust_i18n::t!("commands.analyze.depth_header", component = component)
+                    .bold()
+                    .green()
+            ));
+
+            output.push_str(&format!(
+                "{}: {}\n",
+                rust_i18n::t!("commands.analyze.max_depth").bold(),
+                profile.max_depth
+            ));
+            output.push_str(&format!(
+                "{}: {:.2}\n",
+                rust_i18n::t!("commands.analyze.average_depth").bold(),
+                profile.average_depth
+            ));
+            output.push_str(&format!(
+                "{}: {}\n",
+                rust_i18n::t!("commands.analyze.manufacturing_steps").bold(),
+                profile.total_manufacturing_steps
+            ));
+            output.push_str(&format!(
+                "{}: {}\n\n",
+                rust_i18n::t!("commands.analyze.deepest_components").bold(),
+                profile
+                    .nodes_at_max_depth
+                    .iter()
+                    .map(|id| id.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+                    .cyan()
+            ));
+
+            output.push_str(&format!(
+                "{} | {}\n",
+                rust_i18n::t!("commands.analyze.depth").bold().cyan(),
+                rust_i18n::t!("commands.analyze.count").bold().cyan()
+            ));
+            output.push_str(&format!("{}\n", "─".repeat(80).dimmed()));
+
+            for level in &histogram {
+                output.push_str(&format!(
+                    "{:>5} | {:>5} {}\n",
+                    level.depth,
+                    level.count,
+                    "█".repeat(level.count.min(60)).blue()
+                ));
+            }
+
+            Ok(output)
+        }
+        "csv" => output::format_output(&histogram, format),
+        _ => output::format_output(
+            &DepthProfileOutput {
+                component: component.to_string(),
+                max_depth: profile.max_depth,
+                average_depth: profile.average_depth,
+                total_manufacturing_steps: profile.total_manufacturing_steps,
+                nodes_at_max_depth: profile
+                    .nodes_at_max_depth
+                    .iter()
+                    .map(|id| id.as_str().to_string())
+                    .collect(),
+                depth_histogram: histogram,
+            },
+            format,
+        ),
+    }
+}
+
+fn sorted_histogram(profile: &DepthProfile) -> Vec<DepthLevelOutput> {
+    let mut histogram: Vec<DepthLevelOutput> = profile
+        .depth_histogram
+        .iter()
+        .map(|(&depth, &count)| DepthLevelOutput { depth, count })
+        .collect();
+    histogram.sort_by_key(|level| level.depth);
+    histogram
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{component, item};
+
+    #[test]
+    fn test_analyze_depth_profile_json() {
+        // A -> B -> C, A -> D
+        let bom_data = BomData {
+            components: vec![component("A"), component("B"), component("C"), component("D")],
+            bom_items: vec![item("A", "B", "1"), item("B", "C", "1"), item("A", "D", "1")],
+        };
+
+        let output = execute(&bom_data, "A", "depth", "json").unwrap();
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(json["max_depth"], 2);
+        assert_eq!(json["total_manufacturing_steps"], 2);
+        assert_eq!(json["nodes_at_max_depth"], serde_json::json!(["C"]));
+
+        let counts: Vec<u64> = json["depth_histogram"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|level| level["count"].as_u64().unwrap())
+            .collect();
+        assert_eq!(counts, vec![1, 2, 1]);
+
+        assert!(execute(&bom_data, "A", "width", "json").is_err());
+    }
+}
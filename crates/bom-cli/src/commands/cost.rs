@@ -17,18 +17,24 @@ struct CostOutput {
     overhead_cost: String,
 }
 
-pub fn execute(bom_data: &BomData, component: &str, format: &str) -> Result<String> {
-    let (components, bom_items) = bom_data.to_core()?;
+#[derive(Debug, Serialize)]
+struct CategoryCostOutput {
+    category: String,
+    total_cost: String,
+    total_pct: String,
+    component_count: usize,
+    components: String,
+}
 
-    // Create repository and add data
-    let repo = InMemoryRepository::new();
-    for component in components {
-        repo.add_component(component);
-    }
-    for bom_item in bom_items {
-        repo.add_bom_item(bom_item);
+pub fn execute(bom_data: &BomData, component: &str, category_breakdown: bool, format: &str) -> Result<String> {
+    if category_breakdown {
+        return by_category(bom_data.to_repository()?, component, format);
     }
+    run(bom_data.to_repository()?, component, format)
+}
 
+/// Calculate cost against an already loaded repository
+pub fn run(repo: InMemoryRepository, component: &str, format: &str) -> Result<String> {
     let engine = BomEngine::new(repo)?;
     let component_id = ComponentId::new(component);
     let result = engine
@@ -76,3 +82,100 @@ pub fn execute(bom_data: &BomData, component: &str, format: &str) -> Result<Stri
         output::format_output(&output_data, format)
     }
 }
+
+/// Cost split by component type, highest cost first
+fn by_category(repo: InMemoryRepository, component: &str, format: &str) -> Result<String> {
+    let engine = BomEngine::new(repo)?;
+    let breakdown = engine
+        .calculate_cost_by_category(&ComponentId::new(component))
+        .map_err(|e| anyhow::anyhow!(rust_i18n::t!("errors.calculation_error", error = e.to_string())))?;
+
+    let mut categories: Vec<_> = breakdown.categories.into_iter().collect();
+    categories.sort_by(|a, b| {
+        b.1.total_cost
+            .cmp(&a.1.total_cost)
+            .then_with(|| format!("{:?}", a.0).cmp(&format!("{:?}", b.0)))
+    });
+
+    let rows: Vec<CategoryCostOutput> = categories
+        .into_iter()
+        .map(|(component_type, category)| CategoryCostOutput {
+            category: format!("{:?}", component_type),
+            total_cost: category.total_cost.to_string(),
+            total_pct: category.total_pct.round_dp(2).to_string(),
+            component_count: category.component_count,
+            components: category
+                .components
+                .iter()
+                .map(|id| id.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        })
+        .collect();
+
+    if format != "table" {
+        return output::format_output(&rows, format);
+    }
+
+    let mut output = String::new();
+    output.push_str(&format!(
+        "\n{}\n\n",
+        rust_i18n::t!("commands.cost.category_header", component = component)
+            .bold()
+            .green()
+    ));
+    output.push_str(&format!(
+        "{:<16} | {:<12} | {:<8} | {}\n",
+        rust_i18n::t!("commands.cost.category").bold().cyan(),
+        rust_i18n::t!("commands.cost.total_cost").bold().cyan(),
+        "%".bold().cyan(),
+        rust_i18n::t!("commands.cost.components").bold().cyan()
+    ));
+    output.push_str(&format!("{}\n", "─".repeat(80).dimmed()));
+
+    for row in &rows {
+        output.push_str(&format!(
+            "{:<16} | {:<12} | {:<8} | {}\n",
+            row.category,
+            format!("${}", row.total_cost),
+            row.total_pct,
+            row.components
+        ));
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{component_of_type as component, item};
+
+    #[test]
+    fn test_cost_category_breakdown_json() {
+        let bom_data = BomData {
+            components: vec![
+                component("BIKE-001", "FinishedProduct", "10"),
+                component("FRAME-001", "SemiFinished", "20"),
+                component("TUBE-001", "RawMaterial", "5"),
+                component("SEAT-001", "RawMaterial", "15"),
+            ],
+            bom_items: vec![
+                item("BIKE-001", "FRAME-001", "1"),
+                item("BIKE-001", "SEAT-001", "1"),
+                item("FRAME-001", "TUBE-001", "3"),
+            ],
+        };
+
+        let output = execute(&bom_data, "BIKE-001", true, "json").unwrap();
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let rows = json.as_array().unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0]["category"], "RawMaterial");
+        assert_eq!(rows[0]["total_cost"], "30");
+        assert_eq!(rows[0]["total_pct"], "50.00");
+        assert_eq!(rows[0]["components"], "SEAT-001, TUBE-001");
+        assert_eq!(rows[1]["category"], "SemiFinished");
+        assert_eq!(rows[2]["category"], "FinishedProduct");
+    }
+}
@@ -0,0 +1,138 @@
+use anyhow::{Context, Result};
+use bom_cache::PersistentCache;
+use bom_core::repository::memory::InMemoryRepository;
+use std::path::Path;
+
+use crate::data::{self, BomData};
+
+use super::{cost, explode, where_used};
+
+/// Calculation to run against a stored BOM database
+pub enum Query<'a> {
+    Explode {
+        component: &'a str,
+        quantity: &'a str,
+        format: &'a str,
+    },
+    Cost {
+        component: &'a str,
+        format: &'a str,
+    },
+    WhereUsed {
+        component: &'a str,
+        format: &'a str,
+    },
+}
+
+/// Store BOM data in a redb database, replacing what was stored before
+pub fn import(bom_data: &BomData, database: &Path) -> Result<String> {
+    let (components, bom_items) = bom_data.to_core()?;
+
+    let cache = open(database)?;
+    cache
+        .store_bom_data(&components, &bom_items)
+        .with_context(|| rust_i18n::t!("errors.database_error", path = database.display()))?;
+
+    Ok(rust_i18n::t!(
+        "commands.import.done",
+        components = components.len(),
+        items = bom_items.len(),
+        path = database.display()
+    )
+    .to_string())
+}
+
+/// Run a calculation against BOM data previously stored with `import`
+pub fn query(database: &Path, query: Query) -> Result<String> {
+    let repo = load_repository(database)?;
+
+    match query {
+        Query::Explode {
+            component,
+            quantity,
+            format,
+        } => explode::run(repo, component, quantity, format),
+        Query::Cost { component, format } => cost::run(repo, component, format),
+        Query::WhereUsed { component, format } => where_used::run(repo, component, format),
+    }
+}
+
+/// Load BOM data previously stored with `import`
+pub fn load_repository(database: &Path) -> Result<InMemoryRepository> {
+    if !database.exists() {
+        anyhow::bail!(rust_i18n::t!("errors.file_not_found", path = database.display()));
+    }
+    let cache = open(database)?;
+    let error = || rust_i18n::t!("errors.database_error", path = database.display());
+
+    let components = cache.load_components().with_context(error)?;
+    let bom_items = cache.load_bom_items().with_context(error)?;
+    data::build_repository(components, bom_items)
+}
+
+fn open(database: &Path) -> Result<PersistentCache> {
+    PersistentCache::new(database).with_context(|| rust_i18n::t!("errors.database_error", path = database.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{component_with_cost as component, item};
+
+    /// Explosion rows sorted so runs can be compared regardless of order within a level
+    fn sorted_rows(output: &str) -> Vec<serde_json::Value> {
+        let mut rows: Vec<serde_json::Value> = serde_json::from_str(output).unwrap();
+        rows.sort_by_key(|row| row["component"].as_str().unwrap().to_string());
+        rows
+    }
+
+    #[test]
+    fn test_import_then_query() {
+        // A -> B (2) -> C (3), A -> D (1)
+        let bom_data = BomData {
+            components: vec![
+                component("A", "10"),
+                component("B", "5"),
+                component("C", "1"),
+                component("D", "2"),
+            ],
+            bom_items: vec![item("A", "B", "2"), item("B", "C", "3"), item("A", "D", "1")],
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let database = dir.path().join("bom.db");
+        import(&bom_data, &database).unwrap();
+
+        let stored = query(
+            &database,
+            Query::Explode {
+                component: "A",
+                quantity: "10",
+                format: "json",
+            },
+        )
+        .unwrap();
+        let direct = explode::execute(&bom_data, "A", "10", "json").unwrap();
+        assert_eq!(sorted_rows(&stored), sorted_rows(&direct));
+
+        let stored = query(
+            &database,
+            Query::Cost {
+                component: "A",
+                format: "json",
+            },
+        )
+        .unwrap();
+        let direct = cost::execute(&bom_data, "A", false, "json").unwrap();
+        assert_eq!(stored, direct);
+
+        assert!(query(
+            &dir.path().join("missing.db"),
+            Query::Cost {
+                component: "A",
+                format: "json",
+            },
+        )
+        .is_err());
+    }
+}
@@ -1,10 +1,12 @@
 use anyhow::Result;
 use bom_calc::BomEngine;
 use bom_core::repository::memory::InMemoryRepository;
-use bom_core::ComponentId;
+use bom_core::{BomRepository, Component, ComponentId, ExplosionResult};
+use bom_graph::BomGraph;
 use colored::*;
 use rust_decimal::Decimal;
 use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 
 use crate::data::BomData;
@@ -18,26 +20,24 @@ struct ExplosionOutput {
 }
 
 pub fn execute(bom_data: &BomData, component: &str, quantity_str: &str, format: &str) -> Result<String> {
+    run(bom_data.to_repository()?, component, quantity_str, format)
+}
+
+/// Explode against an already loaded repository
+pub fn run(repo: InMemoryRepository, component: &str, quantity_str: &str, format: &str) -> Result<String> {
     let quantity = Decimal::from_str(quantity_str)
         .map_err(|_| anyhow::anyhow!(rust_i18n::t!("errors.invalid_quantity", qty = quantity_str)))?;
 
-    let (components, bom_items) = bom_data.to_core()?;
-
-    // Create repository and add data
-    let repo = InMemoryRepository::new();
-    for component in components {
-        repo.add_component(component);
-    }
-    for bom_item in bom_items {
-        repo.add_bom_item(bom_item);
-    }
-
     let engine = BomEngine::new(repo)?;
     let component_id = ComponentId::new(component);
     let result = engine
         .explode(&component_id, quantity)
         .map_err(|e| anyhow::anyhow!(rust_i18n::t!("errors.calculation_error", error = e.to_string())))?;
 
+    if format == "d3" {
+        return d3_graph(&engine, &result);
+    }
+
     if format == "table" {
         let mut output = String::new();
         output.push_str(&format!(
@@ -82,3 +82,67 @@ pub fn execute(bom_data: &BomData, component: &str, quantity_str: &str, format:
         output::format_output(&output_data, format)
     }
 }
+
+/// The exploded part of the BOM as D3.js force-directed graph JSON
+fn d3_graph(engine: &BomEngine<InMemoryRepository>, result: &ExplosionResult) -> Result<String> {
+    let components: HashSet<&ComponentId> = result.items.iter().map(|item| &item.component_id).collect();
+
+    let mut graph = BomGraph::new();
+    graph.arena_mut().add_node(result.root_component.clone());
+    for (_, _, item) in engine.graph().iter_edges() {
+        if components.contains(&item.parent_id) {
+            graph.add_bom_item(item.clone())?;
+        }
+    }
+
+    let ids: Vec<ComponentId> = components.into_iter().cloned().collect();
+    let component_data: HashMap<ComponentId, Component> = engine
+        .repository()
+        .get_components(&ids)?
+        .into_iter()
+        .map(|component| (component.id.clone(), component))
+        .collect();
+    let costs = engine
+        .calculate_all_costs()
+        .map_err(|e| anyhow::anyhow!(rust_i18n::t!("errors.calculation_error", error = e.to_string())))?;
+
+    Ok(serde_json::to_string_pretty(&graph.to_d3_force_graph(&component_data, Some(&costs)))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{component_of_type, item};
+
+    #[test]
+    fn test_explode_d3_format() {
+        let bom_data = BomData {
+            components: vec![
+                component_of_type("BIKE-001", "FinishedProduct", "10"),
+                component_of_type("FRAME-001", "SemiFinished", "10"),
+                component_of_type("TUBE-001", "RawMaterial", "10"),
+                component_of_type("CART-001", "FinishedProduct", "10"),
+            ],
+            bom_items: vec![
+                item("BIKE-001", "FRAME-001", "1"),
+                item("FRAME-001", "TUBE-001", "4"),
+                item("CART-001", "TUBE-001", "2"),
+            ],
+        };
+
+        let output = execute(&bom_data, "BIKE-001", "1", "d3").unwrap();
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        // Only the exploded component's BOM, not CART-001
+        let nodes = json["nodes"].as_array().unwrap();
+        let links = json["links"].as_array().unwrap();
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(links.len(), 2);
+
+        let bike = nodes.iter().find(|node| node["id"] == "BIKE-001").unwrap();
+        assert_eq!(bike["group"], 1);
+        assert_eq!(bike["cost"], 60.0);
+        let tube_link = links.iter().find(|link| link["target"] == "TUBE-001").unwrap();
+        assert_eq!(tube_link["value"], 4.0);
+    }
+}
@@ -0,0 +1,397 @@
+use anyhow::Result;
+use bom_calc::BomEngine;
+use bom_core::repository::memory::InMemoryRepository;
+use bom_core::{BomRepository, ComponentId};
+use bom_graph::NodeIndex;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use rust_decimal::Decimal;
+use std::collections::HashSet;
+
+/// Open the interactive BOM tree explorer
+pub fn execute(repo: InMemoryRepository) -> Result<()> {
+    let mut app = ExplorerApp::new(repo)?;
+
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+
+    result
+}
+
+/// One visible line of the tree
+struct TreeRow {
+    /// Components from the root down to this row (the same component can appear under several parents)
+    path: Vec<ComponentId>,
+    /// Quantity per parent (none for roots)
+    quantity: Option<Decimal>,
+    has_children: bool,
+}
+
+impl TreeRow {
+    fn component_id(&self) -> &ComponentId {
+        self.path.last().unwrap()
+    }
+}
+
+/// State of the tree explorer
+pub struct ExplorerApp {
+    engine: BomEngine<InMemoryRepository>,
+    rows: Vec<TreeRow>,
+    expanded: HashSet<Vec<ComponentId>>,
+    list_state: ListState,
+    /// Result of the last `c`/`w` command
+    detail: Vec<String>,
+    should_quit: bool,
+}
+
+impl ExplorerApp {
+    pub fn new(repo: InMemoryRepository) -> Result<Self> {
+        let mut app = Self {
+            engine: BomEngine::new(repo)?,
+            rows: Vec::new(),
+            expanded: HashSet::new(),
+            list_state: ListState::default().with_selected(Some(0)),
+            detail: vec![rust_i18n::t!("commands.explore.help").to_string()],
+            should_quit: false,
+        };
+        app.rebuild_rows();
+        Ok(app)
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        while !self.should_quit {
+            terminal.draw(|frame| self.render(frame))?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.handle_key(key.code);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply a key press
+    pub fn handle_key(&mut self, key: KeyCode) {
+        let selected = self.list_state.selected().unwrap_or(0);
+
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+            KeyCode::Up => self.list_state.select(Some(selected.saturating_sub(1))),
+            KeyCode::Down => self
+                .list_state
+                .select(Some((selected + 1).min(self.rows.len().saturating_sub(1)))),
+            KeyCode::Enter => self.set_expanded(selected, None),
+            KeyCode::Right => self.set_expanded(selected, Some(true)),
+            KeyCode::Left => self.set_expanded(selected, Some(false)),
+            KeyCode::Char('c') => self.show_cost(selected),
+            KeyCode::Char('w') => self.show_where_used(selected),
+            _ => {}
+        }
+    }
+
+    /// Expand or collapse a row (`None` toggles)
+    fn set_expanded(&mut self, row: usize, expanded: Option<bool>) {
+        let Some(row) = self.rows.get(row) else { return };
+        if !row.has_children {
+            return;
+        }
+
+        let path = row.path.clone();
+        let expand = expanded.unwrap_or(!self.expanded.contains(&path));
+        if expand {
+            self.expanded.insert(path);
+        } else {
+            self.expanded.remove(&path);
+        }
+        self.rebuild_rows();
+    }
+
+    fn show_cost(&mut self, row: usize) {
+        let Some(row) = self.rows.get(row) else { return };
+        let component_id = row.component_id().clone();
+
+        self.detail = match self.engine.calculate_cost(&component_id) {
+            Ok(cost) => vec![
+                rust_i18n::t!("commands.cost.result_header", component = component_id.as_str()).to_string(),
+                String::new(),
+                format!("{}: {}", rust_i18n::t!("commands.cost.material_cost"), cost.material_cost),
+                format!("{}: {}", rust_i18n::t!("commands.cost.total_cost"), cost.total_cost),
+            ],
+            Err(e) => vec![rust_i18n::t!("errors.calculation_error", error = e.to_string()).to_string()],
+        };
+    }
+
+    fn show_where_used(&mut self, row: usize) {
+        let Some(row) = self.rows.get(row) else { return };
+        let component_id = row.component_id().clone();
+
+        self.detail = match self.engine.where_used(&component_id) {
+            Ok(result) => {
+                let mut lines = vec![
+                    rust_i18n::t!("commands.where_used.result_header", component = component_id.as_str()).to_string(),
+                    rust_i18n::t!("commands.where_used.used_in", count = result.used_in.len()).to_string(),
+                    String::new(),
+                ];
+                for item in &result.used_in {
+                    lines.push(format!("{} x{}", item.parent_id.as_str(), item.quantity));
+                    // Paths from the roots down to the assembly
+                    lines.extend(item.paths.iter().map(|path| {
+                        let path: Vec<&str> = path.iter().map(|id| id.as_str()).collect();
+                        format!("  {}", path.join(" > "))
+                    }));
+                }
+                lines
+            }
+            Err(e) => vec![rust_i18n::t!("errors.calculation_error", error = e.to_string()).to_string()],
+        };
+    }
+
+    /// Flatten the tree into the rows currently visible
+    fn rebuild_rows(&mut self) {
+        let graph = self.engine.graph();
+        let arena = graph.arena();
+
+        let mut roots: Vec<(NodeIndex, &ComponentId)> = graph.iter_root_nodes().collect();
+        roots.sort_by(|a, b| a.1.as_str().cmp(b.1.as_str()));
+
+        // Depth-first, children in sequence order
+        let mut stack: Vec<(NodeIndex, Vec<ComponentId>, Option<Decimal>)> = roots
+            .into_iter()
+            .rev()
+            .map(|(node, id)| (node, vec![id.clone()], None))
+            .collect();
+        let mut rows = Vec::new();
+
+        while let Some((node, path, quantity)) = stack.pop() {
+            let mut children: Vec<_> = arena.children(node).collect();
+            children.sort_by_key(|(_, edge)| edge.bom_item.sequence);
+
+            if self.expanded.contains(&path) {
+                for (child, edge) in children.iter().rev() {
+                    let mut child_path = path.clone();
+                    child_path.push(edge.bom_item.child_id.clone());
+                    stack.push((*child, child_path, Some(edge.bom_item.quantity)));
+                }
+            }
+
+            rows.push(TreeRow {
+                path,
+                quantity,
+                has_children: !children.is_empty(),
+            });
+        }
+
+        self.rows = rows;
+        let selected = self.list_state.selected().unwrap_or(0);
+        self.list_state
+            .select(Some(selected.min(self.rows.len().saturating_sub(1))));
+    }
+
+    /// Draw the tree, the detail panel and the status bar
+    pub fn render(&mut self, frame: &mut Frame) {
+        let [main, status] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(1)])
+            .areas(frame.area());
+        let [tree, detail] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+            .areas(main);
+
+        let repository = self.engine.repository();
+        let items: Vec<ListItem> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let indent = "  ".repeat(row.path.len() - 1);
+                let marker = match (row.has_children, self.expanded.contains(&row.path)) {
+                    (false, _) => " ",
+                    (true, true) => "▾",
+                    (true, false) => "▸",
+                };
+                let component = repository.get_component(row.component_id()).ok();
+                let description = component.as_ref().map(|c| c.description.as_str()).unwrap_or("");
+                let cost = component
+                    .as_ref()
+                    .and_then(|c| c.standard_cost)
+                    .map(|cost| cost.to_string())
+                    .unwrap_or_else(|| "-".to_string());
+                let quantity = row
+                    .quantity
+                    .map(|quantity| format!("x{}", quantity))
+                    .unwrap_or_default();
+
+                ListItem::new(format!(
+                    "{}{} {}  {}  {}  {}",
+                    indent,
+                    marker,
+                    row.component_id().as_str(),
+                    description,
+                    quantity,
+                    cost
+                ))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(rust_i18n::t!("commands.explore.tree_title").to_string()),
+            )
+            .highlight_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+            .highlight_symbol("> ");
+        frame.render_stateful_widget(list, tree, &mut self.list_state);
+
+        let detail_text: Vec<Line> = self.detail.iter().map(|line| Line::from(line.as_str())).collect();
+        frame.render_widget(
+            Paragraph::new(detail_text)
+                .wrap(Wrap { trim: false })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(rust_i18n::t!("commands.explore.detail_title").to_string()),
+                ),
+            detail,
+        );
+
+        let stats = self.engine.stats();
+        frame.render_widget(
+            Paragraph::new(
+                rust_i18n::t!(
+                    "commands.explore.status",
+                    nodes = stats.node_count,
+                    edges = stats.edge_count,
+                    roots = stats.root_count,
+                    depth = stats.max_depth
+                )
+                .to_string(),
+            )
+            .style(Style::default().add_modifier(Modifier::REVERSED)),
+            status,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{BomData, BomItemData, ComponentData};
+    use crate::fixtures::{component_with_cost, item};
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn component(id: &str, cost: &str) -> ComponentData {
+        ComponentData {
+            description: format!("Part {}", id),
+            ..component_with_cost(id, cost)
+        }
+    }
+
+    fn item_at(parent: &str, child: &str, quantity: &str, sequence: i32) -> BomItemData {
+        BomItemData {
+            sequence,
+            ..item(parent, child, quantity)
+        }
+    }
+
+    fn create_app() -> ExplorerApp {
+        // A -> B (2) -> D (3), A -> C (1)
+        let bom_data = BomData {
+            components: vec![
+                component("A", "10"),
+                component("B", "5"),
+                component("C", "1"),
+                component("D", "2"),
+            ],
+            bom_items: vec![
+                item_at("A", "B", "2", 10),
+                item_at("A", "C", "1", 20),
+                item_at("B", "D", "3", 10),
+            ],
+        };
+        ExplorerApp::new(bom_data.to_repository().unwrap()).unwrap()
+    }
+
+    /// Render the app and return the screen as lines
+    fn draw(terminal: &mut Terminal<TestBackend>, app: &mut ExplorerApp) -> Vec<String> {
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let width = buffer.area.width as usize;
+        buffer
+            .content()
+            .chunks(width)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect()
+    }
+
+    fn screen_contains(screen: &[String], text: &str) -> bool {
+        screen.iter().any(|line| line.contains(text))
+    }
+
+    #[test]
+    fn test_explore_tree_navigation() {
+        let mut terminal = Terminal::new(TestBackend::new(100, 12)).unwrap();
+        let mut app = create_app();
+
+        // Collapsed: only the root is shown
+        let screen = draw(&mut terminal, &mut app);
+        assert!(screen_contains(&screen, "> ▸ A  Part A    10"));
+        assert!(!screen_contains(&screen, "Part B"));
+
+        // Expand the root, move to B and expand it
+        app.handle_key(KeyCode::Enter);
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Enter);
+        let screen = draw(&mut terminal, &mut app);
+        assert!(screen_contains(&screen, "▾ A  Part A"));
+        assert!(screen_contains(&screen, ">   ▾ B  Part B  x2  5"));
+        assert!(screen_contains(&screen, "D  Part D  x3  2"));
+        assert!(screen_contains(&screen, "C  Part C  x1  1"));
+
+        // D is listed before C (depth first)
+        let position = |text: &str| screen.iter().position(|line| line.contains(text)).unwrap();
+        assert!(position("Part D") < position("Part C"));
+
+        // Collapse B again
+        app.handle_key(KeyCode::Left);
+        let screen = draw(&mut terminal, &mut app);
+        assert!(!screen_contains(&screen, "Part D"));
+        assert!(screen_contains(&screen, "Part C"));
+
+        app.handle_key(KeyCode::Char('q'));
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn test_explore_cost_and_where_used() {
+        let mut terminal = Terminal::new(TestBackend::new(100, 12)).unwrap();
+        let mut app = create_app();
+
+        // A = 10 + 2 * (5 + 3 * 2) + 1
+        app.handle_key(KeyCode::Char('c'));
+        let screen = draw(&mut terminal, &mut app);
+        assert!(screen_contains(&screen, ": 33"));
+
+        // Where-used of D lists B with its path from the root, then A above it
+        app.handle_key(KeyCode::Enter);
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Enter);
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Char('w'));
+        let screen = draw(&mut terminal, &mut app);
+        assert!(screen_contains(&screen, "B x3"));
+        assert!(screen_contains(&screen, "  A > B"));
+        assert!(screen_contains(&screen, "A x6"));
+
+        // Status bar reflects the graph
+        let stats = app.engine.stats();
+        assert_eq!((stats.node_count, stats.edge_count), (4, 3));
+    }
+}
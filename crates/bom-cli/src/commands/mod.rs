@@ -1,3 +1,12 @@
 pub mod explode;
 pub mod cost;
 pub mod where_used;
+pub mod validate;
+pub mod analyze;
+pub mod search;
+pub mod notes_search;
+pub mod usage;
+pub mod report;
+pub mod database;
+#[cfg(feature = "tui")]
+pub mod explore;
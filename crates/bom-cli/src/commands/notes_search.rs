@@ -0,0 +1,106 @@
+use anyhow::Result;
+use bom_calc::BomEngine;
+use colored::*;
+use serde::Serialize;
+
+use crate::data::BomData;
+use crate::output;
+
+#[derive(Debug, Serialize)]
+struct NotesSearchOutput {
+    parent: String,
+    child: String,
+    quantity: String,
+    notes: String,
+}
+
+pub fn execute(bom_data: &BomData, query: &str, format: &str) -> Result<String> {
+    let engine = BomEngine::new(bom_data.to_repository()?)?;
+    let items = engine
+        .find_items_with_notes(query)
+        .map_err(|e| anyhow::anyhow!(rust_i18n::t!("errors.calculation_error", error = e.to_string())))?;
+
+    let rows: Vec<NotesSearchOutput> = items
+        .into_iter()
+        .map(|item| NotesSearchOutput {
+            parent: item.parent_id.as_str().to_string(),
+            child: item.child_id.as_str().to_string(),
+            quantity: item.quantity.to_string(),
+            notes: item.notes.unwrap_or_default(),
+        })
+        .collect();
+
+    if format != "table" {
+        return output::format_output(&rows, format);
+    }
+
+    let mut output = String::new();
+    output.push_str(&format!(
+        "\n{}\n\n",
+        rust_i18n::t!("commands.notes_search.result_header", query = query)
+            .bold()
+            .green()
+    ));
+    output.push_str(&format!(
+        "{}\n\n",
+        rust_i18n::t!("commands.notes_search.found", count = rows.len())
+    ));
+
+    if rows.is_empty() {
+        return Ok(output);
+    }
+
+    output.push_str(&format!(
+        "{:<15} | {:<15} | {:<10} | {}\n",
+        rust_i18n::t!("commands.notes_search.parent").bold().cyan(),
+        rust_i18n::t!("commands.notes_search.child").bold().cyan(),
+        rust_i18n::t!("commands.notes_search.quantity").bold().cyan(),
+        rust_i18n::t!("commands.notes_search.notes").bold().cyan()
+    ));
+    output.push_str(&format!("{}\n", "─".repeat(80).dimmed()));
+
+    for row in &rows {
+        output.push_str(&format!(
+            "{:<15} | {:<15} | {:<10} | {}\n",
+            row.parent, row.child, row.quantity, row.notes
+        ));
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::BomItemData;
+    use crate::fixtures::{component, item};
+
+    fn with_notes(item: BomItemData, notes: &str) -> BomItemData {
+        BomItemData {
+            notes: Some(notes.to_string()),
+            ..item
+        }
+    }
+
+    #[test]
+    fn test_notes_search_json() {
+        let bom_data = BomData {
+            components: ["BIKE-001", "FRAME-001", "WHEEL-001", "SEAT-001"]
+                .into_iter()
+                .map(component)
+                .collect(),
+            bom_items: vec![
+                with_notes(item("BIKE-001", "FRAME-001", "1"), "Use only with ECO-1234 approval"),
+                with_notes(item("BIKE-001", "WHEEL-001", "1"), "Torque to 40 Nm"),
+                item("BIKE-001", "SEAT-001", "1"),
+            ],
+        };
+
+        let output = execute(&bom_data, "eco-1234", "json").unwrap();
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let rows = json.as_array().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["child"], "FRAME-001");
+        assert_eq!(rows[0]["notes"], "Use only with ECO-1234 approval");
+    }
+}
@@ -0,0 +1,52 @@
+use anyhow::Result;
+use bom_calc::{BomEngine, ReportOptions};
+use bom_core::ComponentId;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+use crate::data::BomData;
+
+/// Build a Markdown report; without any selected section, every section is included
+pub fn execute(bom_data: &BomData, component: &str, quantity_str: &str, options: ReportOptions) -> Result<String> {
+    let quantity = Decimal::from_str(quantity_str)
+        .map_err(|_| anyhow::anyhow!(rust_i18n::t!("errors.invalid_quantity", qty = quantity_str)))?;
+    let options = if options == ReportOptions::default() {
+        ReportOptions::all()
+    } else {
+        options
+    };
+
+    let engine = BomEngine::new(bom_data.to_repository()?)?;
+    let report = engine
+        .generate_report(&ComponentId::new(component), quantity, options)
+        .map_err(|e| anyhow::anyhow!(rust_i18n::t!("errors.calculation_error", error = e.to_string())))?;
+
+    Ok(report.to_markdown())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{component_with_cost, item};
+
+    #[test]
+    fn test_report_sections() {
+        let bom_data = BomData {
+            components: vec![component_with_cost("BIKE-001", "100"), component_with_cost("FRAME-001", "40")],
+            bom_items: vec![item("BIKE-001", "FRAME-001", "1")],
+        };
+
+        let full = execute(&bom_data, "BIKE-001", "2", ReportOptions::default()).unwrap();
+        assert!(full.contains("## Cost"));
+        assert!(full.contains("## Analytics"));
+
+        let cost_only = ReportOptions {
+            include_cost: true,
+            ..Default::default()
+        };
+        let report = execute(&bom_data, "BIKE-001", "2", cost_only).unwrap();
+        assert!(report.contains("## Cost"));
+        assert!(!report.contains("## Where Used"));
+        assert!(!report.contains("## Analytics"));
+    }
+}
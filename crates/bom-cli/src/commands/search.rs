@@ -0,0 +1,106 @@
+use anyhow::Result;
+use bom_calc::BomEngine;
+use colored::*;
+use serde::Serialize;
+
+use crate::data::BomData;
+use crate::output;
+
+#[derive(Debug, Serialize)]
+struct SearchOutput {
+    id: String,
+    description: String,
+    component_type: String,
+    standard_cost: Option<String>,
+}
+
+pub fn execute(bom_data: &BomData, query: &str, format: &str) -> Result<String> {
+    let engine = BomEngine::new(bom_data.to_repository()?)?;
+    let components = engine
+        .search(query)
+        .map_err(|e| anyhow::anyhow!(rust_i18n::t!("errors.calculation_error", error = e.to_string())))?;
+
+    let rows: Vec<SearchOutput> = components
+        .into_iter()
+        .map(|component| SearchOutput {
+            id: component.id.as_str().to_string(),
+            description: component.description,
+            component_type: format!("{:?}", component.component_type),
+            standard_cost: component.standard_cost.map(|cost| cost.to_string()),
+        })
+        .collect();
+
+    if format != "table" {
+        return output::format_output(&rows, format);
+    }
+
+    let mut output = String::new();
+    output.push_str(&format!(
+        "\n{}\n\n",
+        rust_i18n::t!("commands.search.result_header", query = query)
+            .bold()
+            .green()
+    ));
+    output.push_str(&format!(
+        "{}\n\n",
+        rust_i18n::t!("commands.search.found", count = rows.len())
+    ));
+
+    if rows.is_empty() {
+        return Ok(output);
+    }
+
+    output.push_str(&format!(
+        "{:<15} | {:<30} | {:<15} | {}\n",
+        rust_i18n::t!("commands.search.component").bold().cyan(),
+        rust_i18n::t!("commands.search.description").bold().cyan(),
+        rust_i18n::t!("commands.search.type").bold().cyan(),
+        rust_i18n::t!("commands.search.cost").bold().cyan()
+    ));
+    output.push_str(&format!("{}\n", "─".repeat(80).dimmed()));
+
+    for row in &rows {
+        output.push_str(&format!(
+            "{:<15} | {:<30} | {:<15} | {}\n",
+            row.id,
+            row.description,
+            row.component_type,
+            row.standard_cost.as_deref().unwrap_or("-")
+        ));
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::ComponentData;
+    use crate::fixtures;
+
+    fn component(id: &str, description: &str) -> ComponentData {
+        ComponentData {
+            description: description.to_string(),
+            ..fixtures::component(id)
+        }
+    }
+
+    #[test]
+    fn test_search_json() {
+        let bom_data = BomData {
+            components: vec![
+                component("TUBE-001", "Aluminum Tube"),
+                component("TUBE-002", "Steel Tube"),
+                component("SEAT-001", "Saddle"),
+            ],
+            bom_items: vec![],
+        };
+
+        let output = execute(&bom_data, "aluminum tube", "json").unwrap();
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let rows = json.as_array().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["id"], "TUBE-001");
+        assert_eq!(rows[0]["description"], "Aluminum Tube");
+    }
+}
@@ -0,0 +1,94 @@
+use anyhow::Result;
+use bom_calc::BomEngine;
+use colored::*;
+use serde::Serialize;
+
+use crate::data::BomData;
+use crate::output;
+
+#[derive(Debug, Serialize)]
+struct UsageOutput {
+    component: String,
+    root_products: usize,
+    parents: usize,
+    cumulative_quantity: String,
+}
+
+pub fn execute(bom_data: &BomData, format: &str) -> Result<String> {
+    let engine = BomEngine::new(bom_data.to_repository()?)?;
+    let frequencies = engine
+        .calculate_component_usage_frequency()
+        .map_err(|e| anyhow::anyhow!(rust_i18n::t!("errors.calculation_error", error = e.to_string())))?;
+
+    let rows: Vec<UsageOutput> = frequencies
+        .into_iter()
+        .map(|usage| UsageOutput {
+            component: usage.component_id.as_str().to_string(),
+            root_products: usage.total_root_products,
+            parents: usage.total_parents,
+            cumulative_quantity: usage.cumulative_quantity_across_products.to_string(),
+        })
+        .collect();
+
+    if format != "table" {
+        return output::format_output(&rows, format);
+    }
+
+    let mut output = String::new();
+    output.push_str(&format!(
+        "\n{}\n\n",
+        rust_i18n::t!("commands.usage.result_header").bold().green()
+    ));
+
+    if rows.is_empty() {
+        output.push_str(&format!("{}\n", rust_i18n::t!("commands.usage.none")));
+        return Ok(output);
+    }
+
+    output.push_str(&format!(
+        "{:<15} | {:<10} | {:<10} | {}\n",
+        rust_i18n::t!("commands.usage.component").bold().cyan(),
+        rust_i18n::t!("commands.usage.root_products").bold().cyan(),
+        rust_i18n::t!("commands.usage.parents").bold().cyan(),
+        rust_i18n::t!("commands.usage.cumulative_quantity").bold().cyan()
+    ));
+    output.push_str(&format!("{}\n", "─".repeat(80).dimmed()));
+
+    for row in &rows {
+        output.push_str(&format!(
+            "{:<15} | {:<10} | {:<10} | {}\n",
+            row.component, row.root_products, row.parents, row.cumulative_quantity
+        ));
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{component, item};
+
+    #[test]
+    fn test_usage_json() {
+        let bom_data = BomData {
+            components: ["BIKE-001", "TRIKE-001", "WHEEL-001", "SEAT-001"]
+                .into_iter()
+                .map(component)
+                .collect(),
+            bom_items: vec![
+                item("BIKE-001", "WHEEL-001", "2"),
+                item("TRIKE-001", "WHEEL-001", "3"),
+                item("TRIKE-001", "SEAT-001", "1"),
+            ],
+        };
+
+        let output = execute(&bom_data, "json").unwrap();
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let rows = json.as_array().unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["component"], "WHEEL-001");
+        assert_eq!(rows[0]["root_products"], 2);
+        assert_eq!(rows[0]["cumulative_quantity"], "5");
+    }
+}
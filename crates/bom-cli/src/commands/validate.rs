@@ -0,0 +1,353 @@
+use anyhow::Result;
+use bom_calc::{BomValidator, IssueSeverity, ValidationIssue};
+use colored::*;
+use serde::Serialize;
+
+use crate::data::BomData;
+use crate::output;
+
+/// Which checks to run
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidateChecks {
+    pub cycles: bool,
+    /// With `cycles`, report every elementary cycle instead of one per cyclic path
+    pub all_cycles: bool,
+    pub completeness: bool,
+    pub costs: bool,
+    pub effectivity: bool,
+    pub duplicates: bool,
+    pub quantities: bool,
+    /// Report overlapping effectivity as errors; implies `effectivity`
+    pub strict: bool,
+}
+
+impl ValidateChecks {
+    /// Every check enabled
+    pub fn all() -> Self {
+        Self {
+            cycles: true,
+            all_cycles: false,
+            completeness: true,
+            costs: true,
+            effectivity: true,
+            duplicates: true,
+            quantities: true,
+            strict: false,
+        }
+    }
+
+    /// Use the selected checks, or all of them if none was selected
+    /// `all_cycles` and `strict` are modes, not checks of their own
+    pub fn or_all(self) -> Self {
+        if self.cycles || self.completeness || self.costs || self.effectivity || self.duplicates || self.quantities {
+            Self {
+                effectivity: self.effectivity || self.strict,
+                ..self
+            }
+        } else {
+            Self {
+                all_cycles: self.all_cycles,
+                strict: self.strict,
+                ..Self::all()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct IssueOutput {
+    severity: String,
+    category: String,
+    component_id: String,
+    message: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ValidateOutput {
+    issues: Vec<IssueOutput>,
+    error_count: usize,
+    warning_count: usize,
+}
+
+/// Formatted report plus the counts that decide the exit code
+pub struct ValidateResult {
+    pub output: String,
+    pub error_count: usize,
+    pub warning_count: usize,
+}
+
+pub fn execute(bom_data: &BomData, checks: ValidateChecks, format: &str) -> Result<ValidateResult> {
+    let issues = run_checks(bom_data, checks)?;
+
+    let error_count = issues
+        .iter()
+        .filter(|issue| issue.severity == IssueSeverity::Error)
+        .count();
+    let warning_count = issues.len() - error_count;
+
+    let issues: Vec<IssueOutput> = issues
+        .into_iter()
+        .map(|issue| IssueOutput {
+            severity: severity_label(issue.severity).to_string(),
+            category: format!("{:?}", issue.category),
+            component_id: issue.component_id.as_str().to_string(),
+            message: issue.message,
+        })
+        .collect();
+
+    let output = match format {
+        "table" => {
+            let mut output = String::new();
+            output.push_str(&format!(
+                "\n{}\n\n",
+                rust_i18n::t!("commands.validate.result_header").bold().green()
+            ));
+
+            if issues.is_empty() {
+                output.push_str(&format!("{}\n", rust_i18n::t!("commands.validate.no_issues").green()));
+            }
+
+            for issue in &issues {
+                let severity = if issue.severity == "ERROR" {
+                    format!("{:<8}", issue.severity).red().bold()
+                } else {
+                    format!("{:<8}", issue.severity).yellow().bold()
+                };
+                output.push_str(&format!(
+                    "{} {:<20} {}\n",
+                    severity,
+                    issue.component_id.cyan(),
+                    issue.message
+                ));
+            }
+
+            output.push_str(&format!(
+                "\n{}\n",
+                rust_i18n::t!(
+                    "commands.validate.summary",
+                    errors = error_count,
+                    warnings = warning_count
+                )
+            ));
+            output
+        }
+        "csv" => output::format_output(&issues, format)?,
+        _ => output::format_output(
+            &ValidateOutput {
+                issues,
+                error_count,
+                warning_count,
+            },
+            format,
+        )?,
+    };
+
+    Ok(ValidateResult {
+        output,
+        error_count,
+        warning_count,
+    })
+}
+
+fn run_checks(bom_data: &BomData, checks: ValidateChecks) -> Result<Vec<ValidationIssue>> {
+    // Neither the graph nor a checked repository is built here: they would reject cyclic
+    // data and invalid items before they can be reported
+    let repo = bom_data.to_unchecked_repository()?;
+
+    let validator = BomValidator::new(&repo);
+    // Invalid items are always reported, whichever checks were selected
+    let mut issues = validator.validate_items()?;
+
+    if checks.cycles && checks.all_cycles {
+        issues.extend(validator.validate_all_cycles()?);
+    } else if checks.cycles {
+        issues.extend(validator.validate_cycles()?);
+    }
+    if checks.completeness {
+        issues.extend(validator.find_missing_components()?);
+    }
+    if checks.costs {
+        issues.extend(validator.validate_costs()?);
+    }
+    if checks.effectivity {
+        let mut effectivity = validator.validate_effectivity()?;
+        if checks.strict {
+            // Overlaps are the only effectivity warnings; inverted ranges already are errors
+            for issue in &mut effectivity {
+                issue.severity = IssueSeverity::Error;
+            }
+        }
+        issues.extend(effectivity);
+    }
+    if checks.duplicates {
+        issues.extend(validator.validate_duplicates()?);
+    }
+    if checks.quantities {
+        issues.extend(validator.validate_quantities()?);
+    }
+
+    Ok(issues)
+}
+
+fn severity_label(severity: IssueSeverity) -> &'static str {
+    match severity {
+        IssueSeverity::Error => "ERROR",
+        IssueSeverity::Warning => "WARNING",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::ComponentData;
+    use crate::fixtures::{component_with_cost, item};
+
+    fn component(id: &str, cost: Option<&str>) -> ComponentData {
+        ComponentData {
+            standard_cost: cost.map(str::to_string),
+            ..component_with_cost(id, "0")
+        }
+    }
+
+    /// A -> B -> C -> B (cycle), A -> X (missing), A -> D twice, D without cost
+    fn fixture() -> BomData {
+        BomData {
+            components: vec![
+                component("A", Some("100")),
+                component("B", Some("50")),
+                component("C", Some("10")),
+                component("D", None),
+            ],
+            bom_items: vec![
+                item("A", "B", "1"),
+                item("B", "C", "1"),
+                item("C", "B", "1"),
+                item("A", "X", "1"),
+                item("A", "D", "1"),
+                item("A", "D", "1"),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_validate_all_checks() {
+        let result = execute(&fixture(), ValidateChecks::default().or_all(), "json").unwrap();
+
+        // Errors: cycle, missing X
+        // Warnings: D without cost, A -> D duplicated and (both open-ended) overlapping
+        assert_eq!(result.error_count, 2);
+        assert_eq!(result.warning_count, 3);
+
+        let json: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(json["issues"].as_array().unwrap().len(), 5);
+        assert_eq!(json["error_count"], 2);
+        assert_eq!(json["warning_count"], 3);
+    }
+
+    #[test]
+    fn test_validate_selected_checks() {
+        let checks = ValidateChecks {
+            cycles: true,
+            ..Default::default()
+        };
+        let result = execute(&fixture(), checks.or_all(), "json").unwrap();
+
+        assert_eq!(result.error_count, 1);
+        assert_eq!(result.warning_count, 0);
+    }
+
+    #[test]
+    fn test_validate_check_quantities() {
+        let bom_data = BomData {
+            components: vec![component("A", Some("100")), component("B", Some("1"))],
+            bom_items: vec![item("A", "B", "1.5")],
+        };
+        let checks = ValidateChecks {
+            quantities: true,
+            ..Default::default()
+        };
+        let result = execute(&bom_data, checks.or_all(), "json").unwrap();
+
+        assert_eq!(result.error_count, 1);
+        let json: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(json["issues"][0]["category"], "Quantity");
+        assert!(json["issues"][0]["message"].as_str().unwrap().contains("suggested: 2"));
+    }
+
+    #[test]
+    fn test_validate_all_cycles() {
+        let mut bom_data = fixture();
+        // C -> A and A -> C add A -> B -> C -> A and A -> C -> A, sharing nodes with B -> C -> B;
+        // the per-path check stops at C once it is finished and misses A -> C -> A
+        bom_data.bom_items.push(item("C", "A", "1"));
+        bom_data.bom_items.push(item("A", "C", "1"));
+
+        let cycles_only = ValidateChecks {
+            cycles: true,
+            ..Default::default()
+        };
+        let all_cycles = ValidateChecks {
+            cycles: true,
+            all_cycles: true,
+            ..Default::default()
+        };
+        assert_eq!(execute(&bom_data, cycles_only.or_all(), "json").unwrap().error_count, 2);
+        assert_eq!(execute(&bom_data, all_cycles.or_all(), "json").unwrap().error_count, 3);
+
+        // Alone it keeps every check enabled: missing X adds one more error
+        let everything = ValidateChecks {
+            all_cycles: true,
+            ..Default::default()
+        }
+        .or_all();
+        assert!(everything.costs);
+        assert_eq!(execute(&bom_data, everything, "json").unwrap().error_count, 4);
+    }
+
+    #[test]
+    fn test_validate_reports_invalid_items() {
+        let bom_data = BomData {
+            components: vec![component("A", Some("1")), component("B", Some("1"))],
+            bom_items: vec![item("A", "B", "0")],
+        };
+        let checks = ValidateChecks {
+            costs: true,
+            ..Default::default()
+        };
+        let result = execute(&bom_data, checks.or_all(), "json").unwrap();
+
+        assert_eq!(result.error_count, 1);
+        let json: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(json["issues"][0]["category"], "InvalidItem");
+        assert!(json["issues"][0]["message"].as_str().unwrap().contains("Invalid quantity"));
+    }
+
+    #[test]
+    fn test_validate_strict_reports_overlaps_as_errors() {
+        // A -> D is in the fixture twice, both open-ended, so the items overlap
+        let checks = ValidateChecks {
+            duplicates: true,
+            strict: true,
+            ..Default::default()
+        };
+        let result = execute(&fixture(), checks.or_all(), "json").unwrap();
+
+        // The duplicate stays a warning, the overlap becomes an error
+        assert_eq!(result.error_count, 1);
+        assert_eq!(result.warning_count, 1);
+        let json: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        let overlap = json["issues"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|issue| issue["category"] == "Effectivity")
+            .unwrap();
+        assert_eq!(overlap["severity"], "ERROR");
+
+        // Alone it keeps every check enabled and only changes the overlap
+        let everything = execute(&fixture(), ValidateChecks { strict: true, ..Default::default() }.or_all(), "json")
+            .unwrap();
+        assert_eq!(everything.error_count, 3);
+        assert_eq!(everything.warning_count, 2);
+    }
+}
@@ -16,17 +16,11 @@ struct WhereUsedOutput {
 }
 
 pub fn execute(bom_data: &BomData, component: &str, format: &str) -> Result<String> {
-    let (components, bom_items) = bom_data.to_core()?;
-
-    // Create repository and add data
-    let repo = InMemoryRepository::new();
-    for component in components {
-        repo.add_component(component);
-    }
-    for bom_item in bom_items {
-        repo.add_bom_item(bom_item);
-    }
+    run(bom_data.to_repository()?, component, format)
+}
 
+/// Where-used analysis against an already loaded repository
+pub fn run(repo: InMemoryRepository, component: &str, format: &str) -> Result<String> {
     let engine = BomEngine::new(repo)?;
     let component_id = ComponentId::new(component);
     let result = engine
@@ -1,19 +1,21 @@
 use anyhow::{Context, Result};
+use bom_core::repository::memory::InMemoryRepository;
 use bom_core::*;
 use chrono::Utc;
 use rust_decimal::Decimal;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct BomData {
     pub components: Vec<ComponentData>,
     pub bom_items: Vec<BomItemData>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ComponentData {
     pub id: String,
     pub description: String,
@@ -28,7 +30,7 @@ pub struct ComponentData {
     pub organization: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct BomItemData {
     pub parent_id: String,
     pub child_id: String,
@@ -37,6 +39,8 @@ pub struct BomItemData {
     pub scrap_factor: String,
     #[serde(default = "default_sequence")]
     pub sequence: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
 }
 
 fn default_uom() -> String {
@@ -48,17 +52,27 @@ fn default_sequence() -> i32 {
 }
 
 pub fn load_bom(path: &Path) -> Result<BomData> {
+    let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+    load_bom_as(path, extension)
+}
+
+/// Load a BOM file in the given format (json or csv), regardless of its extension
+pub fn load_bom_as(path: &Path, format: &str) -> Result<BomData> {
     let content = std::fs::read_to_string(path)
         .with_context(|| rust_i18n::t!("errors.file_not_found", path = path.display()))?;
 
-    let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("");
-
-    match extension {
+    match format {
         "json" => {
-            serde_json::from_str(&content).with_context(|| rust_i18n::t!("errors.parse_error", error = "JSON"))
+            let document: serde_json::Value = serde_json::from_str(&content)
+                .with_context(|| rust_i18n::t!("errors.parse_error", error = "JSON"))?;
+            let violations = crate::schema::validate_against_schema(&document);
+            if !violations.is_empty() {
+                anyhow::bail!(rust_i18n::t!("errors.schema_error", errors = violations.join("\n  ")));
+            }
+            serde_json::from_value(document).with_context(|| rust_i18n::t!("errors.parse_error", error = "JSON"))
         }
         "csv" => load_csv(&content),
-        _ => anyhow::bail!(rust_i18n::t!("errors.invalid_format", format = extension)),
+        _ => anyhow::bail!(rust_i18n::t!("errors.invalid_format", format = format)),
     }
 }
 
@@ -106,6 +120,7 @@ fn load_csv(content: &str) -> Result<BomData> {
             quantity: qty,
             scrap_factor: "0".to_string(),
             sequence: 10,
+            notes: None,
         });
     }
 
@@ -116,6 +131,25 @@ fn load_csv(content: &str) -> Result<BomData> {
 }
 
 impl BomData {
+    /// Convert to core models and load them into an in-memory repository
+    pub fn to_repository(&self) -> Result<InMemoryRepository> {
+        let (components, bom_items) = self.to_core()?;
+        build_repository(components, bom_items)
+    }
+
+    /// Like `to_repository`, but invalid BOM items are kept (for `validate` to report them)
+    pub fn to_unchecked_repository(&self) -> Result<InMemoryRepository> {
+        let (components, bom_items) = self.to_core()?;
+        let repo = InMemoryRepository::new();
+        for component in components {
+            repo.add_component(component);
+        }
+        for bom_item in bom_items {
+            repo.add_bom_item_unchecked(bom_item);
+        }
+        Ok(repo)
+    }
+
     pub fn to_core(&self) -> Result<(Vec<Component>, Vec<BomItem>)> {
         let components: Vec<Component> = self
             .components
@@ -138,6 +172,7 @@ impl BomData {
                         "Buy" => ProcurementType::Buy,
                         _ => ProcurementType::Buy,
                     },
+                    preferred_vendor: None,
                     organization: c.organization.clone(),
                     version: 0,
                     created_at: Utc::now(),
@@ -162,7 +197,7 @@ impl BomData {
                     alternative_group: None,
                     is_phantom: false,
                     reference_designator: None,
-                    notes: None,
+                    notes: item.notes.clone(),
                     operation_sequence: None,
                     alternative_priority: None,
                     position: None,
@@ -174,3 +209,16 @@ impl BomData {
         Ok((components, bom_items))
     }
 }
+
+/// Create an in-memory repository holding the given data
+/// Fails on the first invalid BOM item (see `BomItem::validate`)
+pub fn build_repository(components: Vec<Component>, bom_items: Vec<BomItem>) -> Result<InMemoryRepository> {
+    let repo = InMemoryRepository::new();
+    for component in components {
+        repo.add_component(component);
+    }
+    for bom_item in bom_items {
+        repo.add_bom_item(bom_item)?;
+    }
+    Ok(repo)
+}
@@ -0,0 +1,41 @@
+//! Test data shared by the command tests
+
+use crate::data::{BomItemData, ComponentData};
+
+/// A bought raw material counted in EA, described by its ID, with a standard cost of 1
+pub fn component(id: &str) -> ComponentData {
+    component_with_cost(id, "1")
+}
+
+/// Like `component`, with the given standard cost
+pub fn component_with_cost(id: &str, cost: &str) -> ComponentData {
+    ComponentData {
+        id: id.to_string(),
+        description: id.to_string(),
+        component_type: "RawMaterial".to_string(),
+        standard_cost: Some(cost.to_string()),
+        uom: "EA".to_string(),
+        procurement_type: "Buy".to_string(),
+        organization: "PLANT-01".to_string(),
+    }
+}
+
+/// Like `component_with_cost`, with the given component type
+pub fn component_of_type(id: &str, component_type: &str, cost: &str) -> ComponentData {
+    ComponentData {
+        component_type: component_type.to_string(),
+        ..component_with_cost(id, cost)
+    }
+}
+
+/// A BOM item at sequence 10 without scrap or notes
+pub fn item(parent: &str, child: &str, quantity: &str) -> BomItemData {
+    BomItemData {
+        parent_id: parent.to_string(),
+        child_id: child.to_string(),
+        quantity: quantity.to_string(),
+        scrap_factor: "0".to_string(),
+        sequence: 10,
+        notes: None,
+    }
+}
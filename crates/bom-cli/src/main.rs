@@ -7,7 +7,10 @@ use std::path::PathBuf;
 
 mod commands;
 mod data;
+#[cfg(test)]
+mod fixtures;
 mod output;
+mod schema;
 
 use commands::*;
 
@@ -16,9 +19,9 @@ use commands::*;
 #[command(about = "BOM Calculation Engine CLI")]
 #[command(version)]
 struct Cli {
-    /// Input file (JSON or CSV)
-    #[arg(short, long, value_name = "FILE")]
-    input: PathBuf,
+    /// Input file (JSON or CSV); not needed for `query`
+    #[arg(short, long, value_name = "FILE", global = true)]
+    input: Option<PathBuf>,
 
     /// Output file (optional, prints to stdout if not specified)
     #[arg(short, long, value_name = "FILE")]
@@ -47,6 +50,189 @@ enum Commands {
         #[arg(short, long, default_value = "1")]
         quantity: String,
 
+        /// Output format (json, csv, table, d3)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+
+    /// Calculate cost
+    Cost {
+        /// Component ID
+        component: String,
+
+        /// Split the cost by component type
+        #[arg(long)]
+        category_breakdown: bool,
+
+        /// Output format (json, csv, table)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+
+    /// Where-used analysis
+    WhereUsed {
+        /// Component ID
+        component: String,
+
+        /// Output format (json, csv, table)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+
+    /// Analyze BOM structure
+    Analyze {
+        /// Component ID
+        component: String,
+
+        /// Analysis profile (depth)
+        #[arg(short, long, default_value = "depth")]
+        profile: String,
+
+        /// Output format (json, csv, table)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+
+    /// Find components by description (case-insensitive)
+    Search {
+        /// Text to look for in component descriptions
+        query: String,
+
+        /// Output format (json, csv, table)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+
+    /// Find BOM items by their notes (case-insensitive)
+    NotesSearch {
+        /// Text to look for in BOM item notes (e.g. "ECO-1234")
+        query: String,
+
+        /// Output format (json, csv, table)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+
+    /// Rank components by the number of root products using them
+    Usage {
+        /// Output format (json, csv, table)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+
+    /// Generate a Markdown report (all sections if none is selected)
+    Report {
+        /// Component ID
+        component: String,
+
+        /// Quantity to manufacture
+        #[arg(short, long, default_value = "1")]
+        quantity: String,
+
+        /// Include the cost breakdown
+        #[arg(long)]
+        cost: bool,
+
+        /// Include the assemblies using the component
+        #[arg(long)]
+        where_used: bool,
+
+        /// Include the cumulative lead time and critical path
+        #[arg(long)]
+        lead_times: bool,
+
+        /// Include structure statistics
+        #[arg(long)]
+        analytics: bool,
+    },
+
+    /// Store the input file in a BOM database for repeated queries
+    Import {
+        /// Database file (created if missing, previous data is replaced)
+        #[arg(short, long, value_name = "FILE")]
+        database: PathBuf,
+
+        /// Input file format (json, csv)
+        #[arg(short, long, default_value = "json")]
+        format: String,
+    },
+
+    /// Print the JSON Schema of the JSON input format
+    Schema,
+
+    /// Run a calculation against a BOM database created with `import`
+    Query {
+        /// Database file
+        #[arg(short, long, value_name = "FILE")]
+        database: PathBuf,
+
+        #[command(subcommand)]
+        command: QueryCommands,
+    },
+
+    /// Browse the BOM tree interactively (from --input or a database)
+    #[cfg(feature = "tui")]
+    Explore {
+        /// Database file created with `import` (instead of --input)
+        #[arg(short, long, value_name = "FILE")]
+        database: Option<PathBuf>,
+    },
+
+    /// Check BOM data quality (runs all checks if none is selected)
+    Validate {
+        /// Check for circular references
+        #[arg(long)]
+        check_cycles: bool,
+
+        /// Report every elementary cycle, including cycles sharing components
+        #[arg(long)]
+        all_cycles: bool,
+
+        /// Check for BOM items referencing missing components
+        #[arg(long)]
+        check_completeness: bool,
+
+        /// Check for missing or negative standard costs
+        #[arg(long)]
+        check_costs: bool,
+
+        /// Check for invalid or overlapping effectivity
+        #[arg(long)]
+        check_effectivity: bool,
+
+        /// Check for duplicate BOM items
+        #[arg(long)]
+        check_duplicates: bool,
+
+        /// Check for fractional quantities of discrete (EA) raw materials
+        #[arg(long)]
+        check_quantities: bool,
+
+        /// Report overlapping effectivity as errors (turns on the effectivity check)
+        #[arg(long)]
+        strict: bool,
+
+        /// Exit with a non-zero code on warnings too
+        #[arg(long)]
+        fail_on_warnings: bool,
+
+        /// Output format (json, csv, table)
+        #[arg(short, long, default_value = "table")]
+        format: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum QueryCommands {
+    /// Explode BOM structure
+    Explode {
+        /// Component ID
+        component: String,
+
+        /// Quantity to manufacture
+        #[arg(default_value = "1")]
+        quantity: String,
+
         /// Output format (json, csv, table)
         #[arg(short, long, default_value = "table")]
         format: String,
@@ -84,18 +270,58 @@ fn main() -> Result<()> {
     };
     rust_i18n::set_locale(&locale);
 
+    if let Commands::Schema = &cli.command {
+        return write_output(&cli, serde_json::to_string_pretty(&schema::generate_json_schema())?);
+    }
+
+    // Queries read the database instead of an input file
+    if let Commands::Query { database, command } = &cli.command {
+        let query = match command {
+            QueryCommands::Explode {
+                component,
+                quantity,
+                format,
+            } => database::Query::Explode {
+                component,
+                quantity,
+                format,
+            },
+            QueryCommands::Cost { component, format } => database::Query::Cost { component, format },
+            QueryCommands::WhereUsed { component, format } => database::Query::WhereUsed { component, format },
+        };
+        let result = database::query(database, query)?;
+        return write_output(&cli, result);
+    }
+
+    #[cfg(feature = "tui")]
+    if let Commands::Explore {
+        database: Some(database),
+    } = &cli.command
+    {
+        return explore::execute(database::load_repository(database)?);
+    }
+
+    let input = cli
+        .input
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!(rust_i18n::t!("errors.missing_input")))?;
+
     if cli.verbose {
-        println!("{}", rust_i18n::t!("messages.loading", path = cli.input.display()).cyan());
+        println!("{}", rust_i18n::t!("messages.loading", path = input.display()).cyan());
     }
 
-    // Load BOM data
-    let bom_data = data::load_bom(&cli.input)?;
+    // Load BOM data (`import` may override the format detected from the extension)
+    let bom_data = match &cli.command {
+        Commands::Import { format, .. } => data::load_bom_as(input, format)?,
+        _ => data::load_bom(input)?,
+    };
 
     if cli.verbose {
         println!("{}", rust_i18n::t!("messages.processing").cyan());
     }
 
     // Execute command
+    let mut failed = false;
     let result = match &cli.command {
         Commands::Explode {
             component,
@@ -103,14 +329,92 @@ fn main() -> Result<()> {
             format,
         } => explode::execute(&bom_data, component, quantity, format),
 
-        Commands::Cost { component, format } => cost::execute(&bom_data, component, format),
+        Commands::Cost {
+            component,
+            category_breakdown,
+            format,
+        } => cost::execute(&bom_data, component, *category_breakdown, format),
 
         Commands::WhereUsed { component, format } => {
             where_used::execute(&bom_data, component, format)
         }
+
+        Commands::Analyze {
+            component,
+            profile,
+            format,
+        } => analyze::execute(&bom_data, component, profile, format),
+
+        Commands::Search { query, format } => search::execute(&bom_data, query, format),
+
+        Commands::NotesSearch { query, format } => notes_search::execute(&bom_data, query, format),
+
+        Commands::Usage { format } => usage::execute(&bom_data, format),
+
+        Commands::Report {
+            component,
+            quantity,
+            cost,
+            where_used,
+            lead_times,
+            analytics,
+        } => {
+            let options = bom_calc::ReportOptions {
+                include_cost: *cost,
+                include_where_used: *where_used,
+                include_lead_times: *lead_times,
+                include_analytics: *analytics,
+            };
+            report::execute(&bom_data, component, quantity, options)
+        }
+
+        Commands::Import { database, .. } => database::import(&bom_data, database),
+
+        // Handled above, before any input file is loaded
+        Commands::Schema | Commands::Query { .. } => unreachable!(),
+
+        #[cfg(feature = "tui")]
+        Commands::Explore { .. } => return explore::execute(bom_data.to_repository()?),
+
+        Commands::Validate {
+            check_cycles,
+            all_cycles,
+            check_completeness,
+            check_costs,
+            check_effectivity,
+            check_duplicates,
+            check_quantities,
+            strict,
+            fail_on_warnings,
+            format,
+        } => {
+            let checks = validate::ValidateChecks {
+                cycles: *check_cycles,
+                all_cycles: *all_cycles,
+                completeness: *check_completeness,
+                costs: *check_costs,
+                effectivity: *check_effectivity,
+                duplicates: *check_duplicates,
+                quantities: *check_quantities,
+                strict: *strict,
+            };
+            let report = validate::execute(&bom_data, checks.or_all(), format)?;
+            failed = report.error_count > 0 || (*fail_on_warnings && report.warning_count > 0);
+            Ok(report.output)
+        }
     }?;
 
-    // Output result
+    write_output(&cli, result)?;
+
+    if failed {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Write the result to the output file, or print it
+fn write_output(cli: &Cli, result: String) -> Result<()> {
     if let Some(output_path) = &cli.output {
         std::fs::write(output_path, result)?;
         if cli.verbose {
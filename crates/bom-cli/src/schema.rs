@@ -0,0 +1,62 @@
+use serde_json::Value;
+
+use crate::data::BomData;
+
+/// JSON Schema (draft 2020-12) of the JSON input format (`BomData`)
+pub fn generate_json_schema() -> Value {
+    schemars::schema_for!(BomData).to_value()
+}
+
+/// Check a JSON document against the input schema
+/// Returns one message per violation, prefixed with its location (e.g. `/bom_items/0`)
+pub fn validate_against_schema(document: &Value) -> Vec<String> {
+    let schema = generate_json_schema();
+    // The generated schema is always a valid draft 2020-12 schema
+    let validator = jsonschema::validator_for(&schema).expect("generated schema is valid");
+
+    validator
+        .iter_errors(document)
+        .map(|error| {
+            let location = error.instance_path.to_string();
+            if location.is_empty() {
+                error.to_string()
+            } else {
+                format!("{}: {}", location, error)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_lists_required_fields() {
+        let schema = generate_json_schema();
+        assert_eq!(schema["$schema"], "https://json-schema.org/draft/2020-12/schema");
+        assert_eq!(schema["required"], serde_json::json!(["components", "bom_items"]));
+    }
+
+    #[test]
+    fn test_schema_rejects_missing_required_fields() {
+        let valid = serde_json::json!({
+            "components": [{ "id": "BIKE-001", "description": "Bicycle", "standard_cost": "100" }],
+            "bom_items": [{ "parent_id": "BIKE-001", "child_id": "FRAME-001", "quantity": "1" }]
+        });
+        assert!(validate_against_schema(&valid).is_empty());
+
+        let missing_quantity = serde_json::json!({
+            "components": [],
+            "bom_items": [{ "parent_id": "BIKE-001", "child_id": "FRAME-001" }]
+        });
+        let errors = validate_against_schema(&missing_quantity);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("/bom_items/0: "));
+        assert!(errors[0].contains("\"quantity\" is a required property"));
+
+        let missing_items = serde_json::json!({ "components": [] });
+        let errors = validate_against_schema(&missing_items);
+        assert_eq!(errors, vec!["\"bom_items\" is a required property".to_string()]);
+    }
+}
@@ -11,9 +11,15 @@ pub enum BomError {
     #[error("BOM structure not found: {0}")]
     BomNotFound(String),
 
+    #[error("BOM item not found: {0}")]
+    BomItemNotFound(String),
+
     #[error("Invalid quantity: {0}")]
     InvalidQuantity(String),
 
+    #[error("Invalid BOM item: {0}")]
+    InvalidBomItem(String),
+
     #[error("Invalid effectivity date range: {from} to {to}")]
     InvalidEffectivityRange {
         from: String,
@@ -45,4 +51,14 @@ pub enum BomError {
     CalculationError(String),
 }
 
+/// Error parsing a reference designator list (see `ReferenceDesignatorList::parse`)
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RdError {
+    #[error("Empty entry at position {0} in reference designator list")]
+    EmptyEntry(usize),
+
+    #[error("Invalid reference designator \"{0}\": expected letters followed by digits (e.g. R12)")]
+    InvalidDesignator(String),
+}
+
 pub type Result<T> = std::result::Result<T, BomError>;
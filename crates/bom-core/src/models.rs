@@ -1,15 +1,25 @@
+use crate::{BomError, RdError, Result};
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Unique identifier for components
+/// Clones share the ID string, so a graph holding the same ID in many nodes and BOM items
+/// can store it once (see `bom_graph::ComponentIdInterner`)
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct ComponentId(pub String);
+pub struct ComponentId(Arc<str>);
 
 impl ComponentId {
     pub fn new(id: impl Into<String>) -> Self {
-        Self(id.into())
+        Self(Arc::from(id.into()))
+    }
+
+    /// Whether both IDs share the same string (not just equal ones)
+    pub fn shares_storage(&self, other: &ComponentId) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
     }
 
     pub fn as_str(&self) -> &str {
@@ -42,6 +52,10 @@ pub struct Component {
     /// Procurement type (Make/Buy)
     pub procurement_type: ProcurementType,
 
+    /// Preferred vendor for purchased components (SAP LIFNR)
+    #[serde(default)]
+    pub preferred_vendor: Option<String>,
+
     /// Organization/Plant (SAP WERKS, Oracle Organization_id)
     pub organization: String,
 
@@ -53,7 +67,18 @@ pub struct Component {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+impl Component {
+    /// Similarity of the two descriptions from 0.0 (unrelated) to 1.0 (identical)
+    /// Normalized Levenshtein distance, ignoring case and surrounding whitespace
+    pub fn similarity_score(&self, other: &Component) -> f64 {
+        strsim::normalized_levenshtein(
+            &self.description.trim().to_lowercase(),
+            &other.description.trim().to_lowercase(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ComponentType {
     /// 成品 (Finished Product - FERT in SAP)
     FinishedProduct,
@@ -144,10 +169,272 @@ impl BomItem {
 
     /// Check if this item is effective at given date
     pub fn is_effective_at(&self, date: &DateTime<Utc>) -> bool {
-        let after_start = self.effective_from.as_ref().map_or(true, |from| date >= from);
-        let before_end = self.effective_to.as_ref().map_or(true, |to| date <= to);
+        let after_start = self.effective_from.as_ref().is_none_or(|from| date >= from);
+        let before_end = self.effective_to.as_ref().is_none_or(|to| date <= to);
         after_start && before_end
     }
+
+    /// Whether the effectivity ranges of two items share at least one instant
+    /// (open ends count as unbounded)
+    pub fn effectivity_overlaps(&self, other: &BomItem) -> bool {
+        let starts_before_other_ends = match (self.effective_from, other.effective_to) {
+            (Some(from), Some(to)) => from <= to,
+            _ => true,
+        };
+        let ends_after_other_starts = match (self.effective_to, other.effective_from) {
+            (Some(to), Some(from)) => from <= to,
+            _ => true,
+        };
+        starts_before_other_ends && ends_after_other_starts
+    }
+
+    /// Check the item for values that make no sense in a BOM
+    /// (see `validate_for_child` for the checks that need the child component)
+    /// - `quantity` must be positive
+    /// - `scrap_factor` must be in `0..1`
+    /// - `sequence` must be positive
+    /// - `effective_to` must not be before `effective_from`
+    pub fn validate(&self) -> Result<()> {
+        let item = || format!("{} -> {}", self.parent_id.as_str(), self.child_id.as_str());
+
+        if self.quantity <= Decimal::ZERO {
+            return Err(BomError::InvalidQuantity(format!("{} ({})", item(), self.quantity)));
+        }
+
+        if self.scrap_factor < Decimal::ZERO || self.scrap_factor >= Decimal::ONE {
+            return Err(BomError::InvalidBomItem(format!(
+                "{}: scrap factor {} is outside 0..1",
+                item(),
+                self.scrap_factor
+            )));
+        }
+
+        if self.sequence == 0 {
+            return Err(BomError::InvalidBomItem(format!("{}: sequence must be positive", item())));
+        }
+
+        // Checked last, so `InvalidEffectivityRange` means every other value is valid
+        if let (Some(from), Some(to)) = (&self.effective_from, &self.effective_to) {
+            if to < from {
+                return Err(BomError::InvalidEffectivityRange {
+                    from: from.to_rfc3339(),
+                    to: to.to_rfc3339(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// `validate`, plus the checks that need the child component
+    /// - with reference designators set on a child counted in `EA`, their number must equal
+    ///   `quantity.round()` (a malformed list is left to `BomValidator`)
+    pub fn validate_for_child(&self, child: &Component) -> Result<()> {
+        self.validate()?;
+
+        if child.uom == "EA" {
+            if let Ok(count) = self.reference_designator_count() {
+                if self.reference_designator.is_some() && Decimal::from(count) != self.quantity.round() {
+                    return Err(BomError::InvalidBomItem(format!(
+                        "{} -> {}: {} reference designators for quantity {}",
+                        self.parent_id.as_str(),
+                        self.child_id.as_str(),
+                        count,
+                        self.quantity
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Number of reference designators (0 if none are set)
+    /// Fails if `reference_designator` is not a valid list (see `ReferenceDesignatorList::parse`)
+    pub fn reference_designator_count(&self) -> Result<usize> {
+        let Some(designators) = &self.reference_designator else {
+            return Ok(0);
+        };
+        ReferenceDesignatorList::parse(designators)
+            .map(|list| list.count())
+            .map_err(|e| {
+                BomError::InvalidBomItem(format!(
+                    "{} -> {}: {}",
+                    self.parent_id.as_str(),
+                    self.child_id.as_str(),
+                    e
+                ))
+            })
+    }
+}
+
+/// Parsed reference designators of a BOM item (e.g. `"R1, R2,C5"`), the board
+/// positions in an electronics BOM
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReferenceDesignatorList {
+    designators: Vec<String>,
+}
+
+impl ReferenceDesignatorList {
+    /// Split at commas and trim each entry; every entry must be uppercase letters
+    /// followed by digits (`[A-Z]+[0-9]+`). A blank input is an empty list
+    pub fn parse(input: &str) -> std::result::Result<Self, RdError> {
+        if input.trim().is_empty() {
+            return Ok(Self::default());
+        }
+
+        let mut designators = Vec::new();
+        for (position, entry) in input.split(',').map(str::trim).enumerate() {
+            if entry.is_empty() {
+                return Err(RdError::EmptyEntry(position + 1));
+            }
+            let digits_start = entry.find(|c: char| !c.is_ascii_uppercase()).unwrap_or(entry.len());
+            let (letters, digits) = entry.split_at(digits_start);
+            if letters.is_empty() || digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(RdError::InvalidDesignator(entry.to_string()));
+            }
+            designators.push(entry.to_string());
+        }
+
+        Ok(Self { designators })
+    }
+
+    pub fn count(&self) -> usize {
+        self.designators.len()
+    }
+
+    pub fn designators(&self) -> &[String] {
+        &self.designators
+    }
+}
+
+/// Audit log entry recording a change to BOM data (變更紀錄)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// When the change was made
+    pub timestamp: DateTime<Utc>,
+
+    /// What was changed
+    pub operation: AuditOperation,
+
+    /// Component the change applies to (the parent for BOM item changes)
+    pub component_id: ComponentId,
+
+    /// BOM item after the change, or the removed item (for BOM item operations)
+    pub bom_item: Option<BomItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AuditOperation {
+    /// Standard cost changed
+    UpdateComponentCost {
+        old_cost: Option<Decimal>,
+        new_cost: Decimal,
+    },
+
+    /// BOM item added
+    InsertBomItem,
+
+    /// BOM item changed (e.g. renumbered or merged)
+    UpdateBomItem,
+
+    /// BOM item removed
+    RemoveBomItem,
+
+    /// Component created (e.g. a sub-assembly duplicated by a deep copy)
+    InsertComponent,
+
+    /// Component deleted together with the BOM items referencing it
+    CascadeDelete {
+        deleted_bom_items: Vec<Uuid>,
+        deleted_components: Vec<ComponentId>,
+    },
+}
+
+/// Outcome of a bulk cost update (e.g. a supplier price list import)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BatchUpdateResult {
+    /// Number of components whose cost was updated
+    pub updated: usize,
+    /// Components that do not exist in the repository
+    pub not_found: Vec<ComponentId>,
+    /// Components changed concurrently by another writer (not updated)
+    pub version_conflicts: Vec<ComponentId>,
+}
+
+/// Cross-reference to an equivalent component (交叉參照)
+/// Used to maintain approved manufacturer lists (AML) and customer part numbers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrossReference {
+    /// The equivalent component
+    pub equivalent_id: ComponentId,
+
+    /// How the equivalent relates to the referencing component
+    pub relationship_type: CrossReferenceType,
+
+    /// Where the reference comes from (manufacturer, customer, standard, ...)
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CrossReferenceType {
+    /// 可互換 (Fully interchangeable in both directions)
+    Interchangeable,
+
+    /// 替代 (Can substitute, possibly with restrictions)
+    Substitute,
+
+    /// 客戶料號 (Customer part number for the same item)
+    CustomerEquivalent,
+
+    /// 製造商料號 (Manufacturer part number for the same item)
+    ManufacturerEquivalent,
+
+    /// 新料取代 (The referencing component is phased out and replaced by the equivalent)
+    /// Successive supersessions form a chain (A -> B -> C)
+    SupersededBy,
+}
+
+/// Routing - manufacturing operations of a component (製程途程)
+/// Compatible with SAP PLKO/PLPO and Oracle BOM_OPERATIONAL_ROUTINGS
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Routing {
+    /// Component produced by the routing
+    pub component_id: ComponentId,
+
+    /// Operations in processing order
+    pub operations: Vec<RoutingOperation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoutingOperation {
+    /// Operation sequence (工序號碼), matched by `BomItem::operation_sequence`
+    pub sequence: String,
+
+    /// Work center (工作中心) performing the operation
+    pub work_center: String,
+
+    /// Setup time per production run, in hours
+    pub setup_time_hours: Decimal,
+
+    /// Run time per produced unit, in hours
+    pub run_time_per_unit_hours: Decimal,
+}
+
+/// Alternative way of producing a component (替代途程), e.g. on another assembly line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlternativeRoute {
+    /// Route identifier, unique per component
+    pub route_id: String,
+
+    /// Operations (work centers) the route passes through, in processing order
+    pub operations: Vec<String>,
+
+    /// Lead time of the route in days
+    pub lead_time_days: u32,
+
+    /// Cost relative to the standard route (1.0 = same cost)
+    pub cost_factor: Decimal,
 }
 
 /// BOM Header - represents a complete BOM for a component
@@ -288,6 +575,103 @@ pub struct ExplosionItem {
     pub is_phantom: bool,
 }
 
+impl ExplosionResult {
+    /// Differences between this explosion and `other`, e.g. a baseline and a new implementation
+    /// `quantity_delta` holds `other`'s total quantity minus this one's for components in
+    /// both with different quantities; the component lists are sorted by ID
+    pub fn compare(&self, other: &ExplosionResult) -> ExplosionDiff {
+        let quantities = |result: &ExplosionResult| -> HashMap<ComponentId, Decimal> {
+            result
+                .items
+                .iter()
+                .map(|item| (item.component_id.clone(), item.total_quantity))
+                .collect()
+        };
+        let ours = quantities(self);
+        let theirs = quantities(other);
+
+        let mut quantity_delta = HashMap::new();
+        let mut missing_in_other = Vec::new();
+        for (component_id, quantity) in &ours {
+            match theirs.get(component_id) {
+                Some(other_quantity) if other_quantity != quantity => {
+                    quantity_delta.insert(component_id.clone(), other_quantity - quantity);
+                }
+                Some(_) => {}
+                None => missing_in_other.push(component_id.clone()),
+            }
+        }
+        let mut extra_in_other: Vec<ComponentId> =
+            theirs.keys().filter(|id| !ours.contains_key(*id)).cloned().collect();
+
+        missing_in_other.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        extra_in_other.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        ExplosionDiff {
+            component_id: self.root_component.clone(),
+            quantity_delta,
+            missing_in_other,
+            extra_in_other,
+        }
+    }
+
+    /// The explosion as CSV (`BOM_CSV_HEADER` plus one `ExplosionItem::to_bom_csv_row` line per item)
+    pub fn to_bom_csv(&self) -> String {
+        let mut csv = String::new();
+        csv.push_str(BOM_CSV_HEADER);
+        csv.push('\n');
+        for item in &self.items {
+            csv.push_str(&item.to_bom_csv_row());
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Header line of `ExplosionResult::to_bom_csv`
+pub const BOM_CSV_HEADER: &str = "level,component_id,total_quantity";
+
+impl ExplosionItem {
+    /// One CSV line (without line break) in `BOM_CSV_HEADER` column order
+    pub fn to_bom_csv_row(&self) -> String {
+        format!(
+            "{},{},{}",
+            self.level,
+            csv_field(self.component_id.as_str()),
+            self.total_quantity
+        )
+    }
+}
+
+/// Quote a CSV field containing a separator, quote or line break (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Result of `ExplosionResult::compare`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExplosionDiff {
+    /// Root component of this explosion
+    pub component_id: ComponentId,
+    /// Quantity change per component (other minus self)
+    pub quantity_delta: HashMap<ComponentId, Decimal>,
+    /// Components of this explosion that `other` lacks
+    pub missing_in_other: Vec<ComponentId>,
+    /// Components of `other` that this explosion lacks
+    pub extra_in_other: Vec<ComponentId>,
+}
+
+impl ExplosionDiff {
+    /// True if both explosions require the same components in the same quantities
+    pub fn is_empty(&self) -> bool {
+        self.quantity_delta.is_empty() && self.missing_in_other.is_empty() && self.extra_in_other.is_empty()
+    }
+}
+
 /// Where-used query result (反查結果)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WhereUsedResult {
@@ -303,15 +687,173 @@ pub struct WhereUsedResult {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WhereUsedItem {
-    /// Parent component ID
+    /// Assembly using the component (a direct parent or a higher ancestor)
     pub parent_id: ComponentId,
 
-    /// Quantity per parent
+    /// Quantity of the queried component per unit of this assembly
     pub quantity: Decimal,
 
-    /// BOM level (how many levels up from the queried component)
+    /// BOM level (how many levels up from the queried component; 1 = direct parent)
     pub level: usize,
 
-    /// All paths from this parent to the queried component
+    /// All paths from the root assemblies down to this assembly
     pub paths: Vec<Vec<ComponentId>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn create_test_bom_item() -> BomItem {
+        BomItem {
+            id: Uuid::new_v4(),
+            parent_id: ComponentId::new("A"),
+            child_id: ComponentId::new("B"),
+            quantity: Decimal::from(2),
+            scrap_factor: Decimal::ZERO,
+            sequence: 10,
+            operation_sequence: None,
+            is_phantom: false,
+            effective_from: None,
+            effective_to: None,
+            alternative_group: None,
+            alternative_priority: None,
+            reference_designator: None,
+            position: None,
+            notes: None,
+            version: 0,
+        }
+    }
+
+    #[test]
+    fn test_component_similarity_score() {
+        let component = |description: &str| Component {
+            id: ComponentId::new("X"),
+            description: description.to_string(),
+            component_type: ComponentType::RawMaterial,
+            uom: "EA".to_string(),
+            standard_cost: None,
+            lead_time_days: None,
+            procurement_type: ProcurementType::Buy,
+            preferred_vendor: None,
+            organization: "ORG01".to_string(),
+            version: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        let tube = component("Aluminum Tube 6061");
+        assert_eq!(tube.similarity_score(&component("aluminum tube 6061")), 1.0);
+        assert!(tube.similarity_score(&component("Aluminum Tube, 6061")) >= 0.9);
+        assert!(tube.similarity_score(&component("Steel Bolt M6")) < 0.5);
+    }
+
+    #[test]
+    fn test_bom_item_validate() {
+        assert!(create_test_bom_item().validate().is_ok());
+
+        let now = Utc::now();
+        let mut item = create_test_bom_item();
+        item.effective_from = Some(now);
+        item.effective_to = Some(now);
+        assert!(item.validate().is_ok());
+        item.effective_to = Some(now - Duration::days(1));
+        assert!(matches!(item.validate(), Err(BomError::InvalidEffectivityRange { .. })));
+
+        for quantity in [Decimal::ZERO, Decimal::from(-1)] {
+            let mut item = create_test_bom_item();
+            item.quantity = quantity;
+            assert!(matches!(item.validate(), Err(BomError::InvalidQuantity(_))));
+        }
+
+        for scrap_factor in [Decimal::new(-1, 2), Decimal::ONE, Decimal::from(2)] {
+            let mut item = create_test_bom_item();
+            item.scrap_factor = scrap_factor;
+            assert!(matches!(item.validate(), Err(BomError::InvalidBomItem(_))));
+        }
+
+        let mut item = create_test_bom_item();
+        item.sequence = 0;
+        assert!(matches!(item.validate(), Err(BomError::InvalidBomItem(_))));
+    }
+
+    #[test]
+    fn test_reference_designator_list() {
+        let list = ReferenceDesignatorList::parse("R1, R2,C5 ").unwrap();
+        assert_eq!(list.count(), 3);
+        assert_eq!(list.designators(), ["R1", "R2", "C5"]);
+        assert_eq!(ReferenceDesignatorList::parse("  ").unwrap().count(), 0);
+
+        assert_eq!(ReferenceDesignatorList::parse("R1,,R2"), Err(RdError::EmptyEntry(2)));
+        for invalid in ["r1", "R", "12", "R1A", "R-1", "R1-R5"] {
+            assert_eq!(
+                ReferenceDesignatorList::parse(invalid),
+                Err(RdError::InvalidDesignator(invalid.to_string()))
+            );
+        }
+
+        let mut item = create_test_bom_item();
+        assert_eq!(item.reference_designator_count().unwrap(), 0);
+        item.reference_designator = Some("U1,U2".to_string());
+        assert_eq!(item.reference_designator_count().unwrap(), 2);
+        item.reference_designator = Some("U1,U 2".to_string());
+        assert!(matches!(item.reference_designator_count(), Err(BomError::InvalidBomItem(_))));
+
+        // The format is checked by `BomValidator`, not when storing items
+        assert!(item.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_for_child_counts_designators() {
+        let mut child = Component {
+            id: ComponentId::new("B"),
+            description: "Resistor 10k".to_string(),
+            component_type: ComponentType::RawMaterial,
+            uom: "EA".to_string(),
+            standard_cost: None,
+            lead_time_days: None,
+            procurement_type: ProcurementType::Buy,
+            preferred_vendor: None,
+            organization: "ORG01".to_string(),
+            version: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        // Quantity 2
+        let mut item = create_test_bom_item();
+        assert!(item.validate_for_child(&child).is_ok());
+        item.reference_designator = Some("R1,R2".to_string());
+        assert!(item.validate_for_child(&child).is_ok());
+        item.reference_designator = Some("R1,R2,R3".to_string());
+        assert!(matches!(item.validate_for_child(&child), Err(BomError::InvalidBomItem(_))));
+
+        // Only counted units are compared
+        child.uom = "M".to_string();
+        assert!(item.validate_for_child(&child).is_ok());
+    }
+
+    #[test]
+    fn test_explosion_to_bom_csv() {
+        let item = |id: &str, quantity: Decimal, level: usize| ExplosionItem {
+            component_id: ComponentId::new(id),
+            total_quantity: quantity,
+            level,
+            paths: Vec::new(),
+            is_phantom: false,
+        };
+        let result = ExplosionResult {
+            root_component: ComponentId::new("A"),
+            items: vec![item("A", Decimal::ONE, 0), item("BOLT \"M6,10\"", Decimal::new(25, 1), 1)],
+            unique_component_count: 2,
+            max_depth: 1,
+            calculated_at: Utc::now(),
+        };
+
+        assert_eq!(
+            result.to_bom_csv(),
+            "level,component_id,total_quantity\n0,A,1\n1,\"BOLT \"\"M6,10\"\"\",2.5\n"
+        );
+    }
+}
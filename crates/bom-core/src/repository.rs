@@ -1,5 +1,10 @@
-use crate::{BomHeader, BomItem, Component, ComponentId, Result};
+use crate::{
+    AlternativeRoute, AuditEntry, BatchUpdateResult, BomError, BomHeader, BomItem, Component, ComponentId,
+    CostBreakdown, CrossReference, Result, Routing,
+};
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use uuid::Uuid;
 
 /// Repository trait for BOM data access
 /// PLM/ERP systems implement this trait to provide data
@@ -30,6 +35,125 @@ pub trait BomRepository: Send + Sync {
 
     /// Find all parents of a component (for where-used)
     fn find_parents(&self, component_id: &ComponentId) -> Result<Vec<BomItem>>;
+
+    /// Get cross-references (equivalent components) for a component
+    /// Repositories without cross-reference data return an empty list
+    fn get_cross_references(&self, _component_id: &ComponentId) -> Result<Vec<CrossReference>> {
+        Ok(Vec::new())
+    }
+
+    /// Get the routing (manufacturing operations) of a component
+    /// Repositories without routing data return `None`
+    fn get_routing(&self, _component_id: &ComponentId) -> Result<Option<Routing>> {
+        Ok(None)
+    }
+
+    /// Get the alternative routes (ways of producing) of a component
+    /// Repositories without route data return an empty list
+    fn get_alternative_routes(&self, _component_id: &ComponentId) -> Result<Vec<AlternativeRoute>> {
+        Ok(Vec::new())
+    }
+
+    /// Find components whose description contains `query`
+    /// Repositories without search support return an empty list
+    fn search_components(&self, _query: &str) -> Result<Vec<Component>> {
+        Ok(Vec::new())
+    }
+
+    /// Find BOM items whose notes contain `query` (e.g. "ECO-1234")
+    /// Repositories without search support return an empty list
+    fn search_bom_item_notes(&self, _query: &str) -> Result<Vec<BomItem>> {
+        Ok(Vec::new())
+    }
+
+    // Write operations
+    // Read-only repositories can rely on the defaults, which report the operation as unsupported
+
+    /// Insert a new component (or replace one with the same ID)
+    fn insert_component(&self, component: Component) -> Result<()> {
+        Err(unsupported("insert_component", format!("component {}", component.id.as_str())))
+    }
+
+    /// Remove a component by ID, returning the removed component
+    /// BOM items referencing it are not touched
+    fn remove_component(&self, id: &ComponentId) -> Result<Component> {
+        Err(unsupported("remove_component", format!("component {}", id.as_str())))
+    }
+
+    /// Set the standard cost of many components at once (e.g. from a supplier price list)
+    /// Each updated component gets a new version. The default updates one component at a
+    /// time through `get_component` / `insert_component`
+    fn batch_update_costs(&self, updates: &[(ComponentId, Decimal)]) -> Result<BatchUpdateResult> {
+        let mut result = BatchUpdateResult::default();
+
+        for (id, cost) in updates {
+            let mut component = match self.get_component(id) {
+                Ok(component) => component,
+                Err(BomError::ComponentNotFound(_)) => {
+                    result.not_found.push(id.clone());
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            component.standard_cost = Some(*cost);
+            component.version += 1;
+            component.updated_at = Utc::now();
+            self.insert_component(component)?;
+            result.updated += 1;
+        }
+
+        Ok(result)
+    }
+
+    /// Insert a new BOM item
+    /// Implementations should reject invalid items (see `BomItem::validate_for_child`)
+    fn insert_bom_item(&self, item: BomItem) -> Result<()> {
+        Err(unsupported("insert_bom_item", format!("item {}", item.id)))
+    }
+
+    /// Replace an existing BOM item (matched by `id`)
+    fn update_bom_item(&self, item: &BomItem) -> Result<()> {
+        Err(unsupported("update_bom_item", format!("item {}", item.id)))
+    }
+
+    /// Remove a BOM item by ID, returning the removed item
+    fn remove_bom_item(&self, id: &Uuid) -> Result<BomItem> {
+        Err(unsupported("remove_bom_item", format!("item {}", id)))
+    }
+}
+
+/// Cache holding calculated costs that must be dropped when source costs change
+pub trait CostInvalidation: Send + Sync {
+    /// Drop the cached costs of the given components
+    fn invalidate_cost_batch(&self, component_ids: &[ComponentId]);
+
+    /// Store costs calculated elsewhere (e.g. collected during an explosion)
+    /// Caches that only track invalidation can keep the default, which drops them
+    fn store_cost_batch(&self, _costs: &[(ComponentId, CostBreakdown)]) {}
+}
+
+impl<T: CostInvalidation + ?Sized> CostInvalidation for std::sync::Arc<T> {
+    fn invalidate_cost_batch(&self, component_ids: &[ComponentId]) {
+        (**self).invalidate_cost_batch(component_ids)
+    }
+
+    fn store_cost_batch(&self, costs: &[(ComponentId, CostBreakdown)]) {
+        (**self).store_cost_batch(costs)
+    }
+}
+
+/// Destination for audit entries of BOM changes
+/// Implemented by persistent stores so the change history survives restarts
+pub trait AuditLog: Send + Sync {
+    /// Append an entry
+    fn log_audit(&self, entry: &AuditEntry) -> Result<()>;
+}
+
+fn unsupported(operation: &str, subject: String) -> BomError {
+    BomError::RepositoryError(format!(
+        "{} is not supported by this repository ({})",
+        operation, subject
+    ))
 }
 
 /// In-memory repository for testing and simple use cases
@@ -43,6 +167,9 @@ pub mod memory {
         components: Arc<RwLock<HashMap<ComponentId, Component>>>,
         bom_headers: Arc<RwLock<HashMap<ComponentId, Vec<BomHeader>>>>,
         bom_items: Arc<RwLock<Vec<BomItem>>>,
+        cross_references: Arc<RwLock<HashMap<ComponentId, Vec<CrossReference>>>>,
+        routings: Arc<RwLock<HashMap<ComponentId, Routing>>>,
+        alternative_routes: Arc<RwLock<HashMap<ComponentId, Vec<AlternativeRoute>>>>,
     }
 
     impl InMemoryRepository {
@@ -51,6 +178,9 @@ pub mod memory {
                 components: Arc::new(RwLock::new(HashMap::new())),
                 bom_headers: Arc::new(RwLock::new(HashMap::new())),
                 bom_items: Arc::new(RwLock::new(Vec::new())),
+                cross_references: Arc::new(RwLock::new(HashMap::new())),
+                routings: Arc::new(RwLock::new(HashMap::new())),
+                alternative_routes: Arc::new(RwLock::new(HashMap::new())),
             }
         }
 
@@ -63,14 +193,48 @@ pub mod memory {
             let mut headers = self.bom_headers.write().unwrap();
             headers
                 .entry(header.component_id.clone())
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(header);
         }
 
-        pub fn add_bom_item(&self, item: BomItem) {
+        /// Add a BOM item, rejecting invalid ones (see `BomItem::validate_for_child`;
+        /// only `BomItem::validate` applies while the child component is unknown)
+        pub fn add_bom_item(&self, item: BomItem) -> Result<()> {
+            self.validate_item(&item)?;
+            self.add_bom_item_unchecked(item);
+            Ok(())
+        }
+
+        fn validate_item(&self, item: &BomItem) -> Result<()> {
+            match self.components.read().unwrap().get(&item.child_id) {
+                Some(child) => item.validate_for_child(child),
+                None => item.validate(),
+            }
+        }
+
+        /// Add a BOM item without validating it
+        /// For loading data that may be invalid so that `BomValidator` can report it
+        pub fn add_bom_item_unchecked(&self, item: BomItem) {
             let mut items = self.bom_items.write().unwrap();
             items.push(item);
         }
+
+        pub fn add_cross_reference(&self, component_id: ComponentId, reference: CrossReference) {
+            let mut references = self.cross_references.write().unwrap();
+            references.entry(component_id).or_default().push(reference);
+        }
+
+        /// Add a routing (replacing any previous routing of the same component)
+        pub fn add_routing(&self, routing: Routing) {
+            let mut routings = self.routings.write().unwrap();
+            routings.insert(routing.component_id.clone(), routing);
+        }
+
+        /// Add an alternative route of a component
+        pub fn add_alternative_route(&self, component_id: ComponentId, route: AlternativeRoute) {
+            let mut routes = self.alternative_routes.write().unwrap();
+            routes.entry(component_id).or_default().push(route);
+        }
     }
 
     impl Default for InMemoryRepository {
@@ -85,7 +249,7 @@ pub mod memory {
             components
                 .get(id)
                 .cloned()
-                .ok_or_else(|| crate::BomError::ComponentNotFound(id.0.clone()))
+                .ok_or_else(|| crate::BomError::ComponentNotFound(id.as_str().to_string()))
         }
 
         fn get_components(&self, ids: &[ComponentId]) -> Result<Vec<Component>> {
@@ -95,7 +259,7 @@ pub mod memory {
                     components
                         .get(id)
                         .cloned()
-                        .ok_or_else(|| crate::BomError::ComponentNotFound(id.0.clone()))
+                        .ok_or_else(|| crate::BomError::ComponentNotFound(id.as_str().to_string()))
                 })
                 .collect()
         }
@@ -109,7 +273,7 @@ pub mod memory {
             let headers = self.bom_headers.read().unwrap();
             let component_headers = headers
                 .get(component_id)
-                .ok_or_else(|| crate::BomError::BomNotFound(component_id.0.clone()))?;
+                .ok_or_else(|| crate::BomError::BomNotFound(component_id.as_str().to_string()))?;
 
             let effective_date = effective_date.unwrap_or_else(Utc::now);
 
@@ -123,13 +287,13 @@ pub mod memory {
                     };
 
                     // Check effectivity
-                    let after_start = h.effective_from.as_ref().map_or(true, |from| &effective_date >= from);
-                    let before_end = h.effective_to.as_ref().map_or(true, |to| &effective_date <= to);
+                    let after_start = h.effective_from.as_ref().is_none_or(|from| &effective_date >= from);
+                    let before_end = h.effective_to.as_ref().is_none_or(|to| &effective_date <= to);
 
                     alt_match && after_start && before_end
                 })
                 .cloned()
-                .ok_or_else(|| crate::BomError::BomNotFound(component_id.0.clone()))
+                .ok_or_else(|| crate::BomError::BomNotFound(component_id.as_str().to_string()))
         }
 
         fn get_bom_items(
@@ -162,5 +326,454 @@ pub mod memory {
                 .cloned()
                 .collect())
         }
+
+        fn get_cross_references(&self, component_id: &ComponentId) -> Result<Vec<CrossReference>> {
+            let references = self.cross_references.read().unwrap();
+            Ok(references.get(component_id).cloned().unwrap_or_default())
+        }
+
+        fn get_routing(&self, component_id: &ComponentId) -> Result<Option<Routing>> {
+            let routings = self.routings.read().unwrap();
+            Ok(routings.get(component_id).cloned())
+        }
+
+        fn get_alternative_routes(&self, component_id: &ComponentId) -> Result<Vec<AlternativeRoute>> {
+            let routes = self.alternative_routes.read().unwrap();
+            Ok(routes.get(component_id).cloned().unwrap_or_default())
+        }
+
+        /// Case-insensitive substring match on the description, sorted by component ID
+        fn search_components(&self, query: &str) -> Result<Vec<Component>> {
+            let query = query.to_lowercase();
+            let components = self.components.read().unwrap();
+            let mut matches: Vec<Component> = components
+                .values()
+                .filter(|component| component.description.to_lowercase().contains(&query))
+                .cloned()
+                .collect();
+            matches.sort_by(|a, b| a.id.as_str().cmp(b.id.as_str()));
+            Ok(matches)
+        }
+
+        /// Case-insensitive substring match on the notes, sorted by parent, child and sequence
+        fn search_bom_item_notes(&self, query: &str) -> Result<Vec<BomItem>> {
+            let query = query.to_lowercase();
+            let mut matches: Vec<BomItem> = self
+                .get_all_bom_items()?
+                .into_iter()
+                .filter(|item| item.notes.as_deref().is_some_and(|notes| notes.to_lowercase().contains(&query)))
+                .collect();
+            matches.sort_by(|a, b| {
+                (a.parent_id.as_str(), a.child_id.as_str(), a.sequence)
+                    .cmp(&(b.parent_id.as_str(), b.child_id.as_str(), b.sequence))
+            });
+            Ok(matches)
+        }
+
+        fn insert_component(&self, component: Component) -> Result<()> {
+            self.add_component(component);
+            Ok(())
+        }
+
+        fn remove_component(&self, id: &ComponentId) -> Result<Component> {
+            let mut components = self.components.write().unwrap();
+            components
+                .remove(id)
+                .ok_or_else(|| crate::BomError::ComponentNotFound(id.as_str().to_string()))
+        }
+
+        /// Updates all components under a single write lock, so no version conflicts occur
+        fn batch_update_costs(&self, updates: &[(ComponentId, Decimal)]) -> Result<BatchUpdateResult> {
+            let mut components = self.components.write().unwrap();
+            let mut result = BatchUpdateResult::default();
+            let now = Utc::now();
+
+            for (id, cost) in updates {
+                match components.get_mut(id) {
+                    Some(component) => {
+                        component.standard_cost = Some(*cost);
+                        component.version += 1;
+                        component.updated_at = now;
+                        result.updated += 1;
+                    }
+                    None => result.not_found.push(id.clone()),
+                }
+            }
+
+            Ok(result)
+        }
+
+        fn insert_bom_item(&self, item: BomItem) -> Result<()> {
+            self.add_bom_item(item)
+        }
+
+        fn update_bom_item(&self, item: &BomItem) -> Result<()> {
+            self.validate_item(item)?;
+            let mut items = self.bom_items.write().unwrap();
+            let existing = items
+                .iter_mut()
+                .find(|existing| existing.id == item.id)
+                .ok_or_else(|| crate::BomError::BomItemNotFound(item.id.to_string()))?;
+            *existing = item.clone();
+            Ok(())
+        }
+
+        fn remove_bom_item(&self, id: &Uuid) -> Result<BomItem> {
+            let mut items = self.bom_items.write().unwrap();
+            let position = items
+                .iter()
+                .position(|item| item.id == *id)
+                .ok_or_else(|| crate::BomError::BomItemNotFound(id.to_string()))?;
+            Ok(items.remove(position))
+        }
+    }
+}
+
+/// Connection pooling for database-backed repositories
+/// Each pooled instance is expected to hold its own connection, so cloning the
+/// repository opens a new one. Instances are created lazily up to the configured maximum
+pub mod pool {
+    use super::*;
+    use std::sync::{Condvar, Mutex};
+
+    /// Default upper bound on pooled repository instances
+    pub const DEFAULT_MAX_CONNECTIONS: usize = 10;
+
+    /// Pool of repository instances shared between threads
+    pub struct RepositoryPool<R: BomRepository + Clone> {
+        template: R,
+        max_connections: usize,
+        state: Mutex<PoolState<R>>,
+        returned: Condvar,
+    }
+
+    struct PoolState<R> {
+        idle: Vec<R>,
+        in_use: usize,
+    }
+
+    /// Pool usage snapshot
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct PoolStats {
+        /// Idle instances ready to be checked out
+        pub available: usize,
+        /// Instances currently running an operation
+        pub in_use: usize,
+    }
+
+    impl<R: BomRepository + Clone> RepositoryPool<R> {
+        /// Create a pool that clones `repository` for each new connection
+        pub fn new(repository: R) -> Self {
+            Self {
+                template: repository,
+                max_connections: DEFAULT_MAX_CONNECTIONS,
+                state: Mutex::new(PoolState {
+                    idle: Vec::new(),
+                    in_use: 0,
+                }),
+                returned: Condvar::new(),
+            }
+        }
+
+        /// Limit the number of instances (at least one); callers beyond it wait for a free one
+        pub fn with_max_connections(mut self, max_connections: usize) -> Self {
+            self.max_connections = max_connections.max(1);
+            self
+        }
+
+        /// Get pool usage
+        pub fn stats(&self) -> PoolStats {
+            let state = self.state.lock().unwrap();
+            PoolStats {
+                available: state.idle.len(),
+                in_use: state.in_use,
+            }
+        }
+
+        /// Run an operation on a checked-out instance, returning it to the pool afterwards
+        pub fn with_connection<T>(&self, operation: impl FnOnce(&R) -> Result<T>) -> Result<T> {
+            let connection = self.checkout();
+            operation(connection.repository())
+        }
+
+        fn checkout(&self) -> PooledConnection<'_, R> {
+            let mut state = self.state.lock().unwrap();
+            loop {
+                if let Some(repository) = state.idle.pop() {
+                    state.in_use += 1;
+                    return PooledConnection {
+                        pool: self,
+                        repository: Some(repository),
+                    };
+                }
+                if state.in_use < self.max_connections {
+                    state.in_use += 1;
+                    return PooledConnection {
+                        pool: self,
+                        repository: Some(self.template.clone()),
+                    };
+                }
+                state = self.returned.wait(state).unwrap();
+            }
+        }
+    }
+
+    /// Checked-out instance, returned to the pool on drop (also when the operation panics)
+    struct PooledConnection<'a, R: BomRepository + Clone> {
+        pool: &'a RepositoryPool<R>,
+        /// Only `None` while being returned in `drop`
+        repository: Option<R>,
+    }
+
+    impl<R: BomRepository + Clone> PooledConnection<'_, R> {
+        fn repository(&self) -> &R {
+            self.repository.as_ref().expect("connection used after being returned")
+        }
+    }
+
+    impl<R: BomRepository + Clone> Drop for PooledConnection<'_, R> {
+        fn drop(&mut self) {
+            let mut state = self.pool.state.lock().unwrap_or_else(|e| e.into_inner());
+            // The same instance goes back, so clones are only made up to `max_connections`
+            if let Some(repository) = self.repository.take() {
+                state.idle.push(repository);
+            }
+            state.in_use -= 1;
+            self.pool.returned.notify_one();
+        }
+    }
+
+    impl<R: BomRepository + Clone> BomRepository for RepositoryPool<R> {
+        fn get_component(&self, id: &ComponentId) -> Result<Component> {
+            self.with_connection(|repo| repo.get_component(id))
+        }
+
+        fn get_components(&self, ids: &[ComponentId]) -> Result<Vec<Component>> {
+            self.with_connection(|repo| repo.get_components(ids))
+        }
+
+        fn get_bom_header(
+            &self,
+            component_id: &ComponentId,
+            alternative: Option<&str>,
+            effective_date: Option<DateTime<Utc>>,
+        ) -> Result<BomHeader> {
+            self.with_connection(|repo| repo.get_bom_header(component_id, alternative, effective_date))
+        }
+
+        fn get_bom_items(
+            &self,
+            component_id: &ComponentId,
+            effective_date: Option<DateTime<Utc>>,
+        ) -> Result<Vec<BomItem>> {
+            self.with_connection(|repo| repo.get_bom_items(component_id, effective_date))
+        }
+
+        fn get_all_bom_items(&self) -> Result<Vec<BomItem>> {
+            self.with_connection(|repo| repo.get_all_bom_items())
+        }
+
+        fn find_parents(&self, component_id: &ComponentId) -> Result<Vec<BomItem>> {
+            self.with_connection(|repo| repo.find_parents(component_id))
+        }
+
+        fn get_cross_references(&self, component_id: &ComponentId) -> Result<Vec<CrossReference>> {
+            self.with_connection(|repo| repo.get_cross_references(component_id))
+        }
+
+        fn get_routing(&self, component_id: &ComponentId) -> Result<Option<Routing>> {
+            self.with_connection(|repo| repo.get_routing(component_id))
+        }
+
+        fn get_alternative_routes(&self, component_id: &ComponentId) -> Result<Vec<AlternativeRoute>> {
+            self.with_connection(|repo| repo.get_alternative_routes(component_id))
+        }
+
+        fn search_components(&self, query: &str) -> Result<Vec<Component>> {
+            self.with_connection(|repo| repo.search_components(query))
+        }
+
+        fn search_bom_item_notes(&self, query: &str) -> Result<Vec<BomItem>> {
+            self.with_connection(|repo| repo.search_bom_item_notes(query))
+        }
+
+        fn insert_component(&self, component: Component) -> Result<()> {
+            self.with_connection(|repo| repo.insert_component(component))
+        }
+
+        fn remove_component(&self, id: &ComponentId) -> Result<Component> {
+            self.with_connection(|repo| repo.remove_component(id))
+        }
+
+        fn batch_update_costs(&self, updates: &[(ComponentId, Decimal)]) -> Result<BatchUpdateResult> {
+            self.with_connection(|repo| repo.batch_update_costs(updates))
+        }
+
+        fn insert_bom_item(&self, item: BomItem) -> Result<()> {
+            self.with_connection(|repo| repo.insert_bom_item(item))
+        }
+
+        fn update_bom_item(&self, item: &BomItem) -> Result<()> {
+            self.with_connection(|repo| repo.update_bom_item(item))
+        }
+
+        fn remove_bom_item(&self, id: &Uuid) -> Result<BomItem> {
+            self.with_connection(|repo| repo.remove_bom_item(id))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::memory::InMemoryRepository;
+        use crate::{ComponentType, ProcurementType};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        /// Tracks how many clones exist and run an operation at the same time
+        struct TrackingRepository {
+            inner: InMemoryRepository,
+            active: Arc<AtomicUsize>,
+            peak: Arc<AtomicUsize>,
+            clones: Arc<AtomicUsize>,
+        }
+
+        impl Clone for TrackingRepository {
+            fn clone(&self) -> Self {
+                self.clones.fetch_add(1, Ordering::SeqCst);
+                Self {
+                    inner: self.inner.clone(),
+                    active: self.active.clone(),
+                    peak: self.peak.clone(),
+                    clones: self.clones.clone(),
+                }
+            }
+        }
+
+        impl TrackingRepository {
+            fn track<T>(&self, operation: impl FnOnce() -> Result<T>) -> Result<T> {
+                let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+                self.peak.fetch_max(active, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(1));
+                let result = operation();
+                self.active.fetch_sub(1, Ordering::SeqCst);
+                result
+            }
+        }
+
+        impl BomRepository for TrackingRepository {
+            fn get_component(&self, id: &ComponentId) -> Result<Component> {
+                self.track(|| self.inner.get_component(id))
+            }
+
+            fn get_components(&self, ids: &[ComponentId]) -> Result<Vec<Component>> {
+                self.track(|| self.inner.get_components(ids))
+            }
+
+            fn get_bom_header(
+                &self,
+                component_id: &ComponentId,
+                alternative: Option<&str>,
+                effective_date: Option<DateTime<Utc>>,
+            ) -> Result<BomHeader> {
+                self.track(|| self.inner.get_bom_header(component_id, alternative, effective_date))
+            }
+
+            fn get_bom_items(
+                &self,
+                component_id: &ComponentId,
+                effective_date: Option<DateTime<Utc>>,
+            ) -> Result<Vec<BomItem>> {
+                self.track(|| self.inner.get_bom_items(component_id, effective_date))
+            }
+
+            fn get_all_bom_items(&self) -> Result<Vec<BomItem>> {
+                self.track(|| self.inner.get_all_bom_items())
+            }
+
+            fn find_parents(&self, component_id: &ComponentId) -> Result<Vec<BomItem>> {
+                self.track(|| self.inner.find_parents(component_id))
+            }
+        }
+
+        fn create_test_component(id: &str) -> Component {
+            Component {
+                id: ComponentId::new(id),
+                description: format!("Component {}", id),
+                component_type: ComponentType::RawMaterial,
+                uom: "EA".to_string(),
+                standard_cost: None,
+                lead_time_days: None,
+                procurement_type: ProcurementType::Buy,
+                preferred_vendor: None,
+                organization: "ORG-01".to_string(),
+                version: 1,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            }
+        }
+
+        #[test]
+        fn test_concurrent_calls_share_pool() {
+            let inner = InMemoryRepository::new();
+            inner.add_component(create_test_component("A"));
+
+            let repository = TrackingRepository {
+                inner,
+                active: Arc::new(AtomicUsize::new(0)),
+                peak: Arc::new(AtomicUsize::new(0)),
+                clones: Arc::new(AtomicUsize::new(0)),
+            };
+            let peak = repository.peak.clone();
+            let clones = repository.clones.clone();
+            let pool = Arc::new(RepositoryPool::new(repository).with_max_connections(2));
+
+            let handles: Vec<_> = (0..8)
+                .map(|_| {
+                    let pool = pool.clone();
+                    std::thread::spawn(move || {
+                        for _ in 0..10 {
+                            let component = pool.get_component(&ComponentId::new("A")).unwrap();
+                            assert_eq!(component.id.as_str(), "A");
+                        }
+                        assert!(pool.get_component(&ComponentId::new("missing")).is_err());
+                    })
+                })
+                .collect();
+            for handle in handles {
+                handle.join().unwrap();
+            }
+
+            // Never more operations in flight than pooled instances
+            assert!(peak.load(Ordering::SeqCst) <= 2);
+
+            // All instances were returned and none were created beyond the limit
+            let stats = pool.stats();
+            assert_eq!(stats.in_use, 0);
+            assert!(stats.available >= 1 && stats.available <= 2);
+
+            // Returned instances are reused rather than replaced by fresh clones
+            assert_eq!(clones.load(Ordering::SeqCst), stats.available);
+        }
+
+        #[test]
+        fn test_connection_returned_after_panic() {
+            let pool = RepositoryPool::new(InMemoryRepository::new()).with_max_connections(1);
+
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                pool.with_connection(|_| -> Result<()> { panic!("operation failed") })
+            }));
+            assert!(result.is_err());
+
+            assert_eq!(
+                pool.stats(),
+                PoolStats {
+                    available: 1,
+                    in_use: 0
+                }
+            );
+            assert!(pool.get_all_bom_items().unwrap().is_empty());
+        }
     }
 }
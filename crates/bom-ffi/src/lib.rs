@@ -1,3 +1,6 @@
+// Exported functions take raw pointers from C callers and null-check them before use
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
 use bom_calc::costing::CostCalculator;
 use bom_calc::explosion::ExplosionCalculator;
 use bom_calc::where_used::WhereUsedAnalyzer;
@@ -1,17 +1,20 @@
-use bom_core::{BomItem, ComponentId};
+use crate::interner::ComponentIdInterner;
+use bom_core::{BomError, BomItem, ComponentId, Result};
 use rust_decimal::Decimal;
-use std::collections::HashMap;
 
 /// Node index in the arena
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NodeIndex(pub usize);
 
 /// Edge index in the arena
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EdgeIndex(pub usize);
 
 /// Node data in the BOM graph
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Node {
     /// Component ID
     pub component_id: ComponentId,
@@ -34,6 +37,7 @@ pub struct Node {
 
 /// Cached computation results for a node
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NodeCache {
     /// Cached total material cost (from all child components)
     pub total_material_cost: Option<Decimal>,
@@ -47,6 +51,7 @@ pub struct NodeCache {
 
 /// Edge data representing parent-child relationship
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Edge {
     /// Source node (parent)
     pub source: NodeIndex,
@@ -62,7 +67,14 @@ pub struct Edge {
 }
 
 /// Arena-based graph structure for BOM
-/// Uses contiguous memory for better cache locality
+/// Uses contiguous memory for better cache locality. Component IDs are interned: nodes and
+/// the parent and child IDs of BOM items share one string per component
+/// With the `serde` feature it serializes as-is (indices, free lists and node caches included)
+/// except for the interned IDs, which are rebuilt from the nodes and edges on load.
+/// Loading fails if any node or edge index points outside the arena
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "ArenaData"))]
 pub struct Arena {
     /// All nodes stored in a contiguous vector
     nodes: Vec<Node>,
@@ -70,14 +82,154 @@ pub struct Arena {
     /// All edges stored in a contiguous vector
     edges: Vec<Edge>,
 
-    /// Map from ComponentId to NodeIndex for fast lookup
-    component_index: HashMap<ComponentId, NodeIndex>,
+    /// Every component ID the arena has seen, stored once
+    #[cfg_attr(feature = "serde", serde(skip))]
+    interner: ComponentIdInterner,
+
+    /// Node of each interned component ID, for fast lookup (`None` once the node is removed)
+    #[cfg_attr(feature = "serde", serde(skip))]
+    interned_nodes: Vec<Option<NodeIndex>>,
 
     /// Free list for deleted nodes (for reuse)
     free_nodes: Vec<NodeIndex>,
 
     /// Free list for deleted edges (for reuse)
     free_edges: Vec<EdgeIndex>,
+
+    /// Which node slots are in use (not on `free_nodes`)
+    #[cfg_attr(feature = "serde", serde(skip))]
+    live_node_slots: SlotSet,
+
+    /// Which edge slots are in use (not on `free_edges`)
+    #[cfg_attr(feature = "serde", serde(skip))]
+    live_edge_slots: SlotSet,
+}
+
+/// One bit per arena slot, so liveness checks do not scan the free lists
+#[derive(Debug, Clone, Default)]
+struct SlotSet {
+    words: Vec<u64>,
+}
+
+impl SlotSet {
+    /// `len` slots, all set
+    #[cfg(feature = "serde")]
+    fn full(len: usize) -> Self {
+        let mut set = Self::default();
+        for idx in 0..len {
+            set.insert(idx);
+        }
+        set
+    }
+
+    fn contains(&self, idx: usize) -> bool {
+        self.words.get(idx / 64).is_some_and(|word| word & (1 << (idx % 64)) != 0)
+    }
+
+    fn insert(&mut self, idx: usize) {
+        if self.words.len() <= idx / 64 {
+            self.words.resize(idx / 64 + 1, 0);
+        }
+        self.words[idx / 64] |= 1 << (idx % 64);
+    }
+
+    /// Clear a slot, returning whether it was set
+    fn remove(&mut self, idx: usize) -> bool {
+        let was_set = self.contains(idx);
+        if was_set {
+            self.words[idx / 64] &= !(1 << (idx % 64));
+        }
+        was_set
+    }
+}
+
+/// Serialized form of `Arena`: everything but the interned IDs
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct ArenaData {
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+    free_nodes: Vec<NodeIndex>,
+    free_edges: Vec<EdgeIndex>,
+}
+
+#[cfg(feature = "serde")]
+impl ArenaData {
+    /// Check that every node and edge index points into the arena
+    /// and that no slot is freed twice
+    fn check_indices(&self) -> Result<()> {
+        let out_of_range = |what: &str, idx: usize| {
+            BomError::SerializationError(format!("{} index {} is out of range", what, idx))
+        };
+
+        for edge in &self.edges {
+            for node in [edge.source, edge.target] {
+                if node.0 >= self.nodes.len() {
+                    return Err(out_of_range("node", node.0));
+                }
+            }
+        }
+        if let Some(node) = self.free_nodes.iter().find(|node| node.0 >= self.nodes.len()) {
+            return Err(out_of_range("free node", node.0));
+        }
+
+        let adjacent = self.nodes.iter().flat_map(|node| node.incoming.iter().chain(&node.outgoing));
+        if let Some(edge) = adjacent.chain(&self.free_edges).find(|edge| edge.0 >= self.edges.len()) {
+            return Err(out_of_range("edge", edge.0));
+        }
+
+        let freed_twice = |what: &str, idx: usize| {
+            BomError::SerializationError(format!("{} index {} is freed more than once", what, idx))
+        };
+        let mut free = std::collections::HashSet::new();
+        if let Some(node) = self.free_nodes.iter().find(|node| !free.insert(node.0)) {
+            return Err(freed_twice("node", node.0));
+        }
+        free.clear();
+        if let Some(edge) = self.free_edges.iter().find(|edge| !free.insert(edge.0)) {
+            return Err(freed_twice("edge", edge.0));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<ArenaData> for Arena {
+    type Error = BomError;
+
+    fn try_from(data: ArenaData) -> Result<Self> {
+        data.check_indices()?;
+
+        let mut arena = Self {
+            live_node_slots: SlotSet::full(data.nodes.len()),
+            live_edge_slots: SlotSet::full(data.edges.len()),
+            nodes: data.nodes,
+            edges: data.edges,
+            interner: ComponentIdInterner::new(),
+            interned_nodes: Vec::new(),
+            free_nodes: data.free_nodes,
+            free_edges: data.free_edges,
+        };
+        for node in &arena.free_nodes {
+            arena.live_node_slots.remove(node.0);
+        }
+        for edge in &arena.free_edges {
+            arena.live_edge_slots.remove(edge.0);
+        }
+
+        // Freed slots keep their old node, which must not be found again
+        for idx in 0..arena.nodes.len() {
+            let (interned, component_id) = arena.intern(&arena.nodes[idx].component_id.clone());
+            arena.nodes[idx].component_id = component_id;
+            if arena.live_node_slots.contains(idx) {
+                arena.interned_nodes[interned as usize] = Some(NodeIndex(idx));
+            }
+        }
+        for idx in 0..arena.edges.len() {
+            arena.share_item_ids(EdgeIndex(idx));
+        }
+        Ok(arena)
+    }
 }
 
 impl Arena {
@@ -86,9 +238,12 @@ impl Arena {
         Self {
             nodes: Vec::new(),
             edges: Vec::new(),
-            component_index: HashMap::new(),
+            interner: ComponentIdInterner::new(),
+            interned_nodes: Vec::new(),
             free_nodes: Vec::new(),
             free_edges: Vec::new(),
+            live_node_slots: SlotSet::default(),
+            live_edge_slots: SlotSet::default(),
         }
     }
 
@@ -97,16 +252,39 @@ impl Arena {
         Self {
             nodes: Vec::with_capacity(node_capacity),
             edges: Vec::with_capacity(edge_capacity),
-            component_index: HashMap::with_capacity(node_capacity),
+            interner: ComponentIdInterner::with_capacity(node_capacity),
+            interned_nodes: Vec::with_capacity(node_capacity),
             free_nodes: Vec::new(),
             free_edges: Vec::new(),
+            live_node_slots: SlotSet::default(),
+            live_edge_slots: SlotSet::default(),
         }
     }
 
+    /// Interned index of `component_id` and the shared copy of it, interning it if it is new
+    fn intern(&mut self, component_id: &ComponentId) -> (u32, ComponentId) {
+        let interned = self.interner.intern(component_id);
+        if self.interned_nodes.len() < self.interner.len() {
+            self.interned_nodes.resize(self.interner.len(), None);
+        }
+        (interned, self.interner.resolve(interned).clone())
+    }
+
+    /// Point the parent and child IDs of an edge's BOM item at the interned strings
+    fn share_item_ids(&mut self, index: EdgeIndex) {
+        let (_, parent_id) = self.intern(&self.edges[index.0].bom_item.parent_id.clone());
+        let (_, child_id) = self.intern(&self.edges[index.0].bom_item.child_id.clone());
+        let bom_item = &mut self.edges[index.0].bom_item;
+        bom_item.parent_id = parent_id;
+        bom_item.child_id = child_id;
+    }
+
     /// Add a new node to the arena
     pub fn add_node(&mut self, component_id: ComponentId) -> NodeIndex {
+        let (interned, component_id) = self.intern(&component_id);
+
         // Check if node already exists
-        if let Some(&idx) = self.component_index.get(&component_id) {
+        if let Some(idx) = self.interned_nodes[interned as usize] {
             return idx;
         }
 
@@ -135,7 +313,8 @@ impl Arena {
             idx
         };
 
-        self.component_index.insert(component_id, index);
+        self.interned_nodes[interned as usize] = Some(index);
+        self.live_node_slots.insert(index.0);
         index
     }
 
@@ -169,6 +348,9 @@ impl Arena {
             idx
         };
 
+        self.share_item_ids(edge_idx);
+        self.live_edge_slots.insert(edge_idx.0);
+
         // Update adjacency lists
         self.nodes[parent.0].outgoing.push(edge_idx);
         self.nodes[child.0].incoming.push(edge_idx);
@@ -179,6 +361,47 @@ impl Arena {
         edge_idx
     }
 
+    /// Remove an edge, unlinking it from both nodes; the slot is reused by the next `add_edge`
+    /// Fails if the edge is unknown or already removed
+    pub fn remove_edge(&mut self, index: EdgeIndex) -> Result<()> {
+        if !self.live_edge_slots.remove(index.0) {
+            return Err(BomError::ComponentNotFound(format!("edge {}", index.0)));
+        }
+        let (source, target) = (self.edges[index.0].source, self.edges[index.0].target);
+
+        self.nodes[source.0].outgoing.retain(|&e| e != index);
+        self.nodes[target.0].incoming.retain(|&e| e != index);
+        self.free_edges.push(index);
+
+        self.mark_dirty_recursive(source);
+        Ok(())
+    }
+
+    /// Remove a node without edges; the slot is reused by the next `add_node`
+    /// Fails if the node is unknown or still has parents or children (remove those edges first)
+    pub fn remove_node(&mut self, index: NodeIndex) -> Result<()> {
+        if !self.live_node_slots.contains(index.0) {
+            return Err(BomError::ComponentNotFound(format!("node {}", index.0)));
+        }
+        let node = &self.nodes[index.0];
+        let edges = node.incoming.len() + node.outgoing.len();
+        if edges > 0 {
+            return Err(BomError::InvalidBomItem(format!(
+                "component {} is still linked by {} BOM items",
+                node.component_id.as_str(),
+                edges
+            )));
+        }
+
+        // The ID stays interned for when the component is added again
+        if let Some(interned) = self.interner.get(&node.component_id) {
+            self.interned_nodes[interned as usize] = None;
+        }
+        self.live_node_slots.remove(index.0);
+        self.free_nodes.push(index);
+        Ok(())
+    }
+
     /// Get node by index
     #[inline]
     pub fn node(&self, index: NodeIndex) -> Option<&Node> {
@@ -205,7 +428,13 @@ impl Arena {
 
     /// Find node index by component ID
     pub fn find_node(&self, component_id: &ComponentId) -> Option<NodeIndex> {
-        self.component_index.get(component_id).copied()
+        let interned = self.interner.get(component_id)?;
+        self.interned_nodes[interned as usize]
+    }
+
+    /// Interned component IDs (including those of removed nodes)
+    pub fn interner(&self) -> &ComponentIdInterner {
+        &self.interner
     }
 
     /// Get all nodes
@@ -218,6 +447,24 @@ impl Arena {
         &self.edges
     }
 
+    /// Iterate over nodes in use, skipping freed slots
+    pub fn live_nodes(&self) -> impl Iterator<Item = (NodeIndex, &Node)> + '_ {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| self.live_node_slots.contains(*idx))
+            .map(|(idx, node)| (NodeIndex(idx), node))
+    }
+
+    /// Iterate over edges in use, skipping freed slots
+    pub fn live_edges(&self) -> impl Iterator<Item = (EdgeIndex, &Edge)> + '_ {
+        self.edges
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| self.live_edge_slots.contains(*idx))
+            .map(|(idx, edge)| (EdgeIndex(idx), edge))
+    }
+
     /// Get node count
     pub fn node_count(&self) -> usize {
         self.nodes.len() - self.free_nodes.len()
@@ -228,6 +475,35 @@ impl Arena {
         self.edges.len() - self.free_edges.len()
     }
 
+    /// Number of nodes the arena holds without reallocating
+    pub fn node_capacity(&self) -> usize {
+        self.nodes.capacity()
+    }
+
+    /// Number of edges the arena holds without reallocating
+    pub fn edge_capacity(&self) -> usize {
+        self.edges.capacity()
+    }
+
+    /// Heap bytes allocated for the arena's vectors, adjacency lists and interned IDs
+    /// (component ID strings not included)
+    #[cfg(test)]
+    pub(crate) fn heap_bytes(&self) -> usize {
+        use std::mem::size_of;
+
+        let adjacency: usize = self
+            .nodes
+            .iter()
+            .map(|node| (node.incoming.capacity() + node.outgoing.capacity()) * size_of::<EdgeIndex>())
+            .sum();
+        self.nodes.capacity() * size_of::<Node>()
+            + self.edges.capacity() * size_of::<Edge>()
+            + adjacency
+            + self.interned_nodes.capacity() * size_of::<Option<NodeIndex>>()
+            + (self.live_node_slots.words.capacity() + self.live_edge_slots.words.capacity()) * size_of::<u64>()
+            + self.interner.heap_bytes()
+    }
+
     /// Mark a node and all its ancestors as dirty (for incremental computation)
     pub fn mark_dirty_recursive(&mut self, node: NodeIndex) {
         if let Some(n) = self.node_mut(node) {
@@ -279,6 +555,29 @@ impl Arena {
             })
     }
 
+    /// Edges leaving `source` (its BOM items), in insertion order
+    /// Indexes the edge vector directly from the node's `outgoing` list; empty for unknown nodes
+    pub fn iter_edges_by_source(&self, source: NodeIndex) -> impl Iterator<Item = &Edge> + '_ {
+        self.node(source)
+            .into_iter()
+            .flat_map(|n| n.outgoing.iter())
+            .map(|&edge_idx| &self.edges[edge_idx.0])
+    }
+
+    /// Edges entering `target` (BOM items using it), in insertion order
+    pub fn iter_edges_by_target(&self, target: NodeIndex) -> impl Iterator<Item = &Edge> + '_ {
+        self.node(target)
+            .into_iter()
+            .flat_map(|n| n.incoming.iter())
+            .map(|&edge_idx| &self.edges[edge_idx.0])
+    }
+
+    /// All edges from `source` to `target`; more than one when the child appears under the
+    /// parent several times (e.g. at different sequences)
+    pub fn edges_between(&self, source: NodeIndex, target: NodeIndex) -> impl Iterator<Item = &Edge> + '_ {
+        self.iter_edges_by_source(source).filter(move |edge| edge.target == target)
+    }
+
     /// Check if there's a path from source to target (for cycle detection)
     pub fn has_path(&self, source: NodeIndex, target: NodeIndex) -> bool {
         let mut visited = vec![false; self.nodes.len()];
@@ -362,4 +661,194 @@ mod tests {
         assert_eq!(arena.children(node_a).count(), 1);
         assert_eq!(arena.parents(node_b).count(), 1);
     }
+
+    #[test]
+    fn test_edge_iterators_with_multi_edges() {
+        let mut arena = Arena::new();
+
+        let node_a = arena.add_node(ComponentId::new("A"));
+        let node_b = arena.add_node(ComponentId::new("B"));
+        let node_c = arena.add_node(ComponentId::new("C"));
+
+        let bom_item = |parent: &str, child: &str, sequence: u32| BomItem {
+            id: uuid::Uuid::new_v4(),
+            parent_id: ComponentId::new(parent),
+            child_id: ComponentId::new(child),
+            quantity: Decimal::ONE,
+            scrap_factor: Decimal::ZERO,
+            sequence,
+            operation_sequence: None,
+            is_phantom: false,
+            effective_from: None,
+            effective_to: None,
+            alternative_group: None,
+            alternative_priority: None,
+            reference_designator: None,
+            position: None,
+            notes: None,
+            version: 0,
+        };
+
+        // B appears twice under A (sequences 10 and 30), C under both A and B
+        arena.add_edge(node_a, node_b, bom_item("A", "B", 10));
+        arena.add_edge(node_a, node_c, bom_item("A", "C", 20));
+        arena.add_edge(node_a, node_b, bom_item("A", "B", 30));
+        arena.add_edge(node_b, node_c, bom_item("B", "C", 10));
+
+        let sequences = |edges: Vec<&Edge>| -> Vec<u32> { edges.iter().map(|edge| edge.bom_item.sequence).collect() };
+        assert_eq!(sequences(arena.iter_edges_by_source(node_a).collect()), vec![10, 20, 30]);
+        assert_eq!(sequences(arena.edges_between(node_a, node_b).collect()), vec![10, 30]);
+        assert_eq!(arena.edges_between(node_b, node_a).count(), 0);
+        assert_eq!(arena.edges_between(node_a, node_c).count(), 1);
+
+        let parents: Vec<NodeIndex> = arena.iter_edges_by_target(node_c).map(|edge| edge.source).collect();
+        assert_eq!(parents, vec![node_a, node_b]);
+        assert_eq!(arena.iter_edges_by_target(node_b).count(), 2);
+        assert_eq!(arena.iter_edges_by_source(node_c).count(), 0);
+        assert_eq!(arena.iter_edges_by_source(NodeIndex(99)).count(), 0);
+    }
+
+    #[test]
+    fn test_live_nodes_skip_freed_slots() {
+        let mut arena = Arena::new();
+
+        arena.add_node(ComponentId::new("A"));
+        let node_b = arena.add_node(ComponentId::new("B"));
+        arena.add_node(ComponentId::new("C"));
+
+        arena.remove_node(node_b).unwrap();
+
+        let live: Vec<_> = arena.live_nodes().map(|(_, node)| node.component_id.as_str()).collect();
+        assert_eq!(live, vec!["A", "C"]);
+        assert_eq!(arena.live_nodes().count(), arena.node_count());
+    }
+
+    #[test]
+    fn test_remove_node_and_edge() {
+        let mut arena = Arena::new();
+        let node_a = arena.add_node(ComponentId::new("A"));
+        let node_b = arena.add_node(ComponentId::new("B"));
+
+        let bom_item = BomItem {
+            id: uuid::Uuid::new_v4(),
+            parent_id: ComponentId::new("A"),
+            child_id: ComponentId::new("B"),
+            quantity: Decimal::ONE,
+            scrap_factor: Decimal::ZERO,
+            sequence: 10,
+            operation_sequence: None,
+            is_phantom: false,
+            effective_from: None,
+            effective_to: None,
+            alternative_group: None,
+            alternative_priority: None,
+            reference_designator: None,
+            position: None,
+            notes: None,
+            version: 0,
+        };
+        let edge = arena.add_edge(node_a, node_b, bom_item);
+        arena.nodes[node_a.0].dirty = false;
+
+        // Linked nodes stay
+        assert!(arena.remove_node(node_b).is_err());
+        assert_eq!(arena.node_count(), 2);
+
+        arena.remove_edge(edge).unwrap();
+        assert!(matches!(arena.remove_edge(edge), Err(BomError::ComponentNotFound(_))));
+        assert!(matches!(arena.remove_edge(EdgeIndex(99)), Err(BomError::ComponentNotFound(_))));
+        assert_eq!(arena.edge_count(), 0);
+        assert_eq!(arena.children(node_a).count(), 0);
+        assert_eq!(arena.parents(node_b).count(), 0);
+        assert!(arena.node(node_a).unwrap().dirty);
+
+        arena.remove_node(node_b).unwrap();
+        assert_eq!(arena.node_count(), 1);
+        assert_eq!(arena.find_node(&ComponentId::new("B")), None);
+        assert!(arena.remove_node(node_b).is_err());
+        assert!(arena.remove_node(NodeIndex(99)).is_err());
+
+        // The freed slot is reused
+        assert_eq!(arena.add_node(ComponentId::new("C")), node_b);
+        assert_eq!(arena.node_count(), 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_arena_serde_round_trip() {
+        let mut arena = Arena::new();
+        let node_a = arena.add_node(ComponentId::new("A"));
+        let node_b = arena.add_node(ComponentId::new("B"));
+        let node_c = arena.add_node(ComponentId::new("C"));
+
+        let bom_item = |parent: &str, child: &str| BomItem {
+            id: uuid::Uuid::new_v4(),
+            parent_id: ComponentId::new(parent),
+            child_id: ComponentId::new(child),
+            quantity: Decimal::from(3),
+            scrap_factor: Decimal::new(1, 1),
+            sequence: 10,
+            operation_sequence: None,
+            is_phantom: false,
+            effective_from: None,
+            effective_to: None,
+            alternative_group: None,
+            alternative_priority: None,
+            reference_designator: None,
+            position: None,
+            notes: None,
+            version: 0,
+        };
+        arena.add_edge(node_a, node_c, bom_item("A", "C"));
+        let removed = arena.add_edge(node_a, node_b, bom_item("A", "B"));
+        arena.remove_edge(removed).unwrap();
+        arena.remove_node(node_b).unwrap();
+        arena.nodes[node_a.0].cache.total_material_cost = Some(Decimal::from(42));
+
+        let json = serde_json::to_string(&arena).unwrap();
+        assert!(!json.contains("component_index"));
+        let restored: Arena = serde_json::from_str(&json).unwrap();
+
+        // Indices survive as they were; the freed node is not indexed again
+        assert_eq!(restored.find_node(&ComponentId::new("A")), Some(node_a));
+        assert_eq!(restored.find_node(&ComponentId::new("C")), Some(node_c));
+        assert_eq!(restored.find_node(&ComponentId::new("B")), None);
+        assert_eq!(restored.node_count(), arena.node_count());
+        assert_eq!(restored.edge_count(), arena.edge_count());
+
+        let children: Vec<(NodeIndex, Decimal)> = restored
+            .children(node_a)
+            .map(|(child, edge)| (child, edge.effective_quantity))
+            .collect();
+        assert_eq!(children, vec![(node_c, Decimal::new(33, 1))]);
+        assert_eq!(restored.node(node_a).unwrap().cache.total_material_cost, Some(Decimal::from(42)));
+
+        // IDs are interned again
+        let (_, edge) = restored.children(node_a).next().unwrap();
+        assert!(edge.bom_item.parent_id.shares_storage(&restored.node(node_a).unwrap().component_id));
+        assert!(edge.bom_item.child_id.shares_storage(&restored.node(node_c).unwrap().component_id));
+
+        // Corrupt indices are rejected instead of panicking later
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["nodes"][node_a.0]["outgoing"][0] = serde_json::json!(7);
+        let Err(error) = serde_json::from_value::<Arena>(value) else {
+            panic!("out-of-range edge index accepted");
+        };
+        assert!(error.to_string().contains("edge index 7 is out of range"));
+
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["edges"][0]["target"] = serde_json::json!(3);
+        assert!(serde_json::from_value::<Arena>(value).is_err());
+
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["free_edges"] = serde_json::json!([5]);
+        assert!(serde_json::from_value::<Arena>(value).is_err());
+
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["free_nodes"] = serde_json::json!([node_b.0, node_b.0]);
+        let Err(error) = serde_json::from_value::<Arena>(value) else {
+            panic!("duplicate free slot accepted");
+        };
+        assert!(error.to_string().contains("is freed more than once"));
+    }
 }
@@ -1,6 +1,16 @@
 use crate::arena::{Arena, NodeIndex};
 use bom_core::{BomError, ComponentId, Result};
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
+
+/// Bookkeeping for Tarjan's algorithm, indexed by node slot
+struct TarjanState {
+    next_index: usize,
+    index: Vec<Option<usize>>,
+    lowlink: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<NodeIndex>,
+    components: Vec<Vec<NodeIndex>>,
+}
 
 /// Detect cycles in the BOM graph
 pub struct CycleDetector<'a> {
@@ -19,10 +29,8 @@ impl<'a> CycleDetector<'a> {
 
         for (idx, _) in self.arena.nodes().iter().enumerate() {
             let node = NodeIndex(idx);
-            if !visited.contains(&node) {
-                if self.dfs_cycle(node, &mut visited, &mut rec_stack) {
-                    return true;
-                }
+            if !visited.contains(&node) && self.dfs_cycle(node, &mut visited, &mut rec_stack) {
+                return true;
             }
         }
 
@@ -112,6 +120,71 @@ impl<'a> CycleDetector<'a> {
         rec_stack.remove(&node);
     }
 
+    /// Find strongly connected components with Tarjan's algorithm
+    /// Every node belongs to exactly one component; in a DAG each component is a single node
+    pub fn strongly_connected_components(&self) -> Vec<Vec<NodeIndex>> {
+        let node_count = self.arena.nodes().len();
+        let mut state = TarjanState {
+            next_index: 0,
+            index: vec![None; node_count],
+            lowlink: vec![0; node_count],
+            on_stack: vec![false; node_count],
+            stack: Vec::new(),
+            components: Vec::new(),
+        };
+
+        for (node, _) in self.arena.live_nodes() {
+            if state.index[node.0].is_none() {
+                self.tarjan(node, &mut state);
+            }
+        }
+
+        state.components
+    }
+
+    /// Check if a strongly connected component contains a cycle
+    /// (more than one node, or a single node referencing itself)
+    pub fn is_cyclic_component(&self, component: &[NodeIndex]) -> bool {
+        match component {
+            [node] => self.arena.children(*node).any(|(child, _)| child == *node),
+            _ => component.len() > 1,
+        }
+    }
+
+    /// Tarjan DFS from `node`, emitting components as their roots are finished
+    fn tarjan(&self, node: NodeIndex, state: &mut TarjanState) {
+        state.index[node.0] = Some(state.next_index);
+        state.lowlink[node.0] = state.next_index;
+        state.next_index += 1;
+        state.stack.push(node);
+        state.on_stack[node.0] = true;
+
+        for (child, _) in self.arena.children(node) {
+            match state.index[child.0] {
+                None => {
+                    self.tarjan(child, state);
+                    state.lowlink[node.0] = state.lowlink[node.0].min(state.lowlink[child.0]);
+                }
+                Some(child_index) if state.on_stack[child.0] => {
+                    state.lowlink[node.0] = state.lowlink[node.0].min(child_index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(state.lowlink[node.0]) == state.index[node.0] {
+            let mut component = Vec::new();
+            while let Some(member) = state.stack.pop() {
+                state.on_stack[member.0] = false;
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            state.components.push(component);
+        }
+    }
+
     /// Get a human-readable description of a cycle
     pub fn describe_cycle(&self, cycle: &[NodeIndex]) -> Vec<ComponentId> {
         cycle
@@ -120,6 +193,142 @@ impl<'a> CycleDetector<'a> {
             .map(|node| node.component_id.clone())
             .collect()
     }
+
+    /// Component IDs of each component, sorted within and across components
+    pub fn describe_components(&self, components: &[Vec<NodeIndex>]) -> Vec<Vec<ComponentId>> {
+        let mut described: Vec<Vec<ComponentId>> = components
+            .iter()
+            .map(|component| {
+                let mut ids = self.describe_cycle(component);
+                ids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+                ids
+            })
+            .collect();
+        described.sort_by(|a, b| a.iter().map(|id| id.as_str()).cmp(b.iter().map(|id| id.as_str())));
+        described
+    }
+}
+
+/// Bookkeeping for Johnson's circuit search, indexed by node slot
+struct JohnsonState {
+    /// Nodes of the strongly connected component being searched
+    in_component: Vec<bool>,
+    blocked: Vec<bool>,
+    /// Nodes to unblock once the key node is unblocked
+    blocked_by: Vec<BTreeSet<usize>>,
+    stack: Vec<NodeIndex>,
+    circuits: Vec<Vec<NodeIndex>>,
+}
+
+/// Every elementary circuit (no repeated component) of the graph, with Johnson's algorithm
+/// Unlike `CycleDetector::find_cycles`, overlapping cycles sharing nodes are all reported.
+/// Each circuit is rotated to start at its smallest component ID, without repeating it
+/// at the end; circuits are sorted by their IDs. Parallel items count once
+pub fn find_all_elementary_cycles(arena: &Arena) -> Vec<Vec<ComponentId>> {
+    let slots = arena.nodes().len();
+    let nodes: Vec<NodeIndex> = arena.live_nodes().map(|(node, _)| node).collect();
+    let mut circuits = Vec::new();
+
+    for (position, &start) in nodes.iter().enumerate() {
+        // Circuits through earlier nodes were found already: search the subgraph of the
+        // remaining nodes, within the strongly connected component of `start`
+        let mut allowed = vec![false; slots];
+        for node in &nodes[position..] {
+            allowed[node.0] = true;
+        }
+        let forward = reachable(arena, start, &allowed, |node| arena.children(node).map(|(child, _)| child));
+        let backward = reachable(arena, start, &allowed, |node| arena.parents(node).map(|(parent, _)| parent));
+
+        let mut state = JohnsonState {
+            in_component: (0..slots).map(|slot| forward[slot] && backward[slot]).collect(),
+            blocked: vec![false; slots],
+            blocked_by: vec![BTreeSet::new(); slots],
+            stack: Vec::new(),
+            circuits: Vec::new(),
+        };
+        johnson_circuit(arena, start, start, &mut state);
+        circuits.extend(state.circuits);
+    }
+
+    let mut described: Vec<Vec<ComponentId>> = circuits
+        .iter()
+        .map(|circuit| {
+            let mut ids: Vec<ComponentId> = circuit
+                .iter()
+                .filter_map(|&node| arena.node(node))
+                .map(|node| node.component_id.clone())
+                .collect();
+            let smallest = (0..ids.len()).min_by(|&a, &b| ids[a].as_str().cmp(ids[b].as_str())).unwrap_or(0);
+            ids.rotate_left(smallest);
+            ids
+        })
+        .collect();
+    described.sort_by(|a, b| a.iter().map(|id| id.as_str()).cmp(b.iter().map(|id| id.as_str())));
+    described
+}
+
+/// Nodes reachable from `start` through `allowed` nodes only, indexed by slot
+fn reachable<I: Iterator<Item = NodeIndex>>(
+    arena: &Arena,
+    start: NodeIndex,
+    allowed: &[bool],
+    next: impl Fn(NodeIndex) -> I,
+) -> Vec<bool> {
+    let mut seen = vec![false; arena.nodes().len()];
+    seen[start.0] = true;
+    let mut stack = vec![start];
+    while let Some(node) = stack.pop() {
+        for other in next(node) {
+            if allowed[other.0] && !seen[other.0] {
+                seen[other.0] = true;
+                stack.push(other);
+            }
+        }
+    }
+    seen
+}
+
+/// Johnson's CIRCUIT: record every circuit from `start` through `node`
+/// Returns whether one was found, which decides if `node` is unblocked right away
+fn johnson_circuit(arena: &Arena, start: NodeIndex, node: NodeIndex, state: &mut JohnsonState) -> bool {
+    let successors: BTreeSet<usize> = arena
+        .children(node)
+        .map(|(child, _)| child.0)
+        .filter(|&slot| state.in_component[slot])
+        .collect();
+
+    let mut found = false;
+    state.stack.push(node);
+    state.blocked[node.0] = true;
+
+    for &slot in &successors {
+        if slot == start.0 {
+            state.circuits.push(state.stack.clone());
+            found = true;
+        } else if !state.blocked[slot] && johnson_circuit(arena, start, NodeIndex(slot), state) {
+            found = true;
+        }
+    }
+
+    if found {
+        johnson_unblock(node.0, state);
+    } else {
+        for &slot in &successors {
+            state.blocked_by[slot].insert(node.0);
+        }
+    }
+
+    state.stack.pop();
+    found
+}
+
+fn johnson_unblock(slot: usize, state: &mut JohnsonState) {
+    state.blocked[slot] = false;
+    for waiting in std::mem::take(&mut state.blocked_by[slot]) {
+        if state.blocked[waiting] {
+            johnson_unblock(waiting, state);
+        }
+    }
 }
 
 /// Validate BOM graph for common issues
@@ -141,10 +350,24 @@ pub fn validate_graph(arena: &Arena) -> Result<()> {
             })
             .collect();
 
+        // Strongly connected components name every component caught in a cycle,
+        // including those on cycles the DFS above only reports partially
+        let cyclic_components: Vec<Vec<NodeIndex>> = detector
+            .strongly_connected_components()
+            .into_iter()
+            .filter(|component| detector.is_cyclic_component(component))
+            .collect();
+        let component_descriptions: Vec<String> = detector
+            .describe_components(&cyclic_components)
+            .iter()
+            .map(|ids| format!("{{{}}}", ids.iter().map(|id| id.as_str()).collect::<Vec<_>>().join(", ")))
+            .collect();
+
         return Err(BomError::CircularDependency(format!(
-            "Found {} cycle(s): {}",
+            "Found {} cycle(s): {} (strongly connected: {})",
             cycles.len(),
-            cycle_descriptions.join("; ")
+            cycle_descriptions.join("; "),
+            component_descriptions.join("; ")
         )));
     }
 
@@ -256,4 +479,79 @@ mod tests {
         // Adding A -> C would not create cycle (already exists as path)
         assert!(!detector.would_create_cycle(a, c));
     }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let mut arena = Arena::new();
+
+        // Two separate cycles: A -> B -> A and C -> D -> E -> C, plus B -> C and E -> F
+        let ids = ["A", "B", "C", "D", "E", "F"];
+        let nodes: Vec<NodeIndex> = ids.iter().map(|id| arena.add_node(ComponentId::new(*id))).collect();
+        for (from, to) in [(0, 1), (1, 0), (1, 2), (2, 3), (3, 4), (4, 2), (4, 5)] {
+            arena.add_edge(nodes[from], nodes[to], create_test_bom_item(ids[from], ids[to]));
+        }
+
+        let detector = CycleDetector::new(&arena);
+        let components = detector.describe_components(&detector.strongly_connected_components());
+
+        let expected: Vec<Vec<ComponentId>> = vec![
+            vec![ComponentId::new("A"), ComponentId::new("B")],
+            vec![ComponentId::new("C"), ComponentId::new("D"), ComponentId::new("E")],
+            vec![ComponentId::new("F")],
+        ];
+        assert_eq!(components, expected);
+
+        let message = validate_graph(&arena).unwrap_err().to_string();
+        assert!(message.contains("strongly connected: {A, B}; {C, D, E}"), "{}", message);
+    }
+
+    fn cycle_arena(ids: &[&str], edges: &[(usize, usize)]) -> Arena {
+        let mut arena = Arena::new();
+        let nodes: Vec<NodeIndex> = ids.iter().map(|id| arena.add_node(ComponentId::new(*id))).collect();
+        for &(from, to) in edges {
+            arena.add_edge(nodes[from], nodes[to], create_test_bom_item(ids[from], ids[to]));
+        }
+        arena
+    }
+
+    fn circuit_ids(circuits: &[Vec<ComponentId>]) -> Vec<Vec<&str>> {
+        circuits
+            .iter()
+            .map(|circuit| circuit.iter().map(|id| id.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_all_elementary_cycles_independent() {
+        // A -> B -> A and C -> D -> E -> C, joined by B -> C, plus a tail E -> F
+        let arena = cycle_arena(
+            &["A", "B", "C", "D", "E", "F"],
+            &[(0, 1), (1, 0), (1, 2), (2, 3), (3, 4), (4, 2), (4, 5)],
+        );
+
+        let circuits = find_all_elementary_cycles(&arena);
+        assert_eq!(circuit_ids(&circuits), vec![vec!["A", "B"], vec!["C", "D", "E"]]);
+    }
+
+    #[test]
+    fn test_all_elementary_cycles_interleaved() {
+        // A -> B -> C -> A with shortcuts back B -> A and C -> B: three circuits sharing B
+        let arena = cycle_arena(&["C", "B", "A"], &[(2, 1), (1, 0), (0, 2), (1, 2), (0, 1)]);
+
+        let circuits = find_all_elementary_cycles(&arena);
+        assert_eq!(
+            circuit_ids(&circuits),
+            vec![vec!["A", "B"], vec!["A", "B", "C"], vec!["B", "C"]]
+        );
+
+        // Every pair of A, B and C in both directions: 3 two-node and 2 three-node circuits
+        let complete = cycle_arena(&["A", "B", "C"], &[(0, 1), (1, 0), (1, 2), (2, 1), (0, 2), (2, 0)]);
+        assert_eq!(find_all_elementary_cycles(&complete).len(), 5);
+
+        // Parallel items and acyclic graphs add nothing
+        let parallel = cycle_arena(&["A", "B"], &[(0, 1), (0, 1), (1, 0)]);
+        assert_eq!(find_all_elementary_cycles(&parallel).len(), 1);
+        let acyclic = cycle_arena(&["A", "B", "C"], &[(0, 1), (1, 2), (0, 2)]);
+        assert!(find_all_elementary_cycles(&acyclic).is_empty());
+    }
 }
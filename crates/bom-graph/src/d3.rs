@@ -0,0 +1,168 @@
+use crate::graph::BomGraph;
+use bom_core::{Component, ComponentId, ComponentType, CostBreakdown};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+/// `group` of a node in `BomGraph::to_d3_force_graph` (1-5; components of unknown type get 0)
+pub fn d3_group(component_type: ComponentType) -> u32 {
+    match component_type {
+        ComponentType::FinishedProduct => 1,
+        ComponentType::SemiFinished => 2,
+        ComponentType::RawMaterial => 3,
+        ComponentType::Packaging => 4,
+        ComponentType::Service => 5,
+    }
+}
+
+impl BomGraph {
+    /// Export the graph for a D3.js force-directed layout (力導向圖)
+    /// `{ "nodes": [{ "id", "group", "cost" }], "links": [{ "source", "target", "value" }] }`
+    /// with one node per component and one link per BOM item. `group` comes from the type
+    /// in `components` (see `d3_group`), `value` is the item quantity and `cost` the
+    /// component's total cost; nodes only have a `cost` when `cost_map` is given
+    /// (`null` for components missing from it)
+    pub fn to_d3_force_graph(
+        &self,
+        components: &HashMap<ComponentId, Component>,
+        cost_map: Option<&HashMap<ComponentId, CostBreakdown>>,
+    ) -> Value {
+        let nodes: Vec<Value> = self
+            .iter_nodes()
+            .map(|(_, id)| {
+                let mut node = Map::new();
+                node.insert("id".to_string(), json!(id.as_str()));
+                node.insert(
+                    "group".to_string(),
+                    json!(components
+                        .get(id)
+                        .map(|component| d3_group(component.component_type))
+                        .unwrap_or(0)),
+                );
+                if let Some(cost_map) = cost_map {
+                    node.insert(
+                        "cost".to_string(),
+                        json!(cost_map.get(id).and_then(|cost| number(cost.total_cost))),
+                    );
+                }
+                Value::Object(node)
+            })
+            .collect();
+
+        let links: Vec<Value> = self
+            .iter_edges()
+            .map(|(_, _, item)| {
+                json!({
+                    "source": item.parent_id.as_str(),
+                    "target": item.child_id.as_str(),
+                    "value": number(item.quantity),
+                })
+            })
+            .collect();
+
+        json!({ "nodes": nodes, "links": links })
+    }
+}
+
+/// D3 expects plain JSON numbers, not the string form `Decimal` serializes to
+fn number(value: Decimal) -> Option<f64> {
+    value.to_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bom_core::{BomItem, ProcurementType};
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn create_test_component(id: &str, component_type: ComponentType) -> Component {
+        Component {
+            id: ComponentId::new(id),
+            description: id.to_string(),
+            component_type,
+            uom: "EA".to_string(),
+            standard_cost: None,
+            lead_time_days: None,
+            procurement_type: ProcurementType::Make,
+            preferred_vendor: None,
+            organization: "PLANT-01".to_string(),
+            version: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn create_test_bom_item(parent: &str, child: &str, qty: i64) -> BomItem {
+        BomItem {
+            id: Uuid::new_v4(),
+            parent_id: ComponentId::new(parent),
+            child_id: ComponentId::new(child),
+            quantity: Decimal::from(qty),
+            scrap_factor: Decimal::ZERO,
+            sequence: 10,
+            operation_sequence: None,
+            is_phantom: false,
+            effective_from: None,
+            effective_to: None,
+            alternative_group: None,
+            alternative_priority: None,
+            reference_designator: None,
+            position: None,
+            notes: None,
+            version: 0,
+        }
+    }
+
+    #[test]
+    fn test_to_d3_force_graph() {
+        let mut graph = BomGraph::new();
+        graph.add_bom_item(create_test_bom_item("BIKE", "FRAME", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("BIKE", "WHEEL", 2)).unwrap();
+        graph.add_bom_item(create_test_bom_item("FRAME", "TUBE", 4)).unwrap();
+
+        let components: HashMap<ComponentId, Component> = [
+            create_test_component("BIKE", ComponentType::FinishedProduct),
+            create_test_component("TUBE", ComponentType::RawMaterial),
+        ]
+        .into_iter()
+        .map(|component| (component.id.clone(), component))
+        .collect();
+        let cost_map = HashMap::from([(
+            ComponentId::new("BIKE"),
+            CostBreakdown {
+                component_id: ComponentId::new("BIKE"),
+                material_cost: Decimal::new(1255, 1),
+                labor_cost: Decimal::ZERO,
+                overhead_cost: Decimal::ZERO,
+                subcontract_cost: Decimal::ZERO,
+                total_cost: Decimal::new(1255, 1),
+                calculated_at: Utc::now(),
+            },
+        )]);
+
+        let output = graph.to_d3_force_graph(&components, Some(&cost_map)).to_string();
+        let parsed: Value = serde_json::from_str(&output).unwrap();
+        let nodes = parsed["nodes"].as_array().unwrap();
+        let links = parsed["links"].as_array().unwrap();
+        let stats = graph.stats();
+        assert_eq!(nodes.len(), stats.node_count);
+        assert_eq!(links.len(), stats.edge_count);
+
+        let node = |id: &str| nodes.iter().find(|node| node["id"] == id).unwrap();
+        assert_eq!(node("BIKE")["group"], 1);
+        assert_eq!(node("BIKE")["cost"], 125.5);
+        assert_eq!(node("TUBE")["group"], 3);
+        assert_eq!(node("WHEEL")["group"], 0);
+        assert!(node("WHEEL")["cost"].is_null());
+
+        let wheel = links.iter().find(|link| link["target"] == "WHEEL").unwrap();
+        assert_eq!(wheel["source"], "BIKE");
+        assert_eq!(wheel["value"], 2.0);
+
+        // Without costs nodes carry no cost at all
+        let plain = graph.to_d3_force_graph(&components, None);
+        assert!(plain["nodes"][0].get("cost").is_none());
+    }
+}
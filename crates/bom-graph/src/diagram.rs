@@ -0,0 +1,38 @@
+//! Helpers shared by the DOT and Mermaid exporters
+use bom_core::ComponentId;
+use std::collections::HashSet;
+
+/// Node ID for a component in a DOT or Mermaid diagram
+/// Anything but ASCII letters and digits becomes `_`, and the `n_` prefix keeps IDs from
+/// starting with a digit or matching a keyword of either language (`end`, `graph`, ...).
+/// IDs that collide after sanitizing get a numeric suffix
+pub(crate) fn diagram_id(id: &ComponentId, used: &mut HashSet<String>) -> String {
+    let sanitized: String = id
+        .as_str()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let base = format!("n_{}", sanitized);
+
+    let mut candidate = base.clone();
+    let mut suffix = 1;
+    while !used.insert(candidate.clone()) {
+        suffix += 1;
+        candidate = format!("{}_{}", base, suffix);
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagram_id() {
+        let mut used = HashSet::new();
+        assert_eq!(diagram_id(&ComponentId::new("PART-1.2"), &mut used), "n_PART_1_2");
+        assert_eq!(diagram_id(&ComponentId::new("PART_1/2"), &mut used), "n_PART_1_2_2");
+        assert_eq!(diagram_id(&ComponentId::new("9-LIVES"), &mut used), "n_9_LIVES");
+        assert_eq!(diagram_id(&ComponentId::new("end"), &mut used), "n_end");
+    }
+}
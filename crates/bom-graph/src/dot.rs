@@ -0,0 +1,294 @@
+use crate::arena::NodeIndex;
+use crate::diagram::diagram_id;
+use crate::graph::BomGraph;
+use bom_core::{Component, ComponentId, ComponentType};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// What `BomGraph::to_dot` writes besides the graph structure (all off by default)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DotOptions {
+    /// Add the component description to node labels
+    pub show_description: bool,
+    /// Add the standard cost to node labels
+    pub show_cost: bool,
+    /// Label edges with the BOM item quantity
+    pub show_quantity: bool,
+    /// Label edges with the scrap rate (as a percentage, only if non-zero)
+    pub show_scrap: bool,
+    /// Fill nodes with a color per `ComponentType` (see `dot_color`)
+    pub color_by_type: bool,
+    /// Put the nodes of each BOM level (0 = root, as in `ExplosionItem::level`) into their own cluster;
+    /// a component used at several levels goes to the deepest. Nodes without a level (on a cycle
+    /// or only below one) go to a last cluster, "No level"
+    pub cluster_by_level: bool,
+}
+
+/// Fill color of a node in `BomGraph::to_dot` with `DotOptions::color_by_type` (X11 color names)
+pub fn dot_color(component_type: ComponentType) -> &'static str {
+    match component_type {
+        ComponentType::FinishedProduct => "lightblue",
+        ComponentType::SemiFinished => "khaki",
+        ComponentType::RawMaterial => "palegreen",
+        ComponentType::Packaging => "wheat",
+        ComponentType::Service => "thistle",
+    }
+}
+
+impl BomGraph {
+    /// Render the graph as Graphviz DOT (`dot -Tpng bom.dot -o bom.png`)
+    /// One node per component, labelled with its ID plus whatever `options` asks for from
+    /// `components` (nodes missing from it only show their ID), and one edge per BOM item.
+    /// Nodes are written sorted by component ID and edges by parent, sequence and child,
+    /// so the output is stable for the same graph
+    pub fn to_dot(&self, components: &HashMap<ComponentId, Component>, options: DotOptions) -> String {
+        let mut nodes: Vec<(NodeIndex, &ComponentId)> = self.iter_nodes().collect();
+        nodes.sort_by(|a, b| a.1.as_str().cmp(b.1.as_str()));
+
+        let mut used = HashSet::new();
+        let dot_ids: HashMap<NodeIndex, String> = nodes
+            .iter()
+            .map(|&(node, id)| (node, diagram_id(id, &mut used)))
+            .collect();
+
+        let mut dot = String::from("digraph BOM {\n    rankdir=TB;\n    node [shape=box];\n");
+        // Writing to a String cannot fail
+        if options.cluster_by_level {
+            let level_of = bom_levels(self);
+            let depth = level_of.values().max().map_or(0, |deepest| deepest + 1);
+
+            for level in 0..depth {
+                let _ = writeln!(dot, "    subgraph cluster_level_{} {{", level);
+                let _ = writeln!(dot, "        label=\"Level {}\";", level);
+                for &(node, id) in nodes.iter().filter(|(node, _)| level_of.get(node) == Some(&level)) {
+                    let _ = writeln!(dot, "        {};", node_statement(&dot_ids[&node], id, components, &options));
+                }
+                dot.push_str("    }\n");
+            }
+
+            let unleveled: Vec<_> = nodes.iter().filter(|(node, _)| !level_of.contains_key(node)).collect();
+            if !unleveled.is_empty() {
+                dot.push_str("    subgraph cluster_no_level {\n        label=\"No level\";\n");
+                for &&(node, id) in &unleveled {
+                    let _ = writeln!(dot, "        {};", node_statement(&dot_ids[&node], id, components, &options));
+                }
+                dot.push_str("    }\n");
+            }
+        } else {
+            for &(node, id) in &nodes {
+                let _ = writeln!(dot, "    {};", node_statement(&dot_ids[&node], id, components, &options));
+            }
+        }
+
+        let mut edges: Vec<_> = self.iter_edges().collect();
+        edges.sort_by(|a, b| {
+            (a.2.parent_id.as_str(), a.2.sequence, a.2.child_id.as_str())
+                .cmp(&(b.2.parent_id.as_str(), b.2.sequence, b.2.child_id.as_str()))
+        });
+        for (parent, child, item) in edges {
+            let mut label = Vec::new();
+            if options.show_quantity {
+                label.push(format!("qty {}", item.quantity));
+            }
+            if options.show_scrap && item.scrap_factor > Decimal::ZERO {
+                label.push(format!("scrap {}%", (item.scrap_factor * Decimal::ONE_HUNDRED).normalize()));
+            }
+
+            let _ = write!(dot, "    {} -> {}", dot_ids[&parent], dot_ids[&child]);
+            if !label.is_empty() {
+                let _ = write!(dot, " [label=\"{}\"]", escape(&label.join(", ")));
+            }
+            dot.push_str(";\n");
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// BOM level of every node below a root: the longest path from any root (roots are 0)
+/// Nodes on a cycle, or reachable only through one, get no level
+fn bom_levels(graph: &BomGraph) -> HashMap<NodeIndex, usize> {
+    let roots: Vec<NodeIndex> = graph.iter_root_nodes().map(|(node, _)| node).collect();
+    let mut level_of: HashMap<NodeIndex, usize> = HashMap::new();
+    // Roots first, so every parent's level is known before its children
+    for node in crate::traversal::topological_sort(graph.arena(), &roots).into_iter().rev() {
+        let level = graph
+            .arena()
+            .parents(node)
+            .filter_map(|(parent, _)| level_of.get(&parent))
+            .max()
+            .map_or(0, |level| level + 1);
+        level_of.insert(node, level);
+    }
+    level_of
+}
+
+/// `id [label=..., ...]` for one component
+fn node_statement(
+    dot_id: &str,
+    id: &ComponentId,
+    components: &HashMap<ComponentId, Component>,
+    options: &DotOptions,
+) -> String {
+    let component = components.get(id);
+
+    let mut label = vec![escape(id.as_str())];
+    if let Some(component) = component {
+        if options.show_description {
+            label.push(escape(&component.description));
+        }
+        if options.show_cost {
+            if let Some(cost) = component.standard_cost {
+                label.push(format!("cost {}", cost));
+            }
+        }
+    }
+
+    let mut statement = format!("{} [label=\"{}\"", dot_id, label.join("\\n"));
+    if let Some(component) = component.filter(|_| options.color_by_type) {
+        let _ = write!(
+            statement,
+            ", style=filled, fillcolor={}",
+            dot_color(component.component_type)
+        );
+    }
+    statement.push(']');
+    statement
+}
+
+/// Labels are double-quoted strings; escape quotes and backslashes
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bom_core::{BomItem, ProcurementType};
+    use chrono::Utc;
+
+    fn create_test_component(id: &str, description: &str, component_type: ComponentType, cost: i64) -> Component {
+        Component {
+            id: ComponentId::new(id),
+            description: description.to_string(),
+            component_type,
+            uom: "EA".to_string(),
+            standard_cost: Some(Decimal::new(cost, 2)),
+            lead_time_days: None,
+            procurement_type: ProcurementType::Make,
+            preferred_vendor: None,
+            organization: "PLANT-01".to_string(),
+            version: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn create_test_bom_item(parent: &str, child: &str, quantity: Decimal, scrap_factor: Decimal) -> BomItem {
+        BomItem {
+            id: uuid::Uuid::new_v4(),
+            parent_id: ComponentId::new(parent),
+            child_id: ComponentId::new(child),
+            quantity,
+            scrap_factor,
+            sequence: 10,
+            operation_sequence: None,
+            is_phantom: false,
+            effective_from: None,
+            effective_to: None,
+            alternative_group: None,
+            alternative_priority: None,
+            reference_designator: None,
+            position: None,
+            notes: None,
+            version: 0,
+        }
+    }
+
+    #[test]
+    fn test_bicycle_dot_matches_golden_file() {
+        // The bicycle from `bom-cli/example_bom.json`
+        let mut graph = BomGraph::new();
+        graph
+            .add_bom_item(create_test_bom_item("BIKE-001", "FRAME-001", Decimal::ONE, Decimal::ZERO))
+            .unwrap();
+        let mut wheel = create_test_bom_item("BIKE-001", "WHEEL-001", Decimal::from(2), Decimal::new(5, 2));
+        wheel.sequence = 20;
+        graph.add_bom_item(wheel).unwrap();
+        graph
+            .add_bom_item(create_test_bom_item("FRAME-001", "TUBE-001", Decimal::from(4), Decimal::new(1, 1)))
+            .unwrap();
+        graph.identify_roots();
+
+        let components: HashMap<ComponentId, Component> = [
+            create_test_component("BIKE-001", "Bicycle", ComponentType::FinishedProduct, 100000),
+            create_test_component("FRAME-001", "Bike Frame", ComponentType::SemiFinished, 30000),
+            create_test_component("WHEEL-001", "Wheel", ComponentType::SemiFinished, 15000),
+            create_test_component("TUBE-001", "Aluminum \"6061\" Tube", ComponentType::RawMaterial, 5000),
+        ]
+        .into_iter()
+        .map(|component| (component.id.clone(), component))
+        .collect();
+
+        let dot = graph.to_dot(
+            &components,
+            DotOptions {
+                show_description: true,
+                show_cost: true,
+                show_quantity: true,
+                show_scrap: true,
+                color_by_type: true,
+                cluster_by_level: true,
+            },
+        );
+        assert_eq!(dot, include_str!("../testdata/bicycle.dot"));
+
+        // Bare structure without any options
+        let plain = graph.to_dot(&components, DotOptions::default());
+        assert!(plain.contains("    n_BIKE_001 [label=\"BIKE-001\"];\n"));
+        assert!(plain.contains("    n_BIKE_001 -> n_WHEEL_001;\n"));
+        assert!(!plain.contains("subgraph"));
+    }
+
+    #[test]
+    fn test_nodes_without_level_get_their_own_cluster() {
+        // BIKE -> FRAME, plus the cycle LOOP-A -> LOOP-B -> LOOP-A that no root reaches
+        let mut graph = BomGraph::new();
+        graph
+            .add_bom_item(create_test_bom_item("BIKE", "FRAME", Decimal::ONE, Decimal::ZERO))
+            .unwrap();
+        let loop_a = graph.arena_mut().add_node(ComponentId::new("LOOP-A"));
+        let loop_b = graph.arena_mut().add_node(ComponentId::new("LOOP-B"));
+        graph
+            .arena_mut()
+            .add_edge(loop_a, loop_b, create_test_bom_item("LOOP-A", "LOOP-B", Decimal::ONE, Decimal::ZERO));
+        graph
+            .arena_mut()
+            .add_edge(loop_b, loop_a, create_test_bom_item("LOOP-B", "LOOP-A", Decimal::ONE, Decimal::ZERO));
+        graph.identify_roots();
+
+        let options = DotOptions {
+            cluster_by_level: true,
+            ..DotOptions::default()
+        };
+        let dot = graph.to_dot(&HashMap::new(), options);
+        assert!(dot.contains(
+            "    subgraph cluster_level_1 {\n        label=\"Level 1\";\n        n_FRAME [label=\"FRAME\"];\n    }\n"
+        ));
+        assert!(dot.contains(concat!(
+            "    subgraph cluster_no_level {\n        label=\"No level\";\n",
+            "        n_LOOP_A [label=\"LOOP-A\"];\n        n_LOOP_B [label=\"LOOP-B\"];\n    }\n"
+        )));
+        assert!(dot.contains("    n_LOOP_B -> n_LOOP_A;\n"));
+
+        // Without such nodes there is no extra cluster
+        let mut graph = BomGraph::new();
+        graph
+            .add_bom_item(create_test_bom_item("BIKE", "FRAME", Decimal::ONE, Decimal::ZERO))
+            .unwrap();
+        graph.identify_roots();
+        assert!(!graph.to_dot(&HashMap::new(), options).contains("cluster_no_level"));
+    }
+}
@@ -1,14 +1,24 @@
-use crate::arena::{Arena, NodeIndex};
-use bom_core::{BomError, BomItem, BomRepository, ComponentId, Result};
-use std::collections::HashMap;
+use crate::arena::{Arena, EdgeIndex, NodeIndex};
+use crate::cycle::CycleDetector;
+use crate::traversal::{BomVisitor, VisitAction};
+use bom_core::{BomError, BomItem, BomRepository, ComponentId, CostBreakdown, ProcurementType, Result};
+use rust_decimal::Decimal;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet, VecDeque};
+use uuid::Uuid;
 
 /// BOM Graph - main interface for BOM operations
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BomGraph {
     /// Underlying arena storage
     arena: Arena,
 
     /// Root nodes (components that are not children of any other component)
     roots: Vec<NodeIndex>,
+
+    /// Date the BOM items were filtered by when loading (`from_component`), if any
+    effective_date: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl BomGraph {
@@ -17,6 +27,7 @@ impl BomGraph {
         Self {
             arena: Arena::new(),
             roots: Vec::new(),
+            effective_date: None,
         }
     }
 
@@ -25,6 +36,7 @@ impl BomGraph {
         Self {
             arena: Arena::with_capacity(node_capacity, edge_capacity),
             roots: Vec::new(),
+            effective_date: None,
         }
     }
 
@@ -64,9 +76,15 @@ impl BomGraph {
         let mut graph = Self::new();
         graph.load_component_tree(repo, component_id, effective_date)?;
         graph.identify_roots();
+        graph.effective_date = effective_date;
         Ok(graph)
     }
 
+    /// Effective date the graph was built for; `None` if it holds BOM items of all dates
+    pub fn effective_date(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.effective_date
+    }
+
     /// Recursively load a component's BOM tree
     fn load_component_tree<R: BomRepository>(
         &mut self,
@@ -81,12 +99,16 @@ impl BomGraph {
         let items = repo.get_bom_items(component_id, effective_date)?;
 
         for item in items {
+            // Checked before adding the edge, which creates the child node
+            let child_loaded = self.arena.find_node(&item.child_id).is_some();
+            let child_id = item.child_id.clone();
+
             // Add the edge
-            self.add_bom_item(item.clone())?;
+            self.add_bom_item(item)?;
 
             // Recursively load child if not already loaded
-            if self.arena.find_node(&item.child_id).is_none() {
-                self.load_component_tree(repo, &item.child_id, effective_date)?;
+            if !child_loaded {
+                self.load_component_tree(repo, &child_id, effective_date)?;
             }
         }
 
@@ -94,7 +116,16 @@ impl BomGraph {
     }
 
     /// Add a BOM item to the graph
+    /// Invalid items (see `BomItem::validate`) are rejected before any node is created
     pub fn add_bom_item(&mut self, item: BomItem) -> Result<NodeIndex> {
+        let edge = self.insert_edge(item)?;
+        Ok(self.arena.edge(edge).unwrap().source)
+    }
+
+    /// `add_bom_item`, returning the new edge
+    fn insert_edge(&mut self, item: BomItem) -> Result<EdgeIndex> {
+        item.validate()?;
+
         // Create or get parent node
         let parent_node = self.arena.add_node(item.parent_id.clone());
 
@@ -119,19 +150,507 @@ impl BomGraph {
         }
 
         // Add edge
-        self.arena.add_edge(parent_node, child_node, item);
+        let edge = self.arena.add_edge(parent_node, child_node, item);
+        self.invalidate_levels(parent_node);
 
-        Ok(parent_node)
+        Ok(edge)
     }
 
-    /// Identify root nodes (nodes with no incoming edges)
-    fn identify_roots(&mut self) {
-        self.roots.clear();
-        for (idx, node) in self.arena.nodes().iter().enumerate() {
-            if node.incoming.is_empty() {
-                self.roots.push(NodeIndex(idx));
+    /// Forget the precomputed level of `node` and its ancestors (their height may have changed)
+    /// Stops at nodes without a level: their ancestors were already invalidated
+    fn invalidate_levels(&mut self, node: NodeIndex) {
+        let mut stack = vec![node];
+        while let Some(current) = stack.pop() {
+            let Some(n) = self.arena.node_mut(current) else { continue };
+            if n.cache.level.take().is_none() {
+                continue;
+            }
+            stack.extend(self.arena.parents(current).map(|(parent, _)| parent));
+        }
+    }
+
+    /// Find the edge carrying the BOM item with the given ID
+    pub fn find_edge_by_item_id(&self, item_id: &Uuid) -> Option<EdgeIndex> {
+        self.arena
+            .live_edges()
+            .find(|(_, edge)| edge.bom_item.id == *item_id)
+            .map(|(idx, _)| idx)
+    }
+
+    /// Replace the BOM item data of an existing edge (matched by item ID)
+    /// The parent and child of the item must not change
+    pub fn update_bom_item(&mut self, item: BomItem) -> Result<()> {
+        item.validate()?;
+
+        let edge_idx = self
+            .find_edge_by_item_id(&item.id)
+            .ok_or_else(|| BomError::BomItemNotFound(item.id.to_string()))?;
+        self.replace_edge_item(edge_idx, item)
+    }
+
+    /// `update_bom_item` for an already validated item on a known edge
+    fn replace_edge_item(&mut self, edge_idx: EdgeIndex, mut item: BomItem) -> Result<()> {
+        let source = {
+            let edge = self.arena.edge_mut(edge_idx).unwrap();
+            if edge.bom_item.parent_id != item.parent_id || edge.bom_item.child_id != item.child_id {
+                return Err(BomError::RepositoryError(format!(
+                    "BOM item {} cannot change its parent or child",
+                    item.id
+                )));
+            }
+            // Keep sharing the interned IDs
+            item.parent_id = edge.bom_item.parent_id.clone();
+            item.child_id = edge.bom_item.child_id.clone();
+            edge.effective_quantity = item.effective_quantity();
+            edge.bom_item = item;
+            edge.source
+        };
+
+        self.arena.mark_dirty_recursive(source);
+        Ok(())
+    }
+
+    /// Find parent/child pairs connected by more than one BOM item in effect at the same time
+    /// Valid for multi-use references, but usually a duplicate entered by mistake. Items only
+    /// count if their effectivity ranges overlap and they have the same alternative group,
+    /// phantom flag, scrap factor and operation, so revisions replacing each other and
+    /// alternatives are not reported. Items are ordered by sequence; results by parent and child ID
+    pub fn detect_redundant_paths(&self) -> Vec<RedundantPath> {
+        let mut pairs: HashMap<(NodeIndex, NodeIndex), Vec<&BomItem>> = HashMap::new();
+        for (_, edge) in self.arena.live_edges() {
+            pairs.entry((edge.source, edge.target)).or_default().push(&edge.bom_item);
+        }
+
+        let mut redundant: Vec<RedundantPath> = pairs
+            .into_values()
+            .filter(|items| items.len() > 1)
+            .flat_map(redundant_groups)
+            .map(|items| RedundantPath {
+                parent_id: items[0].parent_id.clone(),
+                child_id: items[0].child_id.clone(),
+                total_quantity: items.iter().map(|item| item.quantity).sum(),
+                mergeable: items[1..].iter().all(|item| same_apart_from_quantity(&items[0], item)),
+                items,
+            })
+            .collect();
+
+        redundant.sort_by(|a, b| {
+            a.parent_id
+                .as_str()
+                .cmp(b.parent_id.as_str())
+                .then_with(|| a.child_id.as_str().cmp(b.child_id.as_str()))
+                .then_with(|| a.items[0].sequence.cmp(&b.items[0].sequence))
+        });
+        redundant
+    }
+
+    /// Merge each mergeable group from `detect_redundant_paths` into its first item, summing quantities
+    /// Groups whose items differ in more than quantity and sequence are left alone, since
+    /// merging them would lose data. The other items are removed from the graph (not from the
+    /// repository). Returns the number of removed items
+    pub fn merge_redundant_paths(&mut self) -> Result<usize> {
+        let mut removed = 0;
+
+        for path in self.detect_redundant_paths().into_iter().filter(|path| path.mergeable) {
+            let (first, duplicates) = path.items.split_first().unwrap();
+
+            let mut merged = first.clone();
+            merged.quantity = path.total_quantity;
+            self.update_bom_item(merged)?;
+
+            for duplicate in duplicates {
+                let edge = self
+                    .find_edge_by_item_id(&duplicate.id)
+                    .ok_or_else(|| BomError::BomItemNotFound(duplicate.id.to_string()))?;
+                self.arena.remove_edge(edge)?;
+                removed += 1;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Apply a BOM delta (e.g. a daily PLM export) without rebuilding the graph
+    /// Removals are applied first. An item whose ID is already in the graph replaces the
+    /// existing one; other items are added. Items that would create a cycle are skipped and
+    /// counted in `cycles_prevented`. Unknown removal IDs are ignored. Invalid items are
+    /// rejected before anything changes. Roots are re-identified afterwards
+    pub fn import_and_merge(&mut self, new_items: Vec<BomItem>, removed_item_ids: Vec<Uuid>) -> Result<MergeResult> {
+        for item in &new_items {
+            item.validate()?;
+        }
+
+        let mut result = MergeResult::default();
+        let mut edges_by_item: HashMap<Uuid, EdgeIndex> = self
+            .arena
+            .live_edges()
+            .map(|(idx, edge)| (edge.bom_item.id, idx))
+            .collect();
+
+        for item_id in &removed_item_ids {
+            if let Some(edge) = edges_by_item.remove(item_id) {
+                self.remove_edge_and_invalidate(edge)?;
+                result.removed += 1;
+            }
+        }
+
+        for item in new_items {
+            let item_id = item.id;
+            let Some(&edge) = edges_by_item.get(&item_id) else {
+                match self.insert_edge(item) {
+                    Ok(edge) => {
+                        edges_by_item.insert(item_id, edge);
+                        result.added += 1;
+                    }
+                    Err(BomError::CircularDependency(_)) => result.cycles_prevented += 1,
+                    Err(e) => return Err(e),
+                }
+                continue;
+            };
+
+            let existing = &self.arena.edge(edge).unwrap().bom_item;
+            if existing.parent_id == item.parent_id && existing.child_id == item.child_id {
+                self.replace_edge_item(edge, item)?;
+                result.modified += 1;
+                continue;
+            }
+
+            // Moved to another parent or child: re-add, restoring the old item on a cycle
+            let previous = self.remove_edge_and_invalidate(edge)?;
+            match self.insert_edge(item) {
+                Ok(edge) => {
+                    edges_by_item.insert(item_id, edge);
+                    result.modified += 1;
+                }
+                Err(BomError::CircularDependency(_)) => {
+                    let edge = self.insert_edge(previous)?;
+                    edges_by_item.insert(item_id, edge);
+                    result.cycles_prevented += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        self.identify_roots();
+        Ok(result)
+    }
+
+    /// BOM item changes from `old` to `new` (the basis of ECO impact analysis)
+    /// Items are matched by parent and child; several items between the same pair are
+    /// matched in sequence order and the surplus counts as added or removed. A matched
+    /// item is modified if its quantity or scrap factor changed. Each list is sorted by
+    /// parent and child ID
+    pub fn diff(old: &BomGraph, new: &BomGraph) -> GraphDiff {
+        let by_pair = |graph: &BomGraph| {
+            let mut pairs: BTreeMap<(String, String), Vec<BomItem>> = BTreeMap::new();
+            for (_, _, item) in graph.iter_edges() {
+                let key = (item.parent_id.as_str().to_string(), item.child_id.as_str().to_string());
+                pairs.entry(key).or_default().push(item.clone());
+            }
+            for items in pairs.values_mut() {
+                items.sort_by_key(|item| item.sequence);
+            }
+            pairs
+        };
+        let mut old_pairs = by_pair(old);
+        let mut new_pairs = by_pair(new);
+
+        let mut keys: Vec<(String, String)> = old_pairs.keys().chain(new_pairs.keys()).cloned().collect();
+        keys.sort();
+        keys.dedup();
+
+        let mut diff = GraphDiff::default();
+        for key in keys {
+            let old_items = old_pairs.remove(&key).unwrap_or_default();
+            let mut new_items = new_pairs.remove(&key).unwrap_or_default().into_iter();
+
+            for old_item in old_items {
+                match new_items.next() {
+                    Some(new_item) => {
+                        if old_item.quantity != new_item.quantity || old_item.scrap_factor != new_item.scrap_factor {
+                            diff.modified_edges.push(BomItemChange::between(Some(&old_item), Some(&new_item)));
+                        }
+                    }
+                    None => diff.removed_edges.push(BomItemChange::between(Some(&old_item), None)),
+                }
+            }
+            for new_item in new_items {
+                diff.added_edges.push(BomItemChange::between(None, Some(&new_item)));
+            }
+        }
+
+        diff
+    }
+
+    /// Remove the BOM items linking `parent_id` to `child_id` (all of them if there are several)
+    /// Both components stay in the graph; roots are re-identified afterwards
+    pub fn remove_bom_item(&mut self, parent_id: &ComponentId, child_id: &ComponentId) -> Result<()> {
+        let not_found = || BomError::BomItemNotFound(format!("{} -> {}", parent_id.as_str(), child_id.as_str()));
+        let (Some(parent), Some(child)) = (self.find_node(parent_id), self.find_node(child_id)) else {
+            return Err(not_found());
+        };
+
+        let edges: Vec<EdgeIndex> = self
+            .arena
+            .node(parent)
+            .map(|node| node.outgoing.clone())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|&edge| self.arena.edge(edge).is_some_and(|edge| edge.target == child))
+            .collect();
+        if edges.is_empty() {
+            return Err(not_found());
+        }
+
+        for edge in edges {
+            self.remove_edge_and_invalidate(edge)?;
+        }
+        self.identify_roots();
+        Ok(())
+    }
+
+    /// Remove an edge and forget the levels it affected, returning its BOM item
+    fn remove_edge_and_invalidate(&mut self, edge: EdgeIndex) -> Result<BomItem> {
+        let removed = self
+            .arena
+            .edge(edge)
+            .cloned()
+            .ok_or_else(|| BomError::ComponentNotFound(format!("edge {}", edge.0)))?;
+        self.arena.remove_edge(edge)?;
+        self.invalidate_levels(removed.source);
+        Ok(removed.bom_item)
+    }
+
+    /// Minimum spanning arborescence of `root_id`'s BOM: for every component below the root,
+    /// only its cheapest incoming BOM item is kept
+    /// Edges are weighted `child_cost * quantity` with costs from `cost_map` (missing = 0), so
+    /// in a diamond the more expensive way of reaching the shared part is cut. Every component
+    /// of the BOM stays reachable from the root, through `node_count - 1` edges (the graph is
+    /// acyclic, so the cheapest incoming edges always form a tree). Components outside the
+    /// root's BOM are left out. A suggestion for restructuring; `self` is not changed
+    pub fn minimum_spanning_tree(
+        &self,
+        root_id: &ComponentId,
+        cost_map: &HashMap<ComponentId, Decimal>,
+    ) -> Result<BomGraph> {
+        let root = self.require_node(root_id)?;
+        let weight = |item: &BomItem| cost_map.get(&item.child_id).copied().unwrap_or(Decimal::ZERO) * item.quantity;
+
+        // The root's BOM, in breadth-first order
+        let mut reachable = HashSet::from([root]);
+        let mut order = vec![root];
+        let mut next = 0;
+        while let Some(&current) = order.get(next) {
+            next += 1;
+            for (child, _) in self.arena.children(current) {
+                if reachable.insert(child) {
+                    order.push(child);
+                }
+            }
+        }
+
+        let mut tree = BomGraph::with_capacity(order.len(), order.len().saturating_sub(1));
+        for &node in &order {
+            tree.arena.add_node(self.arena.node(node).unwrap().component_id.clone());
+        }
+        for &node in &order[1..] {
+            // First of equally cheap items wins
+            let cheapest = self
+                .arena
+                .parents(node)
+                .filter(|(parent, _)| reachable.contains(parent))
+                .map(|(_, edge)| &edge.bom_item)
+                .min_by_key(|item| weight(item))
+                .unwrap();
+
+            let tree_parent = tree.arena.add_node(cheapest.parent_id.clone());
+            let tree_child = tree.arena.add_node(cheapest.child_id.clone());
+            tree.arena.add_edge(tree_parent, tree_child, cheapest.clone());
+        }
+
+        tree.identify_roots();
+        Ok(tree)
+    }
+
+    /// Copy of the graph with phantom assemblies bypassed
+    /// Each phantom item `parent -> phantom` is replaced by edges from the parent to the
+    /// phantom's children, with quantities multiplied through (nested phantoms included).
+    /// A component used only as a phantom disappears; one also used normally is kept
+    pub fn contract_phantoms(&self) -> BomGraph {
+        let mut contracted = BomGraph::with_capacity(self.arena.node_count(), self.arena.edge_count());
+
+        let kept = |node: &crate::arena::Node| {
+            node.incoming.is_empty()
+                || node
+                    .incoming
+                    .iter()
+                    .filter_map(|&edge| self.arena.edge(edge))
+                    .any(|edge| !edge.bom_item.is_phantom)
+        };
+
+        for (_, node) in self.arena.live_nodes().filter(|(_, node)| kept(node)) {
+            contracted.arena.add_node(node.component_id.clone());
+        }
+
+        for (parent, node) in self.arena.live_nodes().filter(|(_, node)| kept(node)) {
+            let mut items = Vec::new();
+            for (_, edge) in self.arena.children(parent) {
+                self.expand_phantom_item(edge.bom_item.clone(), &mut items);
+            }
+
+            let parent_node = contracted.arena.add_node(node.component_id.clone());
+            for item in items {
+                let child_node = contracted.arena.add_node(item.child_id.clone());
+                contracted.arena.add_edge(parent_node, child_node, item);
+            }
+        }
+
+        contracted.identify_roots();
+        contracted
+    }
+
+    /// Split the graph into in-house manufacturing and procurement (自製/採購)
+    /// The make subgraph holds `Make` components and the items between them; the buy
+    /// subgraph holds `Buy` components as unconnected leaves. `Both` components appear
+    /// in both subgraphs, so planning can treat them either way
+    pub fn partition_by_procurement_type<R: BomRepository>(&self, repo: &R) -> Result<BomPartition> {
+        let ids: Vec<ComponentId> = self.iter_nodes().map(|(_, id)| id.clone()).collect();
+        let mut partition = BomPartition {
+            make_components: HashSet::new(),
+            buy_components: HashSet::new(),
+            both_components: HashSet::new(),
+            make_subgraph: BomGraph::new(),
+            buy_subgraph: BomGraph::new(),
+        };
+
+        for component in repo.get_components(&ids)? {
+            match component.procurement_type {
+                ProcurementType::Make => {
+                    partition.make_subgraph.arena.add_node(component.id.clone());
+                    partition.make_components.insert(component.id);
+                }
+                ProcurementType::Buy => {
+                    partition.buy_subgraph.arena.add_node(component.id.clone());
+                    partition.buy_components.insert(component.id);
+                }
+                ProcurementType::Both => {
+                    partition.make_subgraph.arena.add_node(component.id.clone());
+                    partition.buy_subgraph.arena.add_node(component.id.clone());
+                    partition.both_components.insert(component.id);
+                }
+            }
+        }
+
+        let is_made =
+            |id: &ComponentId| partition.make_components.contains(id) || partition.both_components.contains(id);
+        let made_items: Vec<BomItem> = self
+            .iter_edges()
+            .map(|(_, _, item)| item)
+            .filter(|item| is_made(&item.parent_id) && is_made(&item.child_id))
+            .cloned()
+            .collect();
+
+        let make_arena = &mut partition.make_subgraph.arena;
+        for item in made_items {
+            let parent = make_arena.add_node(item.parent_id.clone());
+            let child = make_arena.add_node(item.child_id.clone());
+            make_arena.add_edge(parent, child, item);
+        }
+        partition.make_subgraph.identify_roots();
+        partition.buy_subgraph.identify_roots();
+
+        Ok(partition)
+    }
+
+    /// Push `item`, or if it is a phantom, its child's items re-parented through it
+    fn expand_phantom_item(&self, item: BomItem, items: &mut Vec<BomItem>) {
+        if !item.is_phantom {
+            items.push(item);
+            return;
+        }
+        let Some(phantom) = self.arena.find_node(&item.child_id) else {
+            return;
+        };
+
+        for (_, edge) in self.arena.children(phantom) {
+            let child = &edge.bom_item;
+            let effective_from = item.effective_from.max(child.effective_from);
+            let effective_to = match (item.effective_to, child.effective_to) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+            // Never effective: the phantom and child validity do not overlap
+            if matches!((effective_from, effective_to), (Some(from), Some(to)) if from > to) {
+                continue;
             }
+
+            self.expand_phantom_item(
+                BomItem {
+                    id: Uuid::new_v4(),
+                    parent_id: item.parent_id.clone(),
+                    quantity: item.effective_quantity() * child.quantity,
+                    effective_from,
+                    effective_to,
+                    ..child.clone()
+                },
+                items,
+            );
+        }
+    }
+
+    /// Add a node above all current roots (quantity 1 each, sequences 10, 20, ... by root ID)
+    /// so several products can be analyzed as one graph, e.g. a product family. The virtual
+    /// root has no component in the repository and becomes the graph's only root
+    pub fn add_virtual_root(&mut self, virtual_id: &ComponentId) -> Result<NodeIndex> {
+        if self.find_node(virtual_id).is_some() {
+            return Err(BomError::InvalidBomItem(format!(
+                "virtual root {} is already a component of the graph",
+                virtual_id.as_str()
+            )));
+        }
+
+        let mut roots: Vec<ComponentId> = self.iter_root_nodes().map(|(_, id)| id.clone()).collect();
+        roots.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        let virtual_root = self.arena.add_node(virtual_id.clone());
+        for (position, root) in roots.into_iter().enumerate() {
+            self.add_bom_item(BomItem {
+                id: Uuid::new_v4(),
+                parent_id: virtual_id.clone(),
+                child_id: root,
+                quantity: Decimal::ONE,
+                scrap_factor: Decimal::ZERO,
+                sequence: 10 * (position as u32 + 1),
+                operation_sequence: None,
+                is_phantom: false,
+                effective_from: None,
+                effective_to: None,
+                alternative_group: None,
+                alternative_priority: None,
+                reference_designator: None,
+                position: None,
+                notes: None,
+                version: 0,
+            })?;
         }
+
+        self.identify_roots();
+        Ok(virtual_root)
+    }
+
+    /// Identify root nodes (nodes with no incoming edges)
+    pub(crate) fn identify_roots(&mut self) {
+        self.roots = self.iter_root_nodes().map(|(idx, _)| idx).collect();
+    }
+
+    /// Record a new root without rescanning every node (for graphs built up incrementally)
+    pub(crate) fn push_root(&mut self, node: NodeIndex) {
+        self.roots.push(node);
+    }
+
+    /// Drop a node that got a parent from the roots
+    pub(crate) fn remove_root(&mut self, node: NodeIndex) {
+        self.roots.retain(|&root| root != node);
     }
 
     /// Get the underlying arena
@@ -149,6 +668,36 @@ impl BomGraph {
         &self.roots
     }
 
+    /// Iterate over all nodes
+    /// Runs in parallel with rayon's `par_bridge()`
+    pub fn iter_nodes(&self) -> impl Iterator<Item = (NodeIndex, &ComponentId)> + '_ {
+        self.arena.live_nodes().map(|(idx, node)| (idx, &node.component_id))
+    }
+
+    /// Iterate over all edges as (parent, child, BOM item)
+    pub fn iter_edges(&self) -> impl Iterator<Item = (NodeIndex, NodeIndex, &BomItem)> + '_ {
+        self.arena
+            .live_edges()
+            .map(|(_, edge)| (edge.source, edge.target, &edge.bom_item))
+    }
+
+    /// Iterate over nodes without parents
+    /// Unlike `roots()`, this reflects edges added since the graph was built
+    pub fn iter_root_nodes(&self) -> impl Iterator<Item = (NodeIndex, &ComponentId)> + '_ {
+        self.arena
+            .live_nodes()
+            .filter(|(_, node)| node.incoming.is_empty())
+            .map(|(idx, node)| (idx, &node.component_id))
+    }
+
+    /// Iterate over nodes without children
+    pub fn iter_leaf_nodes(&self) -> impl Iterator<Item = (NodeIndex, &ComponentId)> + '_ {
+        self.arena
+            .live_nodes()
+            .filter(|(_, node)| node.outgoing.is_empty())
+            .map(|(idx, node)| (idx, &node.component_id))
+    }
+
     /// Find node by component ID
     pub fn find_node(&self, component_id: &ComponentId) -> Option<NodeIndex> {
         self.arena.find_node(component_id)
@@ -164,6 +713,119 @@ impl BomGraph {
         }
     }
 
+    /// Estimated additional heap bytes for adding nodes and edges, for capacity planning
+    /// Counts the node and edge slots, one adjacency entry on each end of an edge and the
+    /// interned ID of each node (hash tables stay at most 7/8 full, small vectors hold at
+    /// least four `u32`s); ignores the length of component ID strings and spare capacity
+    /// left by vector growth
+    pub fn estimate_memory_for(&self, additional_nodes: usize, additional_edges: usize) -> usize {
+        use std::mem::size_of;
+
+        let interned = size_of::<ComponentId>()
+            + (size_of::<(u64, Vec<u32>)>() + 1) * 8 / 7
+            + 4 * size_of::<u32>()
+            + size_of::<Option<NodeIndex>>();
+        let per_node = size_of::<crate::arena::Node>() + interned;
+        let per_edge = size_of::<crate::arena::Edge>() + 2 * size_of::<EdgeIndex>();
+        additional_nodes * per_node + additional_edges * per_edge
+    }
+
+    /// Allocated versus used node and edge slots
+    pub fn capacity(&self) -> GraphCapacity {
+        let node_capacity = self.arena.node_capacity();
+        let edge_capacity = self.arena.edge_capacity();
+        let current_nodes = self.arena.node_count();
+        let current_edges = self.arena.edge_count();
+        let total_capacity = node_capacity + edge_capacity;
+
+        GraphCapacity {
+            node_capacity,
+            edge_capacity,
+            current_nodes,
+            current_edges,
+            load_factor: if total_capacity == 0 {
+                0.0
+            } else {
+                (current_nodes + current_edges) as f64 / total_capacity as f64
+            },
+        }
+    }
+
+    /// Betweenness centrality of every component (Brandes' algorithm adapted for DAGs)
+    /// The share of shortest parent-to-descendant paths between other components that pass
+    /// through a component, normalized by `(n-1)*(n-2)`; high values mark single points of
+    /// failure shared by many assemblies. Instead of a breadth-first search per source, one
+    /// topological order settles the shortest paths from every source in a single linear pass
+    /// over the nodes after it, so the total is O(V·(V+E)) without queues or hashing. Components
+    /// on a cycle (only possible in arenas built by hand) are left out of the order and score 0
+    pub fn betweenness_centrality(&self) -> HashMap<ComponentId, f64> {
+        let roots: Vec<NodeIndex> = self.iter_root_nodes().map(|(node, _)| node).collect();
+        // Parents before children
+        let order: Vec<NodeIndex> = crate::traversal::topological_sort(&self.arena, &roots)
+            .into_iter()
+            .rev()
+            .collect();
+
+        let slots = self.arena.nodes().len();
+        let mut centrality = vec![0.0; slots];
+        let mut distance: Vec<Option<usize>> = vec![None; slots];
+        let mut sigma = vec![0.0; slots];
+        let mut delta = vec![0.0; slots];
+        let mut predecessors: Vec<Vec<NodeIndex>> = vec![Vec::new(); slots];
+
+        for (position, &source) in order.iter().enumerate() {
+            // Count shortest paths (sigma) and their predecessors; every parent of a node comes
+            // before it in the order, so its distance is final by the time it is reached
+            distance[source.0] = Some(0);
+            sigma[source.0] = 1.0;
+            let mut reached = Vec::new();
+            for &node in &order[position..] {
+                let Some(node_distance) = distance[node.0] else { continue };
+                reached.push(node);
+                let next = node_distance + 1;
+                for (child, _) in self.arena.children(node) {
+                    match distance[child.0] {
+                        Some(child_distance) if child_distance < next => {}
+                        Some(child_distance) if child_distance == next => {
+                            sigma[child.0] += sigma[node.0];
+                            predecessors[child.0].push(node);
+                        }
+                        _ => {
+                            distance[child.0] = Some(next);
+                            sigma[child.0] = sigma[node.0];
+                            predecessors[child.0] = vec![node];
+                        }
+                    }
+                }
+            }
+
+            // Accumulate dependencies from the bottom back up to the source
+            for &node in reached.iter().rev() {
+                for &predecessor in &predecessors[node.0] {
+                    delta[predecessor.0] += sigma[predecessor.0] / sigma[node.0] * (1.0 + delta[node.0]);
+                }
+                if node != source {
+                    centrality[node.0] += delta[node.0];
+                }
+            }
+
+            for node in reached {
+                distance[node.0] = None;
+                sigma[node.0] = 0.0;
+                delta[node.0] = 0.0;
+                predecessors[node.0].clear();
+            }
+        }
+
+        let nodes: Vec<(NodeIndex, &ComponentId)> = self.iter_nodes().collect();
+        let n = nodes.len() as f64;
+        let scale = if nodes.len() > 2 { 1.0 / ((n - 1.0) * (n - 2.0)) } else { 0.0 };
+        nodes
+            .into_iter()
+            .map(|(node, id)| (id.clone(), centrality[node.0] * scale))
+            .collect()
+    }
+
     /// Calculate maximum depth of the graph
     fn calculate_max_depth(&self) -> usize {
         let mut max_depth = 0;
@@ -197,143 +859,1941 @@ impl BomGraph {
         depth
     }
 
-    /// Clear all cached computation results
-    pub fn clear_cache(&mut self) {
-        let node_count = self.arena.nodes().len();
-        for idx in 0..node_count {
-            if let Some(node) = self.arena.node_mut(NodeIndex(idx)) {
-                node.cache = crate::arena::NodeCache::default();
+    /// Breadth-first walk from `start_id`, letting `visitor` prune branches or stop early
+    /// Each node is visited once, at its shortest distance from the start
+    pub fn bfs_visit<V: BomVisitor>(&self, start_id: &ComponentId, visitor: &mut V) -> Result<()> {
+        let start = self.require_node(start_id)?;
+
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([(start, 0)]);
+
+        while let Some((current, level)) = queue.pop_front() {
+            let Some(node) = self.arena.node(current) else {
+                continue;
+            };
+
+            match visitor.visit_node(&node.component_id, level) {
+                VisitAction::Continue => {}
+                VisitAction::Skip => continue,
+                VisitAction::Stop => return Ok(()),
             }
-        }
-    }
 
-    /// Mark a component and its ancestors as dirty for incremental computation
-    pub fn mark_dirty(&mut self, component_id: &ComponentId) -> Result<()> {
-        let node = self
-            .find_node(component_id)
-            .ok_or_else(|| BomError::ComponentNotFound(component_id.as_str().to_string()))?;
+            for (child, edge) in self.arena.children(current) {
+                let item = &edge.bom_item;
+                match visitor.visit_edge(&item.parent_id, &item.child_id, item) {
+                    VisitAction::Continue => {}
+                    VisitAction::Skip => continue,
+                    VisitAction::Stop => return Ok(()),
+                }
+
+                if visited.insert(child) {
+                    queue.push_back((child, level + 1));
+                }
+            }
+        }
 
-        self.arena.mark_dirty_recursive(node);
         Ok(())
     }
-}
 
-impl Default for BomGraph {
-    fn default() -> Self {
-        Self::new()
+    /// Whether `to_id` is reachable from `from_id` (`None` if either component is not in the graph)
+    pub fn path_exists(&self, from_id: &ComponentId, to_id: &ComponentId) -> Option<bool> {
+        let from = self.find_node(from_id)?;
+        let to = self.find_node(to_id)?;
+        Some(self.arena.has_path(from, to))
     }
-}
 
-/// Graph statistics
-#[derive(Debug, Clone)]
-pub struct GraphStats {
-    pub node_count: usize,
-    pub edge_count: usize,
-    pub root_count: usize,
-    pub max_depth: usize,
-}
+    /// Path from `from_id` to `to_id` with the fewest hops (BFS), or `None` if unreachable
+    pub fn shortest_path(&self, from_id: &ComponentId, to_id: &ComponentId) -> Result<Option<Vec<ComponentId>>> {
+        let from = self.require_node(from_id)?;
+        let to = self.require_node(to_id)?;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use bom_core::repository::memory::InMemoryRepository;
-    use bom_core::*;
-    use chrono::Utc;
-    use rust_decimal::Decimal;
+        let mut predecessor: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        let mut found = from == to;
 
-    fn create_test_component(id: &str, comp_type: ComponentType) -> Component {
-        Component {
-            id: ComponentId::new(id),
-            description: format!("Component {}", id),
-            component_type: comp_type,
-            uom: "EA".to_string(),
-            standard_cost: Some(Decimal::from(100)),
-            lead_time_days: Some(7),
-            procurement_type: ProcurementType::Make,
-            organization: "ORG01".to_string(),
-            version: 0,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
+        while let Some(current) = queue.pop_front() {
+            if found {
+                break;
+            }
+            for (child, _) in self.arena.children(current) {
+                if child == from || predecessor.contains_key(&child) {
+                    continue;
+                }
+                predecessor.insert(child, current);
+                if child == to {
+                    found = true;
+                    break;
+                }
+                queue.push_back(child);
+            }
         }
-    }
 
-    fn create_test_bom_item(parent: &str, child: &str, qty: i32) -> BomItem {
-        BomItem {
-            id: uuid::Uuid::new_v4(),
-            parent_id: ComponentId::new(parent),
-            child_id: ComponentId::new(child),
-            quantity: Decimal::from(qty),
-            scrap_factor: Decimal::ZERO,
-            sequence: 10,
-            operation_sequence: None,
-            is_phantom: false,
-            effective_from: None,
-            effective_to: None,
-            alternative_group: None,
-            alternative_priority: None,
-            reference_designator: None,
-            position: None,
-            notes: None,
-            version: 0,
+        if !found {
+            return Ok(None);
+        }
+
+        let mut path = vec![to];
+        let mut current = to;
+        while current != from {
+            current = predecessor[&current];
+            path.push(current);
         }
+        path.reverse();
+
+        Ok(Some(self.component_path(&path)))
     }
 
-    #[test]
-    fn test_simple_bom_graph() {
-        let repo = InMemoryRepository::new();
+    /// Path from `from_id` to `to_id` with the lowest cumulative cost (Dijkstra), or `None` if unreachable
+    /// Each edge weighs `child total_cost * effective quantity`; components missing from
+    /// `cost_map` cost nothing. Returns the path and its total weight
+    pub fn minimum_cost_path(
+        &self,
+        from_id: &ComponentId,
+        to_id: &ComponentId,
+        cost_map: &HashMap<ComponentId, CostBreakdown>,
+    ) -> Result<Option<(Vec<ComponentId>, Decimal)>> {
+        let from = self.require_node(from_id)?;
+        let to = self.require_node(to_id)?;
 
-        // A -> B (qty 2)
-        // A -> C (qty 1)
-        repo.add_component(create_test_component("A", ComponentType::FinishedProduct));
-        repo.add_component(create_test_component("B", ComponentType::RawMaterial));
-        repo.add_component(create_test_component("C", ComponentType::RawMaterial));
+        let mut distance: HashMap<NodeIndex, Decimal> = HashMap::from([(from, Decimal::ZERO)]);
+        let mut predecessor: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+        let mut heap = BinaryHeap::from([Reverse((Decimal::ZERO, from.0))]);
 
-        repo.add_bom_item(create_test_bom_item("A", "B", 2));
-        repo.add_bom_item(create_test_bom_item("A", "C", 1));
+        while let Some(Reverse((cost, index))) = heap.pop() {
+            let current = NodeIndex(index);
+            if current == to {
+                break;
+            }
+            if distance.get(&current).is_some_and(|&best| cost > best) {
+                // Stale entry, a cheaper route was found meanwhile
+                continue;
+            }
 
-        let graph = BomGraph::from_repository(&repo).unwrap();
+            for (child, edge) in self.arena.children(current) {
+                let child_cost = cost_map
+                    .get(&edge.bom_item.child_id)
+                    .map(|breakdown| breakdown.total_cost)
+                    .unwrap_or(Decimal::ZERO);
+                let next = cost + child_cost * edge.effective_quantity;
 
-        let stats = graph.stats();
-        assert_eq!(stats.node_count, 3);
-        assert_eq!(stats.edge_count, 2);
-        assert_eq!(stats.root_count, 1);
-    }
+                if distance.get(&child).is_none_or(|&best| next < best) {
+                    distance.insert(child, next);
+                    predecessor.insert(child, current);
+                    heap.push(Reverse((next, child.0)));
+                }
+            }
+        }
 
-    #[test]
-    fn test_circular_dependency_detection() {
-        let mut graph = BomGraph::new();
+        let Some(&total) = distance.get(&to) else {
+            return Ok(None);
+        };
 
-        // Try to create A -> B -> A (circular)
-        let item1 = create_test_bom_item("A", "B", 1);
-        let item2 = create_test_bom_item("B", "A", 1);
+        let mut path = vec![to];
+        let mut current = to;
+        while current != from {
+            current = predecessor[&current];
+            path.push(current);
+        }
+        path.reverse();
 
-        graph.add_bom_item(item1).unwrap();
-        let result = graph.add_bom_item(item2);
+        Ok(Some((self.component_path(&path), total)))
+    }
 
-        assert!(matches!(result, Err(BomError::CircularDependency(_))));
+    /// All paths from `from_id` to `to_id`
+    pub fn all_paths_between(&self, from_id: &ComponentId, to_id: &ComponentId) -> Result<Vec<Vec<ComponentId>>> {
+        let from = self.require_node(from_id)?;
+        let to = self.require_node(to_id)?;
+
+        Ok(crate::traversal::find_all_paths(&self.arena, from, to)
+            .iter()
+            .map(|path| self.component_path(path))
+            .collect())
     }
 
-    #[test]
-    fn test_multilevel_bom() {
+    /// All paths from `from_id` to `to_id` with the quantity of `to_id` each one contributes
+    /// The quantity is the product of the effective quantities (scrap included) along the
+    /// path; several items between the same pair are summed. The path quantities add up to
+    /// the total quantity of `to_id` per unit of `from_id`
+    pub fn calculate_path_quantities(
+        &self,
+        from_id: &ComponentId,
+        to_id: &ComponentId,
+    ) -> Result<Vec<PathWithQuantity>> {
+        let from = self.require_node(from_id)?;
+        let to = self.require_node(to_id)?;
+
+        Ok(crate::traversal::find_all_paths(&self.arena, from, to)
+            .iter()
+            .map(|path| PathWithQuantity {
+                quantity: path
+                    .windows(2)
+                    .map(|pair| {
+                        self.arena
+                            .children(pair[0])
+                            .filter(|(child, _)| *child == pair[1])
+                            .map(|(_, edge)| edge.effective_quantity)
+                            .sum::<Decimal>()
+                    })
+                    .product(),
+                path: self.component_path(path),
+            })
+            .collect())
+    }
+
+    /// Root assemblies that directly or indirectly contain any of `component_ids`
+    /// Uses one upward BFS from all inputs; an input without parents is its own root
+    pub fn find_reachable_roots(&self, component_ids: &[ComponentId]) -> Result<HashSet<ComponentId>> {
+        let mut visited: HashSet<NodeIndex> = HashSet::new();
+        let mut queue: VecDeque<NodeIndex> = VecDeque::new();
+        for component_id in component_ids {
+            let node = self.require_node(component_id)?;
+            if visited.insert(node) {
+                queue.push_back(node);
+            }
+        }
+
+        let mut roots = HashSet::new();
+        while let Some(current) = queue.pop_front() {
+            let mut has_parent = false;
+            for (parent, _) in self.arena.parents(current) {
+                has_parent = true;
+                if visited.insert(parent) {
+                    queue.push_back(parent);
+                }
+            }
+            if !has_parent {
+                if let Some(node) = self.arena.node(current) {
+                    roots.insert(node.component_id.clone());
+                }
+            }
+        }
+
+        Ok(roots)
+    }
+
+    /// Like `find_reachable_roots`, but also lists which inputs each root contains
+    /// Inputs are listed in the order they were given
+    pub fn find_reachable_roots_with_coverage(
+        &self,
+        component_ids: &[ComponentId],
+    ) -> Result<HashMap<ComponentId, Vec<ComponentId>>> {
+        // Input positions that reach each node, propagated upward until nothing changes
+        let mut covered: HashMap<NodeIndex, HashSet<usize>> = HashMap::new();
+        let mut queue: VecDeque<NodeIndex> = VecDeque::new();
+        for (position, component_id) in component_ids.iter().enumerate() {
+            let node = self.require_node(component_id)?;
+            covered.entry(node).or_default().insert(position);
+            queue.push_back(node);
+        }
+
+        while let Some(current) = queue.pop_front() {
+            let inputs = covered[&current].clone();
+            for (parent, _) in self.arena.parents(current) {
+                let parent_inputs = covered.entry(parent).or_default();
+                let before = parent_inputs.len();
+                parent_inputs.extend(&inputs);
+                if parent_inputs.len() > before {
+                    queue.push_back(parent);
+                }
+            }
+        }
+
+        let mut coverage = HashMap::new();
+        for (node, inputs) in covered {
+            if self.arena.parents(node).next().is_some() {
+                continue;
+            }
+            let Some(root) = self.arena.node(node) else { continue };
+
+            let mut positions: Vec<usize> = inputs.into_iter().collect();
+            positions.sort_unstable();
+            let mut covered_ids: Vec<ComponentId> = Vec::new();
+            for position in positions {
+                if !covered_ids.contains(&component_ids[position]) {
+                    covered_ids.push(component_ids[position].clone());
+                }
+            }
+            coverage.insert(root.component_id.clone(), covered_ids);
+        }
+
+        Ok(coverage)
+    }
+
+    fn require_node(&self, component_id: &ComponentId) -> Result<NodeIndex> {
+        self.find_node(component_id)
+            .ok_or_else(|| BomError::ComponentNotFound(component_id.as_str().to_string()))
+    }
+
+    fn component_path(&self, path: &[NodeIndex]) -> Vec<ComponentId> {
+        path.iter()
+            .filter_map(|&idx| self.arena.node(idx).map(|node| node.component_id.clone()))
+            .collect()
+    }
+
+    /// All components that (directly or indirectly) use `component_id`, nearest first
+    pub fn ancestors(&self, component_id: &ComponentId) -> Result<Vec<ComponentId>> {
+        let start = self.require_node(component_id)?;
+
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        let mut ancestors = Vec::new();
+
+        while let Some(current) = queue.pop_front() {
+            for (parent, _) in self.arena.parents(current) {
+                if visited.insert(parent) {
+                    ancestors.push(parent);
+                    queue.push_back(parent);
+                }
+            }
+        }
+
+        Ok(self.component_path(&ancestors))
+    }
+
+    /// What deleting `component_id` would break
+    /// `subtree_size` counts the component itself and everything below it. The component
+    /// has an alternative if a parent lists another item in the same alternative group
+    pub fn node_removal_impact(&self, component_id: &ComponentId) -> Result<RemovalImpact> {
+        let node = self.require_node(component_id)?;
+
+        let mut parents: Vec<NodeIndex> = Vec::new();
+        let mut alternative_exists = false;
+        for (parent, edge) in self.arena.parents(node) {
+            if !parents.contains(&parent) {
+                parents.push(parent);
+            }
+            if let Some(group) = &edge.bom_item.alternative_group {
+                alternative_exists |= self.arena.children(parent).any(|(_, sibling)| {
+                    sibling.bom_item.id != edge.bom_item.id
+                        && sibling.bom_item.child_id != *component_id
+                        && sibling.bom_item.alternative_group.as_ref() == Some(group)
+                });
+            }
+        }
+
+        let mut direct_parents_affected = self.component_path(&parents);
+        direct_parents_affected.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        let mut root_products_affected: Vec<ComponentId> = self
+            .find_reachable_roots(std::slice::from_ref(component_id))?
+            .into_iter()
+            .filter(|root| root != component_id)
+            .collect();
+        root_products_affected.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        let mut subtree = HashSet::from([node]);
+        let mut stack = vec![node];
+        while let Some(current) = stack.pop() {
+            for (child, _) in self.arena.children(current) {
+                if subtree.insert(child) {
+                    stack.push(child);
+                }
+            }
+        }
+
+        Ok(RemovalImpact {
+            is_shared: parents.len() > 1,
+            direct_parents_affected,
+            root_products_affected,
+            subtree_size: subtree.len(),
+            alternative_exists,
+        })
+    }
+
+    /// Group all nodes into connected components, ignoring edge direction
+    /// Components are ordered by their first node; nodes keep insertion order
+    pub fn connected_components(&self) -> Vec<Vec<ComponentId>> {
+        let node_count = self.arena.nodes().len();
+        let mut parent: Vec<usize> = (0..node_count).collect();
+
+        for (_, edge) in self.arena.live_edges() {
+            let a = find_set(&mut parent, edge.source.0);
+            let b = find_set(&mut parent, edge.target.0);
+            if a != b {
+                parent[a.max(b)] = a.min(b);
+            }
+        }
+
+        let mut group_of_set: HashMap<usize, usize> = HashMap::new();
+        let mut groups: Vec<Vec<ComponentId>> = Vec::new();
+
+        for (idx, node) in self.arena.live_nodes() {
+            let set = find_set(&mut parent, idx.0);
+            let group = *group_of_set.entry(set).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(node.component_id.clone());
+        }
+
+        groups
+    }
+
+    /// The connected component with the most nodes (empty for an empty graph)
+    pub fn largest_connected_component(&self) -> Vec<ComponentId> {
+        self.connected_components()
+            .into_iter()
+            .reduce(|largest, group| if group.len() > largest.len() { group } else { largest })
+            .unwrap_or_default()
+    }
+
+    /// Nodes without any incoming or outgoing edges
+    pub fn isolated_nodes(&self) -> Vec<ComponentId> {
+        self.arena
+            .live_nodes()
+            .filter(|(_, node)| node.incoming.is_empty() && node.outgoing.is_empty())
+            .map(|(_, node)| node.component_id.clone())
+            .collect()
+    }
+
+    /// Number of nodes per in-degree (`{in_degree: node_count}`)
+    /// The in-degree is the number of distinct parents; roots count as 0
+    pub fn inbound_degree_distribution(&self) -> HashMap<usize, usize> {
+        let mut distribution = HashMap::new();
+        for (node, _) in self.arena.live_nodes() {
+            *distribution.entry(self.parent_count(node)).or_insert(0) += 1;
+        }
+        distribution
+    }
+
+    /// Components with at least `min_in_degree` distinct parents, most used first
+    /// Widely used parts are a supply chain risk: losing one affects many assemblies.
+    /// Roots are never included
+    pub fn high_risk_components(&self, min_in_degree: usize) -> Vec<(ComponentId, usize)> {
+        let mut components: Vec<(ComponentId, usize)> = self
+            .arena
+            .live_nodes()
+            .map(|(node, n)| (n.component_id.clone(), self.parent_count(node)))
+            .filter(|&(_, in_degree)| in_degree >= min_in_degree && in_degree > 0)
+            .collect();
+        components.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.as_str().cmp(b.0.as_str())));
+        components
+    }
+
+    /// Components used by exactly one parent, sorted by ID
+    /// Discontinuing one affects a single assembly, but no other design already uses it
+    pub fn single_source_components(&self) -> Vec<ComponentId> {
+        let mut components: Vec<ComponentId> = self
+            .arena
+            .live_nodes()
+            .filter(|&(node, _)| self.parent_count(node) == 1)
+            .map(|(_, n)| n.component_id.clone())
+            .collect();
+        components.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        components
+    }
+
+    /// Distinct parents of `node` (several items from the same parent count once)
+    fn parent_count(&self, node: NodeIndex) -> usize {
+        self.arena
+            .parents(node)
+            .map(|(parent, _)| parent)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// Strongly connected components (Tarjan), each as a sorted list of component IDs
+    /// Components are ordered by their IDs. In a DAG every component is a single node;
+    /// larger components are the groups of parts caught in a circular reference
+    pub fn strongly_connected_components(&self) -> Vec<Vec<ComponentId>> {
+        let detector = CycleDetector::new(&self.arena);
+        detector.describe_components(&detector.strongly_connected_components())
+    }
+
+    /// Loops made only of phantom items, each as a sorted list of component IDs
+    /// `add_bom_item` rejects cycles, but a graph assembled through `arena_mut` or by
+    /// phantom expansion may not be checked; `contract_phantoms` would never finish on
+    /// such a loop. Cycles that pass through a normal item are not reported here
+    pub fn find_phantom_loops(&self) -> Vec<Vec<ComponentId>> {
+        let mut phantoms = Arena::new();
+        for (_, _, item) in self.iter_edges().filter(|(_, _, item)| item.is_phantom) {
+            let parent = phantoms.add_node(item.parent_id.clone());
+            let child = phantoms.add_node(item.child_id.clone());
+            phantoms.add_edge(parent, child, item.clone());
+        }
+
+        let detector = CycleDetector::new(&phantoms);
+        let loops: Vec<Vec<NodeIndex>> = detector
+            .strongly_connected_components()
+            .into_iter()
+            .filter(|component| detector.is_cyclic_component(component))
+            .collect();
+        detector.describe_components(&loops)
+    }
+
+    /// Compute the level of every node once and store it in `NodeCache::level`
+    /// Explosions then reuse the stored levels instead of re-running `level_grouping`.
+    /// `add_bom_item` invalidates the levels it affects; changes made through
+    /// `arena_mut` are not tracked. All nodes are marked clean, dropping cached
+    /// results of nodes that were dirty
+    pub fn precompute_levels(&mut self) {
+        self.identify_roots();
+        let levels = crate::traversal::level_grouping(&self.arena, &self.roots);
+
+        for (level, nodes) in levels.into_iter().enumerate() {
+            for node in nodes {
+                if let Some(n) = self.arena.node_mut(node) {
+                    n.cache.level = Some(level);
+                    if n.dirty {
+                        n.cache.total_material_cost = None;
+                        n.cache.explosion_quantity = None;
+                        n.dirty = false;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Precomputed level of a component (0 = leaf), `None` if not computed or not in the graph
+    pub fn node_level(&self, component_id: &ComponentId) -> Option<usize> {
+        let node = self.find_node(component_id)?;
+        self.arena.node(node)?.cache.level
+    }
+
+    /// Same grouping as `level_grouping`, built from the precomputed levels
+    /// Returns `None` if any node below `roots` has no precomputed level
+    pub fn cached_level_grouping(&self, roots: &[NodeIndex]) -> Option<Vec<Vec<NodeIndex>>> {
+        let mut levels: Vec<Vec<NodeIndex>> = Vec::new();
+        let mut visited: HashSet<NodeIndex> = HashSet::new();
+        let mut stack: Vec<NodeIndex> = roots.to_vec();
+
+        while let Some(node) = stack.pop() {
+            if !visited.insert(node) {
+                continue;
+            }
+            let level = self.arena.node(node)?.cache.level?;
+            if levels.len() <= level {
+                levels.resize(level + 1, Vec::new());
+            }
+            levels[level].push(node);
+            stack.extend(self.arena.children(node).map(|(child, _)| child));
+        }
+
+        Some(levels)
+    }
+
+    /// Level grouping of the whole graph (see `level_grouping`: 0 = leaves) with the
+    /// component data of every node loaded from `repo` (for indented BOM reports)
+    /// Components within a level are sorted by ID. Fails if a node has no component record
+    pub fn topological_levels(&self, repo: &impl BomRepository) -> Result<Vec<BomLevel>> {
+        let roots: Vec<NodeIndex> = self.iter_root_nodes().map(|(idx, _)| idx).collect();
+        let levels = crate::traversal::level_grouping(&self.arena, &roots);
+
+        let component_ids: Vec<ComponentId> = self.iter_nodes().map(|(_, id)| id.clone()).collect();
+        let mut components: HashMap<ComponentId, bom_core::Component> = repo
+            .get_components(&component_ids)?
+            .into_iter()
+            .map(|component| (component.id.clone(), component))
+            .collect();
+
+        let distinct = |nodes: Vec<NodeIndex>| nodes.into_iter().collect::<HashSet<_>>().len();
+        let mut result = Vec::with_capacity(levels.len());
+        for (level, nodes) in levels.into_iter().enumerate() {
+            let mut level_components = Vec::with_capacity(nodes.len());
+            for node in nodes {
+                let Some(data) = self.arena.node(node) else {
+                    continue;
+                };
+                let component_id = data.component_id.clone();
+                let component = components
+                    .remove(&component_id)
+                    .ok_or_else(|| BomError::ComponentNotFound(component_id.as_str().to_string()))?;
+                level_components.push(LevelComponent {
+                    children_count: distinct(self.arena.children(node).map(|(child, _)| child).collect()),
+                    parents_count: distinct(self.arena.parents(node).map(|(parent, _)| parent).collect()),
+                    component_id,
+                    component,
+                });
+            }
+            level_components.sort_by(|a, b| a.component_id.as_str().cmp(b.component_id.as_str()));
+            result.push(BomLevel {
+                level,
+                components: level_components,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Clear all cached computation results
+    pub fn clear_cache(&mut self) {
+        let node_count = self.arena.nodes().len();
+        for idx in 0..node_count {
+            if let Some(node) = self.arena.node_mut(NodeIndex(idx)) {
+                node.cache = crate::arena::NodeCache::default();
+            }
+        }
+    }
+
+    /// Store a freshly computed total material cost and mark the node clean
+    pub fn cache_material_cost(&mut self, component_id: &ComponentId, total_material_cost: Decimal) -> Result<()> {
+        let node = self.require_node(component_id)?;
+        if let Some(n) = self.arena.node_mut(node) {
+            n.cache.total_material_cost = Some(total_material_cost);
+            n.dirty = false;
+        }
+        Ok(())
+    }
+
+    /// Mark a component and its ancestors as dirty for incremental computation
+    pub fn mark_dirty(&mut self, component_id: &ComponentId) -> Result<()> {
+        let node = self
+            .find_node(component_id)
+            .ok_or_else(|| BomError::ComponentNotFound(component_id.as_str().to_string()))?;
+
+        self.arena.mark_dirty_recursive(node);
+        Ok(())
+    }
+}
+
+/// Union-find lookup with path halving
+fn find_set(parent: &mut [usize], mut x: usize) -> usize {
+    while parent[x] != x {
+        parent[x] = parent[parent[x]];
+        x = parent[x];
+    }
+    x
+}
+
+impl Default for BomGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Components at one level of a BOM (0 = leaves)
+#[derive(Debug, Clone)]
+pub struct BomLevel {
+    pub level: usize,
+    pub components: Vec<LevelComponent>,
+}
+
+/// A component of a `BomLevel` with its repository data and direct links
+#[derive(Debug, Clone)]
+pub struct LevelComponent {
+    pub component_id: ComponentId,
+    pub component: bom_core::Component,
+    /// Distinct direct children
+    pub children_count: usize,
+    /// Distinct direct parents
+    pub parents_count: usize,
+}
+
+/// Parent/child pair connected by more than one BOM item in effect at the same time
+#[derive(Debug, Clone)]
+pub struct RedundantPath {
+    pub parent_id: ComponentId,
+    pub child_id: ComponentId,
+    /// The BOM items connecting the pair, ordered by sequence
+    pub items: Vec<BomItem>,
+    /// Sum of the item quantities
+    pub total_quantity: Decimal,
+    /// The items are identical apart from ID, quantity, sequence and version, so they can be
+    /// merged into one item with `total_quantity` without losing anything
+    pub mergeable: bool,
+}
+
+/// Split the items of one parent/child pair into groups of redundant items (two or more)
+/// Items are redundant if their effectivity overlaps and they are used the same way;
+/// groups are closed under that relation
+fn redundant_groups(mut items: Vec<&BomItem>) -> Vec<Vec<BomItem>> {
+    items.sort_by_key(|item| item.sequence);
+
+    let mut group_of: Vec<usize> = (0..items.len()).collect();
+    for i in 0..items.len() {
+        for j in i + 1..items.len() {
+            let (a, b) = (items[i], items[j]);
+            let used_the_same_way = a.alternative_group == b.alternative_group
+                && a.is_phantom == b.is_phantom
+                && a.scrap_factor == b.scrap_factor
+                && a.operation_sequence == b.operation_sequence;
+            if used_the_same_way && a.effectivity_overlaps(b) && group_of[i] != group_of[j] {
+                let (keep, replace) = (group_of[i], group_of[j]);
+                group_of.iter_mut().filter(|group| **group == replace).for_each(|group| *group = keep);
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<BomItem>> = Vec::new();
+    let mut group_index: HashMap<usize, usize> = HashMap::new();
+    for (item, group) in items.into_iter().zip(group_of) {
+        let index = *group_index.entry(group).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[index].push(item.clone());
+    }
+    groups.retain(|group| group.len() > 1);
+    groups
+}
+
+/// Whether two items differ only in ID, quantity, sequence and version
+fn same_apart_from_quantity(a: &BomItem, b: &BomItem) -> bool {
+    a.scrap_factor == b.scrap_factor
+        && a.operation_sequence == b.operation_sequence
+        && a.is_phantom == b.is_phantom
+        && a.effective_from == b.effective_from
+        && a.effective_to == b.effective_to
+        && a.alternative_group == b.alternative_group
+        && a.alternative_priority == b.alternative_priority
+        && a.reference_designator == b.reference_designator
+        && a.position == b.position
+        && a.notes == b.notes
+}
+
+/// A path between two components with the quantity it carries
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathWithQuantity {
+    pub path: Vec<ComponentId>,
+    /// Units of the last component per unit of the first, along this path only
+    pub quantity: Decimal,
+}
+
+/// Outcome of `BomGraph::node_removal_impact`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemovalImpact {
+    /// Assemblies listing the component directly, sorted
+    pub direct_parents_affected: Vec<ComponentId>,
+    /// Top-level products containing the component at any level, sorted
+    pub root_products_affected: Vec<ComponentId>,
+    /// Unique components in the component's subtree, itself included
+    pub subtree_size: usize,
+    /// Used by more than one parent
+    pub is_shared: bool,
+    /// Another item in one of the component's alternative groups could replace it
+    pub alternative_exists: bool,
+}
+
+/// Outcome of `BomGraph::partition_by_procurement_type`
+/// The three component sets are disjoint and together cover the original graph
+pub struct BomPartition {
+    pub make_components: HashSet<ComponentId>,
+    pub buy_components: HashSet<ComponentId>,
+    pub both_components: HashSet<ComponentId>,
+    /// `Make` and `Both` components with the items between them
+    pub make_subgraph: BomGraph,
+    /// `Buy` and `Both` components, without items
+    pub buy_subgraph: BomGraph,
+}
+
+/// Outcome of `BomGraph::import_and_merge`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeResult {
+    pub added: usize,
+    pub removed: usize,
+    pub modified: usize,
+    /// Items skipped because they would have created a cycle
+    pub cycles_prevented: usize,
+}
+
+/// Outcome of `BomGraph::diff`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GraphDiff {
+    pub added_edges: Vec<BomItemChange>,
+    pub removed_edges: Vec<BomItemChange>,
+    /// Items whose quantity or scrap factor changed
+    pub modified_edges: Vec<BomItemChange>,
+}
+
+impl GraphDiff {
+    /// True if both graphs have the same BOM items with the same quantities and scrap factors
+    pub fn is_empty(&self) -> bool {
+        self.added_edges.is_empty() && self.removed_edges.is_empty() && self.modified_edges.is_empty()
+    }
+}
+
+/// One BOM item in a `GraphDiff`; the old values are `None` for added items, the new ones for removed items
+#[derive(Debug, Clone, PartialEq)]
+pub struct BomItemChange {
+    pub parent_id: ComponentId,
+    pub child_id: ComponentId,
+    pub old_quantity: Option<Decimal>,
+    pub new_quantity: Option<Decimal>,
+    pub old_scrap_factor: Option<Decimal>,
+    pub new_scrap_factor: Option<Decimal>,
+}
+
+impl BomItemChange {
+    /// Change from `old` to `new`, at least one of which is given
+    fn between(old: Option<&BomItem>, new: Option<&BomItem>) -> Self {
+        let item = old.or(new).expect("a change has an old or a new item");
+        Self {
+            parent_id: item.parent_id.clone(),
+            child_id: item.child_id.clone(),
+            old_quantity: old.map(|item| item.quantity),
+            new_quantity: new.map(|item| item.quantity),
+            old_scrap_factor: old.map(|item| item.scrap_factor),
+            new_scrap_factor: new.map(|item| item.scrap_factor),
+        }
+    }
+}
+
+/// Graph statistics
+#[derive(Debug, Clone)]
+pub struct GraphStats {
+    pub node_count: usize,
+    pub edge_count: usize,
+    pub root_count: usize,
+    pub max_depth: usize,
+}
+
+/// Storage usage of a graph (see `BomGraph::capacity`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphCapacity {
+    /// Nodes the graph holds without reallocating
+    pub node_capacity: usize,
+    /// Edges the graph holds without reallocating
+    pub edge_capacity: usize,
+    pub current_nodes: usize,
+    pub current_edges: usize,
+    /// Used share of all allocated node and edge slots (0.0 when nothing is allocated)
+    pub load_factor: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bom_core::repository::memory::InMemoryRepository;
+    use bom_core::*;
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+
+    fn create_test_component(id: &str, comp_type: ComponentType) -> Component {
+        Component {
+            id: ComponentId::new(id),
+            description: format!("Component {}", id),
+            component_type: comp_type,
+            uom: "EA".to_string(),
+            standard_cost: Some(Decimal::from(100)),
+            lead_time_days: Some(7),
+            procurement_type: ProcurementType::Make,
+            preferred_vendor: None,
+            organization: "ORG01".to_string(),
+            version: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn create_test_bom_item(parent: &str, child: &str, qty: i32) -> BomItem {
+        BomItem {
+            id: uuid::Uuid::new_v4(),
+            parent_id: ComponentId::new(parent),
+            child_id: ComponentId::new(child),
+            quantity: Decimal::from(qty),
+            scrap_factor: Decimal::ZERO,
+            sequence: 10,
+            operation_sequence: None,
+            is_phantom: false,
+            effective_from: None,
+            effective_to: None,
+            alternative_group: None,
+            alternative_priority: None,
+            reference_designator: None,
+            position: None,
+            notes: None,
+            version: 0,
+        }
+    }
+
+    #[test]
+    fn test_simple_bom_graph() {
         let repo = InMemoryRepository::new();
 
-        // A -> B -> D
-        //   -> C -> D (shared component)
+        // A -> B (qty 2)
+        // A -> C (qty 1)
         repo.add_component(create_test_component("A", ComponentType::FinishedProduct));
-        repo.add_component(create_test_component("B", ComponentType::SemiFinished));
-        repo.add_component(create_test_component("C", ComponentType::SemiFinished));
-        repo.add_component(create_test_component("D", ComponentType::RawMaterial));
+        repo.add_component(create_test_component("B", ComponentType::RawMaterial));
+        repo.add_component(create_test_component("C", ComponentType::RawMaterial));
 
-        repo.add_bom_item(create_test_bom_item("A", "B", 1));
-        repo.add_bom_item(create_test_bom_item("A", "C", 1));
-        repo.add_bom_item(create_test_bom_item("B", "D", 2));
-        repo.add_bom_item(create_test_bom_item("C", "D", 3));
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
 
         let graph = BomGraph::from_repository(&repo).unwrap();
 
+        let stats = graph.stats();
+        assert_eq!(stats.node_count, 3);
+        assert_eq!(stats.edge_count, 2);
+        assert_eq!(stats.root_count, 1);
+    }
+
+    #[test]
+    fn test_from_component_loads_all_levels() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B -> D, A -> C -> D (shared), plus X -> C outside A's tree
+        for (parent, child) in [("A", "B"), ("A", "C"), ("B", "D"), ("C", "D"), ("X", "C")] {
+            repo.add_bom_item(create_test_bom_item(parent, child, 1)).unwrap();
+        }
+
+        let graph = BomGraph::from_component(&repo, &ComponentId::new("A"), None).unwrap();
+
         let stats = graph.stats();
         assert_eq!(stats.node_count, 4);
         assert_eq!(stats.edge_count, 4);
-        assert_eq!(stats.max_depth, 2);
+        assert_eq!(graph.path_exists(&ComponentId::new("A"), &ComponentId::new("D")), Some(true));
+        assert!(graph.find_node(&ComponentId::new("X")).is_none());
+    }
+
+    #[test]
+    fn test_circular_dependency_detection() {
+        let mut graph = BomGraph::new();
+
+        // Try to create A -> B -> A (circular)
+        let item1 = create_test_bom_item("A", "B", 1);
+        let item2 = create_test_bom_item("B", "A", 1);
+
+        graph.add_bom_item(item1).unwrap();
+        let result = graph.add_bom_item(item2);
+
+        assert!(matches!(result, Err(BomError::CircularDependency(_))));
+    }
+
+    #[test]
+    fn test_multilevel_bom() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B -> D
+        //   -> C -> D (shared component)
+        repo.add_component(create_test_component("A", ComponentType::FinishedProduct));
+        repo.add_component(create_test_component("B", ComponentType::SemiFinished));
+        repo.add_component(create_test_component("C", ComponentType::SemiFinished));
+        repo.add_component(create_test_component("D", ComponentType::RawMaterial));
+
+        repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("C", "D", 3)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+
+        let stats = graph.stats();
+        assert_eq!(stats.node_count, 4);
+        assert_eq!(stats.edge_count, 4);
+        assert_eq!(stats.max_depth, 2);
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let mut graph = BomGraph::new();
+
+        // Product group: A -> B -> C, A -> D
+        graph.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("B", "C", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("A", "D", 1)).unwrap();
+
+        // Orphaned assembly: X -> Y
+        graph.add_bom_item(create_test_bom_item("X", "Y", 1)).unwrap();
+
+        // Component without any BOM relation
+        graph.arena_mut().add_node(ComponentId::new("Z"));
+
+        let components = graph.connected_components();
+        assert_eq!(components.len(), 3);
+        assert_eq!(components[0].len(), 4);
+        assert!(components[1].contains(&ComponentId::new("X")));
+        assert!(components[1].contains(&ComponentId::new("Y")));
+
+        let largest = graph.largest_connected_component();
+        assert_eq!(largest.len(), 4);
+        assert!(largest.contains(&ComponentId::new("C")));
+
+        assert_eq!(graph.isolated_nodes(), vec![ComponentId::new("Z")]);
+    }
+
+    #[test]
+    fn test_connected_components_skip_removed_nodes() {
+        let mut graph = BomGraph::new();
+        graph.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        let z = graph.arena_mut().add_node(ComponentId::new("Z"));
+        let w = graph.arena_mut().add_node(ComponentId::new("W"));
+        graph.arena_mut().remove_node(z).unwrap();
+
+        let components = graph.connected_components();
+        assert_eq!(components.len(), 2);
+        assert!(components.iter().flatten().all(|id| id.as_str() != "Z"));
+        assert_eq!(components[1], vec![ComponentId::new("W")]);
+
+        assert_eq!(graph.isolated_nodes(), vec![ComponentId::new("W")]);
+        graph.arena_mut().remove_node(w).unwrap();
+        assert!(graph.isolated_nodes().is_empty());
+    }
+
+    #[test]
+    fn test_strongly_connected_components_of_dag() {
+        let mut graph = BomGraph::new();
+        graph.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("B", "C", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+
+        let components = graph.strongly_connected_components();
+        assert_eq!(
+            components,
+            vec![
+                vec![ComponentId::new("A")],
+                vec![ComponentId::new("B")],
+                vec![ComponentId::new("C")],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_reachable_roots() {
+        let repo = InMemoryRepository::new();
+
+        // Three products sharing parts
+        // P1 -> S1 -> BOLT
+        // P2 -> S1
+        // P2 -> NUT
+        // P3 -> SCREW
+        repo.add_bom_item(create_test_bom_item("P1", "S1", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("S1", "BOLT", 4)).unwrap();
+        repo.add_bom_item(create_test_bom_item("P2", "S1", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("P2", "NUT", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("P3", "SCREW", 6)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let id = ComponentId::new;
+
+        let roots = graph.find_reachable_roots(&[id("BOLT"), id("NUT")]).unwrap();
+        assert_eq!(roots, HashSet::from([id("P1"), id("P2")]));
+
+        // A root given as input is its own root
+        let roots = graph.find_reachable_roots(&[id("SCREW"), id("P1")]).unwrap();
+        assert_eq!(roots, HashSet::from([id("P1"), id("P3")]));
+
+        let coverage = graph
+            .find_reachable_roots_with_coverage(&[id("BOLT"), id("NUT"), id("SCREW")])
+            .unwrap();
+        assert_eq!(coverage.len(), 3);
+        assert_eq!(coverage[&id("P1")], vec![id("BOLT")]);
+        assert_eq!(coverage[&id("P2")], vec![id("BOLT"), id("NUT")]);
+        assert_eq!(coverage[&id("P3")], vec![id("SCREW")]);
+
+        assert!(graph.find_reachable_roots(&[id("MISSING")]).is_err());
+    }
+
+    #[test]
+    fn test_precompute_levels() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B -> C, A -> C, D -> C
+        repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("D", "C", 1)).unwrap();
+
+        let mut graph = BomGraph::from_repository(&repo).unwrap();
+        let id = ComponentId::new;
+        assert_eq!(graph.node_level(&id("A")), None);
+
+        graph.mark_dirty(&id("C")).unwrap();
+        graph.precompute_levels();
+
+        // Level = longest path down to a leaf
+        assert_eq!(graph.node_level(&id("C")), Some(0));
+        assert_eq!(graph.node_level(&id("B")), Some(1));
+        assert_eq!(graph.node_level(&id("D")), Some(1));
+        assert_eq!(graph.node_level(&id("A")), Some(2));
+        assert!(graph.arena().nodes().iter().all(|node| !node.dirty));
+
+        let root = graph.find_node(&id("A")).unwrap();
+        let cached = graph.cached_level_grouping(&[root]).unwrap();
+        assert_eq!(cached.iter().map(Vec::len).collect::<Vec<_>>(), vec![1, 1, 1]);
+
+        // Growing the structure below C invalidates C and all its ancestors
+        graph.add_bom_item(create_test_bom_item("C", "E", 1)).unwrap();
+        assert_eq!(graph.node_level(&id("B")), None);
+        assert_eq!(graph.node_level(&id("A")), None);
+        assert_eq!(graph.node_level(&id("D")), None);
+        assert!(graph.cached_level_grouping(&[root]).is_none());
+
+        graph.precompute_levels();
+        assert_eq!(graph.node_level(&id("A")), Some(3));
+        assert_eq!(graph.node_level(&id("E")), Some(0));
+    }
+
+    #[test]
+    fn test_topological_levels() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B -> C, A -> C, D -> C
+        for id in ["A", "B", "C", "D"] {
+            repo.add_component(create_test_component(id, ComponentType::SemiFinished));
+        }
+        repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("D", "C", 1)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let levels = graph.topological_levels(&repo).unwrap();
+        fn ids(level: &BomLevel) -> Vec<&str> {
+            level.components.iter().map(|c| c.component_id.as_str()).collect()
+        }
+
+        assert_eq!(levels.len(), 3);
+        assert_eq!(ids(&levels[0]), vec!["C"]);
+        assert_eq!(ids(&levels[1]), vec!["B", "D"]);
+        assert_eq!(ids(&levels[2]), vec!["A"]);
+        assert!(levels[0].components.iter().all(|c| c.children_count == 0));
+        assert!(levels[2].components.iter().all(|c| c.parents_count == 0));
+
+        let c = &levels[0].components[0];
+        assert_eq!(c.parents_count, 3);
+        assert_eq!(c.component.component_type, ComponentType::SemiFinished);
+        assert_eq!(levels[2].components[0].children_count, 2);
+
+        // Every node needs a component record
+        repo.add_bom_item(create_test_bom_item("D", "X", 1)).unwrap();
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        assert!(matches!(graph.topological_levels(&repo), Err(BomError::ComponentNotFound(id)) if id == "X"));
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        let mut graph = BomGraph::new();
+
+        // A -> B -> C -> D (indirect)
+        // A -> D           (direct)
+        graph.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("B", "C", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("C", "D", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("A", "D", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("X", "Y", 1)).unwrap();
+
+        let a = ComponentId::new("A");
+        let d = ComponentId::new("D");
+
+        let path = graph.shortest_path(&a, &d).unwrap().unwrap();
+        assert_eq!(path, vec![a.clone(), d.clone()]);
+
+        assert_eq!(graph.all_paths_between(&a, &d).unwrap().len(), 2);
+
+        assert_eq!(graph.path_exists(&a, &d), Some(true));
+        assert_eq!(graph.path_exists(&d, &a), Some(false));
+        assert_eq!(graph.path_exists(&a, &ComponentId::new("MISSING")), None);
+
+        assert_eq!(graph.shortest_path(&a, &ComponentId::new("Y")).unwrap(), None);
+        assert!(graph.shortest_path(&a, &ComponentId::new("MISSING")).is_err());
+    }
+
+    #[test]
+    fn test_minimum_cost_path() {
+        let mut graph = BomGraph::new();
+
+        // Diamond: A -> B (qty 2) -> D, A -> C (qty 1) -> D
+        //          B costs 50, C costs 80, D costs 10
+        graph.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        graph.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("B", "D", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("C", "D", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("X", "Y", 1)).unwrap();
+
+        let cost = |id: &str, total: i64| {
+            (
+                ComponentId::new(id),
+                CostBreakdown {
+                    component_id: ComponentId::new(id),
+                    material_cost: Decimal::from(total),
+                    labor_cost: Decimal::ZERO,
+                    overhead_cost: Decimal::ZERO,
+                    subcontract_cost: Decimal::ZERO,
+                    total_cost: Decimal::from(total),
+                    calculated_at: Utc::now(),
+                },
+            )
+        };
+        let cost_map: HashMap<ComponentId, CostBreakdown> =
+            HashMap::from([cost("B", 50), cost("C", 80), cost("D", 10)]);
+
+        let a = ComponentId::new("A");
+        let d = ComponentId::new("D");
+
+        // Via B: 2 * 50 + 10 = 110, via C: 80 + 10 = 90
+        let (path, total) = graph.minimum_cost_path(&a, &d, &cost_map).unwrap().unwrap();
+        assert_eq!(path, vec![a.clone(), ComponentId::new("C"), d.clone()]);
+        assert_eq!(total, Decimal::from(90));
+
+        // Hop count alone cannot tell the two apart
+        assert_eq!(graph.shortest_path(&a, &d).unwrap().unwrap().len(), 3);
+
+        let (path, total) = graph.minimum_cost_path(&a, &a, &cost_map).unwrap().unwrap();
+        assert_eq!(path, vec![a.clone()]);
+        assert_eq!(total, Decimal::ZERO);
+
+        assert_eq!(graph.minimum_cost_path(&a, &ComponentId::new("Y"), &cost_map).unwrap(), None);
+        assert!(graph.minimum_cost_path(&a, &ComponentId::new("MISSING"), &cost_map).is_err());
+    }
+
+    #[test]
+    fn test_node_and_edge_iterators() {
+        use rayon::iter::{ParallelBridge, ParallelIterator};
+
+        let repo = InMemoryRepository::new();
+
+        // A -> B -> D, A -> C
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 3)).unwrap();
+
+        let mut graph = BomGraph::from_repository(&repo).unwrap();
+
+        let assert_counts = |graph: &BomGraph| {
+            let stats = graph.stats();
+            assert_eq!(graph.iter_nodes().count(), stats.node_count);
+            assert_eq!(graph.iter_edges().count(), stats.edge_count);
+            assert_eq!(graph.iter_nodes().par_bridge().count(), stats.node_count);
+        };
+        let sorted_ids = |nodes: Vec<(NodeIndex, &ComponentId)>| {
+            let mut ids: Vec<String> = nodes.into_iter().map(|(_, id)| id.as_str().to_string()).collect();
+            ids.sort();
+            ids
+        };
+
+        assert_counts(&graph);
+        assert_eq!(sorted_ids(graph.iter_root_nodes().collect()), vec!["A"]);
+        assert_eq!(sorted_ids(graph.iter_leaf_nodes().collect()), vec!["C", "D"]);
+
+        // New root E above A, new leaf F below C
+        graph.add_bom_item(create_test_bom_item("E", "A", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("C", "F", 4)).unwrap();
+        assert_counts(&graph);
+        assert_eq!(sorted_ids(graph.iter_root_nodes().collect()), vec!["E"]);
+        assert_eq!(sorted_ids(graph.iter_leaf_nodes().collect()), vec!["D", "F"]);
+
+        // Updating an item keeps the shape
+        let (_, _, item) = graph
+            .iter_edges()
+            .find(|(_, _, item)| item.child_id.as_str() == "D")
+            .unwrap();
+        let mut item = item.clone();
+        item.quantity = Decimal::from(5);
+        graph.update_bom_item(item).unwrap();
+        assert_counts(&graph);
+
+        // Rejected cycles leave no dangling edge
+        assert!(graph.add_bom_item(create_test_bom_item("D", "E", 1)).is_err());
+        assert_counts(&graph);
+
+        for (parent, child, item) in graph.iter_edges() {
+            assert_eq!(graph.arena().node(parent).unwrap().component_id, item.parent_id);
+            assert_eq!(graph.arena().node(child).unwrap().component_id, item.child_id);
+        }
+    }
+
+    #[test]
+    fn test_minimum_spanning_tree_cuts_expensive_path() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B -> D
+        //   -> C -> D   (C is the expensive way to D)
+        // plus X -> Y outside A's BOM
+        for id in ["A", "B", "C", "D"] {
+            repo.add_component(create_test_component(id, ComponentType::SemiFinished));
+        }
+        for (parent, child, qty) in [("A", "B", 1), ("A", "C", 1), ("B", "D", 1), ("C", "D", 2), ("X", "Y", 1)] {
+            repo.add_bom_item(create_test_bom_item(parent, child, qty)).unwrap();
+        }
+        let graph = BomGraph::from_repository(&repo).unwrap();
+
+        let costs = HashMap::from([
+            (ComponentId::new("B"), Decimal::from(10)),
+            (ComponentId::new("C"), Decimal::from(50)),
+            (ComponentId::new("D"), Decimal::from(5)),
+        ]);
+        let root = ComponentId::new("A");
+        let tree = graph.minimum_spanning_tree(&root, &costs).unwrap();
+
+        // Weights: B-D 5, A-B 10, C-D 10, A-C 50. An undirected spanning tree would cut A-C,
+        // leaving C outside A's BOM; C's only incoming item must stay
+        assert_eq!(tree.stats().node_count, 4);
+        assert_eq!(tree.stats().edge_count, 3);
+        let mut edges: Vec<(&str, &str)> = tree
+            .iter_edges()
+            .map(|(_, _, item)| (item.parent_id.as_str(), item.child_id.as_str()))
+            .collect();
+        edges.sort();
+        assert_eq!(edges, vec![("A", "B"), ("A", "C"), ("B", "D")]);
+
+        // Every component of the original BOM is still under the root
+        for id in ["B", "C", "D"] {
+            assert_eq!(tree.path_exists(&root, &ComponentId::new(id)), Some(true), "{}", id);
+        }
+        assert_eq!(tree.iter_root_nodes().count(), 1);
+
+        // The original graph is unchanged
+        assert_eq!(graph.stats().edge_count, 5);
+        assert!(graph.minimum_spanning_tree(&ComponentId::new("MISSING"), &costs).is_err());
+    }
+
+    #[test]
+    fn test_supply_chain_risk() {
+        let repo = InMemoryRepository::new();
+
+        // SCREW is used by five products, P1 uses it twice; CHIP by two, each OEM part by one
+        for i in 1..=5 {
+            let product = format!("P{}", i);
+            let oem = format!("OEM-{}", i);
+            repo.add_component(create_test_component(&product, ComponentType::FinishedProduct));
+            repo.add_component(create_test_component(&oem, ComponentType::RawMaterial));
+            repo.add_bom_item(create_test_bom_item(&product, "SCREW", 4)).unwrap();
+            repo.add_bom_item(create_test_bom_item(&product, &oem, 1)).unwrap();
+        }
+        repo.add_bom_item(create_test_bom_item("P1", "SCREW", 2)).unwrap();
+        repo.add_bom_item(create_test_bom_item("P1", "CHIP", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("P2", "CHIP", 1)).unwrap();
+        for id in ["SCREW", "CHIP"] {
+            repo.add_component(create_test_component(id, ComponentType::RawMaterial));
+        }
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+
+        let distribution = graph.inbound_degree_distribution();
+        assert_eq!(distribution, HashMap::from([(0, 5), (1, 5), (2, 1), (5, 1)]));
+
+        let high_risk = graph.high_risk_components(2);
+        assert_eq!(
+            high_risk,
+            vec![(ComponentId::new("SCREW"), 5), (ComponentId::new("CHIP"), 2)]
+        );
+        assert_eq!(graph.high_risk_components(3), vec![(ComponentId::new("SCREW"), 5)]);
+
+        let oem_parts: Vec<ComponentId> = (1..=5).map(|i| ComponentId::new(format!("OEM-{}", i))).collect();
+        assert_eq!(graph.single_source_components(), oem_parts);
+    }
+
+    #[test]
+    fn test_node_removal_impact() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B -> D
+        //   -> X -> Z   (X and Y are alternatives)
+        //   -> Y
+        // C -> B
+        for id in ["A", "B", "C", "D", "X", "Y", "Z"] {
+            repo.add_component(create_test_component(id, ComponentType::SemiFinished));
+        }
+        for (parent, child) in [("A", "B"), ("B", "D"), ("C", "B"), ("X", "Z")] {
+            repo.add_bom_item(create_test_bom_item(parent, child, 1)).unwrap();
+        }
+        for child in ["X", "Y"] {
+            let mut item = create_test_bom_item("A", child, 1);
+            item.alternative_group = Some("G1".to_string());
+            repo.add_bom_item(item).unwrap();
+        }
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let ids = |ids: &[&str]| ids.iter().map(|id| ComponentId::new(*id)).collect::<Vec<_>>();
+
+        // Shared: removing B breaks both products
+        let shared = graph.node_removal_impact(&ComponentId::new("B")).unwrap();
+        assert!(shared.is_shared);
+        assert!(!shared.alternative_exists);
+        assert_eq!(shared.direct_parents_affected, ids(&["A", "C"]));
+        assert_eq!(shared.root_products_affected, ids(&["A", "C"]));
+        assert_eq!(shared.subtree_size, 2);
+
+        // Exclusive to A, and Y can take its place
+        let exclusive = graph.node_removal_impact(&ComponentId::new("X")).unwrap();
+        assert!(!exclusive.is_shared);
+        assert!(exclusive.alternative_exists);
+        assert_eq!(exclusive.direct_parents_affected, ids(&["A"]));
+        assert_eq!(exclusive.root_products_affected, ids(&["A"]));
+        assert_eq!(exclusive.subtree_size, 2);
+
+        let root = graph.node_removal_impact(&ComponentId::new("A")).unwrap();
+        assert!(root.direct_parents_affected.is_empty());
+        assert!(root.root_products_affected.is_empty());
+        assert_eq!(root.subtree_size, 6);
+
+        assert!(graph.node_removal_impact(&ComponentId::new("MISSING")).is_err());
+    }
+
+    #[test]
+    fn test_partition_by_procurement_type() {
+        let repo = InMemoryRepository::new();
+
+        // A (Make) -> B (Make) -> D (Buy)
+        //          -> C (Both) -> E (Buy)
+        //                      -> F (Make)
+        for (id, procurement) in [
+            ("A", ProcurementType::Make),
+            ("B", ProcurementType::Make),
+            ("C", ProcurementType::Both),
+            ("D", ProcurementType::Buy),
+            ("E", ProcurementType::Buy),
+            ("F", ProcurementType::Make),
+        ] {
+            let mut component = create_test_component(id, ComponentType::SemiFinished);
+            component.procurement_type = procurement;
+            repo.add_component(component);
+        }
+        for (parent, child) in [("A", "B"), ("A", "C"), ("B", "D"), ("C", "E"), ("C", "F")] {
+            repo.add_bom_item(create_test_bom_item(parent, child, 1)).unwrap();
+        }
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let partition = graph.partition_by_procurement_type(&repo).unwrap();
+
+        let ids = |set: &HashSet<ComponentId>| {
+            let mut ids: Vec<String> = set.iter().map(|id| id.as_str().to_string()).collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(ids(&partition.make_components), vec!["A", "B", "F"]);
+        assert_eq!(ids(&partition.buy_components), vec!["D", "E"]);
+        assert_eq!(ids(&partition.both_components), vec!["C"]);
+
+        // Together the subgraphs cover every component; only `Both` is in both
+        let make: HashSet<ComponentId> = partition.make_subgraph.iter_nodes().map(|(_, id)| id.clone()).collect();
+        let buy: HashSet<ComponentId> = partition.buy_subgraph.iter_nodes().map(|(_, id)| id.clone()).collect();
+        let all: HashSet<ComponentId> = graph.iter_nodes().map(|(_, id)| id.clone()).collect();
+        assert_eq!(make.union(&buy).cloned().collect::<HashSet<_>>(), all);
+        assert_eq!(make.intersection(&buy).cloned().collect::<HashSet<_>>(), partition.both_components);
+
+        let mut make_edges: Vec<(&str, &str)> = partition
+            .make_subgraph
+            .iter_edges()
+            .map(|(_, _, item)| (item.parent_id.as_str(), item.child_id.as_str()))
+            .collect();
+        make_edges.sort();
+        assert_eq!(make_edges, vec![("A", "B"), ("A", "C"), ("C", "F")]);
+
+        assert_eq!(partition.buy_subgraph.stats().edge_count, 0);
+        assert_eq!(partition.buy_subgraph.iter_leaf_nodes().count(), 3);
+    }
+
+    /// Records visited nodes and does not descend below purchased components
+    struct StopAtBuy {
+        procurement: HashMap<ComponentId, ProcurementType>,
+        visited: Vec<(String, usize)>,
+        edges: usize,
+    }
+
+    impl BomVisitor for StopAtBuy {
+        fn visit_node(&mut self, node: &ComponentId, level: usize) -> VisitAction {
+            self.visited.push((node.as_str().to_string(), level));
+            match self.procurement.get(node) {
+                Some(ProcurementType::Buy) => VisitAction::Skip,
+                _ => VisitAction::Continue,
+            }
+        }
+
+        fn visit_edge(&mut self, _parent: &ComponentId, _child: &ComponentId, _item: &BomItem) -> VisitAction {
+            self.edges += 1;
+            VisitAction::Continue
+        }
+    }
+
+    #[test]
+    fn test_bfs_visit_prunes_branches() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B (Buy) -> D
+        //   -> C (Make) -> E
+        for id in ["A", "C", "D", "E"] {
+            repo.add_component(create_test_component(id, ComponentType::SemiFinished));
+        }
+        let mut b = create_test_component("B", ComponentType::SemiFinished);
+        b.procurement_type = ProcurementType::Buy;
+        repo.add_component(b);
+
+        repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "D", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("C", "E", 1)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let mut visitor = StopAtBuy {
+            procurement: repo
+                .get_components(&graph.iter_nodes().map(|(_, id)| id.clone()).collect::<Vec<_>>())
+                .unwrap()
+                .into_iter()
+                .map(|c| (c.id, c.procurement_type))
+                .collect(),
+            visited: Vec::new(),
+            edges: 0,
+        };
+
+        graph.bfs_visit(&ComponentId::new("A"), &mut visitor).unwrap();
+
+        let mut visited = visitor.visited.clone();
+        visited.sort();
+        assert_eq!(
+            visited,
+            vec![
+                ("A".to_string(), 0),
+                ("B".to_string(), 1),
+                ("C".to_string(), 1),
+                ("E".to_string(), 2),
+            ]
+        );
+        // B's edge to D is never offered
+        assert_eq!(visitor.edges, 3);
+
+        assert!(graph.bfs_visit(&ComponentId::new("MISSING"), &mut visitor).is_err());
+    }
+
+    /// Stops as soon as it reaches `target`
+    struct StopAt {
+        target: ComponentId,
+        visited: usize,
+    }
+
+    impl BomVisitor for StopAt {
+        fn visit_node(&mut self, node: &ComponentId, _level: usize) -> VisitAction {
+            self.visited += 1;
+            if *node == self.target {
+                VisitAction::Stop
+            } else {
+                VisitAction::Continue
+            }
+        }
+    }
+
+    #[test]
+    fn test_bfs_visit_stop() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B -> C -> D
+        for id in ["A", "B", "C", "D"] {
+            repo.add_component(create_test_component(id, ComponentType::SemiFinished));
+        }
+        repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("C", "D", 1)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let mut visitor = StopAt {
+            target: ComponentId::new("C"),
+            visited: 0,
+        };
+        graph.bfs_visit(&ComponentId::new("A"), &mut visitor).unwrap();
+
+        assert_eq!(visitor.visited, 3);
+    }
+
+    #[test]
+    fn test_detect_and_merge_redundant_paths() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B entered twice (2 + 3), A -> C once
+        repo.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        let mut duplicate = create_test_bom_item("A", "B", 3);
+        duplicate.sequence = 20;
+        repo.add_bom_item(duplicate).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+
+        let mut graph = BomGraph::from_repository(&repo).unwrap();
+
+        let redundant = graph.detect_redundant_paths();
+        assert_eq!(redundant.len(), 1);
+        assert_eq!(redundant[0].parent_id, ComponentId::new("A"));
+        assert_eq!(redundant[0].child_id, ComponentId::new("B"));
+        assert_eq!(redundant[0].items.len(), 2);
+        assert_eq!(redundant[0].total_quantity, Decimal::from(5));
+        assert!(redundant[0].mergeable);
+
+        assert_eq!(graph.merge_redundant_paths().unwrap(), 1);
+        assert!(graph.detect_redundant_paths().is_empty());
+        assert_eq!(graph.stats().edge_count, 2);
+
+        let a = graph.find_node(&ComponentId::new("A")).unwrap();
+        let b = graph.find_node(&ComponentId::new("B")).unwrap();
+        let merged: Vec<_> = graph.arena().children(a).filter(|(child, _)| *child == b).collect();
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].1.bom_item.quantity, Decimal::from(5));
+        assert_eq!(merged[0].1.effective_quantity, Decimal::from(5));
+        assert_eq!(graph.iter_edges().count(), 2);
+    }
+
+    #[test]
+    fn test_redundant_paths_respect_effectivity_and_usage() {
+        let start = Utc::now();
+        let mut graph = BomGraph::new();
+
+        // A -> B revision 1 until day 30, revision 2 from day 31: never in effect together
+        let mut rev1 = create_test_bom_item("A", "B", 2);
+        rev1.effective_to = Some(start + chrono::Duration::days(30));
+        let mut rev2 = create_test_bom_item("A", "B", 3);
+        rev2.sequence = 20;
+        rev2.effective_from = Some(start + chrono::Duration::days(31));
+        graph.add_bom_item(rev1).unwrap();
+        graph.add_bom_item(rev2).unwrap();
+
+        // A -> C in two alternative groups
+        let mut primary = create_test_bom_item("A", "C", 1);
+        primary.alternative_group = Some("G1".to_string());
+        let mut other = create_test_bom_item("A", "C", 1);
+        other.sequence = 20;
+        other.alternative_group = Some("G2".to_string());
+        graph.add_bom_item(primary).unwrap();
+        graph.add_bom_item(other).unwrap();
+
+        assert!(graph.detect_redundant_paths().is_empty());
+        assert_eq!(graph.merge_redundant_paths().unwrap(), 0);
+        assert_eq!(graph.stats().edge_count, 4);
+
+        // A -> D twice in effect at once, but only from day 10 for the second one: reported,
+        // yet merging would lose the second item's start date
+        graph.add_bom_item(create_test_bom_item("A", "D", 1)).unwrap();
+        let mut later = create_test_bom_item("A", "D", 1);
+        later.sequence = 20;
+        later.effective_from = Some(start + chrono::Duration::days(10));
+        graph.add_bom_item(later).unwrap();
+
+        let redundant = graph.detect_redundant_paths();
+        assert_eq!(redundant.len(), 1);
+        assert_eq!(redundant[0].child_id, ComponentId::new("D"));
+        assert!(!redundant[0].mergeable);
+        assert_eq!(graph.merge_redundant_paths().unwrap(), 0);
+        assert_eq!(graph.stats().edge_count, 6);
+    }
+
+    #[test]
+    fn test_calculate_path_quantities() {
+        let mut graph = BomGraph::new();
+
+        // Diamond: A -> B (2) -> D (3), A -> C (4) -> D (5)
+        graph.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        graph.add_bom_item(create_test_bom_item("A", "C", 4)).unwrap();
+        graph.add_bom_item(create_test_bom_item("B", "D", 3)).unwrap();
+        graph.add_bom_item(create_test_bom_item("C", "D", 5)).unwrap();
+
+        let mut paths = graph
+            .calculate_path_quantities(&ComponentId::new("A"), &ComponentId::new("D"))
+            .unwrap();
+        paths.sort_by_key(|p| p.quantity);
+
+        let ids = |ids: &[&str]| ids.iter().map(|id| ComponentId::new(*id)).collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                PathWithQuantity {
+                    path: ids(&["A", "B", "D"]),
+                    quantity: Decimal::from(6),
+                },
+                PathWithQuantity {
+                    path: ids(&["A", "C", "D"]),
+                    quantity: Decimal::from(20),
+                },
+            ]
+        );
+        assert_eq!(paths.iter().map(|p| p.quantity).sum::<Decimal>(), Decimal::from(26));
+
+        assert!(graph
+            .calculate_path_quantities(&ComponentId::new("A"), &ComponentId::new("MISSING"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_path_quantities_with_two_items_between_a_pair() {
+        let mut graph = BomGraph::new();
+
+        // A -> B twice (2 + 3) -> D (1): one path of 5, not two
+        graph.add_bom_item(create_test_bom_item("A", "B", 2)).unwrap();
+        let mut second = create_test_bom_item("A", "B", 3);
+        second.sequence = 20;
+        graph.add_bom_item(second).unwrap();
+        graph.add_bom_item(create_test_bom_item("B", "D", 1)).unwrap();
+
+        let paths = graph
+            .calculate_path_quantities(&ComponentId::new("A"), &ComponentId::new("D"))
+            .unwrap();
+        assert_eq!(
+            paths,
+            vec![PathWithQuantity {
+                path: vec![ComponentId::new("A"), ComponentId::new("B"), ComponentId::new("D")],
+                quantity: Decimal::from(5),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_import_and_merge() {
+        let repo = InMemoryRepository::new();
+
+        // A -> B -> C, A -> D
+        let a_b = create_test_bom_item("A", "B", 1);
+        let b_c = create_test_bom_item("B", "C", 2);
+        let a_d = create_test_bom_item("A", "D", 1);
+        for item in [&a_b, &b_c, &a_d] {
+            repo.add_bom_item(item.clone()).unwrap();
+        }
+        let mut graph = BomGraph::from_repository(&repo).unwrap();
+
+        // Delta: add E -> A, drop A -> D, change B -> C to 5, and C -> A (a cycle)
+        let mut changed = b_c.clone();
+        changed.quantity = Decimal::from(5);
+        let delta = vec![create_test_bom_item("E", "A", 1), changed, create_test_bom_item("C", "A", 1)];
+
+        let result = graph.import_and_merge(delta, vec![a_d.id, Uuid::new_v4()]).unwrap();
+        assert_eq!(
+            result,
+            MergeResult {
+                added: 1,
+                removed: 1,
+                modified: 1,
+                cycles_prevented: 1,
+            }
+        );
+
+        assert!(graph.find_edge_by_item_id(&a_d.id).is_none());
+        let edge = graph.find_edge_by_item_id(&b_c.id).unwrap();
+        assert_eq!(graph.arena().edge(edge).unwrap().bom_item.quantity, Decimal::from(5));
+        assert_eq!(graph.path_exists(&ComponentId::new("C"), &ComponentId::new("A")), Some(false));
+
+        // E is the new top; D was orphaned and is a root of its own
+        let mut roots: Vec<&str> = graph
+            .roots()
+            .iter()
+            .map(|&root| graph.arena().node(root).unwrap().component_id.as_str())
+            .collect();
+        roots.sort();
+        assert_eq!(roots, vec!["D", "E"]);
+
+        // Invalid items reject the whole delta
+        let mut invalid = create_test_bom_item("E", "F", 1);
+        invalid.quantity = Decimal::ZERO;
+        assert!(graph.import_and_merge(vec![invalid], vec![b_c.id]).is_err());
+        assert!(graph.find_edge_by_item_id(&b_c.id).is_some());
+    }
+
+    #[test]
+    fn test_diff() {
+        // Old: A -> B (1), A -> C (2), B -> D (3)
+        let a_b = create_test_bom_item("A", "B", 1);
+        let a_c = create_test_bom_item("A", "C", 2);
+        let b_d = create_test_bom_item("B", "D", 3);
+        let mut old = BomGraph::new();
+        for item in [&a_b, &a_c, &b_d] {
+            old.add_bom_item(item.clone()).unwrap();
+        }
+
+        // New: A -> C dropped, B -> D changed to 5, A -> E added, A -> B unchanged
+        let mut new = BomGraph::new();
+        new.add_bom_item(a_b.clone()).unwrap();
+        let mut changed = b_d.clone();
+        changed.quantity = Decimal::from(5);
+        new.add_bom_item(changed).unwrap();
+        new.add_bom_item(create_test_bom_item("A", "E", 4)).unwrap();
+
+        let diff = BomGraph::diff(&old, &new);
+        let change = |parent: &str, child: &str, old: Option<i32>, new: Option<i32>| BomItemChange {
+            parent_id: ComponentId::new(parent),
+            child_id: ComponentId::new(child),
+            old_quantity: old.map(Decimal::from),
+            new_quantity: new.map(Decimal::from),
+            old_scrap_factor: old.map(|_| Decimal::ZERO),
+            new_scrap_factor: new.map(|_| Decimal::ZERO),
+        };
+        assert_eq!(diff.added_edges, vec![change("A", "E", None, Some(4))]);
+        assert_eq!(diff.removed_edges, vec![change("A", "C", Some(2), None)]);
+        assert_eq!(diff.modified_edges, vec![change("B", "D", Some(3), Some(5))]);
+
+        // Scrap changes count too; a second item between the same pair is an addition
+        let mut scrapped = a_b.clone();
+        scrapped.scrap_factor = Decimal::new(5, 2);
+        let mut again = create_test_bom_item("A", "B", 1);
+        again.sequence = 20;
+        let mut newer = BomGraph::new();
+        newer.add_bom_item(scrapped).unwrap();
+        newer.add_bom_item(again).unwrap();
+        let diff = BomGraph::diff(&old, &newer);
+        assert_eq!(diff.modified_edges.len(), 1);
+        assert_eq!(diff.modified_edges[0].new_scrap_factor, Some(Decimal::new(5, 2)));
+        assert_eq!(diff.added_edges, vec![change("A", "B", None, Some(1))]);
+        assert_eq!(diff.removed_edges.len(), 2);
+
+        assert!(BomGraph::diff(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_remove_bom_item() {
+        let mut graph = BomGraph::new();
+
+        // A -> B -> C, A -> C (twice), A -> D
+        graph.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("B", "C", 2)).unwrap();
+        graph.add_bom_item(create_test_bom_item("A", "C", 3)).unwrap();
+        graph.add_bom_item(create_test_bom_item("A", "C", 4)).unwrap();
+        graph.add_bom_item(create_test_bom_item("A", "D", 1)).unwrap();
+        graph.precompute_levels();
+        let id = ComponentId::new;
+
+        graph.remove_bom_item(&id("A"), &id("C")).unwrap();
+        assert_eq!(graph.arena().edge_count(), 3);
+        assert_eq!(graph.all_paths_between(&id("A"), &id("C")).unwrap(), vec![vec![id("A"), id("B"), id("C")]]);
+        assert_eq!(graph.node_level(&id("A")), None);
+
+        // D is no longer reached from A and becomes a root of its own
+        graph.remove_bom_item(&id("A"), &id("D")).unwrap();
+        assert_eq!(graph.arena().edge_count(), 2);
+        assert_eq!(graph.path_exists(&id("A"), &id("D")), Some(false));
+        let a = graph.find_node(&id("A")).unwrap();
+        let children: Vec<&str> = graph
+            .arena()
+            .children(a)
+            .map(|(child, _)| graph.arena().node(child).unwrap().component_id.as_str())
+            .collect();
+        assert_eq!(children, vec!["B"]);
+        assert_eq!(graph.roots().len(), 2);
+
+        assert!(matches!(
+            graph.remove_bom_item(&id("A"), &id("D")),
+            Err(BomError::BomItemNotFound(_))
+        ));
+        assert!(graph.remove_bom_item(&id("A"), &id("X")).is_err());
+    }
+
+    #[test]
+    fn test_contract_phantoms() {
+        let repo = InMemoryRepository::new();
+
+        // A -> P (phantom, qty 2, 10% scrap) -> C (qty 3)
+        //   -> D (qty 1)
+        let mut phantom = create_test_bom_item("A", "P", 2);
+        phantom.is_phantom = true;
+        phantom.scrap_factor = Decimal::new(1, 1);
+        repo.add_bom_item(phantom).unwrap();
+        repo.add_bom_item(create_test_bom_item("P", "C", 3)).unwrap();
+        repo.add_bom_item(create_test_bom_item("A", "D", 1)).unwrap();
+
+        let graph = BomGraph::from_repository(&repo).unwrap();
+        let contracted = graph.contract_phantoms();
+
+        assert!(contracted.find_node(&ComponentId::new("P")).is_none());
+        assert_eq!(contracted.stats().node_count, 3);
+
+        let a = contracted.find_node(&ComponentId::new("A")).unwrap();
+        assert_eq!(contracted.roots(), &[a]);
+
+        let mut edges: Vec<(&str, &str, Decimal)> = contracted
+            .iter_edges()
+            .map(|(_, _, item)| (item.parent_id.as_str(), item.child_id.as_str(), item.effective_quantity()))
+            .collect();
+        edges.sort_by_key(|(_, child, _)| *child);
+        assert_eq!(
+            edges,
+            vec![("A", "C", Decimal::new(66, 1)), ("A", "D", Decimal::ONE)]
+        );
+
+        // The original graph is unchanged
+        assert!(graph.find_node(&ComponentId::new("P")).is_some());
+    }
+
+    #[test]
+    fn test_find_phantom_loops() {
+        let mut graph = BomGraph::new();
+        graph.add_bom_item(create_test_bom_item("ROOT", "A", 1)).unwrap();
+        assert!(graph.find_phantom_loops().is_empty());
+
+        // A -> P1 -> P2 -> A, every item a phantom; `arena_mut` bypasses the cycle check
+        let mut link = |parent: &str, child: &str, is_phantom: bool| {
+            let mut item = create_test_bom_item(parent, child, 1);
+            item.is_phantom = is_phantom;
+            let arena = graph.arena_mut();
+            let parent = arena.add_node(item.parent_id.clone());
+            let child = arena.add_node(item.child_id.clone());
+            arena.add_edge(parent, child, item);
+        };
+        link("A", "P1", true);
+        link("P1", "P2", true);
+        link("P2", "A", true);
+        // B -> P3 -> B is a loop, but B -> P3 is a normal item
+        link("B", "P3", false);
+        link("P3", "B", true);
+
+        let loops = graph.find_phantom_loops();
+        assert_eq!(loops.len(), 1);
+        let ids: Vec<&str> = loops[0].iter().map(|id| id.as_str()).collect();
+        assert_eq!(ids, ["A", "P1", "P2"]);
+    }
+
+    #[test]
+    fn test_estimate_memory_for_matches_growth() {
+        let mut graph = BomGraph::new();
+        let empty = graph.capacity();
+        assert_eq!(empty.current_nodes, 0);
+        assert_eq!(empty.load_factor, 0.0);
+        let before = graph.arena().heap_bytes();
+        let estimate = graph.estimate_memory_for(1000, 2000);
+
+        // 1000 nodes N0000..N0999, each using the next ones: 999 + 998 + 3 = 2000 edges
+        let mut edges = Vec::new();
+        for offset in 1..=3 {
+            edges.extend((0..1000 - offset).map(|i| (i, i + offset)));
+        }
+        for (parent, child) in edges.into_iter().take(2000) {
+            let item = create_test_bom_item(&format!("N{:04}", parent), &format!("N{:04}", child), 1);
+            graph.add_bom_item(item).unwrap();
+        }
+
+        let capacity = graph.capacity();
+        assert_eq!(capacity.current_nodes, 1000);
+        assert_eq!(capacity.current_edges, 2000);
+        assert!(capacity.node_capacity >= 1000 && capacity.edge_capacity >= 2000);
+        assert!(capacity.load_factor > 0.5 && capacity.load_factor <= 1.0);
+
+        // Spare capacity from vector growth is not estimated, so the estimate is a lower bound
+        let actual = graph.arena().heap_bytes() - before;
+        assert!(estimate <= actual && actual < estimate * 3 / 2, "estimate {} actual {}", estimate, actual);
+    }
+
+    #[test]
+    fn test_betweenness_centrality() {
+        // Diamond A -> B -> D, A -> C -> D, with D's own child E so that D lies between
+        // components (as an end point of every path D itself would score zero)
+        let mut graph = BomGraph::new();
+        for (parent, child) in [("A", "B"), ("A", "C"), ("B", "D"), ("C", "D"), ("D", "E")] {
+            graph.add_bom_item(create_test_bom_item(parent, child, 1)).unwrap();
+        }
+
+        let centrality = graph.betweenness_centrality();
+        let score = |id: &str| centrality[&ComponentId::new(id)];
+        assert_eq!(centrality.len(), 5);
+        // D is on A->E, B->E and C->E; B and C each carry half of A->D and A->E
+        assert!((score("D") - 3.0 / 12.0).abs() < 1e-9);
+        assert!((score("B") - 1.0 / 12.0).abs() < 1e-9);
+        assert_eq!(score("B"), score("C"));
+        assert!(score("D") > score("B"));
+        assert_eq!(score("A"), 0.0);
+        assert_eq!(score("E"), 0.0);
+
+        // A shortcut A -> D makes the paths through B and C longer than the shortest
+        graph.add_bom_item(create_test_bom_item("A", "D", 1)).unwrap();
+        let centrality = graph.betweenness_centrality();
+        let score = |id: &str| centrality[&ComponentId::new(id)];
+        // D is still on A->E, B->E and C->E; B and C no longer carry anything
+        assert!((score("D") - 3.0 / 12.0).abs() < 1e-9);
+        assert_eq!(score("B"), 0.0);
+        assert_eq!(score("C"), 0.0);
+    }
+
+    #[test]
+    fn test_add_virtual_root() {
+        let mut graph = BomGraph::new();
+        graph.add_bom_item(create_test_bom_item("BIKE", "FRAME", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("TRIKE", "FRAME", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("TRIKE", "BASKET", 1)).unwrap();
+        graph.identify_roots();
+        assert_eq!(graph.roots().len(), 2);
+
+        let family = ComponentId::new("FAMILY");
+        let node = graph.add_virtual_root(&family).unwrap();
+        assert_eq!(graph.roots(), [node]);
+
+        let children: Vec<(&str, Decimal, u32)> = graph
+            .arena()
+            .iter_edges_by_source(node)
+            .map(|edge| (edge.bom_item.child_id.as_str(), edge.bom_item.quantity, edge.bom_item.sequence))
+            .collect();
+        assert_eq!(children, vec![("BIKE", Decimal::ONE, 10), ("TRIKE", Decimal::ONE, 20)]);
+
+        assert!(matches!(
+            graph.add_virtual_root(&ComponentId::new("BIKE")),
+            Err(BomError::InvalidBomItem(_))
+        ));
     }
 }
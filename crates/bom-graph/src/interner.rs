@@ -0,0 +1,152 @@
+use bom_core::ComponentId;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, RandomState};
+
+/// Maps each distinct component ID to a `u32`, storing every ID string once
+/// (the lookup index holds hashes, not copies of the IDs). The arena of every `BomGraph`
+/// interns its IDs, so its nodes and BOM items share these strings
+#[derive(Debug, Clone, Default)]
+pub struct ComponentIdInterner {
+    ids: Vec<ComponentId>,
+    /// Interned indices by ID hash; more than one only on hash collisions
+    index: HashMap<u64, Vec<u32>>,
+    hasher: RandomState,
+}
+
+impl ComponentIdInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interner with room for `capacity` IDs
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            ids: Vec::with_capacity(capacity),
+            index: HashMap::with_capacity(capacity),
+            hasher: RandomState::new(),
+        }
+    }
+
+    /// Index of `component_id`, assigning the next free one if it is new
+    pub fn intern(&mut self, component_id: &ComponentId) -> u32 {
+        if let Some(interned) = self.get(component_id) {
+            return interned;
+        }
+        let interned = u32::try_from(self.ids.len()).expect("more than u32::MAX component IDs");
+        self.ids.push(component_id.clone());
+        let hash = self.hasher.hash_one(component_id);
+        self.index.entry(hash).or_default().push(interned);
+        interned
+    }
+
+    /// Index of an already interned `component_id`
+    pub fn get(&self, component_id: &ComponentId) -> Option<u32> {
+        let hash = self.hasher.hash_one(component_id);
+        self.index
+            .get(&hash)?
+            .iter()
+            .copied()
+            .find(|&interned| &self.ids[interned as usize] == component_id)
+    }
+
+    /// Component ID of an index returned by `intern`
+    /// Panics if the index was not handed out by this interner
+    pub fn resolve(&self, interned: u32) -> &ComponentId {
+        &self.ids[interned as usize]
+    }
+
+    /// Number of distinct component IDs
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Heap bytes of the ID list and lookup index (ID strings not included)
+    #[cfg(test)]
+    pub(crate) fn heap_bytes(&self) -> usize {
+        use std::mem::size_of;
+
+        let buckets: usize = self.index.values().map(|bucket| bucket.capacity() * size_of::<u32>()).sum();
+        self.ids.capacity() * size_of::<ComponentId>()
+            + self.index.capacity() * (size_of::<(u64, Vec<u32>)>() + 1)
+            + buckets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::BomGraph;
+    use bom_core::BomItem;
+    use rust_decimal::Decimal;
+    use uuid::Uuid;
+
+    fn create_test_bom_item(parent: &str, child: &str, qty: i64) -> BomItem {
+        BomItem {
+            id: Uuid::new_v4(),
+            parent_id: ComponentId::new(parent),
+            child_id: ComponentId::new(child),
+            quantity: Decimal::from(qty),
+            scrap_factor: Decimal::ZERO,
+            sequence: 10,
+            operation_sequence: None,
+            is_phantom: false,
+            effective_from: None,
+            effective_to: None,
+            alternative_group: None,
+            alternative_priority: None,
+            reference_designator: None,
+            position: None,
+            notes: None,
+            version: 0,
+        }
+    }
+
+    #[test]
+    fn test_interner_round_trip() {
+        let mut interner = ComponentIdInterner::new();
+        let ids: Vec<ComponentId> = ["BIKE-001", "FRAME-001", "WHEEL-001"]
+            .into_iter()
+            .map(ComponentId::new)
+            .collect();
+
+        let interned: Vec<u32> = ids.iter().map(|id| interner.intern(id)).collect();
+        assert_eq!(interned, vec![0, 1, 2]);
+        assert_eq!(interner.intern(&ComponentId::new("FRAME-001")), 1);
+        assert_eq!(interner.len(), 3);
+
+        for (id, &index) in ids.iter().zip(&interned) {
+            assert_eq!(interner.resolve(index), id);
+            assert_eq!(interner.get(id), Some(index));
+        }
+        assert_eq!(interner.get(&ComponentId::new("SEAT-001")), None);
+    }
+
+    #[test]
+    fn test_graph_shares_interned_ids() {
+        // BIKE -> FRAME -> TUBE (x4), BIKE -> WHEEL (x2), FRAME -> WHEEL (x1)
+        let mut graph = BomGraph::new();
+        graph.add_bom_item(create_test_bom_item("BIKE", "FRAME", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("BIKE", "WHEEL", 2)).unwrap();
+        graph.add_bom_item(create_test_bom_item("FRAME", "TUBE", 4)).unwrap();
+        graph.add_bom_item(create_test_bom_item("FRAME", "WHEEL", 1)).unwrap();
+
+        let arena = graph.arena();
+        assert_eq!(arena.interner().len(), 4);
+        for (node, id) in graph.iter_nodes() {
+            let interned = arena.interner().get(id).unwrap();
+            assert_eq!(arena.interner().resolve(interned), id);
+            assert!(arena.interner().resolve(interned).shares_storage(id));
+            assert_eq!(graph.find_node(id), Some(node));
+        }
+
+        // Every BOM item refers to its components through the nodes' strings
+        for (parent, child, item) in graph.iter_edges() {
+            assert!(item.parent_id.shares_storage(&arena.node(parent).unwrap().component_id));
+            assert!(item.child_id.shares_storage(&arena.node(child).unwrap().component_id));
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use crate::graph::BomGraph;
+use bom_core::{BomItem, BomRepository, ComponentId, Result};
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// BOM graph that loads BOM items from the repository on demand
+/// For repositories too large to load with `BomGraph::from_repository`
+pub struct LazyBomGraph {
+    repository: Arc<dyn BomRepository>,
+    graph: BomGraph,
+    /// Components whose children have been loaded
+    loaded: HashSet<ComponentId>,
+    effective_date: Option<DateTime<Utc>>,
+}
+
+impl LazyBomGraph {
+    /// Create an empty lazy graph (items effective now)
+    pub fn new(repository: Arc<dyn BomRepository>) -> Self {
+        Self {
+            repository,
+            graph: BomGraph::new(),
+            loaded: HashSet::new(),
+            effective_date: None,
+        }
+    }
+
+    /// Create an empty lazy graph that loads items effective at `effective_date`
+    pub fn with_effective_date(repository: Arc<dyn BomRepository>, effective_date: DateTime<Utc>) -> Self {
+        Self {
+            effective_date: Some(effective_date),
+            ..Self::new(repository)
+        }
+    }
+
+    /// BOM items of a component, loading them from the repository on first access
+    pub fn children(&mut self, component_id: &ComponentId) -> Result<Vec<BomItem>> {
+        self.ensure_loaded(component_id)?;
+
+        let graph = &self.graph;
+        Ok(graph
+            .find_node(component_id)
+            .map(|node| {
+                graph
+                    .arena()
+                    .children(node)
+                    .map(|(_, edge)| edge.bom_item.clone())
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// Load the whole tree below a component and return the graph
+    /// The result can be passed to the explosion and cost calculators
+    pub fn load_subtree(&mut self, component_id: &ComponentId) -> Result<&BomGraph> {
+        let mut pending = vec![component_id.clone()];
+        let mut visited = HashSet::new();
+
+        while let Some(current) = pending.pop() {
+            if !visited.insert(current.clone()) {
+                continue;
+            }
+            for item in self.children(&current)? {
+                pending.push(item.child_id);
+            }
+        }
+
+        Ok(&self.graph)
+    }
+
+    /// Whether the children of a component have been loaded
+    pub fn is_loaded(&self, component_id: &ComponentId) -> bool {
+        self.loaded.contains(component_id)
+    }
+
+    /// The part of the graph loaded so far
+    pub fn graph(&self) -> &BomGraph {
+        &self.graph
+    }
+
+    fn ensure_loaded(&mut self, component_id: &ComponentId) -> Result<()> {
+        if self.loaded.contains(component_id) {
+            return Ok(());
+        }
+
+        let items = self.repository.get_bom_items(component_id, self.effective_date)?;
+
+        // Roots are kept up to date per item; rescanning all nodes would make loading quadratic
+        if self.graph.find_node(component_id).is_none() {
+            let node = self.graph.arena_mut().add_node(component_id.clone());
+            self.graph.push_root(node);
+        }
+        for item in items {
+            let child_was_root = self
+                .graph
+                .find_node(&item.child_id)
+                .filter(|&node| self.graph.arena().parents(node).next().is_none());
+            self.graph.add_bom_item(item)?;
+            if let Some(node) = child_was_root {
+                self.graph.remove_root(node);
+            }
+        }
+
+        self.loaded.insert(component_id.clone());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bom_core::repository::memory::InMemoryRepository;
+    use bom_core::*;
+    use rust_decimal::Decimal;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use uuid::Uuid;
+
+    /// Repository wrapper counting `get_bom_items` calls
+    struct CountingRepository {
+        inner: InMemoryRepository,
+        bom_item_calls: AtomicUsize,
+    }
+
+    impl BomRepository for CountingRepository {
+        fn get_component(&self, id: &ComponentId) -> Result<Component> {
+            self.inner.get_component(id)
+        }
+
+        fn get_components(&self, ids: &[ComponentId]) -> Result<Vec<Component>> {
+            self.inner.get_components(ids)
+        }
+
+        fn get_bom_header(
+            &self,
+            component_id: &ComponentId,
+            alternative: Option<&str>,
+            effective_date: Option<DateTime<Utc>>,
+        ) -> Result<BomHeader> {
+            self.inner.get_bom_header(component_id, alternative, effective_date)
+        }
+
+        fn get_bom_items(
+            &self,
+            component_id: &ComponentId,
+            effective_date: Option<DateTime<Utc>>,
+        ) -> Result<Vec<BomItem>> {
+            self.bom_item_calls.fetch_add(1, Ordering::SeqCst);
+            self.inner.get_bom_items(component_id, effective_date)
+        }
+
+        fn get_all_bom_items(&self) -> Result<Vec<BomItem>> {
+            self.inner.get_all_bom_items()
+        }
+
+        fn find_parents(&self, component_id: &ComponentId) -> Result<Vec<BomItem>> {
+            self.inner.find_parents(component_id)
+        }
+    }
+
+    fn create_test_bom_item(parent: &str, child: &str, qty: i32) -> BomItem {
+        BomItem {
+            id: Uuid::new_v4(),
+            parent_id: ComponentId::new(parent),
+            child_id: ComponentId::new(child),
+            quantity: Decimal::from(qty),
+            scrap_factor: Decimal::ZERO,
+            sequence: 10,
+            operation_sequence: None,
+            is_phantom: false,
+            effective_from: None,
+            effective_to: None,
+            alternative_group: None,
+            alternative_priority: None,
+            reference_designator: None,
+            position: None,
+            notes: None,
+            version: 0,
+        }
+    }
+
+    #[test]
+    fn test_children_are_loaded_once() {
+        let inner = InMemoryRepository::new();
+
+        // A -> B -> D
+        //   -> C
+        inner.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        inner.add_bom_item(create_test_bom_item("A", "C", 1)).unwrap();
+        inner.add_bom_item(create_test_bom_item("B", "D", 1)).unwrap();
+
+        let repo = Arc::new(CountingRepository {
+            inner,
+            bom_item_calls: AtomicUsize::new(0),
+        });
+        let mut lazy = LazyBomGraph::new(repo.clone());
+
+        let a = ComponentId::new("A");
+        assert_eq!(lazy.children(&a).unwrap().len(), 2);
+        assert_eq!(repo.bom_item_calls.load(Ordering::SeqCst), 1);
+
+        // Second access is served from the loaded graph
+        assert_eq!(lazy.children(&a).unwrap().len(), 2);
+        assert_eq!(repo.bom_item_calls.load(Ordering::SeqCst), 1);
+        assert!(!lazy.is_loaded(&ComponentId::new("B")));
+
+        // Loading the subtree only fetches the components not loaded yet (B, C, D)
+        let graph = lazy.load_subtree(&a).unwrap();
+        assert_eq!(graph.stats().node_count, 4);
+        assert_eq!(graph.stats().edge_count, 3);
+        assert_eq!(graph.roots().len(), 1);
+        assert_eq!(repo.bom_item_calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_roots_follow_load_order() {
+        let repo = InMemoryRepository::new();
+        repo.add_bom_item(create_test_bom_item("A", "B", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("B", "C", 1)).unwrap();
+        repo.add_bom_item(create_test_bom_item("X", "C", 1)).unwrap();
+        let mut lazy = LazyBomGraph::new(Arc::new(repo));
+
+        // B is loaded first, so it is a root until its parent A is loaded
+        lazy.children(&ComponentId::new("B")).unwrap();
+        lazy.children(&ComponentId::new("X")).unwrap();
+        let root_ids = |lazy: &LazyBomGraph| {
+            let mut ids: Vec<String> = lazy
+                .graph()
+                .roots()
+                .iter()
+                .map(|&node| lazy.graph().arena().node(node).unwrap().component_id.as_str().to_string())
+                .collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(root_ids(&lazy), vec!["B", "X"]);
+
+        lazy.children(&ComponentId::new("A")).unwrap();
+        assert_eq!(root_ids(&lazy), vec!["A", "X"]);
+
+        // Same roots as a full scan
+        let mut scanned: Vec<&str> = lazy.graph().iter_root_nodes().map(|(_, id)| id.as_str()).collect();
+        scanned.sort();
+        assert_eq!(scanned, vec!["A", "X"]);
+    }
+}
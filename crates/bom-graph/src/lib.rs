@@ -2,8 +2,26 @@ pub mod arena;
 pub mod graph;
 pub mod traversal;
 pub mod cycle;
+pub mod lazy;
+pub mod matrix;
+pub mod mermaid;
+pub mod interner;
+pub mod snapshot;
+pub mod d3;
+pub mod dot;
+mod diagram;
+#[cfg(feature = "petgraph")]
+pub mod petgraph_interop;
 
 pub use arena::*;
 pub use graph::*;
 pub use traversal::*;
 pub use cycle::*;
+pub use lazy::*;
+pub use matrix::*;
+pub use interner::*;
+pub use snapshot::*;
+pub use d3::*;
+pub use dot::*;
+#[cfg(feature = "petgraph")]
+pub use petgraph_interop::*;
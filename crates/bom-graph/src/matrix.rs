@@ -0,0 +1,286 @@
+use crate::graph::BomGraph;
+use bom_core::{BomError, ComponentId, Result};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// Direct-requirements matrix of a BOM (input-output / Leontief representation)
+/// `matrix[i][j]` is the quantity of `component_ids[j]` needed per unit of `component_ids[i]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdjacencyMatrix {
+    pub component_ids: Vec<ComponentId>,
+    pub matrix: Vec<Vec<Decimal>>,
+}
+
+impl AdjacencyMatrix {
+    /// Quantity of `child` needed per unit of `parent` (zero if either is unknown)
+    pub fn get(&self, parent: &ComponentId, child: &ComponentId) -> Decimal {
+        let position = |id: &ComponentId| self.component_ids.iter().position(|c| c == id);
+        match (position(parent), position(child)) {
+            (Some(i), Some(j)) => self.matrix[i][j],
+            _ => Decimal::ZERO,
+        }
+    }
+
+    /// Matrix product `self * other`
+    /// Multiplying the direct-requirements matrix by itself gives the requirements two
+    /// levels down, and so on. Summing the powers gives the total requirements.
+    /// Fails if the two matrices are not square matrices over the same components in the same order
+    pub fn multiply(&self, other: &Self) -> Result<Self> {
+        if self.component_ids != other.component_ids {
+            return Err(BomError::CalculationError(
+                "Matrices must be over the same components".to_string(),
+            ));
+        }
+        let n = self.component_ids.len();
+        let is_square = |matrix: &[Vec<Decimal>]| matrix.len() == n && matrix.iter().all(|row| row.len() == n);
+        if !is_square(&self.matrix) || !is_square(&other.matrix) {
+            return Err(BomError::CalculationError(format!(
+                "Matrices must be {}x{} to match their components",
+                n, n
+            )));
+        }
+
+        let mut matrix = vec![vec![Decimal::ZERO; n]; n];
+        for (i, row) in self.matrix.iter().enumerate() {
+            for (k, &quantity) in row.iter().enumerate() {
+                if quantity.is_zero() {
+                    continue;
+                }
+                for (j, cell) in matrix[i].iter_mut().enumerate() {
+                    *cell += quantity * other.matrix[k][j];
+                }
+            }
+        }
+
+        Ok(Self {
+            component_ids: self.component_ids.clone(),
+            matrix,
+        })
+    }
+
+    /// Convert to an `ndarray` matrix of `f64` (for linear algebra crates)
+    #[cfg(feature = "ndarray")]
+    pub fn to_ndarray(&self) -> ndarray::Array2<f64> {
+        use rust_decimal::prelude::ToPrimitive;
+
+        let n = self.component_ids.len();
+        ndarray::Array2::from_shape_fn((n, n), |(i, j)| self.matrix[i][j].to_f64().unwrap_or(f64::NAN))
+    }
+}
+
+/// Precomputed ancestor/descendant relation of a BOM (transitive closure)
+/// Stored as one bit per node pair, so memory grows with the square of the node count:
+/// about 12 MB at 10k nodes. Only suitable for graphs under ~10k nodes; larger graphs
+/// should keep using `BomGraph::path_exists`
+#[derive(Debug, Clone)]
+pub struct ReachabilityMatrix {
+    index: HashMap<ComponentId, usize>,
+    words_per_row: usize,
+    /// Row `i` has bit `j` set if node `j` is reachable from node `i`
+    bits: Vec<u64>,
+}
+
+impl ReachabilityMatrix {
+    /// Check if `ancestor_id` uses `descendant_id`, directly or through sub-assemblies
+    /// A component is not its own ancestor; unknown components have no relations
+    pub fn is_ancestor(&self, ancestor_id: &ComponentId, descendant_id: &ComponentId) -> bool {
+        match (self.index.get(ancestor_id), self.index.get(descendant_id)) {
+            (Some(&i), Some(&j)) => i != j && self.reaches(i, j),
+            _ => false,
+        }
+    }
+
+    /// Check if `descendant_id` is used in `ancestor_id`, directly or through sub-assemblies
+    pub fn is_descendant(&self, descendant_id: &ComponentId, ancestor_id: &ComponentId) -> bool {
+        self.is_ancestor(ancestor_id, descendant_id)
+    }
+
+    fn reaches(&self, from: usize, to: usize) -> bool {
+        self.bits[from * self.words_per_row + to / 64] & (1 << (to % 64)) != 0
+    }
+}
+
+impl BomGraph {
+    /// Precompute reachability between all node pairs for O(1) ancestor queries
+    /// Runs a DFS from every node, so building takes O(V * (V + E)); see
+    /// `ReachabilityMatrix` for the memory cost
+    pub fn compute_reachability(&self) -> ReachabilityMatrix {
+        let arena = self.arena();
+        let node_count = arena.nodes().len();
+        let words_per_row = node_count.div_ceil(64);
+        let mut bits = vec![0u64; node_count * words_per_row];
+
+        for (start, _) in arena.live_nodes() {
+            let row = &mut bits[start.0 * words_per_row..(start.0 + 1) * words_per_row];
+            let mut stack = vec![start];
+
+            while let Some(current) = stack.pop() {
+                for (child, _) in arena.children(current) {
+                    let (word, bit) = (child.0 / 64, 1u64 << (child.0 % 64));
+                    if row[word] & bit == 0 {
+                        row[word] |= bit;
+                        stack.push(child);
+                    }
+                }
+            }
+        }
+
+        let index = arena
+            .live_nodes()
+            .map(|(idx, node)| (node.component_id.clone(), idx.0))
+            .collect();
+
+        ReachabilityMatrix {
+            index,
+            words_per_row,
+            bits,
+        }
+    }
+
+    /// Export the graph as a direct-requirements matrix
+    /// Rows and columns follow node insertion order. Entries are effective quantities
+    /// (scrap included); several items for the same pair are summed
+    pub fn to_adjacency_matrix(&self) -> AdjacencyMatrix {
+        let component_ids: Vec<ComponentId> = self.iter_nodes().map(|(_, id)| id.clone()).collect();
+        let position: HashMap<&ComponentId, usize> =
+            component_ids.iter().enumerate().map(|(i, id)| (id, i)).collect();
+
+        let n = component_ids.len();
+        let mut matrix = vec![vec![Decimal::ZERO; n]; n];
+        for (_, edge) in self.arena().live_edges() {
+            let item = &edge.bom_item;
+            if let (Some(&i), Some(&j)) = (position.get(&item.parent_id), position.get(&item.child_id)) {
+                matrix[i][j] += edge.effective_quantity;
+            }
+        }
+
+        AdjacencyMatrix { component_ids, matrix }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bom_core::BomItem;
+
+    fn create_test_bom_item(parent: &str, child: &str, quantity: Decimal, scrap_factor: Decimal) -> BomItem {
+        BomItem {
+            id: uuid::Uuid::new_v4(),
+            parent_id: ComponentId::new(parent),
+            child_id: ComponentId::new(child),
+            quantity,
+            scrap_factor,
+            sequence: 10,
+            operation_sequence: None,
+            is_phantom: false,
+            effective_from: None,
+            effective_to: None,
+            alternative_group: None,
+            alternative_priority: None,
+            reference_designator: None,
+            position: None,
+            notes: None,
+            version: 0,
+        }
+    }
+
+    /// The bicycle from `bom-cli/example_bom.json`
+    fn bicycle() -> BomGraph {
+        let mut graph = BomGraph::new();
+        graph
+            .add_bom_item(create_test_bom_item("BIKE-001", "FRAME-001", Decimal::ONE, Decimal::ZERO))
+            .unwrap();
+        graph
+            .add_bom_item(create_test_bom_item("BIKE-001", "WHEEL-001", Decimal::from(2), Decimal::new(5, 2)))
+            .unwrap();
+        graph
+            .add_bom_item(create_test_bom_item("FRAME-001", "TUBE-001", Decimal::from(4), Decimal::new(1, 1)))
+            .unwrap();
+        graph
+    }
+
+    #[test]
+    fn test_bicycle_adjacency_matrix() {
+        let matrix = bicycle().to_adjacency_matrix();
+
+        let ids: Vec<&str> = matrix.component_ids.iter().map(|id| id.as_str()).collect();
+        assert_eq!(ids, vec!["BIKE-001", "FRAME-001", "WHEEL-001", "TUBE-001"]);
+
+        let zero = Decimal::ZERO;
+        assert_eq!(
+            matrix.matrix,
+            vec![
+                vec![zero, Decimal::ONE, Decimal::new(21, 1), zero],
+                vec![zero, zero, zero, Decimal::new(44, 1)],
+                vec![zero, zero, zero, zero],
+                vec![zero, zero, zero, zero],
+            ]
+        );
+
+        // Two levels down: tubes per bike through the frame
+        let squared = matrix.multiply(&matrix).unwrap();
+        let bike = ComponentId::new("BIKE-001");
+        assert_eq!(squared.get(&bike, &ComponentId::new("TUBE-001")), Decimal::new(44, 1));
+        assert_eq!(squared.get(&bike, &ComponentId::new("FRAME-001")), zero);
+
+        // Three levels: nothing left
+        let cubed = squared.multiply(&matrix).unwrap();
+        assert!(cubed.matrix.iter().flatten().all(|quantity| quantity.is_zero()));
+
+        // Different components
+        let mut other = matrix.clone();
+        other.component_ids.reverse();
+        assert!(matrix.multiply(&other).is_err());
+
+        // Not square
+        let mut ragged = matrix.clone();
+        ragged.matrix[0].pop();
+        assert!(matrix.multiply(&ragged).is_err());
+    }
+
+    #[test]
+    fn test_reachability_matches_has_path() {
+        let mut graph = bicycle();
+        // Shared part and a second product, plus more than 64 nodes to span several words
+        graph
+            .add_bom_item(create_test_bom_item("WHEEL-001", "TUBE-001", Decimal::ONE, Decimal::ZERO))
+            .unwrap();
+        graph
+            .add_bom_item(create_test_bom_item("TRIKE-001", "WHEEL-001", Decimal::from(3), Decimal::ZERO))
+            .unwrap();
+        for i in 0..70 {
+            let child = format!("BOLT-{:03}", i);
+            graph
+                .add_bom_item(create_test_bom_item("FRAME-001", &child, Decimal::ONE, Decimal::ZERO))
+                .unwrap();
+        }
+
+        let reachability = graph.compute_reachability();
+        let arena = graph.arena();
+
+        for (from, from_node) in arena.live_nodes() {
+            for (to, to_node) in arena.live_nodes() {
+                let (ancestor, descendant) = (&from_node.component_id, &to_node.component_id);
+                let expected = from != to && arena.has_path(from, to);
+                assert_eq!(reachability.is_ancestor(ancestor, descendant), expected);
+                assert_eq!(reachability.is_descendant(descendant, ancestor), expected);
+            }
+        }
+
+        let bike = ComponentId::new("BIKE-001");
+        assert!(reachability.is_ancestor(&bike, &ComponentId::new("BOLT-069")));
+        assert!(!reachability.is_ancestor(&ComponentId::new("TRIKE-001"), &ComponentId::new("BOLT-000")));
+        assert!(!reachability.is_ancestor(&bike, &bike));
+        assert!(!reachability.is_ancestor(&bike, &ComponentId::new("UNKNOWN")));
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn test_to_ndarray() {
+        let array = bicycle().to_adjacency_matrix().to_ndarray();
+
+        assert_eq!(array.dim(), (4, 4));
+        assert_eq!(array[[0, 2]], 2.1);
+        assert_eq!(array[[1, 3]], 4.4);
+    }
+}
@@ -0,0 +1,249 @@
+use crate::diagram::diagram_id;
+use crate::graph::BomGraph;
+use bom_core::{BomItem, Component, ComponentId};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+impl BomGraph {
+    /// Render the BOM as a Mermaid `erDiagram` (the BOM as a data model)
+    /// Each component becomes an entity with its `id` and, if it is in `components`, its
+    /// `description`, `uom` and `standard_cost`; each BOM item a `parent ||--o{ child`
+    /// relationship labelled with its quantity. Entities are sorted by component ID
+    pub fn to_mermaid_er(&self, components: &HashMap<ComponentId, Component>) -> String {
+        let (ids, names) = self.diagram_ids();
+
+        let mut diagram = String::from("erDiagram\n");
+        // Writing to a String cannot fail
+        for id in &ids {
+            let _ = writeln!(diagram, "    {} {{", names[id]);
+            let _ = writeln!(diagram, "        string id \"{}\"", comment(id.as_str()));
+            if let Some(component) = components.get(id) {
+                let _ = writeln!(diagram, "        string description \"{}\"", comment(&component.description));
+                let _ = writeln!(diagram, "        string uom \"{}\"", comment(&component.uom));
+                match component.standard_cost {
+                    Some(cost) => {
+                        let _ = writeln!(diagram, "        decimal standard_cost \"{}\"", cost);
+                    }
+                    None => diagram.push_str("        decimal standard_cost\n"),
+                }
+            }
+            diagram.push_str("    }\n");
+        }
+
+        for item in self.sorted_items() {
+            let _ = writeln!(
+                diagram,
+                "    {} ||--o{{ {} : \"qty {}\"",
+                names[&item.parent_id],
+                names[&item.child_id],
+                item.quantity
+            );
+        }
+
+        diagram
+    }
+
+    /// Render the BOM as a Mermaid `flowchart TD`
+    /// Each component becomes one rectangle labelled with its ID and, if it is in `components`,
+    /// its description; each BOM item an arrow labelled with its quantity. A component used
+    /// by several parents keeps a single node, so shared parts show as converging arrows.
+    /// Nodes are sorted by component ID and arrows by parent, sequence and child
+    pub fn to_mermaid(&self, components: &HashMap<ComponentId, Component>) -> String {
+        let (ids, node_ids) = self.diagram_ids();
+
+        let mut diagram = String::from("flowchart TD\n");
+        // Writing to a String cannot fail
+        for id in &ids {
+            let _ = match components.get(id) {
+                Some(component) => writeln!(
+                    diagram,
+                    "    {}[\"{}<br/>{}\"]",
+                    node_ids[id],
+                    label(id.as_str()),
+                    label(&component.description)
+                ),
+                None => writeln!(diagram, "    {}[\"{}\"]", node_ids[id], label(id.as_str())),
+            };
+        }
+
+        for item in self.sorted_items() {
+            let _ = writeln!(
+                diagram,
+                "    {} -->|qty {}| {}",
+                node_ids[&item.parent_id],
+                item.quantity,
+                node_ids[&item.child_id]
+            );
+        }
+
+        diagram
+    }
+
+    /// Component IDs sorted, with their diagram IDs (see `diagram_id`)
+    fn diagram_ids(&self) -> (Vec<&ComponentId>, HashMap<&ComponentId, String>) {
+        let mut ids: Vec<&ComponentId> = self.iter_nodes().map(|(_, id)| id).collect();
+        ids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        let mut used = HashSet::new();
+        let diagram_ids = ids.iter().map(|&id| (id, diagram_id(id, &mut used))).collect();
+        (ids, diagram_ids)
+    }
+
+    /// BOM items sorted by parent, sequence and child
+    fn sorted_items(&self) -> Vec<&BomItem> {
+        let mut items: Vec<&BomItem> = self.iter_edges().map(|(_, _, item)| item).collect();
+        items.sort_by(|a, b| {
+            (a.parent_id.as_str(), a.sequence, a.child_id.as_str())
+                .cmp(&(b.parent_id.as_str(), b.sequence, b.child_id.as_str()))
+        });
+        items
+    }
+}
+
+/// Node labels are double-quoted; quotes become the `#quot;` entity and `<`, `>` their HTML
+/// entities so descriptions cannot inject markup next to the `<br/>`
+fn label(text: &str) -> String {
+    text.replace('"', "#quot;").replace('<', "#lt;").replace('>', "#gt;")
+}
+
+/// Attribute comments are double-quoted and cannot contain double quotes
+fn comment(text: &str) -> String {
+    text.replace('"', "'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bom_core::*;
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+
+    fn create_test_component(id: &str, description: &str, standard_cost: Option<Decimal>) -> Component {
+        Component {
+            id: ComponentId::new(id),
+            description: description.to_string(),
+            component_type: ComponentType::SemiFinished,
+            uom: "EA".to_string(),
+            standard_cost,
+            lead_time_days: None,
+            procurement_type: ProcurementType::Make,
+            preferred_vendor: None,
+            organization: "PLANT-01".to_string(),
+            version: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn component_map<const N: usize>(components: [Component; N]) -> HashMap<ComponentId, Component> {
+        components.into_iter().map(|component| (component.id.clone(), component)).collect()
+    }
+
+    fn create_test_bom_item(parent: &str, child: &str, quantity: Decimal) -> BomItem {
+        BomItem {
+            id: uuid::Uuid::new_v4(),
+            parent_id: ComponentId::new(parent),
+            child_id: ComponentId::new(child),
+            quantity,
+            scrap_factor: Decimal::ZERO,
+            sequence: 10,
+            operation_sequence: None,
+            is_phantom: false,
+            effective_from: None,
+            effective_to: None,
+            alternative_group: None,
+            alternative_priority: None,
+            reference_designator: None,
+            position: None,
+            notes: None,
+            version: 0,
+        }
+    }
+
+    #[test]
+    fn test_bicycle_mermaid_er() {
+        // The bicycle from `bom-cli/example_bom.json`
+        let mut graph = BomGraph::new();
+        graph.add_bom_item(create_test_bom_item("BIKE-001", "FRAME-001", Decimal::ONE)).unwrap();
+        graph.add_bom_item(create_test_bom_item("BIKE-001", "WHEEL-001", Decimal::from(2))).unwrap();
+        graph.add_bom_item(create_test_bom_item("FRAME-001", "TUBE-001", Decimal::from(4))).unwrap();
+        graph.add_bom_item(create_test_bom_item("TUBE-001", "end", Decimal::ONE)).unwrap();
+        let components = component_map([
+            create_test_component("BIKE-001", "Bicycle", Some(Decimal::new(100000, 2))),
+            create_test_component("FRAME-001", "Frame \"Sport\"", Some(Decimal::new(30000, 2))),
+            create_test_component("WHEEL-001", "Wheel", None),
+            create_test_component("TUBE-001", "Aluminum Tube", Some(Decimal::new(5000, 2))),
+        ]);
+
+        let diagram = graph.to_mermaid_er(&components);
+
+        assert!(diagram.starts_with("erDiagram\n"));
+        assert!(diagram.contains(
+            "    n_BIKE_001 {\n        string id \"BIKE-001\"\n        string description \"Bicycle\"\n        \
+             string uom \"EA\"\n        decimal standard_cost \"1000.00\"\n    }\n"
+        ));
+        assert!(diagram.contains("        string description \"Frame 'Sport'\"\n"));
+        assert!(diagram.contains("    n_WHEEL_001 {\n"));
+        assert!(diagram.contains("        decimal standard_cost\n"));
+        // Without component data only the ID is known
+        assert!(diagram.contains("    n_end {\n        string id \"end\"\n    }\n"));
+
+        assert!(diagram.contains("    n_BIKE_001 ||--o{ n_FRAME_001 : \"qty 1\"\n"));
+        assert!(diagram.contains("    n_BIKE_001 ||--o{ n_WHEEL_001 : \"qty 2\"\n"));
+        assert!(diagram.contains("    n_FRAME_001 ||--o{ n_TUBE_001 : \"qty 4\"\n"));
+        assert_eq!(diagram.matches("||--o{").count(), 4);
+    }
+
+    #[test]
+    fn test_mermaid_flowchart_shares_converging_nodes() {
+        // The bicycle from `bom-cli/example_bom.json`, with the tube also used by the wheel
+        let mut graph = BomGraph::new();
+        graph.add_bom_item(create_test_bom_item("BIKE-001", "FRAME-001", Decimal::ONE)).unwrap();
+        graph.add_bom_item(create_test_bom_item("BIKE-001", "WHEEL-001", Decimal::from(2))).unwrap();
+        graph.add_bom_item(create_test_bom_item("FRAME-001", "TUBE-001", Decimal::from(4))).unwrap();
+        graph.add_bom_item(create_test_bom_item("WHEEL-001", "TUBE-001", Decimal::new(5, 1))).unwrap();
+        // A component named like a Mermaid keyword, without component data
+        graph.add_bom_item(create_test_bom_item("TUBE-001", "end", Decimal::ONE)).unwrap();
+        let components = component_map([
+            create_test_component("BIKE-001", "Bicycle", None),
+            create_test_component("FRAME-001", "Frame \"Sport\"", None),
+            create_test_component("WHEEL-001", "Wheel", None),
+            create_test_component("TUBE-001", "Aluminum Tube", None),
+        ]);
+
+        let diagram = graph.to_mermaid(&components);
+
+        let mut lines = diagram.lines();
+        assert_eq!(lines.next(), Some("flowchart TD"));
+
+        // Parse `ID["label"]` node lines and `A -->|qty q| B` arrow lines
+        let mut nodes: HashMap<String, String> = HashMap::new();
+        let mut arrows: Vec<(String, String, String)> = Vec::new();
+        for line in lines {
+            let line = line.trim();
+            if let Some((from, rest)) = line.split_once(" -->|") {
+                let (quantity, to) = rest.split_once("| ").unwrap();
+                arrows.push((from.to_string(), quantity.to_string(), to.to_string()));
+            } else {
+                let (id, label) = line.split_once("[\"").unwrap();
+                nodes.insert(id.to_string(), label.strip_suffix("\"]").unwrap().to_string());
+            }
+        }
+
+        let stats = graph.stats();
+        assert_eq!(nodes.len(), stats.node_count);
+        assert_eq!(arrows.len(), stats.edge_count);
+        assert_eq!(nodes["n_TUBE_001"], "TUBE-001<br/>Aluminum Tube");
+        assert_eq!(nodes["n_FRAME_001"], "FRAME-001<br/>Frame #quot;Sport#quot;");
+        assert_eq!(nodes["n_end"], "end");
+
+        // Every arrow connects declared nodes; the shared tube is one node with two arrows into it
+        assert!(arrows.iter().all(|(from, _, to)| nodes.contains_key(from) && nodes.contains_key(to)));
+        let into_tube: Vec<(&str, &str)> = arrows
+            .iter()
+            .filter(|(_, _, to)| to == "n_TUBE_001")
+            .map(|(from, quantity, _)| (from.as_str(), quantity.as_str()))
+            .collect();
+        assert_eq!(into_tube, vec![("n_FRAME_001", "qty 4"), ("n_WHEEL_001", "qty 0.5")]);
+    }
+}
@@ -0,0 +1,171 @@
+use crate::graph::BomGraph;
+use bom_core::{BomItem, ComponentId, Result};
+use petgraph::stable_graph::{NodeIndex as PetNodeIndex, StableDiGraph};
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Edge weight of a BOM exported to `petgraph`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BomItemEdgeData {
+    /// Quantity per parent (`BomItem::quantity`)
+    pub quantity: Decimal,
+    pub scrap_factor: Decimal,
+}
+
+impl BomGraph {
+    /// Copy the graph into a `petgraph` graph (one node per component, one edge per BOM item)
+    /// so petgraph's algorithms can run on it. Only component IDs, quantities and scrap
+    /// factors are carried over
+    pub fn to_petgraph(&self) -> StableDiGraph<ComponentId, BomItemEdgeData> {
+        let mut graph = StableDiGraph::with_capacity(self.arena().node_count(), self.arena().edge_count());
+
+        let nodes: HashMap<crate::NodeIndex, PetNodeIndex> = self
+            .iter_nodes()
+            .map(|(node, id)| (node, graph.add_node(id.clone())))
+            .collect();
+        for (parent, child, item) in self.iter_edges() {
+            graph.add_edge(
+                nodes[&parent],
+                nodes[&child],
+                BomItemEdgeData {
+                    quantity: item.quantity,
+                    scrap_factor: item.scrap_factor,
+                },
+            );
+        }
+        graph
+    }
+
+    /// Build a graph from a `petgraph` graph (the reverse of `to_petgraph`)
+    /// The BOM items get new IDs and sequences 10, 20, ... per parent in edge order;
+    /// fails on invalid quantities or cycles like `add_bom_item`
+    pub fn from_petgraph(graph: &StableDiGraph<ComponentId, BomItemEdgeData>) -> Result<BomGraph> {
+        let mut bom_graph = BomGraph::with_capacity(graph.node_count(), graph.edge_count());
+
+        for node in graph.node_indices() {
+            bom_graph.arena_mut().add_node(graph[node].clone());
+        }
+        for parent in graph.node_indices() {
+            // petgraph lists a node's outgoing edges newest first
+            let mut edges: Vec<_> = graph.edges_directed(parent, Direction::Outgoing).collect();
+            edges.sort_by_key(|edge| edge.id());
+
+            for (position, edge) in edges.into_iter().enumerate() {
+                bom_graph.add_bom_item(BomItem {
+                    id: Uuid::new_v4(),
+                    parent_id: graph[parent].clone(),
+                    child_id: graph[edge.target()].clone(),
+                    quantity: edge.weight().quantity,
+                    scrap_factor: edge.weight().scrap_factor,
+                    sequence: 10 * (position as u32 + 1),
+                    operation_sequence: None,
+                    is_phantom: false,
+                    effective_from: None,
+                    effective_to: None,
+                    alternative_group: None,
+                    alternative_priority: None,
+                    reference_designator: None,
+                    position: None,
+                    notes: None,
+                    version: 0,
+                })?;
+            }
+        }
+
+        bom_graph.identify_roots();
+        Ok(bom_graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_bom_item(parent: &str, child: &str, quantity: Decimal, scrap_factor: Decimal) -> BomItem {
+        BomItem {
+            id: Uuid::new_v4(),
+            parent_id: ComponentId::new(parent),
+            child_id: ComponentId::new(child),
+            quantity,
+            scrap_factor,
+            sequence: 10,
+            operation_sequence: None,
+            is_phantom: false,
+            effective_from: None,
+            effective_to: None,
+            alternative_group: None,
+            alternative_priority: None,
+            reference_designator: None,
+            position: None,
+            notes: None,
+            version: 0,
+        }
+    }
+
+    fn edges(graph: &BomGraph) -> Vec<(String, String, Decimal, Decimal)> {
+        let mut edges: Vec<_> = graph
+            .iter_edges()
+            .map(|(_, _, item)| {
+                (
+                    item.parent_id.as_str().to_string(),
+                    item.child_id.as_str().to_string(),
+                    item.quantity,
+                    item.scrap_factor,
+                )
+            })
+            .collect();
+        edges.sort();
+        edges
+    }
+
+    #[test]
+    fn test_bicycle_petgraph_round_trip() {
+        // The bicycle from `bom-cli/example_bom.json`
+        let mut graph = BomGraph::new();
+        graph
+            .add_bom_item(create_test_bom_item("BIKE-001", "FRAME-001", Decimal::ONE, Decimal::ZERO))
+            .unwrap();
+        graph
+            .add_bom_item(create_test_bom_item("BIKE-001", "WHEEL-001", Decimal::from(2), Decimal::new(5, 2)))
+            .unwrap();
+        graph
+            .add_bom_item(create_test_bom_item("FRAME-001", "TUBE-001", Decimal::from(4), Decimal::new(1, 1)))
+            .unwrap();
+        graph.identify_roots();
+
+        let exported = graph.to_petgraph();
+        assert_eq!(exported.node_count(), 4);
+        assert_eq!(exported.edge_count(), 3);
+        assert!(!petgraph::algo::is_cyclic_directed(&exported));
+
+        let restored = BomGraph::from_petgraph(&exported).unwrap();
+        let (before, after) = (graph.stats(), restored.stats());
+        assert_eq!(after.node_count, before.node_count);
+        assert_eq!(after.edge_count, before.edge_count);
+        assert_eq!(after.root_count, before.root_count);
+        assert_eq!(after.max_depth, before.max_depth);
+
+        let mut ids: Vec<&str> = restored.iter_nodes().map(|(_, id)| id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, ["BIKE-001", "FRAME-001", "TUBE-001", "WHEEL-001"]);
+        assert_eq!(edges(&restored), edges(&graph));
+    }
+
+    #[test]
+    fn test_from_petgraph_rejects_cycles() {
+        let mut graph = StableDiGraph::new();
+        let a = graph.add_node(ComponentId::new("A"));
+        let b = graph.add_node(ComponentId::new("B"));
+        let edge = BomItemEdgeData {
+            quantity: Decimal::ONE,
+            scrap_factor: Decimal::ZERO,
+        };
+        graph.add_edge(a, b, edge.clone());
+        graph.add_edge(b, a, edge);
+
+        assert!(BomGraph::from_petgraph(&graph).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+use crate::graph::BomGraph;
+use bom_core::{BomError, BomItem, ComponentId, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Version written by `BomGraph::to_json`
+///
+/// Format history of `graph_data`:
+/// - 1: `{ "items": [BomItem] }`; nodes are the parents and children of the items
+/// - 2: `{ "components": [ComponentId], "bom_items": [BomItem] }`; `items` renamed to
+///   `bom_items`, every node listed so components without BOM items survive a round trip
+pub const CURRENT_SNAPSHOT_VERSION: u32 = 2;
+
+/// Versioned envelope of a serialized `BomGraph` (快照格式)
+/// `graph_data` is kept as raw JSON so older versions can be migrated before parsing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BomGraphSnapshot {
+    pub version: u32,
+    pub graph_data: Value,
+}
+
+/// `graph_data` of the current version
+#[derive(Debug, Serialize, Deserialize)]
+struct GraphData {
+    components: Vec<ComponentId>,
+    bom_items: Vec<BomItem>,
+}
+
+/// Migration from version `n` to `n + 1` is at position `n - 1`
+const MIGRATIONS: [fn(Value) -> Result<Value>; (CURRENT_SNAPSHOT_VERSION - 1) as usize] = [migrate_v1_to_v2];
+
+/// Version 1 -> 2: rename `items` to `bom_items`; version 1 lists no components
+fn migrate_v1_to_v2(graph_data: Value) -> Result<Value> {
+    let Value::Object(mut fields) = graph_data else {
+        return Err(BomError::SerializationError(
+            "snapshot graph_data must be an object".to_string(),
+        ));
+    };
+    let items = fields
+        .remove("items")
+        .ok_or_else(|| BomError::SerializationError("version 1 snapshot without items".to_string()))?;
+    fields.insert("bom_items".to_string(), items);
+    fields.insert("components".to_string(), Value::Array(Vec::new()));
+    Ok(Value::Object(fields))
+}
+
+/// Run the migrations from `version` up to the current version
+fn migrate(version: u32, mut graph_data: Value) -> Result<Value> {
+    if version == 0 || version > CURRENT_SNAPSHOT_VERSION {
+        return Err(BomError::SerializationError(format!(
+            "unsupported snapshot version {} (current is {})",
+            version, CURRENT_SNAPSHOT_VERSION
+        )));
+    }
+    for migration in &MIGRATIONS[(version - 1) as usize..] {
+        graph_data = migration(graph_data)?;
+    }
+    Ok(graph_data)
+}
+
+impl BomGraph {
+    /// Snapshot of the graph's components and BOM items at `CURRENT_SNAPSHOT_VERSION`
+    /// Node caches and indices are not included; they are rebuilt on load
+    pub fn to_snapshot(&self) -> Result<BomGraphSnapshot> {
+        let data = GraphData {
+            components: self.iter_nodes().map(|(_, id)| id.clone()).collect(),
+            bom_items: self.iter_edges().map(|(_, _, item)| item.clone()).collect(),
+        };
+        let graph_data = serde_json::to_value(data).map_err(|e| BomError::SerializationError(e.to_string()))?;
+        Ok(BomGraphSnapshot {
+            version: CURRENT_SNAPSHOT_VERSION,
+            graph_data,
+        })
+    }
+
+    /// Serialize the graph as a versioned JSON snapshot (see `BomGraphSnapshot`)
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(&self.to_snapshot()?).map_err(|e| BomError::SerializationError(e.to_string()))
+    }
+
+    /// Load a graph from a snapshot of any supported version
+    pub fn from_snapshot(snapshot: BomGraphSnapshot) -> Result<Self> {
+        let graph_data = migrate(snapshot.version, snapshot.graph_data)?;
+        let data: GraphData =
+            serde_json::from_value(graph_data).map_err(|e| BomError::SerializationError(e.to_string()))?;
+
+        let mut graph = Self::with_capacity(data.components.len(), data.bom_items.len());
+        for component_id in data.components {
+            graph.arena_mut().add_node(component_id);
+        }
+        for item in data.bom_items {
+            graph.add_bom_item(item)?;
+        }
+        graph.identify_roots();
+        Ok(graph)
+    }
+
+    /// Load a graph written by `to_json`, migrating older snapshot versions
+    pub fn from_json(json: &str) -> Result<Self> {
+        let snapshot: BomGraphSnapshot =
+            serde_json::from_str(json).map_err(|e| BomError::SerializationError(e.to_string()))?;
+        Self::from_snapshot(snapshot)
+    }
+
+    /// Load the `graph_data` of a version 1 snapshot
+    pub fn from_snapshot_v1(graph_data: Value) -> Result<Self> {
+        Self::from_snapshot(BomGraphSnapshot { version: 1, graph_data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use uuid::Uuid;
+
+    fn create_test_bom_item(parent: &str, child: &str, qty: i64) -> BomItem {
+        BomItem {
+            id: Uuid::new_v4(),
+            parent_id: ComponentId::new(parent),
+            child_id: ComponentId::new(child),
+            quantity: Decimal::from(qty),
+            scrap_factor: Decimal::ZERO,
+            sequence: 10,
+            operation_sequence: None,
+            is_phantom: false,
+            effective_from: None,
+            effective_to: None,
+            alternative_group: None,
+            alternative_priority: None,
+            reference_designator: None,
+            position: None,
+            notes: None,
+            version: 0,
+        }
+    }
+
+    fn child_quantities(graph: &BomGraph, parent: &str) -> Vec<(String, Decimal)> {
+        let node = graph.find_node(&ComponentId::new(parent)).unwrap();
+        let mut children: Vec<(String, Decimal)> = graph
+            .arena()
+            .children(node)
+            .map(|(_, edge)| (edge.bom_item.child_id.as_str().to_string(), edge.bom_item.quantity))
+            .collect();
+        children.sort();
+        children
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut graph = BomGraph::new();
+        graph.add_bom_item(create_test_bom_item("BIKE", "FRAME", 1)).unwrap();
+        graph.add_bom_item(create_test_bom_item("FRAME", "TUBE", 4)).unwrap();
+        graph.arena_mut().add_node(ComponentId::new("SPARE"));
+
+        let json = graph.to_json().unwrap();
+        let snapshot: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(snapshot["version"], CURRENT_SNAPSHOT_VERSION);
+
+        let loaded = BomGraph::from_json(&json).unwrap();
+        assert_eq!(loaded.arena().node_count(), 4);
+        assert_eq!(loaded.arena().edge_count(), 2);
+        assert!(loaded.find_node(&ComponentId::new("SPARE")).is_some());
+        assert_eq!(child_quantities(&loaded, "FRAME"), vec![("TUBE".to_string(), Decimal::from(4))]);
+    }
+
+    #[test]
+    fn test_from_json_migrates_v1() {
+        let items = vec![
+            create_test_bom_item("BIKE", "FRAME", 1),
+            create_test_bom_item("BIKE", "WHEEL", 2),
+            create_test_bom_item("FRAME", "TUBE", 4),
+        ];
+        // Version 1 shape: items under `items`, no component list
+        let v1 = serde_json::json!({
+            "version": 1,
+            "graph_data": { "items": serde_json::to_value(&items).unwrap() }
+        });
+
+        let graph = BomGraph::from_json(&v1.to_string()).unwrap();
+        assert_eq!(graph.arena().node_count(), 4);
+        assert_eq!(graph.arena().edge_count(), 3);
+        assert_eq!(
+            child_quantities(&graph, "BIKE"),
+            vec![("FRAME".to_string(), Decimal::ONE), ("WHEEL".to_string(), Decimal::from(2))]
+        );
+        let roots: Vec<&ComponentId> = graph
+            .roots()
+            .iter()
+            .map(|&root| &graph.arena().node(root).unwrap().component_id)
+            .collect();
+        assert_eq!(roots, vec![&ComponentId::new("BIKE")]);
+
+        let direct = BomGraph::from_snapshot_v1(v1["graph_data"].clone()).unwrap();
+        assert_eq!(direct.arena().edge_count(), 3);
+    }
+
+    #[test]
+    fn test_from_json_rejects_unknown_version() {
+        let future = serde_json::json!({ "version": CURRENT_SNAPSHOT_VERSION + 1, "graph_data": {} });
+        assert!(matches!(
+            BomGraph::from_json(&future.to_string()),
+            Err(BomError::SerializationError(_))
+        ));
+    }
+}
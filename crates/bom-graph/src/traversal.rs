@@ -1,4 +1,5 @@
 use crate::arena::{Arena, NodeIndex};
+use bom_core::{BomItem, ComponentId};
 use std::collections::{HashMap, HashSet, VecDeque};
 
 /// Traversal order for BOM graph
@@ -8,6 +9,9 @@ pub enum TraversalOrder {
     DepthFirst,
     /// Breadth-first search
     BreadthFirst,
+    /// Breadth-first search from the leaves towards the roots
+    /// A parent is yielded only after all of its children
+    BreadthFirstBottomUp,
     /// Topological order (bottom-up: leaves first)
     TopologicalBottomUp,
     /// Reverse topological order (top-down: roots first)
@@ -21,6 +25,8 @@ pub struct Traversal<'a> {
     visited: HashSet<NodeIndex>,
     stack: Vec<NodeIndex>,
     queue: VecDeque<NodeIndex>,
+    /// Children not yet yielded, per node (bottom-up BFS only)
+    pending_children: HashMap<NodeIndex, usize>,
 }
 
 impl<'a> Traversal<'a> {
@@ -32,6 +38,7 @@ impl<'a> Traversal<'a> {
             visited: HashSet::new(),
             stack: Vec::new(),
             queue: VecDeque::new(),
+            pending_children: HashMap::new(),
         };
 
         match order {
@@ -41,6 +48,25 @@ impl<'a> Traversal<'a> {
             TraversalOrder::BreadthFirst => {
                 traversal.queue.extend(roots);
             }
+            TraversalOrder::BreadthFirstBottomUp => {
+                let reachable = reachable_from(arena, roots);
+                let mut leaves = Vec::new();
+
+                for &node in &reachable {
+                    let children = arena
+                        .children(node)
+                        .filter(|(child, _)| reachable.contains(child))
+                        .count();
+                    if children == 0 {
+                        leaves.push(node);
+                    } else {
+                        traversal.pending_children.insert(node, children);
+                    }
+                }
+
+                leaves.sort_by_key(|node| node.0);
+                traversal.queue.extend(leaves);
+            }
             TraversalOrder::TopologicalBottomUp | TraversalOrder::TopologicalTopDown => {
                 // Will be computed lazily
                 let topo = topological_sort(arena, roots);
@@ -54,6 +80,16 @@ impl<'a> Traversal<'a> {
 
         traversal
     }
+
+    /// Create a bottom-up traversal that starts at the leaves reachable from `roots`
+    /// `order` must be `BreadthFirstBottomUp` or `TopologicalBottomUp`
+    pub fn from_leaves(arena: &'a Arena, roots: &[NodeIndex], order: TraversalOrder) -> Self {
+        debug_assert!(
+            matches!(order, TraversalOrder::BreadthFirstBottomUp | TraversalOrder::TopologicalBottomUp),
+            "from_leaves requires a bottom-up traversal order"
+        );
+        Self::new(arena, roots, order)
+    }
 }
 
 impl<'a> Iterator for Traversal<'a> {
@@ -91,15 +127,29 @@ impl<'a> Iterator for Traversal<'a> {
                 }
                 None
             }
+            TraversalOrder::BreadthFirstBottomUp => {
+                let node = self.queue.pop_front()?;
+                self.visited.insert(node);
+
+                // A parent becomes ready once its last pending child has been yielded
+                for (parent, _) in self.arena.parents(node) {
+                    if let Some(pending) = self.pending_children.get_mut(&parent) {
+                        *pending -= 1;
+                        if *pending == 0 {
+                            self.pending_children.remove(&parent);
+                            self.queue.push_back(parent);
+                        }
+                    }
+                }
+
+                Some(node)
+            }
         }
     }
 }
 
-/// Compute topological sort of the graph (bottom-up: leaves first)
-/// Only includes nodes reachable from the given roots
-/// Uses Kahn's algorithm
-pub fn topological_sort(arena: &Arena, roots: &[NodeIndex]) -> Vec<NodeIndex> {
-    // First, find all nodes reachable from roots
+/// Collect all nodes reachable from the given roots (including the roots)
+fn reachable_from(arena: &Arena, roots: &[NodeIndex]) -> HashSet<NodeIndex> {
     let mut reachable = HashSet::new();
     let mut stack: Vec<NodeIndex> = roots.to_vec();
 
@@ -113,6 +163,39 @@ pub fn topological_sort(arena: &Arena, roots: &[NodeIndex]) -> Vec<NodeIndex> {
         }
     }
 
+    reachable
+}
+
+/// What a `BomVisitor` wants the traversal to do next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisitAction {
+    /// Keep going (descend into the node or follow the edge)
+    Continue,
+    /// Do not descend below this node / do not follow this edge
+    Skip,
+    /// End the traversal
+    Stop,
+}
+
+/// Callbacks for `BomGraph::bfs_visit`
+/// Lets callers prune branches while the graph is being walked
+pub trait BomVisitor {
+    /// Called once per reached node, with its distance from the start node
+    fn visit_node(&mut self, node: &ComponentId, level: usize) -> VisitAction;
+
+    /// Called for each outgoing edge of a node that was not skipped
+    fn visit_edge(&mut self, _parent: &ComponentId, _child: &ComponentId, _item: &BomItem) -> VisitAction {
+        VisitAction::Continue
+    }
+}
+
+/// Compute topological sort of the graph (bottom-up: leaves first)
+/// Only includes nodes reachable from the given roots
+/// Uses Kahn's algorithm
+pub fn topological_sort(arena: &Arena, roots: &[NodeIndex]) -> Vec<NodeIndex> {
+    // First, find all nodes reachable from roots
+    let reachable = reachable_from(arena, roots);
+
     // Calculate in-degrees only for reachable nodes
     let mut in_degree: HashMap<NodeIndex, usize> = HashMap::new();
     let mut result = Vec::new();
@@ -220,8 +303,10 @@ fn dfs_paths(
     if current == target {
         paths.push(path.clone());
     } else {
+        // Several items between the same pair are one path
+        let mut children_seen = HashSet::new();
         for (child, _) in arena.children(current) {
-            if !visited.contains(&child) {
+            if children_seen.insert(child) && !visited.contains(&child) {
                 dfs_paths(arena, child, target, path, visited, paths);
             }
         }
@@ -340,4 +425,51 @@ mod tests {
         assert!(paths.iter().any(|p| p.len() == 3 && p[1] == b));
         assert!(paths.iter().any(|p| p.len() == 3 && p[1] == c));
     }
+
+    #[test]
+    fn test_breadth_first_bottom_up() {
+        let mut arena = Arena::new();
+
+        // A -> B -> D
+        //   -> C -> D
+        let a = arena.add_node(ComponentId::new("A"));
+        let b = arena.add_node(ComponentId::new("B"));
+        let c = arena.add_node(ComponentId::new("C"));
+        let d = arena.add_node(ComponentId::new("D"));
+
+        arena.add_edge(a, b, create_test_bom_item("A", "B"));
+        arena.add_edge(a, c, create_test_bom_item("A", "C"));
+        arena.add_edge(b, d, create_test_bom_item("B", "D"));
+        arena.add_edge(c, d, create_test_bom_item("C", "D"));
+
+        let order: Vec<_> =
+            Traversal::from_leaves(&arena, &[a], TraversalOrder::BreadthFirstBottomUp).collect();
+
+        // Level order from the leaves: D, then B and C, then A
+        assert_eq!(order.len(), 4);
+        assert_eq!(order[0], d);
+        assert!(order[1..3].contains(&b));
+        assert!(order[1..3].contains(&c));
+        assert_eq!(order[3], a);
+    }
+
+    #[test]
+    fn test_breadth_first_bottom_up_waits_for_all_children() {
+        let mut arena = Arena::new();
+
+        // A -> D (direct) and A -> B -> D
+        // A is a parent of the leaf D but must not be yielded before B
+        let a = arena.add_node(ComponentId::new("A"));
+        let b = arena.add_node(ComponentId::new("B"));
+        let d = arena.add_node(ComponentId::new("D"));
+
+        arena.add_edge(a, d, create_test_bom_item("A", "D"));
+        arena.add_edge(a, b, create_test_bom_item("A", "B"));
+        arena.add_edge(b, d, create_test_bom_item("B", "D"));
+
+        let order: Vec<_> =
+            Traversal::from_leaves(&arena, &[a], TraversalOrder::BreadthFirstBottomUp).collect();
+
+        assert_eq!(order, vec![d, b, a]);
+    }
 }
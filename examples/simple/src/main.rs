@@ -33,10 +33,10 @@ fn main() {
     repo.add_component(create_component("Aluminum Tube", "D", 50));
 
     println!("Building BOM structure...");
-    repo.add_bom_item(create_bom_item("A", "B", 1)); // Bicycle -> Frame
-    repo.add_bom_item(create_bom_item("A", "C", 2)); // Bicycle -> Wheel Set (x2)
-    repo.add_bom_item(create_bom_item("B", "D", 2)); // Frame -> Aluminum Tube (x2)
-    repo.add_bom_item(create_bom_item("C", "D", 1)); // Wheel Set -> Aluminum Tube
+    repo.add_bom_item(create_bom_item("A", "B", 1)).unwrap(); // Bicycle -> Frame
+    repo.add_bom_item(create_bom_item("A", "C", 2)).unwrap(); // Bicycle -> Wheel Set (x2)
+    repo.add_bom_item(create_bom_item("B", "D", 2)).unwrap(); // Frame -> Aluminum Tube (x2)
+    repo.add_bom_item(create_bom_item("C", "D", 1)).unwrap(); // Wheel Set -> Aluminum Tube
 
     // Create BOM engine
     let engine = BomEngine::new(repo).unwrap();
@@ -135,6 +135,7 @@ fn create_component(name: &str, id: &str, cost: i32) -> Component {
         standard_cost: Some(Decimal::from(cost)),
         lead_time_days: Some(7),
         procurement_type: ProcurementType::Make,
+        preferred_vendor: None,
         organization: "FACTORY01".to_string(),
         version: 0,
         created_at: Utc::now(),
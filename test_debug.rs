@@ -14,6 +14,7 @@ fn create_test_component(id: &str, cost: i32) -> Component {
         standard_cost: Some(Decimal::from(cost)),
         lead_time_days: Some(7),
         procurement_type: ProcurementType::Make,
+        preferred_vendor: None,
         organization: "ORG01".to_string(),
         version: 0,
         created_at: Utc::now(),